@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+-- Only the latest version of every dependency is kept, hashed secrets cannot be restored.
+DELETE FROM DEPENDENCIES a USING DEPENDENCIES b
+    WHERE a.dependency_name = b.dependency_name AND (a.date_added, a.ctid) < (b.date_added, b.ctid);
+ALTER TABLE DEPENDENCIES DROP CONSTRAINT dependencies_pkey;
+ALTER TABLE DEPENDENCIES ADD PRIMARY KEY (dependency_name);
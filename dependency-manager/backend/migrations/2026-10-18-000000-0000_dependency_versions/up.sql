@@ -0,0 +1,6 @@
+-- Secrets are only stored as their SHA-256 hash
+UPDATE DEPENDENCIES SET secret = encode(sha256(secret::bytea), 'hex');
+
+-- A dependency can have multiple published versions
+ALTER TABLE DEPENDENCIES DROP CONSTRAINT dependencies_pkey;
+ALTER TABLE DEPENDENCIES ADD PRIMARY KEY (dependency_name, dependency_version);
@@ -0,0 +1,17 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE DEPENDENCIES (
+    dependency_name TEXT NOT NULL,
+    dependency_source_path TEXT NOT NULL,
+    dependency_version TEXT NOT NULL,
+    author TEXT NOT NULL,
+    date_added DATE NOT NULL DEFAULT NOW(),
+    secret TEXT NOT NULL,
+    PRIMARY KEY (dependency_name, dependency_version)
+);
+
+INSERT INTO DEPENDENCIES (dependency_name, dependency_source_path, dependency_version, author, date_added, secret)
+    SELECT VERSIONS.package_name, VERSIONS.source_path, VERSIONS.version, PACKAGES.author, VERSIONS.date_added, PACKAGES.secret
+    FROM VERSIONS INNER JOIN PACKAGES ON VERSIONS.package_name = PACKAGES.package_name;
+
+DROP TABLE VERSIONS;
+DROP TABLE PACKAGES;
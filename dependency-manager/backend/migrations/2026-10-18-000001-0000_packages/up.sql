@@ -0,0 +1,26 @@
+-- Every package is stored once, its published versions are stored separately
+CREATE TABLE PACKAGES (
+    package_name TEXT NOT NULL PRIMARY KEY,
+    author TEXT NOT NULL,
+    date_created DATE NOT NULL DEFAULT NOW(),
+    secret TEXT NOT NULL
+);
+
+CREATE TABLE VERSIONS (
+    package_name TEXT NOT NULL REFERENCES PACKAGES (package_name) ON DELETE CASCADE,
+    version TEXT NOT NULL,
+    source_path TEXT NOT NULL,
+    date_added DATE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (package_name, version)
+);
+
+INSERT INTO PACKAGES (package_name, author, date_created, secret)
+    SELECT DISTINCT ON (dependency_name) dependency_name, author, date_added, secret
+    FROM DEPENDENCIES
+    ORDER BY dependency_name, date_added;
+
+INSERT INTO VERSIONS (package_name, version, source_path, date_added)
+    SELECT dependency_name, dependency_version, dependency_source_path, date_added
+    FROM DEPENDENCIES;
+
+DROP TABLE DEPENDENCIES;
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX versions_date_added_idx;
+
+ALTER TABLE PACKAGES DROP COLUMN keywords;
+ALTER TABLE PACKAGES DROP COLUMN repository;
+ALTER TABLE PACKAGES DROP COLUMN license;
+ALTER TABLE PACKAGES DROP COLUMN description;
@@ -0,0 +1,8 @@
+-- The metadata is taken from the config file of the latest published version
+ALTER TABLE PACKAGES ADD COLUMN description TEXT;
+ALTER TABLE PACKAGES ADD COLUMN license TEXT;
+ALTER TABLE PACKAGES ADD COLUMN repository TEXT;
+ALTER TABLE PACKAGES ADD COLUMN keywords TEXT[] NOT NULL DEFAULT '{}';
+
+-- Recently published versions are listed by their date
+CREATE INDEX versions_date_added_idx ON VERSIONS (date_added DESC);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE VERSIONS DROP COLUMN deprecation;
+ALTER TABLE VERSIONS DROP COLUMN yanked;
@@ -0,0 +1,3 @@
+-- Yanked versions are only served if they are requested with their exact version
+ALTER TABLE VERSIONS ADD COLUMN yanked BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE VERSIONS ADD COLUMN deprecation TEXT;
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+-- The claimed packages get an empty secret, which does not match any secret
+UPDATE PACKAGES SET legacy_secret = '' WHERE legacy_secret IS NULL;
+ALTER TABLE PACKAGES ALTER COLUMN legacy_secret SET NOT NULL;
+ALTER TABLE PACKAGES RENAME COLUMN legacy_secret TO secret;
+
+DROP TABLE OWNER_INVITATIONS;
+DROP TABLE PACKAGE_OWNERS;
+DROP TABLE API_TOKENS;
+DROP TABLE USERS;
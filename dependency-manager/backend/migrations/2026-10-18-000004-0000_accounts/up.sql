@@ -0,0 +1,34 @@
+-- Packages are owned by accounts, which authenticate with API tokens
+CREATE TABLE USERS (
+    username TEXT NOT NULL PRIMARY KEY,
+    date_created DATE NOT NULL DEFAULT NOW()
+);
+
+-- Only the SHA-256 hashes of the tokens are stored
+CREATE TABLE API_TOKENS (
+    token_hash TEXT NOT NULL PRIMARY KEY,
+    username TEXT NOT NULL REFERENCES USERS (username) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    scopes TEXT[] NOT NULL,
+    date_created DATE NOT NULL DEFAULT NOW(),
+    UNIQUE (username, name)
+);
+
+CREATE TABLE PACKAGE_OWNERS (
+    package_name TEXT NOT NULL REFERENCES PACKAGES (package_name) ON DELETE CASCADE,
+    username TEXT NOT NULL REFERENCES USERS (username) ON DELETE CASCADE,
+    PRIMARY KEY (package_name, username)
+);
+
+-- An invited user becomes an owner once they accept the invitation
+CREATE TABLE OWNER_INVITATIONS (
+    package_name TEXT NOT NULL REFERENCES PACKAGES (package_name) ON DELETE CASCADE,
+    username TEXT NOT NULL REFERENCES USERS (username) ON DELETE CASCADE,
+    invited_by TEXT NOT NULL REFERENCES USERS (username) ON DELETE CASCADE,
+    date_created DATE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (package_name, username)
+);
+
+-- The packages published before accounts existed keep their secret until they are claimed by an account
+ALTER TABLE PACKAGES RENAME COLUMN secret TO legacy_secret;
+ALTER TABLE PACKAGES ALTER COLUMN legacy_secret DROP NOT NULL;
@@ -0,0 +1,431 @@
+use crate::{
+    api::auth::{AuthenticatedUser, is_owner},
+    models::{
+        ApiToken, ClaimRequest, OwnerInvitation, OwnerRequest, Package, PackageOwner,
+        PackageOwners, PackageRequest, RegisterRequest, TokenCreateRequest, TokenInformation,
+        TokenReply, TokenRevokeRequest, User,
+    },
+    schema::{api_tokens, owner_invitations, package_owners, packages, users},
+};
+use common::{
+    axum::{
+        Json,
+        extract::{Query, State},
+    },
+    chrono::Utc,
+    dependency_manager::{ServerState, TokenScope, generate_secret, hash_secret, verify_secret},
+    error::dependency_manager::DependencyManagerError,
+};
+use diesel::{
+    Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+    r2d2::{ConnectionManager, PooledConnection},
+    result::{DatabaseErrorKind, Error as DieselError},
+};
+
+/// Usernames are shown by the registry and used in URLs, so they are restricted to ASCII letters, digits, `-` and `_`.
+fn validate_username(username: &str) -> Result<(), DependencyManagerError>
+{
+    if username.is_empty()
+        || username.len() > 64
+        || !username
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '_')
+    {
+        return Err(DependencyManagerError::InvalidUsername(
+            username.to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_connection(
+    state: &ServerState,
+) -> Result<PooledConnection<ConnectionManager<PgConnection>>, DependencyManagerError>
+{
+    state.db_connection.get().map_err(|err| {
+        eprintln!("An error occured while managing accounts in db: {}", err);
+
+        DependencyManagerError::GenericDatabaseError
+    })
+}
+
+fn database_error(err: DieselError) -> DependencyManagerError
+{
+    eprintln!("An error occured while managing accounts in db: {}", err);
+
+    DependencyManagerError::GenericDatabaseError
+}
+
+/// Creates a token for the user, only the hash of the token is stored.
+fn insert_token(
+    pg_connection: &mut PgConnection,
+    state: &ServerState,
+    username: &str,
+    name: &str,
+    scopes: &[TokenScope],
+) -> Result<TokenReply, DieselError>
+{
+    let token = generate_secret::<32>(state);
+
+    diesel::insert_into(api_tokens::table)
+        .values(ApiToken {
+            token_hash: hash_secret(&token),
+            username: username.to_string(),
+            name: name.to_string(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            date_created: Utc::now().date_naive(),
+        })
+        .execute(pg_connection)?;
+
+    Ok(TokenReply {
+        username: username.to_string(),
+        token,
+    })
+}
+
+/// Creates an account with a token named `default`, which has every scope.
+pub async fn register(
+    State(state): State<ServerState>,
+    Json(request): Json<RegisterRequest>,
+) -> Result<Json<TokenReply>, DependencyManagerError>
+{
+    validate_username(&request.username)?;
+
+    let mut pg_connection = get_connection(&state)?;
+
+    let token_reply = pg_connection
+        .transaction(|pg_connection| {
+            diesel::insert_into(users::table)
+                .values(User {
+                    username: request.username.clone(),
+                    date_created: Utc::now().date_naive(),
+                })
+                .execute(pg_connection)?;
+
+            insert_token(
+                pg_connection,
+                &state,
+                &request.username,
+                "default",
+                &TokenScope::ALL,
+            )
+        })
+        .map_err(|err| {
+            match err {
+                DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    DependencyManagerError::UsernameTaken(request.username.clone())
+                },
+                err => database_error(err),
+            }
+        })?;
+
+    Ok(Json(token_reply))
+}
+
+/// Creates a token with the requested scopes for the account of the request.
+pub async fn create_token(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<TokenCreateRequest>,
+) -> Result<Json<TokenReply>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Manage)?;
+
+    if request.name.trim().is_empty() || request.scopes.is_empty() {
+        return Err(DependencyManagerError::BadRequest);
+    }
+
+    let mut pg_connection = get_connection(&state)?;
+
+    let token_reply = insert_token(
+        &mut pg_connection,
+        &state,
+        &user.username,
+        &request.name,
+        &request.scopes,
+    )
+    .map_err(|err| {
+        match err {
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                DependencyManagerError::TokenNameTaken(request.name.clone())
+            },
+            err => database_error(err),
+        }
+    })?;
+
+    Ok(Json(token_reply))
+}
+
+/// Lists the tokens of the account of the request, the tokens themselves are not stored so only their names and scopes are listed.
+pub async fn list_tokens(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+) -> Result<Json<Vec<TokenInformation>>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Manage)?;
+
+    let mut pg_connection = get_connection(&state)?;
+
+    let tokens = api_tokens::table
+        .filter(api_tokens::username.eq(&user.username))
+        .order(api_tokens::name)
+        .select(ApiToken::as_select())
+        .load::<ApiToken>(&mut pg_connection)
+        .map_err(database_error)?;
+
+    Ok(Json(
+        tokens.into_iter().map(TokenInformation::new).collect(),
+    ))
+}
+
+/// Revokes a token of the account of the request.
+pub async fn revoke_token(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<TokenRevokeRequest>,
+) -> Result<Json<()>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Manage)?;
+
+    let mut pg_connection = get_connection(&state)?;
+
+    let revoked_count = diesel::delete(
+        api_tokens::table
+            .filter(api_tokens::username.eq(&user.username))
+            .filter(api_tokens::name.eq(&request.name)),
+    )
+    .execute(&mut pg_connection)
+    .map_err(database_error)?;
+
+    if revoked_count == 0 {
+        return Err(DependencyManagerError::TokenNotFound(request.name));
+    }
+
+    Ok(Json(()))
+}
+
+/// Invites a user to become an owner of the package, only the owners of the package can invite users.
+pub async fn invite_owner(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<OwnerRequest>,
+) -> Result<Json<()>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Manage)?;
+
+    let mut pg_connection = get_connection(&state)?;
+
+    user.require_owner(&mut pg_connection, &request.package_name)?;
+
+    let user_count = users::table
+        .find(&request.username)
+        .count()
+        .get_result::<i64>(&mut pg_connection)
+        .map_err(database_error)?;
+
+    if user_count == 0 {
+        return Err(DependencyManagerError::UserNotFound(request.username));
+    }
+
+    if is_owner(&mut pg_connection, &request.package_name, &request.username)? {
+        return Err(DependencyManagerError::AlreadyOwner(
+            request.package_name,
+            request.username,
+        ));
+    }
+
+    // Inviting a user again keeps the original invitation
+    diesel::insert_into(owner_invitations::table)
+        .values(OwnerInvitation {
+            package_name: request.package_name,
+            username: request.username,
+            invited_by: user.username,
+            date_created: Utc::now().date_naive(),
+        })
+        .on_conflict_do_nothing()
+        .execute(&mut pg_connection)
+        .map_err(database_error)?;
+
+    Ok(Json(()))
+}
+
+/// Accepts the invitation of the account of the request, making it an owner of the package.
+pub async fn accept_invitation(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<PackageRequest>,
+) -> Result<Json<()>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Manage)?;
+
+    let mut pg_connection = get_connection(&state)?;
+
+    pg_connection.transaction(|pg_connection| {
+        let accepted_count =
+            diesel::delete(owner_invitations::table.find((&request.package_name, &user.username)))
+                .execute(pg_connection)
+                .map_err(database_error)?;
+
+        if accepted_count == 0 {
+            return Err(DependencyManagerError::InvitationNotFound(
+                request.package_name.clone(),
+            ));
+        }
+
+        diesel::insert_into(package_owners::table)
+            .values(PackageOwner {
+                package_name: request.package_name.clone(),
+                username: user.username.clone(),
+            })
+            .on_conflict_do_nothing()
+            .execute(pg_connection)
+            .map_err(database_error)?;
+
+        Ok(())
+    })?;
+
+    Ok(Json(()))
+}
+
+/// Removes an owner of the package, only the owners of the package can remove owners. The last owner of a package cannot be removed.
+pub async fn remove_owner(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<OwnerRequest>,
+) -> Result<Json<()>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Manage)?;
+
+    let mut pg_connection = get_connection(&state)?;
+
+    user.require_owner(&mut pg_connection, &request.package_name)?;
+
+    pg_connection.transaction(|pg_connection| {
+        // The owners are locked until the transaction ends, so that concurrent removals cannot remove every owner of the package
+        let owner_count = package_owners::table
+            .filter(package_owners::package_name.eq(&request.package_name))
+            .select(package_owners::username)
+            .for_update()
+            .load::<String>(pg_connection)
+            .map_err(database_error)?
+            .len();
+
+        let removed_count =
+            diesel::delete(package_owners::table.find((&request.package_name, &request.username)))
+                .execute(pg_connection)
+                .map_err(database_error)?;
+
+        if removed_count == 0 {
+            return Err(DependencyManagerError::OwnerNotFound(
+                request.package_name.clone(),
+                request.username.clone(),
+            ));
+        }
+
+        // Removing the owner is rolled back, so that the package keeps an owner
+        if owner_count == 1 {
+            return Err(DependencyManagerError::LastOwner(
+                request.package_name.clone(),
+                request.username.clone(),
+            ));
+        }
+
+        Ok(())
+    })?;
+
+    Ok(Json(()))
+}
+
+/// Lists the owners of the package.
+pub async fn list_owners(
+    State(state): State<ServerState>,
+    Query(request): Query<PackageRequest>,
+) -> Result<Json<PackageOwners>, DependencyManagerError>
+{
+    let mut pg_connection = get_connection(&state)?;
+
+    let package_count = packages::table
+        .find(&request.package_name)
+        .count()
+        .get_result::<i64>(&mut pg_connection)
+        .map_err(database_error)?;
+
+    if package_count == 0 {
+        return Err(DependencyManagerError::DependencyNotFound);
+    }
+
+    let owners = package_owners::table
+        .filter(package_owners::package_name.eq(&request.package_name))
+        .order(package_owners::username)
+        .select(package_owners::username)
+        .load::<String>(&mut pg_connection)
+        .map_err(database_error)?;
+
+    Ok(Json(PackageOwners {
+        package_name: request.package_name,
+        owners,
+    }))
+}
+
+/// Makes the account of the request the owner of a package published before accounts existed, the package's secret is removed once it has been claimed.
+pub async fn claim_package(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<ClaimRequest>,
+) -> Result<Json<()>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Manage)?;
+
+    let mut pg_connection = get_connection(&state)?;
+
+    let package = packages::table
+        .find(&request.package_name)
+        .select(Package::as_select())
+        .first(&mut pg_connection)
+        .optional()
+        .map_err(database_error)?
+        .ok_or(DependencyManagerError::DependencyNotFound)?;
+
+    let Some(legacy_secret) = &package.legacy_secret
+    else {
+        return Err(DependencyManagerError::PackageAlreadyClaimed(
+            request.package_name,
+        ));
+    };
+
+    if !verify_secret(&request.secret, legacy_secret) {
+        return Err(DependencyManagerError::InvalidSecret);
+    }
+
+    pg_connection.transaction(|pg_connection| {
+        // The secret is only removed if it has not been removed or changed since it was verified, so a package can only be claimed once
+        let claimed_count = diesel::update(
+            packages::table
+                .find(&request.package_name)
+                .filter(packages::legacy_secret.eq(legacy_secret)),
+        )
+        .set(packages::legacy_secret.eq(None::<String>))
+        .execute(pg_connection)
+        .map_err(database_error)?;
+
+        if claimed_count == 0 {
+            return Err(DependencyManagerError::PackageAlreadyClaimed(
+                request.package_name.clone(),
+            ));
+        }
+
+        diesel::insert_into(package_owners::table)
+            .values(PackageOwner {
+                package_name: request.package_name.clone(),
+                username: user.username.clone(),
+            })
+            .execute(pg_connection)
+            .map_err(database_error)?;
+
+        Ok(())
+    })?;
+
+    Ok(Json(()))
+}
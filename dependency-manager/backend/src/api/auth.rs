@@ -0,0 +1,143 @@
+use crate::{
+    models::ApiToken,
+    schema::{api_tokens, package_owners, packages},
+};
+use common::{
+    axum::{
+        extract::FromRequestParts,
+        http::{HeaderMap, HeaderValue, header, request::Parts},
+    },
+    dependency_manager::{ServerState, TokenScope, hash_secret},
+    error::dependency_manager::DependencyManagerError,
+};
+use diesel::{OptionalExtension, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+/// The account a request has been authenticated as, with the scopes of the API token sent in the `Authorization: Bearer <token>` header.
+pub struct AuthenticatedUser
+{
+    pub username: String,
+    pub scopes: Vec<TokenScope>,
+}
+
+impl FromRequestParts<ServerState> for AuthenticatedUser
+{
+    type Rejection = DependencyManagerError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ServerState,
+    ) -> Result<Self, Self::Rejection>
+    {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(DependencyManagerError::MissingToken)?;
+
+        let mut pg_connection = state.db_connection.get().map_err(|err| {
+            eprintln!(
+                "An error occured while authenticating a request in db: {}",
+                err
+            );
+
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+        // Only the hashes of the tokens are stored, so the token is looked up by its hash
+        let api_token = api_tokens::table
+            .find(hash_secret(token.trim()))
+            .select(ApiToken::as_select())
+            .first(&mut pg_connection)
+            .optional()
+            .map_err(|err| {
+                eprintln!(
+                    "An error occured while authenticating a request in db: {}",
+                    err
+                );
+
+                DependencyManagerError::GenericDatabaseError
+            })?
+            .ok_or(DependencyManagerError::InvalidToken)?;
+
+        Ok(Self {
+            scopes: api_token.scopes(),
+            username: api_token.username,
+        })
+    }
+}
+
+impl AuthenticatedUser
+{
+    /// Checks that the token of the request has the scope.
+    pub fn require_scope(&self, scope: TokenScope) -> Result<(), DependencyManagerError>
+    {
+        if !self.scopes.contains(&scope) {
+            return Err(DependencyManagerError::MissingScope(scope));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the user is an owner of the package.
+    pub fn require_owner(
+        &self,
+        pg_connection: &mut PgConnection,
+        package_name: &str,
+    ) -> Result<(), DependencyManagerError>
+    {
+        if !is_owner(pg_connection, package_name, &self.username)? {
+            return Err(DependencyManagerError::NotAnOwner(package_name.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns whether the user is an owner of the package, the package must exist.
+pub fn is_owner(
+    pg_connection: &mut PgConnection,
+    package_name: &str,
+    username: &str,
+) -> Result<bool, DependencyManagerError>
+{
+    let package_count = packages::table
+        .find(package_name)
+        .count()
+        .get_result::<i64>(pg_connection)
+        .map_err(|err| {
+            eprintln!("An error occured while fetching owners from db: {}", err);
+
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+    if package_count == 0 {
+        return Err(DependencyManagerError::DependencyNotFound);
+    }
+
+    let owner_count = package_owners::table
+        .find((package_name, username))
+        .count()
+        .get_result::<i64>(pg_connection)
+        .map_err(|err| {
+            eprintln!("An error occured while fetching owners from db: {}", err);
+
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+    Ok(owner_count > 0)
+}
+
+/// Returns a copy of the headers which can be logged, the API token in the `Authorization` header is replaced.
+pub fn redact_credentials(headers: &HeaderMap) -> HeaderMap
+{
+    let mut headers = headers.clone();
+
+    for name in [header::AUTHORIZATION, header::PROXY_AUTHORIZATION] {
+        if headers.contains_key(&name) {
+            headers.insert(name, HeaderValue::from_static("<redacted>"));
+        }
+    }
+
+    headers
+}
@@ -1,24 +1,120 @@
 use crate::{
-    models::{Dependency, DependencyInformation, DependencyUpload, DependencyUploadReply},
-    schema::dependencies::{self, dependency_name, dependency_version},
+    api::auth::AuthenticatedUser,
+    models::{
+        Dependency, DependencyInformation, DependencyUpload, DeprecationRequest, Package,
+        PackageOwner, PackageVersion, PublishedVersion, YankRequest,
+    },
+    schema::{package_owners, packages, versions},
 };
 use common::{
     axum::{Json, body::Bytes, extract::State},
     chrono::Utc,
     compression::{compress_bytes, decompress_bytes, write_zip_to_fs, zip_folder},
     dependency::{DependencyRequest, construct_dependency_path},
-    dependency_manager::{ServerState, generate_secret},
+    dependency_manager::{ServerState, TokenScope},
     error::dependency_manager::DependencyManagerError,
     rmp_serde::*,
+    semver::{Op, Version, VersionReq},
     zip::ZipArchive,
 };
-use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use diesel::{
+    Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+    result::{DatabaseErrorKind, Error as DieselError},
+};
 use std::{
     fs::{self},
     io::Cursor,
     path::PathBuf,
 };
 
+/// Package names are used in the paths of the published files and in URLs, so they are restricted to ASCII letters, digits, `-` and `_`.
+fn validate_package_name(package_name: &str) -> Result<(), DependencyManagerError>
+{
+    if package_name.is_empty()
+        || package_name.len() > 64
+        || !package_name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '_')
+    {
+        return Err(DependencyManagerError::InvalidPackageName(
+            package_name.to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fetches the information of the newest version of a dependency matching the requested version requirement (ie. `^1.2`, `1.2.3` or `*`).
+/// Yanked versions are only fetched for exact requirements (ie. `=1.2.3`), which is how the locked versions of a project are requested.
+fn query_dependency_version(
+    pg_connection: &mut PgConnection,
+    mut request: DependencyRequest,
+) -> Result<DependencyInformation, DependencyManagerError>
+{
+    let requirement = VersionReq::parse(&request.version)
+        .map_err(|_| DependencyManagerError::InvalidVersion(request.version.clone()))?;
+
+    let allow_yanked = is_exact_requirement(&requirement);
+
+    let published_versions = versions::table
+        .filter(versions::package_name.eq(request.name.clone()))
+        .select((versions::version, versions::yanked))
+        .load::<(String, bool)>(pg_connection)
+        .map_err(|err| {
+            eprintln!(
+                "An error occured while fetching dependencies from db: {}",
+                err
+            );
+
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+    // The version is looked up with its published string, as it is stored in the database
+    request.version = published_versions
+        .iter()
+        .filter(|(_, yanked)| allow_yanked || !yanked)
+        .filter_map(|(version, _)| Some((Version::parse(version).ok()?, version)))
+        .filter(|(version, _)| requirement.matches(version))
+        .max_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs))
+        .map(|(_, version)| version.clone())
+        .ok_or(DependencyManagerError::DependencyNotFound)?;
+
+    let (version, package) = versions::table
+        .inner_join(packages::table)
+        .filter(versions::package_name.eq(request.name))
+        .filter(versions::version.eq(request.version))
+        .select((PackageVersion::as_select(), Package::as_select()))
+        .first::<(PackageVersion, Package)>(pg_connection)
+        .map_err(|err| {
+            eprintln!(
+                "An error occured while fetching dependencies from db: {}",
+                err
+            );
+
+            DependencyManagerError::DependencyNotFound
+        })?;
+
+    Ok(DependencyInformation::new(package, version))
+}
+
+/// Returns whether the requirement only matches a single version, ie. `=1.2.3`.
+fn is_exact_requirement(requirement: &VersionReq) -> bool
+{
+    matches!(
+        requirement.comparators.as_slice(),
+        [comparator] if comparator.op == Op::Exact
+            && comparator.minor.is_some()
+            && comparator.patch.is_some()
+    )
+}
+
+/// Parses the version of a dependency, every published version must follow the semver specification.
+fn parse_version(version: &str) -> Result<Version, DependencyManagerError>
+{
+    Version::parse(version).map_err(|_| DependencyManagerError::InvalidVersion(version.to_string()))
+}
+
 pub async fn fetch_dependency_information(
     State(state): State<ServerState>,
     Json(request): Json<DependencyRequest>,
@@ -33,33 +129,58 @@ pub async fn fetch_dependency_information(
         DependencyManagerError::GenericDatabaseError
     })?;
 
-    let dependency_filter = dependencies::table.filter(
-        dependency_name
-            .eq(request.name)
-            .and(dependency_version.eq(request.version)),
-    );
+    Ok(Json(query_dependency_version(&mut pg_connection, request)?))
+}
 
-    let query_result = dependency_filter
-        .select(DependencyInformation::as_select())
-        .first(&mut pg_connection)
+/// Checks that the version has not been published yet, versions which only differ in their build metadata (ie. `1.2.3+build`) count as the same version.
+/// Versions may be published in any order, so that older major versions can receive fixes.
+fn check_version_is_unpublished(
+    pg_connection: &mut PgConnection,
+    package_name: &str,
+    version: &str,
+) -> Result<(), DependencyManagerError>
+{
+    let new_version = parse_version(version)?;
+
+    let published_versions = versions::table
+        .filter(versions::package_name.eq(package_name))
+        .select(versions::version)
+        .load::<String>(pg_connection)
         .map_err(|err| {
             eprintln!(
                 "An error occured while fetching dependencies from db: {}",
                 err
             );
 
-            DependencyManagerError::DependencyNotFound
+            DependencyManagerError::GenericDatabaseError
         })?;
 
-    Ok(Json(query_result))
+    // Every published version has been validated, so versions which cannot be parsed are ignored
+    if published_versions
+        .iter()
+        .filter_map(|version| Version::parse(version).ok())
+        .any(|published_version| published_version.cmp_precedence(&new_version).is_eq())
+    {
+        return Err(DependencyManagerError::VersionAlreadyExists(
+            version.to_string(),
+        ));
+    }
+
+    Ok(())
 }
 
+/// Publishes a version of a package, the request must be authenticated with a token with the [`TokenScope::Publish`] scope.
+/// The first version of a package creates the package, owned by the account of the token. Later versions can only be published by the owners of the package.
+/// The metadata of the package is replaced with the metadata of the published version.
 pub async fn publish_dependency(
     State(state): State<ServerState>,
+    user: AuthenticatedUser,
     // This should be the serialized bytes of type `DependencyUpload`
     serialized_bytes: Bytes,
-) -> Result<Json<DependencyUploadReply>, DependencyManagerError>
+) -> Result<Json<DependencyInformation>, DependencyManagerError>
 {
+    user.require_scope(TokenScope::Publish)?;
+
     let mut pg_connection = state.db_connection.get().map_err(|err| {
         eprintln!(
             "An error occured while uploading a dependency to db: {}",
@@ -72,56 +193,237 @@ pub async fn publish_dependency(
     let decompressed_bytes = decompress_bytes(&serialized_bytes)
         .map_err(|_| DependencyManagerError::DecompressionError)?;
 
-    match from_slice::<DependencyUpload>(&decompressed_bytes) {
-        Ok(dependency_upload) => {
-            // Decompress dependency, write to fs
-            let mut dependency_bytes = Cursor::new(dependency_upload.source_files.clone());
+    let dependency_upload =
+        from_slice::<DependencyUpload>(&decompressed_bytes).map_err(|error| {
+            eprintln!("Error while deserializing request body: {}", error);
+
+            DependencyManagerError::BadRequest
+        })?;
+
+    validate_package_name(&dependency_upload.dependency_name)?;
 
-            let dep_path = construct_dependency_path(
-                state.deps_path.clone(),
-                dependency_upload.dependency_name.clone(),
-                dependency_upload.dependency_version.clone(),
+    let existing_package = packages::table
+        .find(dependency_upload.dependency_name.clone())
+        .select(Package::as_select())
+        .first(&mut pg_connection)
+        .optional()
+        .map_err(|err| {
+            eprintln!(
+                "An error occured while fetching dependencies from db: {}",
+                err
             );
 
-            write_zip_to_fs(
-                &dep_path,
-                ZipArchive::new(&mut dependency_bytes)
-                    .map_err(|_| DependencyManagerError::InvalidZipArchive)?,
-            )?;
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+    if existing_package.is_some() {
+        user.require_owner(&mut pg_connection, &dependency_upload.dependency_name)?;
+
+        check_version_is_unpublished(
+            &mut pg_connection,
+            &dependency_upload.dependency_name,
+            &dependency_upload.dependency_version,
+        )?;
+    }
+    else {
+        parse_version(&dependency_upload.dependency_version)?;
+    }
 
-            let secret = generate_secret::<32>(&state);
+    // Decompress dependency, write to fs
+    let mut dependency_bytes = Cursor::new(dependency_upload.source_files);
 
-            // Store in db
-            diesel::insert_into(dependencies::table)
-                .values(DependencyInformation {
-                    dependency_name: dependency_upload.dependency_name.clone(),
+    let dep_path = construct_dependency_path(
+        state.deps_path.clone(),
+        dependency_upload.dependency_name.clone(),
+        dependency_upload.dependency_version.clone(),
+    );
+
+    // The archive is extracted next to its final path, and only moved there once the version has been stored in db
+    let mut staging_path = dep_path.clone().into_os_string();
+    staging_path.push(format!(
+        ".staging-{}",
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    let staging_path = PathBuf::from(staging_path);
+
+    if let Err(err) = write_zip_to_fs(
+        &staging_path,
+        ZipArchive::new(&mut dependency_bytes)
+            .map_err(|_| DependencyManagerError::InvalidZipArchive)?,
+    ) {
+        let _ = fs::remove_dir_all(&staging_path);
+
+        return Err(err);
+    }
+
+    // Set once the files have been moved to their final path, which has to be cleaned up if the transaction fails to commit
+    let mut is_moved = false;
+
+    let date_added = Utc::now().date_naive();
+
+    // Store the version and the metadata of the package in db, the package and its owner are created with its first version
+    let (package, package_version) = pg_connection
+        .transaction(|pg_connection| {
+            let package = if existing_package.is_some() {
+                diesel::update(packages::table.find(dependency_upload.dependency_name.clone()))
+                    .set((
+                        packages::description.eq(dependency_upload.description),
+                        packages::license.eq(dependency_upload.license),
+                        packages::repository.eq(dependency_upload.repository),
+                        packages::keywords.eq(dependency_upload.keywords),
+                    ))
+                    .get_result::<Package>(pg_connection)?
+            }
+            else {
+                let package = diesel::insert_into(packages::table)
+                    .values(Package {
+                        package_name: dependency_upload.dependency_name.clone(),
+                        author: user.username.clone(),
+                        date_created: date_added,
+                        legacy_secret: None,
+                        description: dependency_upload.description,
+                        license: dependency_upload.license,
+                        repository: dependency_upload.repository,
+                        keywords: dependency_upload.keywords,
+                    })
+                    .get_result::<Package>(pg_connection)?;
+
+                diesel::insert_into(package_owners::table)
+                    .values(PackageOwner {
+                        package_name: package.package_name.clone(),
+                        username: user.username.clone(),
+                    })
+                    .execute(pg_connection)?;
+
+                package
+            };
+
+            let package_version = diesel::insert_into(versions::table)
+                .values(PackageVersion {
+                    package_name: package.package_name.clone(),
+                    version: dependency_upload.dependency_version.clone(),
                     // Dependency name and uploaded folder name must match
-                    dependency_source_path: dep_path.to_string_lossy().to_string(),
-                    dependency_version: dependency_upload.dependency_version.clone(),
-                    author: dependency_upload.author.clone(),
-                    date_added: Utc::now().date_naive(),
-                    secret: secret.clone(),
+                    source_path: dep_path.to_string_lossy().to_string(),
+                    date_added,
+                    yanked: false,
+                    deprecation: None,
                 })
-                .get_result::<DependencyInformation>(&mut pg_connection)
-                .map_err(|err| {
-                    eprintln!(
-                        "Failed to store dependency `{}` to db: {}",
-                        dependency_upload.dependency_name.clone(),
-                        err
-                    );
-
-                    DependencyManagerError::DependencyAlreadyExists
-                })?;
-
-            Ok(Json(DependencyUploadReply {
-                secret_to_dep: secret,
-            }))
-        },
-        Err(error) => {
-            eprintln!("Error while deserializing request body: {}", error);
-            Err(DependencyManagerError::BadRequest)
-        },
-    }
+                .get_result::<PackageVersion>(pg_connection)?;
+
+            // The version has just been inserted, so the files at its path are left over from a failed publish
+            let _ = fs::remove_dir_all(&dep_path);
+
+            fs::rename(&staging_path, &dep_path).map_err(|err| {
+                eprintln!(
+                    "Failed to move dependency `{}` to `{}`: {}",
+                    package.package_name,
+                    dep_path.display(),
+                    err
+                );
+
+                DieselError::RollbackTransaction
+            })?;
+
+            is_moved = true;
+
+            Ok::<_, DieselError>((package, package_version))
+        })
+        .map_err(|err| {
+            eprintln!(
+                "Failed to store dependency `{}` to db: {}",
+                dependency_upload.dependency_name, err
+            );
+
+            let _ = fs::remove_dir_all(&staging_path);
+
+            if is_moved {
+                let _ = fs::remove_dir_all(&dep_path);
+            }
+
+            match err {
+                DieselError::RollbackTransaction => {
+                    DependencyManagerError::FailedToWriteToFile(dep_path.clone())
+                },
+                // The package or the version has been published by a concurrent request
+                DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    DependencyManagerError::VersionAlreadyExists(
+                        dependency_upload.dependency_version.clone(),
+                    )
+                },
+                _ => DependencyManagerError::GenericDatabaseError,
+            }
+        })?;
+
+    Ok(Json(DependencyInformation::new(package, package_version)))
+}
+
+/// Yanks or restores a published version, yanked versions are not selected for new dependency requirements.
+/// The projects which have locked a yanked version can still fetch it with its exact version.
+/// Only the owners of the package can yank its versions, with a token with the [`TokenScope::Yank`] scope.
+pub async fn yank_version(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<YankRequest>,
+) -> Result<Json<PublishedVersion>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Yank)?;
+
+    let mut pg_connection = state.db_connection.get().map_err(|err| {
+        eprintln!("An error occured while yanking a version in db: {}", err);
+
+        DependencyManagerError::GenericDatabaseError
+    })?;
+
+    user.require_owner(&mut pg_connection, &request.dependency_name)?;
+
+    let package_version =
+        diesel::update(versions::table.find((request.dependency_name, request.dependency_version)))
+            .set(versions::yanked.eq(request.yanked))
+            .get_result::<PackageVersion>(&mut pg_connection)
+            .map_err(|err| {
+                eprintln!("An error occured while yanking a version in db: {}", err);
+
+                DependencyManagerError::DependencyNotFound
+            })?;
+
+    Ok(Json(PublishedVersion::new(package_version)))
+}
+
+/// Deprecates a published version with the reason of the deprecation, or removes its deprecation. Deprecated versions can still be depended on.
+/// Only the owners of the package can deprecate its versions, with a token with the [`TokenScope::Yank`] scope.
+pub async fn deprecate_version(
+    State(state): State<ServerState>,
+    user: AuthenticatedUser,
+    Json(request): Json<DeprecationRequest>,
+) -> Result<Json<PublishedVersion>, DependencyManagerError>
+{
+    user.require_scope(TokenScope::Yank)?;
+
+    let mut pg_connection = state.db_connection.get().map_err(|err| {
+        eprintln!(
+            "An error occured while deprecating a version in db: {}",
+            err
+        );
+
+        DependencyManagerError::GenericDatabaseError
+    })?;
+
+    user.require_owner(&mut pg_connection, &request.dependency_name)?;
+
+    let package_version =
+        diesel::update(versions::table.find((request.dependency_name, request.dependency_version)))
+            .set(versions::deprecation.eq(request.deprecation))
+            .get_result::<PackageVersion>(&mut pg_connection)
+            .map_err(|err| {
+                eprintln!(
+                    "An error occured while deprecating a version in db: {}",
+                    err
+                );
+
+                DependencyManagerError::DependencyNotFound
+            })?;
+
+    Ok(Json(PublishedVersion::new(package_version)))
 }
 
 pub async fn fetch_dependency_source(
@@ -138,23 +440,7 @@ pub async fn fetch_dependency_source(
         DependencyManagerError::GenericDatabaseError
     })?;
 
-    let dependency_filter = dependencies::table.filter(
-        dependency_name
-            .eq(request.name)
-            .and(dependency_version.eq(request.version)),
-    );
-
-    let query_result = dependency_filter
-        .select(DependencyInformation::as_select())
-        .first(&mut pg_connection)
-        .map_err(|err| {
-            eprintln!(
-                "An error occured while fetching dependencies from db: {}",
-                err
-            );
-
-            DependencyManagerError::DependencyNotFound
-        })?;
+    let query_result = query_dependency_version(&mut pg_connection, request)?;
 
     let path = PathBuf::from(query_result.dependency_source_path.clone());
 
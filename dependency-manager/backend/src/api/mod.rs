@@ -1 +1,4 @@
+pub mod accounts;
+pub mod auth;
 pub mod manager;
+pub mod search;
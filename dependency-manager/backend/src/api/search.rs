@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use crate::{
+    models::{
+        Package, PackageSummary, PackageVersion, PageRequest, PublishedVersion, RecentlyPublished,
+        RecentlyPublishedPage, SearchRequest, SearchResults, VersionListRequest, VersionListing,
+    },
+    schema::{packages, versions},
+};
+use common::{
+    axum::{
+        Json,
+        extract::{Query, State},
+    },
+    dependency_manager::{PAGE_SIZE, ServerState},
+    error::dependency_manager::DependencyManagerError,
+    semver::Version,
+};
+use diesel::{
+    ExpressionMethods, PgConnection, QueryDsl, QueryableByName, RunQueryDsl, SelectableHelper,
+    sql_types::{BigInt, Text},
+};
+
+/// The document the packages are searched in, it consists of the name, the description and the keywords of a package.
+const SEARCH_DOCUMENT: &str = "to_tsvector('english', package_name || ' ' || coalesce(description, '') || ' ' || array_to_string(keywords, ' '))";
+
+#[derive(QueryableByName)]
+struct Count
+{
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+/// Returns the number of pages the items fill.
+fn page_count(item_count: i64) -> i64
+{
+    (item_count + PAGE_SIZE - 1) / PAGE_SIZE
+}
+
+/// Escapes the wildcards of a `LIKE` pattern.
+fn escape_like_pattern(pattern: &str) -> String
+{
+    pattern
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Fetches the latest version of every package, yanked versions and versions which are not valid semantic versions are ignored.
+fn query_latest_versions(
+    pg_connection: &mut PgConnection,
+    package_names: Vec<String>,
+) -> Result<HashMap<String, PackageVersion>, DependencyManagerError>
+{
+    let published_versions = versions::table
+        .filter(versions::package_name.eq_any(package_names))
+        .filter(versions::yanked.eq(false))
+        .select(PackageVersion::as_select())
+        .load::<PackageVersion>(pg_connection)
+        .map_err(|err| {
+            eprintln!(
+                "An error occured while fetching dependencies from db: {}",
+                err
+            );
+
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+    let mut latest_versions: HashMap<String, (Version, PackageVersion)> = HashMap::new();
+
+    for published_version in published_versions {
+        let Ok(version) = Version::parse(&published_version.version)
+        else {
+            continue;
+        };
+
+        if latest_versions
+            .get(&published_version.package_name)
+            .is_none_or(|(latest_version, _)| *latest_version < version)
+        {
+            latest_versions.insert(
+                published_version.package_name.clone(),
+                (version, published_version),
+            );
+        }
+    }
+
+    Ok(latest_versions
+        .into_iter()
+        .map(|(package_name, (_, latest_version))| (package_name, latest_version))
+        .collect())
+}
+
+/// Searches the packages by their name, description and keywords with PostgreSQL's full-text search.
+/// Packages whose name contains the query are found too, so that packages can be looked up by a part of their name.
+/// Packages with the same name as the query come first, the rest of the packages are sorted by their relevance.
+pub async fn search_packages(
+    State(state): State<ServerState>,
+    Query(request): Query<SearchRequest>,
+) -> Result<Json<SearchResults>, DependencyManagerError>
+{
+    let mut pg_connection = state.db_connection.get().map_err(|err| {
+        eprintln!("An error occured while searching packages in db: {}", err);
+
+        DependencyManagerError::GenericDatabaseError
+    })?;
+
+    let query = request.query.trim();
+    let name_pattern = format!("%{}%", escape_like_pattern(query));
+    let page = request.page.max(0);
+
+    // Packages whose every version has been yanked are not listed
+    let condition = format!(
+        "({SEARCH_DOCUMENT} @@ websearch_to_tsquery('english', $1) OR package_name ILIKE $2) AND EXISTS (SELECT 1 FROM versions WHERE versions.package_name = packages.package_name AND NOT versions.yanked)"
+    );
+
+    let package_count = diesel::sql_query(format!(
+        "SELECT COUNT(*) AS count FROM packages WHERE {condition}"
+    ))
+    .bind::<Text, _>(query)
+    .bind::<Text, _>(&name_pattern)
+    .get_result::<Count>(&mut pg_connection)
+    .map_err(|err| {
+        eprintln!("An error occured while searching packages in db: {}", err);
+
+        DependencyManagerError::GenericDatabaseError
+    })?
+    .count;
+
+    let packages = diesel::sql_query(format!(
+        "SELECT * FROM packages WHERE {condition} ORDER BY package_name = $1 DESC, ts_rank({SEARCH_DOCUMENT}, websearch_to_tsquery('english', $1)) DESC, package_name LIMIT $3 OFFSET $4"
+    ))
+    .bind::<Text, _>(query)
+    .bind::<Text, _>(&name_pattern)
+    .bind::<BigInt, _>(PAGE_SIZE)
+    .bind::<BigInt, _>(page.saturating_mul(PAGE_SIZE))
+    .load::<Package>(&mut pg_connection)
+    .map_err(|err| {
+        eprintln!("An error occured while searching packages in db: {}", err);
+
+        DependencyManagerError::GenericDatabaseError
+    })?;
+
+    let mut latest_versions = query_latest_versions(
+        &mut pg_connection,
+        packages
+            .iter()
+            .map(|package| package.package_name.clone())
+            .collect(),
+    )?;
+
+    // Packages without a valid version which has not been yanked cannot be depended on, so they are left out of the results
+    let packages = packages
+        .into_iter()
+        .filter_map(|package| {
+            latest_versions
+                .remove(&package.package_name)
+                .map(|latest_version| PackageSummary::new(package, latest_version))
+        })
+        .collect();
+
+    Ok(Json(SearchResults {
+        packages,
+        page,
+        page_count: page_count(package_count),
+    }))
+}
+
+/// Lists every published version of a package, the newest version comes first.
+pub async fn list_package_versions(
+    State(state): State<ServerState>,
+    Query(request): Query<VersionListRequest>,
+) -> Result<Json<VersionListing>, DependencyManagerError>
+{
+    let mut pg_connection = state.db_connection.get().map_err(|err| {
+        eprintln!(
+            "An error occured while fetching package versions from db: {}",
+            err
+        );
+
+        DependencyManagerError::GenericDatabaseError
+    })?;
+
+    let published_versions = versions::table
+        .filter(versions::package_name.eq(request.package_name.clone()))
+        .select(PackageVersion::as_select())
+        .load::<PackageVersion>(&mut pg_connection)
+        .map_err(|err| {
+            eprintln!(
+                "An error occured while fetching package versions from db: {}",
+                err
+            );
+
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+    if published_versions.is_empty() {
+        return Err(DependencyManagerError::DependencyNotFound);
+    }
+
+    let mut versions = published_versions
+        .into_iter()
+        .map(PublishedVersion::new)
+        .collect::<Vec<_>>();
+
+    // Every published version has been validated, versions which cannot be parsed are listed last
+    versions.sort_by_cached_key(|published_version| {
+        std::cmp::Reverse(Version::parse(&published_version.version).ok())
+    });
+
+    Ok(Json(VersionListing {
+        package_name: request.package_name,
+        versions,
+    }))
+}
+
+/// Lists the versions published to the registry, the most recently published version comes first.
+pub async fn recently_published(
+    State(state): State<ServerState>,
+    Query(request): Query<PageRequest>,
+) -> Result<Json<RecentlyPublishedPage>, DependencyManagerError>
+{
+    let mut pg_connection = state.db_connection.get().map_err(|err| {
+        eprintln!(
+            "An error occured while fetching recently published versions from db: {}",
+            err
+        );
+
+        DependencyManagerError::GenericDatabaseError
+    })?;
+
+    let page = request.page.max(0);
+
+    let version_count = versions::table
+        .count()
+        .get_result::<i64>(&mut pg_connection)
+        .map_err(|err| {
+            eprintln!(
+                "An error occured while fetching recently published versions from db: {}",
+                err
+            );
+
+            DependencyManagerError::GenericDatabaseError
+        })?;
+
+    let versions = versions::table
+        .inner_join(packages::table)
+        .order((
+            versions::date_added.desc(),
+            versions::package_name,
+            versions::version.desc(),
+        ))
+        .limit(PAGE_SIZE)
+        .offset(page.saturating_mul(PAGE_SIZE))
+        .select((PackageVersion::as_select(), Package::as_select()))
+        .load::<(PackageVersion, Package)>(&mut pg_connection)
+        .map_err(|err| {
+            eprintln!(
+                "An error occured while fetching recently published versions from db: {}",
+                err
+            );
+
+            DependencyManagerError::GenericDatabaseError
+        })?
+        .into_iter()
+        .map(|(version, package)| RecentlyPublished::new(package, version))
+        .collect();
+
+    Ok(Json(RecentlyPublishedPage {
+        versions,
+        page,
+        page_count: page_count(version_count),
+    }))
+}
@@ -3,7 +3,7 @@ use common::{
     axum::{
         Router,
         body::Body,
-        http::{Request, Response, StatusCode},
+        http::{HeaderValue, Request, Response, StatusCode, header},
         middleware::{self, Next},
         routing::{get, post},
         serve,
@@ -14,11 +14,22 @@ use common::{
 };
 
 use backend::{
-    api::manager::{fetch_dependency_information, fetch_dependency_source, publish_dependency},
+    api::{
+        accounts::{
+            accept_invitation, claim_package, create_token, invite_owner, list_owners, list_tokens,
+            register, remove_owner, revoke_token,
+        },
+        auth::redact_credentials,
+        manager::{
+            deprecate_version, fetch_dependency_information, fetch_dependency_source,
+            publish_dependency, yank_version,
+        },
+        search::{list_package_versions, recently_published, search_packages},
+    },
     establish_state,
 };
 use env_logger::Env;
-use std::{env::current_dir, fs::create_dir_all, net::SocketAddr, path::PathBuf};
+use std::{env::current_dir, fs::create_dir_all, net::SocketAddr};
 
 async fn log_request(request: Request<Body>, next: Next) -> Result<Response<Body>, StatusCode>
 {
@@ -26,7 +37,7 @@ async fn log_request(request: Request<Body>, next: Next) -> Result<Response<Body
     let uri = request.uri().clone();
 
     println!("> Incoming: {} {}", method, uri);
-    println!("> Headers: {:?}", request.headers());
+    println!("> Headers: {:?}", redact_credentials(request.headers()));
 
     let response = next.run(request).await;
 
@@ -35,6 +46,19 @@ async fn log_request(request: Request<Body>, next: Next) -> Result<Response<Body
     Ok(response)
 }
 
+/// The registry's frontend is served from a different origin, so the endpoints it calls must allow cross-origin requests.
+async fn allow_cross_origin(request: Request<Body>, next: Next) -> Response<Body>
+{
+    let mut response = next.run(request).await;
+
+    response.headers_mut().insert(
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        HeaderValue::from_static("*"),
+    );
+
+    response
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()>
 {
@@ -43,11 +67,7 @@ async fn main() -> anyhow::Result<()>
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
     let database_url = std::env::var("DATABASE_URL")?;
-    let deps_path = PathBuf::from(format!(
-        "{}\\{}",
-        current_dir()?.display(),
-        std::env::var("DEPENDENCY_PATH")?
-    ));
+    let deps_path = current_dir()?.join(std::env::var("DEPENDENCY_PATH")?);
 
     // Ignore error, since it will return an error if the folder already exists.
     let _ = create_dir_all(&deps_path);
@@ -55,6 +75,14 @@ async fn main() -> anyhow::Result<()>
     // Establish connection with the database
     let servere_state = establish_state(&database_url, deps_path)?;
 
+    // The read-only endpoints used by the frontend
+    let registry_router = Router::new()
+        .route(api::SEARCH, get(search_packages))
+        .route(api::PACKAGE_VERSIONS, get(list_package_versions))
+        .route(api::RECENTLY_PUBLISHED, get(recently_published))
+        .route(api::LIST_OWNERS, get(list_owners))
+        .route_layer(middleware::from_fn(allow_cross_origin));
+
     // Start up the webserver
     let router = Router::new()
         .route("/", get(reply_ok))
@@ -62,6 +90,17 @@ async fn main() -> anyhow::Result<()>
         .route(api::FETCH_DEP_INFO, get(fetch_dependency_information))
         .route(api::FETCH_DEP, get(fetch_dependency_source))
         .route(api::PUBLISH_DEP, post(publish_dependency))
+        .route(api::REGISTER, post(register))
+        .route(api::CREATE_TOKEN, post(create_token))
+        .route(api::REVOKE_TOKEN, post(revoke_token))
+        .route(api::LIST_TOKENS, get(list_tokens))
+        .route(api::INVITE_OWNER, post(invite_owner))
+        .route(api::ACCEPT_INVITATION, post(accept_invitation))
+        .route(api::REMOVE_OWNER, post(remove_owner))
+        .route(api::CLAIM_PACKAGE, post(claim_package))
+        .route(api::YANK_VERSION, post(yank_version))
+        .route(api::DEPRECATE_VERSION, post(deprecate_version))
+        .merge(registry_router)
         .layer(middleware::from_fn(log_request))
         .with_state(servere_state);
 
@@ -1,21 +1,104 @@
-use common::{assert_same_fields, chrono::NaiveDate, serde};
+use common::{assert_same_fields, chrono::NaiveDate, dependency_manager::TokenScope, serde};
 use diesel::{
     Selectable,
     prelude::{Insertable, Queryable, QueryableByName},
 };
 
-#[derive(
-    Debug,
-    Clone,
-    Selectable,
-    QueryableByName,
-    Queryable,
-    serde::Deserialize,
-    serde::Serialize,
-    Insertable,
-)]
-#[diesel(table_name = crate::schema::dependencies)]
+/// A package published to the dependency manager, its versions are stored in [`PackageVersion`].
+#[derive(Debug, Clone, Selectable, QueryableByName, Queryable, Insertable)]
+#[diesel(table_name = crate::schema::packages)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Package
+{
+    pub package_name: String,
+    pub author: String,
+    pub date_created: NaiveDate,
+    /// The SHA-256 hash of the secret of a package published before accounts existed, the secret itself is never stored.
+    /// The secret is removed once the package is claimed by an account, see [`ClaimRequest`].
+    pub legacy_secret: Option<String>,
+    /// The metadata of the package, which is replaced every time a new version is published.
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+/// A published version of a [`Package`].
+#[derive(Debug, Clone, Selectable, QueryableByName, Queryable, Insertable)]
+#[diesel(table_name = crate::schema::versions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PackageVersion
+{
+    pub package_name: String,
+    /// A semantic version, which is validated when the version is published.
+    pub version: String,
+    pub source_path: String,
+    pub date_added: NaiveDate,
+    /// Yanked versions are not selected for version requirements.
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+/// An account of the dependency manager.
+#[derive(Debug, Clone, Selectable, Queryable, Insertable)]
+#[diesel(table_name = crate::schema::users)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct User
+{
+    pub username: String,
+    pub date_created: NaiveDate,
+}
+
+/// An API token of a [`User`].
+#[derive(Debug, Clone, Selectable, Queryable, Insertable)]
+#[diesel(table_name = crate::schema::api_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiToken
+{
+    /// The SHA-256 hash of the token, the token itself is never stored.
+    pub token_hash: String,
+    pub username: String,
+    pub name: String,
+    /// The [`TokenScope`]s of the token.
+    pub scopes: Vec<String>,
+    pub date_created: NaiveDate,
+}
+
+impl ApiToken
+{
+    /// Returns the scopes of the token, the scopes which are not known are ignored.
+    pub fn scopes(&self) -> Vec<TokenScope>
+    {
+        self.scopes
+            .iter()
+            .filter_map(|scope| scope.parse().ok())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Selectable, Queryable, Insertable)]
+#[diesel(table_name = crate::schema::package_owners)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PackageOwner
+{
+    pub package_name: String,
+    pub username: String,
+}
+
+/// An invitation of a [`User`] to become an owner of a package.
+#[derive(Debug, Clone, Selectable, Queryable, Insertable)]
+#[diesel(table_name = crate::schema::owner_invitations)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OwnerInvitation
+{
+    pub package_name: String,
+    pub username: String,
+    pub invited_by: String,
+    pub date_created: NaiveDate,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct DependencyInformation
 {
     pub dependency_name: String,
@@ -23,7 +106,26 @@ pub struct DependencyInformation
     pub dependency_version: String,
     pub author: String,
     pub date_added: NaiveDate,
-    pub secret: String,
+    /// Yanked versions are only sent if they are requested with their exact version.
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+impl DependencyInformation
+{
+    pub fn new(package: Package, version: PackageVersion) -> Self
+    {
+        Self {
+            dependency_name: package.package_name,
+            dependency_source_path: version.source_path,
+            dependency_version: version.version,
+            author: package.author,
+            date_added: version.date_added,
+            yanked: version.yanked,
+            deprecation: version.deprecation,
+        }
+    }
 }
 
 /// Contains both the raw compressed bytes and the information
@@ -34,40 +136,339 @@ pub struct Dependency
     pub source: Vec<u8>,
 }
 
+/// The body of the publish endpoint, the first version of a package is published by the account of the token.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct DependencyUpdateRequest
+pub struct DependencyUpload
 {
     pub dependency_name: String,
-    // pub author: String,
-    pub secret: String,
-    pub updated_source: Vec<u8>,
+    pub dependency_version: String,
+    /// Compressed ZIP source files
+    pub source_files: Vec<u8>,
+    /// A short description of the package, shown in the search results of the registry.
+    pub description: Option<String>,
+    pub license: Option<String>,
+    /// The URL of the package's source repository.
+    pub repository: Option<String>,
+    /// Keywords the package can be searched by.
+    /// The metadata of the package is replaced with the metadata of every newly published version.
+    pub keywords: Vec<String>,
 }
 
+/// The body of the yank endpoint. Yanked versions are not selected for new dependency requirements, but the projects which have locked them can still use them.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct DependencyUploadReply
+pub struct YankRequest
 {
-    /// Updates can ONLY be uploaded with the use of this secret
-    pub secret_to_dep: String,
+    pub dependency_name: String,
+    pub dependency_version: String,
+    /// Whether the version is yanked or restored.
+    pub yanked: bool,
 }
 
+/// The body of the deprecation endpoint.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct DependencyUpload
+pub struct DeprecationRequest
 {
     pub dependency_name: String,
     pub dependency_version: String,
+    /// The reason the version is deprecated, the deprecation is removed if this is not set.
+    pub deprecation: Option<String>,
+}
+
+/// The body of the register endpoint.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RegisterRequest
+{
+    /// Usernames consist of ASCII letters, digits, `-` and `_`.
+    pub username: String,
+}
+
+/// The body of the token creation endpoint.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenCreateRequest
+{
+    /// The name the token can be revoked by, it must be unique among the tokens of the account.
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+}
+
+/// The token is only sent once, only its hash is stored.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenReply
+{
+    pub username: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenRevokeRequest
+{
+    pub name: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenInformation
+{
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub date_created: NaiveDate,
+}
+
+impl TokenInformation
+{
+    pub fn new(token: ApiToken) -> Self
+    {
+        Self {
+            scopes: token.scopes(),
+            name: token.name,
+            date_created: token.date_created,
+        }
+    }
+}
+
+/// The body of the owner invitation and removal endpoints.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OwnerRequest
+{
+    pub package_name: String,
+    pub username: String,
+}
+
+/// The body of the invitation acceptance endpoint and query parameters of the owner listing endpoint.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PackageRequest
+{
+    pub package_name: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PackageOwners
+{
+    pub package_name: String,
+    /// The usernames of the owners, sorted alphabetically.
+    pub owners: Vec<String>,
+}
+
+/// The body of the claim endpoint, packages published before accounts existed can be claimed with their secret.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ClaimRequest
+{
+    pub package_name: String,
+    pub secret: String,
+}
+
+/// Query parameters of the search endpoint, the packages are searched by their name, description and keywords.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SearchRequest
+{
+    pub query: String,
+    /// Pages are numbered from `0`.
+    #[serde(default)]
+    pub page: i64,
+}
+
+/// Query parameters of the recently published endpoint.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PageRequest
+{
+    /// Pages are numbered from `0`.
+    #[serde(default)]
+    pub page: i64,
+}
+
+/// Query parameters of the version listing endpoint.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct VersionListRequest
+{
+    pub package_name: String,
+}
+
+/// A package found by a search, along with its latest version.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PackageSummary
+{
+    pub package_name: String,
     pub author: String,
-    /// Compressed ZIP source files
-    pub source_files: Vec<u8>,
+    /// The newest version which has not been yanked.
+    pub latest_version: String,
+    /// The date the latest version was published.
+    pub date_updated: NaiveDate,
+    /// The reason the latest version has been deprecated.
+    pub deprecation: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    pub keywords: Vec<String>,
 }
 
-assert_same_fields!(common::dependency_manager::DependencyInformation, DependencyInformation, { dependency_name, dependency_source_path, dependency_version, author, date_added, secret });
-assert_same_fields!(common::dependency_manager::DependencyUpload, DependencyUpload, { dependency_name, source_files, dependency_version, author });
-assert_same_fields!(common::dependency_manager::DependencyUpdateRequest, DependencyUpdateRequest, { dependency_name, updated_source, secret });
-assert_same_fields!(
-    common::dependency_manager::DependencyUploadReply,
-    DependencyUploadReply,
-    { secret_to_dep }
-);
+impl PackageSummary
+{
+    pub fn new(package: Package, latest_version: PackageVersion) -> Self
+    {
+        Self {
+            package_name: package.package_name,
+            author: package.author,
+            latest_version: latest_version.version,
+            date_updated: latest_version.date_added,
+            deprecation: latest_version.deprecation,
+            description: package.description,
+            license: package.license,
+            repository: package.repository,
+            keywords: package.keywords,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SearchResults
+{
+    /// The packages on the requested page, the best matches come first.
+    pub packages: Vec<PackageSummary>,
+    pub page: i64,
+    pub page_count: i64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PublishedVersion
+{
+    pub version: String,
+    pub date_added: NaiveDate,
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+impl PublishedVersion
+{
+    pub fn new(version: PackageVersion) -> Self
+    {
+        Self {
+            version: version.version,
+            date_added: version.date_added,
+            yanked: version.yanked,
+            deprecation: version.deprecation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct VersionListing
+{
+    pub package_name: String,
+    /// The published versions of the package, the newest version comes first.
+    pub versions: Vec<PublishedVersion>,
+}
+
+/// A version published to the registry, listed by the recently published endpoint.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RecentlyPublished
+{
+    pub package_name: String,
+    pub version: String,
+    pub author: String,
+    pub description: Option<String>,
+    pub date_added: NaiveDate,
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+impl RecentlyPublished
+{
+    pub fn new(package: Package, version: PackageVersion) -> Self
+    {
+        Self {
+            package_name: package.package_name,
+            version: version.version,
+            author: package.author,
+            description: package.description,
+            date_added: version.date_added,
+            yanked: version.yanked,
+            deprecation: version.deprecation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RecentlyPublishedPage
+{
+    /// The published versions on the requested page, the most recent version comes first.
+    pub versions: Vec<RecentlyPublished>,
+    pub page: i64,
+    pub page_count: i64,
+}
+
+assert_same_fields!(common::dependency_manager::DependencyInformation, DependencyInformation, { dependency_name, dependency_source_path, dependency_version, author, date_added, yanked, deprecation });
+assert_same_fields!(common::dependency_manager::DependencyUpload, DependencyUpload, { dependency_name, source_files, dependency_version, description, license, repository, keywords });
 assert_same_fields!(common::dependency_manager::Dependency, Dependency, {
     source
 });
+assert_same_fields!(common::dependency_manager::YankRequest, YankRequest, {
+    dependency_name, dependency_version, yanked
+});
+assert_same_fields!(common::dependency_manager::DeprecationRequest, DeprecationRequest, {
+    dependency_name, dependency_version, deprecation
+});
+assert_same_fields!(
+    common::dependency_manager::RegisterRequest,
+    RegisterRequest,
+    { username }
+);
+assert_same_fields!(common::dependency_manager::TokenCreateRequest, TokenCreateRequest, {
+    name, scopes
+});
+assert_same_fields!(common::dependency_manager::TokenReply, TokenReply, {
+    username, token
+});
+assert_same_fields!(
+    common::dependency_manager::TokenRevokeRequest,
+    TokenRevokeRequest,
+    { name }
+);
+assert_same_fields!(common::dependency_manager::TokenInformation, TokenInformation, {
+    name, scopes, date_created
+});
+assert_same_fields!(common::dependency_manager::OwnerRequest, OwnerRequest, {
+    package_name, username
+});
+assert_same_fields!(
+    common::dependency_manager::PackageRequest,
+    PackageRequest,
+    { package_name }
+);
+assert_same_fields!(common::dependency_manager::PackageOwners, PackageOwners, {
+    package_name, owners
+});
+assert_same_fields!(common::dependency_manager::ClaimRequest, ClaimRequest, {
+    package_name, secret
+});
+assert_same_fields!(common::dependency_manager::SearchRequest, SearchRequest, {
+    query, page
+});
+assert_same_fields!(common::dependency_manager::PageRequest, PageRequest, {
+    page
+});
+assert_same_fields!(
+    common::dependency_manager::VersionListRequest,
+    VersionListRequest,
+    { package_name }
+);
+assert_same_fields!(common::dependency_manager::PackageSummary, PackageSummary, { package_name, author, latest_version, date_updated, deprecation, description, license, repository, keywords });
+assert_same_fields!(common::dependency_manager::SearchResults, SearchResults, {
+    page, page_count
+});
+assert_same_fields!(
+    common::dependency_manager::PublishedVersion,
+    PublishedVersion,
+    { version, date_added, yanked, deprecation }
+);
+assert_same_fields!(
+    common::dependency_manager::VersionListing,
+    VersionListing,
+    { package_name }
+);
+assert_same_fields!(common::dependency_manager::RecentlyPublished, RecentlyPublished, { package_name, version, author, description, date_added, yanked, deprecation });
+assert_same_fields!(
+    common::dependency_manager::RecentlyPublishedPage,
+    RecentlyPublishedPage,
+    { page, page_count }
+);
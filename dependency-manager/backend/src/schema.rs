@@ -1,12 +1,73 @@
 // @generated automatically by Diesel CLI.
 
 diesel::table! {
-    dependencies (dependency_name) {
-        dependency_name -> Text,
-        dependency_source_path -> Text,
-        dependency_version -> Text,
+    api_tokens (token_hash) {
+        token_hash -> Text,
+        username -> Text,
+        name -> Text,
+        scopes -> Array<Text>,
+        date_created -> Date,
+    }
+}
+
+diesel::table! {
+    owner_invitations (package_name, username) {
+        package_name -> Text,
+        username -> Text,
+        invited_by -> Text,
+        date_created -> Date,
+    }
+}
+
+diesel::table! {
+    package_owners (package_name, username) {
+        package_name -> Text,
+        username -> Text,
+    }
+}
+
+diesel::table! {
+    packages (package_name) {
+        package_name -> Text,
         author -> Text,
+        date_created -> Date,
+        legacy_secret -> Nullable<Text>,
+        description -> Nullable<Text>,
+        license -> Nullable<Text>,
+        repository -> Nullable<Text>,
+        keywords -> Array<Text>,
+    }
+}
+
+diesel::table! {
+    users (username) {
+        username -> Text,
+        date_created -> Date,
+    }
+}
+
+diesel::table! {
+    versions (package_name, version) {
+        package_name -> Text,
+        version -> Text,
+        source_path -> Text,
         date_added -> Date,
-        secret -> Text,
+        yanked -> Bool,
+        deprecation -> Nullable<Text>,
     }
 }
+
+diesel::joinable!(api_tokens -> users (username));
+diesel::joinable!(owner_invitations -> packages (package_name));
+diesel::joinable!(package_owners -> packages (package_name));
+diesel::joinable!(package_owners -> users (username));
+diesel::joinable!(versions -> packages (package_name));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    api_tokens,
+    owner_invitations,
+    package_owners,
+    packages,
+    users,
+    versions,
+);
@@ -0,0 +1,250 @@
+use std::{env, path::PathBuf};
+
+use backend::{
+    api::auth::{AuthenticatedUser, is_owner, redact_credentials},
+    establish_state,
+    models::{ApiToken, Package, PackageOwner, User},
+    schema::{api_tokens, package_owners, packages, users},
+};
+use common::{
+    axum::{
+        extract::FromRequestParts,
+        http::{HeaderMap, HeaderValue, Request, header, request::Parts},
+    },
+    chrono::Utc,
+    dependency_manager::{ServerState, TokenScope, hash_secret},
+    error::dependency_manager::DependencyManagerError,
+};
+use diesel::{
+    ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+    r2d2::{ConnectionManager, Pool},
+};
+
+const OWNER: &str = "auth-test-owner";
+const OTHER_USER: &str = "auth-test-other";
+const PACKAGE: &str = "auth-test-package";
+const TOKEN: &str = "auth-test-token";
+
+/// Returns the parts of a request sent with the `Authorization` header.
+fn request_parts(authorization: Option<&str>) -> Parts
+{
+    let mut request = Request::builder();
+
+    if let Some(authorization) = authorization {
+        request = request.header(header::AUTHORIZATION, authorization);
+    }
+
+    request.body(()).unwrap().into_parts().0
+}
+
+/// A state whose database is never connected to, for the requests which are rejected before the token is looked up.
+fn unconnected_state() -> ServerState
+{
+    ServerState {
+        db_connection: Pool::builder().build_unchecked(ConnectionManager::<PgConnection>::new(
+            "postgres://[::1]:0/none",
+        )),
+        deps_path: PathBuf::from(env!("CARGO_TARGET_TMPDIR")),
+        base64_engine: common::base64::engine::general_purpose::STANDARD,
+    }
+}
+
+/// Connects to the database set in `TEST_DATABASE_URL`, which must have been migrated with `diesel migration run`.
+/// The test using the database is skipped if it is not set. The rows of the test are recreated on every run, so only one test may use the database.
+fn test_state() -> Option<ServerState>
+{
+    let Ok(database_url) = env::var("TEST_DATABASE_URL")
+    else {
+        eprintln!("`TEST_DATABASE_URL` is not set, skipping the test.");
+
+        return None;
+    };
+
+    Some(establish_state(&database_url, PathBuf::from(env!("CARGO_TARGET_TMPDIR"))).unwrap())
+}
+
+/// Creates the owner of the package with a token which can only publish, and a second user who does not own the package.
+fn insert_accounts(pg_connection: &mut PgConnection)
+{
+    diesel::delete(package_owners::table.filter(package_owners::package_name.eq(PACKAGE)))
+        .execute(pg_connection)
+        .unwrap();
+    diesel::delete(packages::table.filter(packages::package_name.eq(PACKAGE)))
+        .execute(pg_connection)
+        .unwrap();
+    diesel::delete(api_tokens::table.filter(api_tokens::username.eq_any([OWNER, OTHER_USER])))
+        .execute(pg_connection)
+        .unwrap();
+    diesel::delete(users::table.filter(users::username.eq_any([OWNER, OTHER_USER])))
+        .execute(pg_connection)
+        .unwrap();
+
+    let today = Utc::now().date_naive();
+
+    diesel::insert_into(users::table)
+        .values(
+            [OWNER, OTHER_USER]
+                .map(|username| {
+                    User {
+                        username: username.to_string(),
+                        date_created: today,
+                    }
+                })
+                .to_vec(),
+        )
+        .execute(pg_connection)
+        .unwrap();
+
+    diesel::insert_into(api_tokens::table)
+        .values(ApiToken {
+            token_hash: hash_secret(TOKEN),
+            username: OWNER.to_string(),
+            name: "publishing".to_string(),
+            scopes: vec![TokenScope::Publish.to_string(), "unknown".to_string()],
+            date_created: today,
+        })
+        .execute(pg_connection)
+        .unwrap();
+
+    diesel::insert_into(packages::table)
+        .values(Package {
+            package_name: PACKAGE.to_string(),
+            author: OWNER.to_string(),
+            date_created: today,
+            legacy_secret: None,
+            description: None,
+            license: None,
+            repository: None,
+            keywords: Vec::new(),
+        })
+        .execute(pg_connection)
+        .unwrap();
+
+    diesel::insert_into(package_owners::table)
+        .values(PackageOwner {
+            package_name: PACKAGE.to_string(),
+            username: OWNER.to_string(),
+        })
+        .execute(pg_connection)
+        .unwrap();
+}
+
+#[test]
+fn requires_the_scopes_of_the_token()
+{
+    let user = AuthenticatedUser {
+        username: OWNER.to_string(),
+        scopes: vec![TokenScope::Publish, TokenScope::Yank],
+    };
+
+    user.require_scope(TokenScope::Publish).unwrap();
+    user.require_scope(TokenScope::Yank).unwrap();
+
+    assert!(matches!(
+        user.require_scope(TokenScope::Manage),
+        Err(DependencyManagerError::MissingScope(TokenScope::Manage))
+    ));
+
+    let user = AuthenticatedUser {
+        username: OWNER.to_string(),
+        scopes: Vec::new(),
+    };
+
+    for scope in TokenScope::ALL {
+        assert!(user.require_scope(scope).is_err());
+    }
+}
+
+#[tokio::test]
+async fn rejects_requests_without_a_bearer_token()
+{
+    let state = unconnected_state();
+
+    for authorization in [None, Some("Basic dXNlcjpwYXNz"), Some("bearer token")] {
+        let result =
+            AuthenticatedUser::from_request_parts(&mut request_parts(authorization), &state).await;
+
+        assert!(
+            matches!(result, Err(DependencyManagerError::MissingToken)),
+            "{authorization:?}"
+        );
+    }
+}
+
+#[test]
+fn redacts_tokens_from_logged_headers()
+{
+    let mut headers = HeaderMap::new();
+
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_static("Bearer secret-token"),
+    );
+    headers.insert(header::USER_AGENT, HeaderValue::from_static("fog"));
+
+    let redacted = redact_credentials(&headers);
+
+    assert!(!format!("{redacted:?}").contains("secret-token"));
+    assert_eq!(redacted[header::USER_AGENT], "fog");
+
+    // Requests without credentials are logged as they are
+    assert_eq!(redact_credentials(&HeaderMap::new()), HeaderMap::new());
+}
+
+#[tokio::test]
+async fn authenticates_users_and_checks_their_ownership()
+{
+    let Some(state) = test_state()
+    else {
+        return;
+    };
+
+    insert_accounts(&mut state.db_connection.get().unwrap());
+
+    // The surrounding whitespace of the token is ignored, and unknown scopes are dropped
+    let user = AuthenticatedUser::from_request_parts(
+        &mut request_parts(Some(&format!("Bearer {TOKEN} "))),
+        &state,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(user.username, OWNER);
+    assert_eq!(user.scopes, [TokenScope::Publish]);
+
+    let result = AuthenticatedUser::from_request_parts(
+        &mut request_parts(Some("Bearer revoked-token")),
+        &state,
+    )
+    .await;
+
+    assert!(matches!(result, Err(DependencyManagerError::InvalidToken)));
+
+    // Only the owners of the package pass the ownership check
+    let mut pg_connection = state.db_connection.get().unwrap();
+
+    assert!(is_owner(&mut pg_connection, PACKAGE, OWNER).unwrap());
+    assert!(!is_owner(&mut pg_connection, PACKAGE, OTHER_USER).unwrap());
+
+    let owner = AuthenticatedUser {
+        username: OWNER.to_string(),
+        scopes: TokenScope::ALL.to_vec(),
+    };
+    let other_user = AuthenticatedUser {
+        username: OTHER_USER.to_string(),
+        scopes: TokenScope::ALL.to_vec(),
+    };
+
+    owner.require_owner(&mut pg_connection, PACKAGE).unwrap();
+
+    assert!(matches!(
+        other_user.require_owner(&mut pg_connection, PACKAGE),
+        Err(DependencyManagerError::NotAnOwner(package)) if package == PACKAGE
+    ));
+
+    // The ownership of a package which does not exist cannot be checked
+    assert!(matches!(
+        owner.require_owner(&mut pg_connection, "auth-test-missing"),
+        Err(DependencyManagerError::DependencyNotFound)
+    ));
+}
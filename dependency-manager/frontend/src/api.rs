@@ -0,0 +1,131 @@
+use serde::{Deserialize, de::DeserializeOwned};
+
+// ****
+// PLEASE NOTE THAT THESE TYPES ARE COPIES OF THE TYPES FOUND IN `common::dependency_manager`. (The frontend is compiled to WebAssembly, so it cannot depend on `common`.)
+// ANY TYPE DEFINITION CHANGES MUST BE COPIED TO THE FRONTEND'S DEFINITIONS
+// ****
+
+/// The address of the dependency manager's backend.
+pub const REGISTRY_URL: &str = "http://[::1]:3004";
+
+pub mod urls
+{
+    pub const SEARCH: &str = "/api/search";
+    pub const PACKAGE_VERSIONS: &str = "/api/package_versions";
+    pub const RECENTLY_PUBLISHED: &str = "/api/recently_published";
+}
+
+/// A package found by a search, along with its latest version.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PackageSummary
+{
+    pub package_name: String,
+    pub author: String,
+    /// The newest version which has not been yanked.
+    pub latest_version: String,
+    /// The date the latest version was published, formatted as `YYYY-MM-DD`.
+    pub date_updated: String,
+    /// The reason the latest version has been deprecated.
+    pub deprecation: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SearchResults
+{
+    /// The packages on the requested page, the best matches come first.
+    pub packages: Vec<PackageSummary>,
+    pub page: i64,
+    pub page_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PublishedVersion
+{
+    pub version: String,
+    /// Formatted as `YYYY-MM-DD`.
+    pub date_added: String,
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VersionListing
+{
+    pub package_name: String,
+    /// The published versions of the package, the newest version comes first.
+    pub versions: Vec<PublishedVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RecentlyPublished
+{
+    pub package_name: String,
+    pub version: String,
+    pub author: String,
+    pub description: Option<String>,
+    /// Formatted as `YYYY-MM-DD`.
+    pub date_added: String,
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RecentlyPublishedPage
+{
+    /// The published versions on the requested page, the most recent version comes first.
+    pub versions: Vec<RecentlyPublished>,
+    pub page: i64,
+    pub page_count: i64,
+}
+
+/// Sends a GET request to an endpoint of the registry, the error contains the reason the request failed.
+async fn get<T: DeserializeOwned>(url: &str, query: &[(&str, String)]) -> Result<T, String>
+{
+    let response = reqwest::Client::new()
+        .get(format!("{REGISTRY_URL}{url}"))
+        .query(query)
+        .send()
+        .await
+        .map_err(|err| format!("Could not reach the registry: {err}"))?;
+
+    // The backend replies with the error message if the request has failed
+    if !response.status().is_success() {
+        return Err(response
+            .text()
+            .await
+            .unwrap_or_else(|_| "The registry could not process the request".to_string()));
+    }
+
+    response
+        .json::<T>()
+        .await
+        .map_err(|err| format!("Invalid reply from the registry: {err}"))
+}
+
+/// Searches the packages by their name, description and keywords.
+pub async fn search_packages(query: String, page: i64) -> Result<SearchResults, String>
+{
+    get(
+        urls::SEARCH,
+        &[("query", query), ("page", page.to_string())],
+    )
+    .await
+}
+
+/// Lists every published version of a package.
+pub async fn list_package_versions(package_name: String) -> Result<VersionListing, String>
+{
+    get(urls::PACKAGE_VERSIONS, &[("package_name", package_name)]).await
+}
+
+/// Lists the versions published to the registry, the most recently published version comes first.
+pub async fn recently_published(page: i64) -> Result<RecentlyPublishedPage, String>
+{
+    get(urls::RECENTLY_PUBLISHED, &[("page", page.to_string())]).await
+}
@@ -1,7 +1,41 @@
 use dioxus::prelude::*;
 
+use crate::api::{PackageSummary, list_package_versions, recently_published, search_packages};
+
 pub fn root_ui(css: Asset) -> Element
 {
+    // The text typed into the search field, the search is only sent once it is submitted
+    let mut search_text = use_signal(String::new);
+    let mut search_query = use_signal(|| None::<String>);
+    let mut search_page = use_signal(|| 0);
+    let mut selected_package = use_signal(|| None::<String>);
+
+    let search_results = use_resource(move || {
+        async move {
+            match search_query() {
+                Some(query) => Some(search_packages(query, search_page()).await),
+                None => None,
+            }
+        }
+    });
+
+    let version_listing = use_resource(move || {
+        async move {
+            match selected_package() {
+                Some(package_name) => Some(list_package_versions(package_name).await),
+                None => None,
+            }
+        }
+    });
+
+    let latest_activity = use_resource(move || async move { recently_published(0).await });
+
+    let mut submit_search = move || {
+        search_page.set(0);
+        selected_package.set(None);
+        search_query.set(Some(search_text()));
+    };
+
     rsx! {
         document::Stylesheet { href: css }
 
@@ -24,8 +58,18 @@ pub fn root_ui(css: Asset) -> Element
                         id: "search_field",
                         {
                             rsx! {
-                                input { id: "search_text", placeholder: "Enter text", {  } }
-                                button { id: "search_icon", { "Search" } }
+                                input {
+                                    id: "search_text",
+                                    placeholder: "Search packages by name, description or keyword",
+                                    value: "{search_text}",
+                                    oninput: move |event| search_text.set(event.value()),
+                                    onkeydown: move |event| {
+                                        if event.key() == Key::Enter {
+                                            submit_search();
+                                        }
+                                    },
+                                }
+                                button { id: "search_icon", onclick: move |_| submit_search(), { "Search" } }
                             }
                         }
                     }
@@ -33,12 +77,94 @@ pub fn root_ui(css: Asset) -> Element
             }
         }
 
+        if let Some(search_result) = &*search_results.read() {
+            div {
+                id: "search_results",
+                match search_result {
+                    Some(Ok(results)) if results.packages.is_empty() => rsx! {
+                        p { class: "search_message", "No packages matched the search." }
+                    },
+                    Some(Ok(results)) => rsx! {
+                        for package in results.packages.iter().cloned() {
+                            {package_card(package, selected_package)}
+                        }
+
+                        if results.page_count > 1 {
+                            div {
+                                id: "search_pages",
+                                button {
+                                    disabled: results.page == 0,
+                                    onclick: move |_| search_page -= 1,
+                                    "Previous"
+                                }
+                                p { "Page {results.page + 1} of {results.page_count}" }
+                                button {
+                                    disabled: results.page + 1 >= results.page_count,
+                                    onclick: move |_| search_page += 1,
+                                    "Next"
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(error)) => rsx! {
+                        p { class: "search_message", "{error}" }
+                    },
+                    None => rsx! {},
+                }
+            }
+        }
+
+        if let Some(Some(listing)) = &*version_listing.read() {
+            div {
+                id: "package_versions",
+                match listing {
+                    Ok(listing) => rsx! {
+                        h2 { "Versions of {listing.package_name}" }
+                        table {
+                            class: "activity_table",
+                            for published_version in listing.versions.iter() {
+                                tr {
+                                    td { "{published_version.version}" }
+                                    td { "{published_version.date_added}" }
+                                    td { {version_status(published_version.yanked, published_version.deprecation.clone())} }
+                                }
+                            }
+                        }
+                    },
+                    Err(error) => rsx! {
+                        p { class: "search_message", "{error}" }
+                    },
+                }
+            }
+        }
+
         div {
             id: "latest_activity",
             {
                 rsx! {
                     h2 { id: "latest_act_title", {"Latest activity"} },
-                    table { id: "latest_act_tbl" }
+                    table {
+                        id: "latest_act_tbl",
+                        class: "activity_table",
+                        match &*latest_activity.read() {
+                            Some(Ok(page)) => rsx! {
+                                for published in page.versions.iter() {
+                                    tr {
+                                        td { "{published.package_name}" }
+                                        td { "{published.version}" }
+                                        td { {version_status(published.yanked, published.deprecation.clone())} }
+                                        td { "{published.author}" }
+                                        td { {published.description.clone().unwrap_or_default()} }
+                                        td { "{published.date_added}" }
+                                    }
+                                }
+                            },
+                            Some(Err(error)) => rsx! {
+                                tr { td { "{error}" } }
+                            },
+                            None => rsx! {},
+                        }
+                    }
                 }
             }
         }
@@ -54,3 +180,56 @@ pub fn root_ui(css: Asset) -> Element
         }
     }
 }
+
+/// Renders a package found by the search, clicking on it lists the versions of the package.
+fn package_card(package: PackageSummary, mut selected_package: Signal<Option<String>>) -> Element
+{
+    let package_name = package.package_name.clone();
+
+    rsx! {
+        div {
+            class: "package_card",
+            onclick: move |_| selected_package.set(Some(package_name.clone())),
+            div {
+                class: "package_header",
+                h3 { "{package.package_name}" }
+                p { class: "package_version", "{package.latest_version}" }
+                {version_status(false, package.deprecation.clone())}
+            }
+            if let Some(description) = &package.description {
+                p { class: "package_description", "{description}" }
+            }
+            div {
+                class: "package_details",
+                p { "By {package.author}, updated {package.date_updated}" }
+                if let Some(license) = &package.license {
+                    p { "{license}" }
+                }
+                if let Some(repository) = &package.repository {
+                    a { href: "{repository}", onclick: move |event| event.stop_propagation(), "Repository" }
+                }
+            }
+            if !package.keywords.is_empty() {
+                div {
+                    class: "package_keywords",
+                    for keyword in package.keywords.iter() {
+                        span { class: "package_keyword", "{keyword}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Renders the badges of a yanked or deprecated version, the reason of the deprecation is shown when the badge is hovered.
+fn version_status(yanked: bool, deprecation: Option<String>) -> Element
+{
+    rsx! {
+        if yanked {
+            span { class: "version_badge yanked", "yanked" }
+        }
+        if let Some(deprecation) = deprecation {
+            span { class: "version_badge deprecated", title: "{deprecation}", "deprecated" }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use frontend::api::{
+    PackageSummary, PublishedVersion, RecentlyPublished, RecentlyPublishedPage, SearchResults,
+    VersionListing, urls,
+};
+
+/// The frontend's copies of the endpoints must match the routes of the backend.
+#[test]
+fn endpoints_match_the_backend_routes()
+{
+    assert_eq!(urls::SEARCH, "/api/search");
+    assert_eq!(urls::PACKAGE_VERSIONS, "/api/package_versions");
+    assert_eq!(urls::RECENTLY_PUBLISHED, "/api/recently_published");
+}
+
+#[test]
+fn parses_search_results()
+{
+    let reply = r#"{
+        "packages": [
+            {
+                "package_name": "math",
+                "author": "fog",
+                "latest_version": "1.4.0",
+                "date_updated": "2025-11-02",
+                "deprecation": null,
+                "description": "Vectors and matrices",
+                "license": "MIT",
+                "repository": null,
+                "keywords": ["linear-algebra", "vectors"]
+            },
+            {
+                "package_name": "mathematics",
+                "author": "someone",
+                "latest_version": "0.2.1",
+                "date_updated": "2024-01-15",
+                "deprecation": "Use `math` instead",
+                "description": null,
+                "license": null,
+                "repository": "https://example.com/mathematics",
+                "keywords": []
+            }
+        ],
+        "page": 1,
+        "page_count": 3
+    }"#;
+
+    let results = serde_json::from_str::<SearchResults>(reply).unwrap();
+
+    assert_eq!((results.page, results.page_count), (1, 3));
+    assert_eq!(
+        results.packages[0],
+        PackageSummary {
+            package_name: "math".to_string(),
+            author: "fog".to_string(),
+            latest_version: "1.4.0".to_string(),
+            date_updated: "2025-11-02".to_string(),
+            deprecation: None,
+            description: Some("Vectors and matrices".to_string()),
+            license: Some("MIT".to_string()),
+            repository: None,
+            keywords: vec!["linear-algebra".to_string(), "vectors".to_string()],
+        }
+    );
+    assert_eq!(
+        results.packages[1].deprecation.as_deref(),
+        Some("Use `math` instead")
+    );
+
+    // A search without matches
+    let results =
+        serde_json::from_str::<SearchResults>(r#"{"packages": [], "page": 0, "page_count": 0}"#)
+            .unwrap();
+
+    assert!(results.packages.is_empty());
+}
+
+#[test]
+fn parses_version_listings()
+{
+    let reply = r#"{
+        "package_name": "math",
+        "versions": [
+            {"version": "1.4.0", "date_added": "2025-11-02", "yanked": false, "deprecation": null},
+            {"version": "1.3.0", "date_added": "2025-06-20", "yanked": true, "deprecation": "Miscompiles `dot`"}
+        ]
+    }"#;
+
+    let listing = serde_json::from_str::<VersionListing>(reply).unwrap();
+
+    assert_eq!(listing.package_name, "math");
+    assert_eq!(
+        listing.versions,
+        [
+            PublishedVersion {
+                version: "1.4.0".to_string(),
+                date_added: "2025-11-02".to_string(),
+                yanked: false,
+                deprecation: None,
+            },
+            PublishedVersion {
+                version: "1.3.0".to_string(),
+                date_added: "2025-06-20".to_string(),
+                yanked: true,
+                deprecation: Some("Miscompiles `dot`".to_string()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn parses_recently_published_versions()
+{
+    let reply = r#"{
+        "versions": [
+            {
+                "package_name": "geometry",
+                "version": "0.3.1",
+                "author": "fog",
+                "description": null,
+                "date_added": "2025-11-03",
+                "yanked": false,
+                "deprecation": null
+            }
+        ],
+        "page": 0,
+        "page_count": 1
+    }"#;
+
+    assert_eq!(
+        serde_json::from_str::<RecentlyPublishedPage>(reply).unwrap(),
+        RecentlyPublishedPage {
+            versions: vec![RecentlyPublished {
+                package_name: "geometry".to_string(),
+                version: "0.3.1".to_string(),
+                author: "fog".to_string(),
+                description: None,
+                date_added: "2025-11-03".to_string(),
+                yanked: false,
+                deprecation: None,
+            }],
+            page: 0,
+            page_count: 1,
+        }
+    );
+
+    // Replies missing a field are refused instead of being shown partially
+    assert!(
+        serde_json::from_str::<RecentlyPublishedPage>(r#"{"versions": [], "page": 0}"#).is_err()
+    );
+}
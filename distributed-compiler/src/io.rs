@@ -1,7 +1,7 @@
 use std::{
     collections::HashMap,
     fs::create_dir_all,
-    io::Cursor,
+    io::{Cursor, Read, Seek},
     net::{Ipv6Addr, SocketAddr},
     path::PathBuf,
     sync::Arc,
@@ -16,7 +16,8 @@ use common::{
     dependency::construct_dependency_path,
     dependency_manager::{Dependency, DependencyInformation},
     distributed_compiler::{CompileJob, DependencyRequest, FinishedJob},
-    error::dependency_manager::DependencyManagerError,
+    error::{dependency::DependencyError, dependency_manager::DependencyManagerError},
+    lockfile::checksum_archive,
     reqwest::Client,
     rmp_serde, serde_json,
     tokio::{
@@ -25,6 +26,7 @@ use common::{
         sync::mpsc::{Sender, channel},
     },
     ty::OrdSet,
+    zip::ZipArchive,
 };
 use dashmap::DashMap;
 
@@ -270,13 +272,25 @@ impl ServerState
                                                             dependency_information =
                                                                 Some(dependency.info.clone());
 
-                                                            // Write dependency to folder
-                                                            if let Err(err) = write_zip_to_fs_async(
-                                                                dep_path.clone(),
+                                                            let mut source_archive =
                                                                 unzip_from_bytes(Cursor::new(
                                                                     dependency.source,
                                                                 ))
-                                                                .unwrap(),
+                                                                .unwrap();
+
+                                                            // The sources are only written if they match the checksum locked by the client
+                                                            if let Err(err) = verify_source_checksum(
+                                                                &request,
+                                                                &mut source_archive,
+                                                            ) {
+                                                                ui_sender_out_clone.send((format!("Refusing to compile dependency `{}({})`: {err}", request.name.clone(), request.version.clone()), thread_id)).unwrap();
+                                                                break;
+                                                            }
+
+                                                            // Write dependency to folder
+                                                            if let Err(err) = write_zip_to_fs_async(
+                                                                dep_path.clone(),
+                                                                source_archive,
                                                             )
                                                             .await
                                                             {
@@ -398,3 +412,25 @@ impl ServerState
         Ok(())
     }
 }
+
+/// Checks that the sources downloaded from the registry match the checksum the client has locked the dependency with.
+/// Dependencies which have not been locked with a checksum yet are accepted as they are.
+fn verify_source_checksum<T: Read + Seek>(
+    request: &DependencyRequest,
+    source_archive: &mut ZipArchive<T>,
+) -> Result<(), DependencyError>
+{
+    if let Some(locked_checksum) = &request.source_checksum {
+        let checksum = checksum_archive(source_archive)?;
+
+        if &checksum != locked_checksum {
+            return Err(DependencyError::ChecksumMismatch(
+                request.name.clone(),
+                locked_checksum.clone(),
+                checksum,
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     fs::{self, create_dir_all},
     net::SocketAddr,
+    num::NonZeroUsize,
     path::PathBuf,
     sync::Arc,
 };
@@ -9,12 +10,14 @@ use std::{
 use crate::io::ServerState;
 use common::{
     anyhow,
-    compiler::ProjectConfig,
+    artifact::{ArtifactPaths, deps_dir, main_source_path, source_dir},
+    compiler::{FeatureSelection, Profile, ProjectConfig},
     compression::zip_folder,
     crossbeam::{channel::Sender, deque},
     distributed_compiler::{CompileJob, FinishedJob},
     error::codegen::CodeGenError,
     linker::BuildManifest,
+    lockfile::checksum_directory,
     tokio, toml,
 };
 use compiler::CompilerState;
@@ -92,7 +95,12 @@ impl ServerState
                     // Fetch the latest job from the job queue, if we couldnt that means we were notified too early.
                     if let Some(job) = job_queue.in_progress.steal().success() {
                         match compile_job(job.clone(), ui_sender.clone(), thread_id) {
-                            Ok((path_to_output_artifacts, project_config, build_manifest)) => {
+                            Ok((
+                                path_to_output_artifacts,
+                                project_config,
+                                build_manifest,
+                                source_checksum,
+                            )) => {
                                 let zipped_artifacts = zip_folder(
                                     fs::read_dir(path_to_output_artifacts).unwrap(),
                                     None,
@@ -111,6 +119,7 @@ impl ServerState
                                                 artifacts_zip_bytes: zip,
                                                 dependency_config: project_config,
                                                 build_manifest,
+                                                source_checksum,
                                             })
                                             .unwrap();
                                     },
@@ -148,13 +157,18 @@ impl ServerState
     }
 }
 
+/// Compiles the dependency of the job, returns the path of its artifacts, its config, its build manifest and the checksum of its sources.
 fn compile_job(
     job: CompileJob,
     ui_sender: Sender<(String, ThreadIdentification)>,
     thread_id: ThreadIdentification,
-) -> anyhow::Result<(PathBuf, ProjectConfig, BuildManifest)>
+) -> anyhow::Result<(PathBuf, ProjectConfig, BuildManifest, String)>
 {
-    let compiler_state = CompilerState::new(job.depdendency_path.clone(), job.features).unwrap();
+    let compiler_state = CompilerState::new(
+        job.depdendency_path.clone(),
+        &FeatureSelection::with_defaults(job.features.iter().cloned()),
+    )
+    .unwrap();
 
     // Send message that we have received a job
     ui_sender
@@ -168,51 +182,52 @@ fn compile_job(
         ))
         .unwrap();
 
-    let source_file =
-        fs::read_to_string(format!("{}\\src\\main.f", job.depdendency_path.display()))
-            .map_err(|_| CodeGenError::NoMain)?;
+    // The build folder and the dependencies folder are not part of the sources, so the checksum matches the archive downloaded from the registry
+    let source_checksum = checksum_directory(
+        &job.depdendency_path,
+        &[compiler_state.config.build_path.as_str(), "deps"],
+    )?;
 
-    let build_arctifacts_path = format!(
-        "{}\\{}",
-        job.depdendency_path.display(),
-        compiler_state.config.build_path
-    );
+    let source_file = fs::read_to_string(main_source_path(&job.depdendency_path))
+        .map_err(|_| CodeGenError::NoMain)?;
 
-    let build_artifact_name = format!(
-        "{}\\{}",
-        build_arctifacts_path,
-        compiler_state.config.name.clone()
+    // The artifacts are named after the target the job was requested for, not the worker's host
+    let artifact_paths = ArtifactPaths::new(
+        &job.depdendency_path,
+        &compiler_state.config.build_path,
+        &compiler_state.config.name,
+        &job.target_triple,
     );
 
-    let _ = create_dir_all(&build_arctifacts_path);
-    let _ = create_dir_all(PathBuf::from(format!(
-        "{}\\deps",
-        compiler_state.root_dir.display()
-    )));
-
-    let target_ir_path = PathBuf::from(format!("{build_artifact_name}.ll"));
-
-    let target_o_path = PathBuf::from(format!("{build_artifact_name}.obj"));
-
-    let build_path = PathBuf::from(format!("{build_artifact_name}.exe"));
-
-    let build_manifest_path = PathBuf::from(format!("{build_artifact_name}.manifest"));
-
-    let build_manifest = compiler_state.compilation_process(
-        &source_file,
-        target_ir_path,
-        target_o_path,
-        build_path,
-        true,
-        true,
-        &format!("{}\\src", job.depdendency_path.display()),
-        &job.flags_passed_in,
-        Some(job.target_triple),
-        job.cpu_name,
-        job.cpu_features,
-    )?;
-
-    fs::write(build_manifest_path, toml::to_string(&build_manifest)?)?;
+    let _ = create_dir_all(artifact_paths.build_dir());
+    let _ = create_dir_all(deps_dir(&compiler_state.root_dir));
+
+    // Dependencies are always compiled with the `release` profile
+    let optimization = Profile::release().optimization_settings();
+
+    // Object files are always produced if no artifacts are requested, so there is always a manifest
+    let build_manifest = compiler_state
+        .compilation_process(
+            &source_file,
+            &artifact_paths,
+            &optimization,
+            true,
+            &source_dir(&job.depdendency_path).display().to_string(),
+            &job.flags_passed_in,
+            Some(job.target_triple),
+            job.cpu_name,
+            job.cpu_features,
+            // Every worker thread compiles its own job
+            NonZeroUsize::new(1),
+            &[],
+            false,
+        )?
+        .expect("Compiling into object files must produce a build manifest.");
+
+    // The artifacts are sent to the client, so the paths must be relative to the dependency's root
+    let build_manifest = build_manifest.localize_paths(&compiler_state.root_dir)?;
+
+    fs::write(artifact_paths.manifest(), toml::to_string(&build_manifest)?)?;
 
     ui_sender
         .send((
@@ -228,6 +243,7 @@ fn compile_job(
     Ok((
         job.depdendency_path,
         compiler_state.config,
-        build_manifest.localize_paths(compiler_state.root_dir),
+        build_manifest,
+        source_checksum,
     ))
 }
@@ -0,0 +1,104 @@
+use common::{
+    error::{Spanned, semantic::SemanticError},
+    parser::{
+        builtin::BuiltinFunction,
+        common::{Context, StatementVariant},
+    },
+};
+
+/// Checks that every built-in function is called with the number of arguments it takes.
+/// Calls to built-in functions are found in every function of every module, including the nested statements.
+pub fn check_builtin_calls(modules: &[Context]) -> Vec<SemanticError>
+{
+    let mut errors = Vec::new();
+
+    for module in modules {
+        for (_path, _name, definition) in module.functions.iter() {
+            check_statements(&definition.body, &mut errors);
+        }
+    }
+
+    errors
+}
+
+fn check_statements(statements: &[Spanned<StatementVariant>], errors: &mut Vec<SemanticError>)
+{
+    for statement in statements {
+        check_statement(&statement.inner, errors);
+    }
+}
+
+fn check_statement(statement: &StatementVariant, errors: &mut Vec<SemanticError>)
+{
+    match statement {
+        StatementVariant::FunctionCall {
+            identifier,
+            arguments,
+        } => {
+            if let StatementVariant::BasicReference { variable_name } = &identifier.inner
+                && let Some(builtin) = BuiltinFunction::from_name(variable_name)
+                && builtin.argument_count() != arguments.len()
+            {
+                errors.push(SemanticError::InvalidBuiltinCall(
+                    builtin.to_string(),
+                    builtin.argument_count(),
+                    arguments.len(),
+                ));
+            }
+
+            check_statement(&identifier.inner, errors);
+
+            for argument in arguments.values() {
+                check_statement(&argument.inner, errors);
+            }
+        },
+        StatementVariant::NewVariable { variable_value, .. } => {
+            check_statement(&variable_value.inner, errors)
+        },
+        StatementVariant::ArrayReference {
+            variable_reference,
+            index,
+        } => {
+            check_statement(&variable_reference.inner, errors);
+            check_statement(&index.inner, errors);
+        },
+        StatementVariant::StructFieldReference {
+            variable_reference, ..
+        } => check_statement(&variable_reference.inner, errors),
+        StatementVariant::TypeCast(value, _)
+        | StatementVariant::NegateValue(value)
+        | StatementVariant::GetPointerTo(value)
+        | StatementVariant::DerefPointer(value)
+        | StatementVariant::ReturnValue { value } => check_statement(&value.inner, errors),
+        StatementVariant::MathematicalExpression { lhs, rhs, .. }
+        | StatementVariant::Comparison(lhs, _, rhs, _) => {
+            check_statement(&lhs.inner, errors);
+            check_statement(&rhs.inner, errors);
+        },
+        StatementVariant::SetValue { receiver, value }
+        | StatementVariant::ModifyValueArithmetic {
+            receiver, value, ..
+        } => {
+            check_statement(&receiver.inner, errors);
+            check_statement(&value.inner, errors);
+        },
+        StatementVariant::If(conditional) => {
+            check_statement(&conditional.condition.inner, errors);
+            check_statements(&conditional.true_branch, errors);
+            check_statements(&conditional.false_branch, errors);
+        },
+        StatementVariant::CodeBlock(statements) => {
+            for statement in statements {
+                check_statement(statement, errors);
+            }
+        },
+        StatementVariant::Brackets(statements, _)
+        | StatementVariant::Loop(statements)
+        | StatementVariant::ArrayInitialization { values: statements } => {
+            check_statements(statements, errors)
+        },
+        StatementVariant::BasicReference { .. }
+        | StatementVariant::Value(_)
+        | StatementVariant::ControlFlow(_) => {},
+    }
+}
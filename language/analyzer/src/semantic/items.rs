@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use common::{
+    error::semantic::SemanticError,
+    parser::{
+        builtin::BuiltinFunction,
+        common::{Context, ItemVisibility},
+    },
+    ty::Type,
+};
+
+/// Checks the items of every module of a project, the main source file must be the first module.
+/// The `main` function is only required if `requires_main` is set, libraries and test harnesses do not have one.
+/// Every error found is returned, so that all of them can be reported at once.
+pub fn check_items(modules: &[Context], requires_main: bool) -> Vec<SemanticError>
+{
+    let mut errors = Vec::new();
+
+    if requires_main
+        && let Some(main_module) = modules.first()
+        && let Some(error) = check_entrypoint(main_module)
+    {
+        errors.push(error);
+    }
+
+    // The types of the modules share a single namespace
+    let mut defined_types: HashMap<String, String> = HashMap::new();
+
+    // The public functions and the tests of the modules are linked together, so their names must not collide
+    let mut defined_functions: HashMap<String, String> = HashMap::new();
+
+    for module in modules {
+        let module_name = module.path.join(".");
+
+        for (_path, name, _item) in module.items.iter() {
+            if let Some(defining_module) =
+                defined_types.insert(name.to_string(), module_name.clone())
+            {
+                errors.push(SemanticError::DuplicateType(
+                    name.to_string(),
+                    defining_module,
+                    module_name.clone(),
+                ));
+            }
+        }
+
+        for (_path, name, definition) in module.functions.iter() {
+            if BuiltinFunction::from_name(name).is_some() {
+                errors.push(SemanticError::BuiltinRedefined(name.to_string()));
+            }
+
+            if definition.is_test()
+                && (definition.signature.return_type != Type::Void
+                    || !definition.signature.args.arguments.is_empty()
+                    || !definition.signature.args.generics.is_empty())
+            {
+                errors.push(SemanticError::InvalidTestSignature(name.to_string()));
+            }
+
+            if definition.visibility == ItemVisibility::Private && !definition.is_test() {
+                continue;
+            }
+
+            if let Some(defining_module) =
+                defined_functions.insert(name.to_string(), module_name.clone())
+            {
+                errors.push(SemanticError::DuplicateFunction(
+                    name.to_string(),
+                    defining_module,
+                    module_name.clone(),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Checks the `main` function of an executable, it must take no arguments and return `int`.
+fn check_entrypoint(main_module: &Context) -> Option<SemanticError>
+{
+    let Some((_path, _name, main)) = main_module
+        .functions
+        .iter()
+        .find(|(_path, name, _definition)| name.as_str() == "main")
+    else {
+        return Some(SemanticError::MissingMain);
+    };
+
+    if main.signature.return_type != Type::I32
+        || !main.signature.args.arguments.is_empty()
+        || main.signature.args.ellipsis_present
+    {
+        return Some(SemanticError::InvalidMain);
+    }
+
+    None
+}
@@ -1,2 +1,19 @@
+use common::{error::semantic::SemanticError, parser::common::Context};
+
+/// Checks the calls of the built-in functions.
+pub mod builtin;
+/// Checks the items defined by the modules of a project.
+pub mod items;
 /// Provides a way to resolve variables'/literals' types. (Most commonly used for converting literals to destination type.)
 pub mod type_inference;
+
+/// Runs every semantic check on the modules of a project, the main source file must be the first module.
+/// The `main` function is only required if `requires_main` is set.
+pub fn check_modules(modules: &[Context], requires_main: bool) -> Vec<SemanticError>
+{
+    let mut errors = items::check_items(modules, requires_main);
+
+    errors.extend(builtin::check_builtin_calls(modules));
+
+    errors
+}
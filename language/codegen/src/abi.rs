@@ -0,0 +1,915 @@
+use common::{
+    DEFAULT_COMPILER_ADDRESS_SPACE_SIZE,
+    anyhow::Result,
+    artifact::{ObjectFormat, TripleComponents},
+    codegen::{CustomItem, ty_to_llvm_ty},
+    error::codegen::CodeGenError,
+    indexmap::IndexMap,
+    inkwell::{
+        AddressSpace,
+        attributes::{Attribute, AttributeLoc},
+        builder::Builder,
+        context::Context,
+        module::Module,
+        targets::{TargetData, TargetMachine},
+        types::{AnyType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType},
+        values::{
+            AnyValue, BasicMetadataValueEnum, BasicValueEnum, CallSiteValue, FunctionValue,
+            PointerValue,
+        },
+    },
+    parser::function::FunctionSignature,
+    ty::Type,
+};
+use std::rc::Rc;
+
+/// The calling conventions the arguments and return values of C functions are lowered for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallingConvention
+{
+    /// The System V AMD64 calling convention, used by x86-64 Linux, BSDs and macOS.
+    SysV64,
+    /// The Microsoft x64 calling convention, used by x86-64 Windows.
+    Win64,
+    /// Any other target, only scalars and pointers can be passed to C functions.
+    Unsupported,
+}
+
+impl CallingConvention
+{
+    pub fn from_triple(target_triple: &str) -> Self
+    {
+        let triple = TripleComponents::parse(target_triple);
+
+        if !triple.is_x86_64() {
+            Self::Unsupported
+        }
+        else if triple.object_format() == ObjectFormat::Coff {
+            Self::Win64
+        }
+        else {
+            Self::SysV64
+        }
+    }
+}
+
+/// The information of the target which decides how values are passed to C functions.
+pub struct TargetAbi
+{
+    pub target_triple: String,
+    pub calling_convention: CallingConvention,
+    /// The layout of the types on the target, the classification of aggregates depends on it.
+    pub target_data: TargetData,
+}
+
+impl TargetAbi
+{
+    pub fn from_target_machine(target_machine: &TargetMachine) -> Self
+    {
+        let target_triple = target_machine
+            .get_triple()
+            .as_str()
+            .to_string_lossy()
+            .into_owned();
+
+        Self {
+            calling_convention: CallingConvention::from_triple(&target_triple),
+            target_triple,
+            target_data: target_machine.get_target_data(),
+        }
+    }
+
+    /// Classifies how a value of the type is passed, `registers` are the argument registers left on the target.
+    fn classify<'ctx>(
+        &self,
+        ctx: &'ctx Context,
+        ty: &Type,
+        llvm_ty: BasicTypeEnum<'ctx>,
+        is_return: bool,
+        registers: &mut Registers,
+    ) -> Result<ValueAbi<'ctx>>
+    {
+        let align = self.target_data.get_abi_alignment(&llvm_ty);
+
+        // Scalars and pointers are passed as they are, the backend assigns them to registers
+        if !matches!(
+            llvm_ty,
+            BasicTypeEnum::StructType(_) | BasicTypeEnum::ArrayType(_)
+        ) {
+            if !is_return {
+                registers.take_scalar(llvm_ty);
+            }
+
+            return Ok(ValueAbi {
+                ty: llvm_ty,
+                mode: PassMode::Direct(Extension::of(ty)),
+                align,
+                slot_ty: llvm_ty,
+            });
+        }
+
+        let size = self.target_data.get_abi_size(&llvm_ty);
+
+        let mode = match self.calling_convention {
+            CallingConvention::SysV64 => {
+                if size == 0 {
+                    PassMode::Ignore
+                }
+                // Aggregates larger than two eightbytes are passed in memory
+                else if size > 16 {
+                    PassMode::Indirect { byval: !is_return }
+                }
+                else {
+                    let pieces = self.sysv_eightbytes(ctx, ty, llvm_ty, size)?;
+
+                    // An aggregate is passed in memory if it does not fit into the registers left
+                    if is_return || registers.take_pieces(&pieces) {
+                        PassMode::Coerced(pieces)
+                    }
+                    else {
+                        PassMode::Indirect { byval: true }
+                    }
+                }
+            },
+            CallingConvention::Win64 => {
+                match size {
+                    0 => PassMode::Ignore,
+                    // Aggregates of the size of an integer are passed as that integer
+                    1 | 2 | 4 | 8 => PassMode::Coerced(vec![integer_piece(ctx, size)]),
+                    // Every other aggregate is passed as a pointer to a copy made by the caller
+                    _ => PassMode::Indirect { byval: false },
+                }
+            },
+            CallingConvention::Unsupported => {
+                return Err(CodeGenError::UnsupportedAbiTarget(self.target_triple.clone()).into());
+            },
+        };
+
+        // The pointer to the returned value is passed in the first integer register
+        if is_return && matches!(mode, PassMode::Indirect { .. }) {
+            registers.integer = registers.integer.saturating_sub(1);
+        }
+
+        // The coerced types may be larger than the aggregate, ie. a 12 byte struct is coerced to `{ <2 x float>, float }`
+        let (slot_ty, align) = match &mode {
+            PassMode::Coerced(pieces) => {
+                let coerced = coerced_type(ctx, pieces);
+
+                let slot_ty = if self.target_data.get_abi_size(&coerced) > size {
+                    coerced
+                }
+                else {
+                    llvm_ty
+                };
+
+                (
+                    slot_ty,
+                    align.max(self.target_data.get_abi_alignment(&coerced)),
+                )
+            },
+            _ => (llvm_ty, align),
+        };
+
+        Ok(ValueAbi {
+            ty: llvm_ty,
+            mode,
+            align,
+            slot_ty,
+        })
+    }
+
+    /// Returns the types an aggregate of at most 16 bytes is passed as with the System V calling convention.
+    /// Every eightbyte of the aggregate is passed in an integer register if it contains an integer or a pointer, and in a vector register otherwise.
+    fn sysv_eightbytes<'ctx>(
+        &self,
+        ctx: &'ctx Context,
+        ty: &Type,
+        llvm_ty: BasicTypeEnum<'ctx>,
+        size: u64,
+    ) -> Result<Vec<BasicTypeEnum<'ctx>>>
+    {
+        let mut scalars = Vec::new();
+
+        self.flatten(llvm_ty, 0, &mut scalars);
+
+        let mut pieces = Vec::new();
+
+        for eightbyte in 0..size.div_ceil(8) {
+            let start = eightbyte * 8;
+            let width = (size - start).min(8);
+
+            let contained = scalars
+                .iter()
+                .filter(|(offset, _scalar)| (start..start + 8).contains(offset))
+                .map(|(_offset, scalar)| *scalar)
+                .collect::<Vec<_>>();
+
+            // Eightbytes holding only padding are never produced by the layout of Fog types, they are passed as integers
+            let is_sse = !contained.is_empty()
+                && contained
+                    .iter()
+                    .all(|scalar| matches!(scalar, BasicTypeEnum::FloatType(_)));
+
+            if !is_sse {
+                pieces.push(integer_piece(ctx, width));
+
+                continue;
+            }
+
+            let float_ty = contained[0].into_float_type();
+            let float_size = self.target_data.get_abi_size(&float_ty);
+
+            // The floats of an eightbyte are passed together in one vector register, ie. `<2 x float>`
+            if contained.iter().any(|scalar| *scalar != contained[0]) {
+                return Err(CodeGenError::UnsupportedAbiType(ty.clone()).into());
+            }
+
+            if width == float_size {
+                pieces.push(float_ty.into());
+            }
+            else {
+                pieces.push(float_ty.vec_type((width / float_size) as u32).into());
+            }
+        }
+
+        Ok(pieces)
+    }
+
+    /// Collects the scalars of an aggregate with their offsets.
+    fn flatten<'ctx>(
+        &self,
+        ty: BasicTypeEnum<'ctx>,
+        offset: u64,
+        scalars: &mut Vec<(u64, BasicTypeEnum<'ctx>)>,
+    )
+    {
+        match ty {
+            BasicTypeEnum::StructType(struct_type) => {
+                for (idx, field) in struct_type.get_field_types().into_iter().enumerate() {
+                    // It is safe to unwrap here, since the index is in the bounds of the struct's fields
+                    let field_offset = self
+                        .target_data
+                        .offset_of_element(&struct_type, idx as u32)
+                        .unwrap();
+
+                    self.flatten(field, offset + field_offset, scalars);
+                }
+            },
+            BasicTypeEnum::ArrayType(array_type) => {
+                let element = array_type.get_element_type();
+                let element_size = self.target_data.get_abi_size(&element);
+
+                for idx in 0..array_type.len() as u64 {
+                    self.flatten(element, offset + idx * element_size, scalars);
+                }
+            },
+            scalar => scalars.push((offset, scalar)),
+        }
+    }
+}
+
+/// The argument registers which have not been assigned yet.
+struct Registers
+{
+    integer: u32,
+    sse: u32,
+}
+
+impl Registers
+{
+    fn new(calling_convention: CallingConvention) -> Self
+    {
+        match calling_convention {
+            CallingConvention::SysV64 => Self { integer: 6, sse: 8 },
+            // Only the System V calling convention decides how aggregates are passed by the registers left
+            CallingConvention::Win64 | CallingConvention::Unsupported => {
+                Self {
+                    integer: u32::MAX,
+                    sse: u32::MAX,
+                }
+            },
+        }
+    }
+
+    fn take_scalar(&mut self, ty: BasicTypeEnum<'_>)
+    {
+        if ty.is_float_type() || ty.is_vector_type() {
+            self.sse = self.sse.saturating_sub(1);
+        }
+        else {
+            self.integer = self.integer.saturating_sub(1);
+        }
+    }
+
+    /// Takes the registers needed by the pieces of an aggregate, returns `false` if there are not enough registers left.
+    fn take_pieces(&mut self, pieces: &[BasicTypeEnum<'_>]) -> bool
+    {
+        let integer = pieces.iter().filter(|piece| piece.is_int_type()).count() as u32;
+        let sse = pieces.len() as u32 - integer;
+
+        if integer > self.integer || sse > self.sse {
+            return false;
+        }
+
+        self.integer -= integer;
+        self.sse -= sse;
+
+        true
+    }
+}
+
+/// How small integers are extended to the size of a register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extension
+{
+    Zero,
+    Sign,
+}
+
+impl Extension
+{
+    fn of(ty: &Type) -> Option<Self>
+    {
+        match ty {
+            Type::U8 | Type::U16 | Type::Boolean => Some(Self::Zero),
+            Type::I16 => Some(Self::Sign),
+            Type::Enum((inner_type, _)) => Self::of(inner_type),
+            _ => None,
+        }
+    }
+
+    fn attribute_name(self) -> &'static str
+    {
+        match self {
+            Self::Zero => "zeroext",
+            Self::Sign => "signext",
+        }
+    }
+}
+
+/// How a value is passed to or returned from a C function.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PassMode<'ctx>
+{
+    /// The value is passed as its own type. Small integers are extended as the C compilers expect it.
+    Direct(Option<Extension>),
+    /// The bytes of the aggregate are reinterpreted as the listed types, which are passed as separate arguments.
+    /// Return values are returned as a struct of the listed types.
+    Coerced(Vec<BasicTypeEnum<'ctx>>),
+    /// A pointer to a copy of the aggregate is passed. With `byval` the copy is placed on the stack of the call.
+    /// Return values are written to the memory an `sret` pointer points to.
+    Indirect
+    {
+        byval: bool
+    },
+    /// Zero sized aggregates are not passed at all.
+    Ignore,
+}
+
+/// The lowering of an argument or a return value.
+#[derive(Debug, Clone)]
+pub struct ValueAbi<'ctx>
+{
+    /// The type of the value in Fog functions.
+    pub ty: BasicTypeEnum<'ctx>,
+    pub mode: PassMode<'ctx>,
+    /// The alignment of the value, coerced values are aligned for the coerced type too.
+    pub align: u32,
+    /// The type of the memory a coerced value is converted in, this is the larger of the value's type and the coerced type.
+    pub slot_ty: BasicTypeEnum<'ctx>,
+}
+
+/// The signature of a function lowered to the C calling convention of the target.
+/// `external` functions are declared, and `publib` functions are exported with this signature, while Fog functions pass aggregates by value.
+#[derive(Debug, Clone)]
+pub struct FunctionAbi<'ctx>
+{
+    /// This is `None` if the function returns `void`.
+    pub return_value: Option<ValueAbi<'ctx>>,
+    pub arguments: Vec<ValueAbi<'ctx>>,
+    pub is_var_args: bool,
+}
+
+impl<'ctx> FunctionAbi<'ctx>
+{
+    pub fn new(
+        ctx: &'ctx Context,
+        target_abi: &TargetAbi,
+        signature: &FunctionSignature,
+        custom_types: Rc<IndexMap<String, CustomItem>>,
+    ) -> Result<Self>
+    {
+        let mut registers = Registers::new(target_abi.calling_convention);
+
+        // The return value is classified first, since an `sret` pointer takes an argument register
+        let return_value = match &signature.return_type {
+            Type::Void => None,
+            return_type => {
+                Some(target_abi.classify(
+                    ctx,
+                    return_type,
+                    ty_to_llvm_ty(ctx, return_type, custom_types.clone())?,
+                    true,
+                    &mut registers,
+                )?)
+            },
+        };
+
+        let mut arguments = Vec::new();
+
+        for (_name, (ty, _id)) in signature.args.arguments.iter() {
+            arguments.push(target_abi.classify(
+                ctx,
+                ty,
+                ty_to_llvm_ty(ctx, ty, custom_types.clone())?,
+                false,
+                &mut registers,
+            )?);
+        }
+
+        Ok(Self {
+            return_value,
+            arguments,
+            is_var_args: signature.args.ellipsis_present,
+        })
+    }
+
+    /// Returns `true` if every argument and the return value is passed as it is in Fog functions.
+    /// The attributes of the function must still be added with [`FunctionAbi::add_attributes`].
+    pub fn is_direct(&self) -> bool
+    {
+        self.return_value
+            .iter()
+            .chain(&self.arguments)
+            .all(|value| matches!(value.mode, PassMode::Direct(_)))
+    }
+
+    fn has_sret(&self) -> bool
+    {
+        matches!(
+            self.return_value,
+            Some(ValueAbi {
+                mode: PassMode::Indirect { .. },
+                ..
+            })
+        )
+    }
+
+    /// Creates the LLVM type of the lowered function.
+    pub fn fn_type(&self, ctx: &'ctx Context) -> FunctionType<'ctx>
+    {
+        let ptr_type = ctx.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
+
+        let mut parameters: Vec<BasicMetadataTypeEnum> = Vec::new();
+
+        if self.has_sret() {
+            parameters.push(ptr_type.into());
+        }
+
+        for argument in &self.arguments {
+            match &argument.mode {
+                PassMode::Direct(_) => parameters.push(argument.ty.into()),
+                PassMode::Coerced(pieces) => {
+                    parameters.extend(
+                        pieces
+                            .iter()
+                            .map(|piece| BasicMetadataTypeEnum::from(*piece)),
+                    )
+                },
+                PassMode::Indirect { .. } => parameters.push(ptr_type.into()),
+                PassMode::Ignore => (),
+            }
+        }
+
+        match &self.return_value {
+            Some(ValueAbi {
+                ty,
+                mode: PassMode::Direct(_),
+                ..
+            }) => ty.fn_type(&parameters, self.is_var_args),
+            Some(ValueAbi {
+                mode: PassMode::Coerced(pieces),
+                ..
+            }) => coerced_type(ctx, pieces).fn_type(&parameters, self.is_var_args),
+            _ => ctx.void_type().fn_type(&parameters, self.is_var_args),
+        }
+    }
+
+    /// Returns the attributes of the lowered parameters and the return value.
+    fn attributes(&self, ctx: &'ctx Context) -> Vec<(AttributeLoc, Attribute)>
+    {
+        let enum_attribute = |name: &str, value: u64| {
+            ctx.create_enum_attribute(Attribute::get_named_enum_kind_id(name), value)
+        };
+        let type_attribute = |name: &str, ty: BasicTypeEnum<'ctx>| {
+            ctx.create_type_attribute(
+                Attribute::get_named_enum_kind_id(name),
+                ty.as_any_type_enum(),
+            )
+        };
+
+        let mut attributes = Vec::new();
+        let mut param_idx = 0;
+
+        if let Some(return_value) = &self.return_value {
+            match return_value.mode {
+                PassMode::Direct(Some(extension)) => {
+                    attributes.push((
+                        AttributeLoc::Return,
+                        enum_attribute(extension.attribute_name(), 0),
+                    ));
+                },
+                PassMode::Indirect { .. } => {
+                    attributes.push((
+                        AttributeLoc::Param(0),
+                        type_attribute("sret", return_value.ty),
+                    ));
+                    attributes.push((AttributeLoc::Param(0), enum_attribute("noalias", 0)));
+                    attributes.push((
+                        AttributeLoc::Param(0),
+                        enum_attribute("align", return_value.align.into()),
+                    ));
+
+                    param_idx = 1;
+                },
+                _ => (),
+            }
+        }
+
+        for argument in &self.arguments {
+            match &argument.mode {
+                PassMode::Direct(extension) => {
+                    if let Some(extension) = extension {
+                        attributes.push((
+                            AttributeLoc::Param(param_idx),
+                            enum_attribute(extension.attribute_name(), 0),
+                        ));
+                    }
+
+                    param_idx += 1;
+                },
+                PassMode::Coerced(pieces) => param_idx += pieces.len() as u32,
+                PassMode::Indirect { byval } => {
+                    if *byval {
+                        attributes.push((
+                            AttributeLoc::Param(param_idx),
+                            type_attribute("byval", argument.ty),
+                        ));
+                    }
+
+                    attributes.push((
+                        AttributeLoc::Param(param_idx),
+                        enum_attribute("align", argument.align.into()),
+                    ));
+
+                    param_idx += 1;
+                },
+                PassMode::Ignore => (),
+            }
+        }
+
+        attributes
+    }
+
+    /// Adds the attributes of the lowered signature to a function declared or defined with [`FunctionAbi::fn_type`].
+    pub fn add_attributes(&self, ctx: &'ctx Context, function: FunctionValue<'ctx>)
+    {
+        for (location, attribute) in self.attributes(ctx) {
+            function.add_attribute(location, attribute);
+        }
+    }
+
+    /// Calls a function with the lowered signature, the arguments are passed in and the returned value is returned as Fog values.
+    /// Variable arguments which are not in the signature are passed as they are.
+    pub fn build_call(
+        &self,
+        ctx: &'ctx Context,
+        builder: &Builder<'ctx>,
+        function: FunctionValue<'ctx>,
+        arguments: &[BasicValueEnum<'ctx>],
+        name: &str,
+    ) -> Result<Option<BasicValueEnum<'ctx>>>
+    {
+        let mut lowered_arguments: Vec<BasicMetadataValueEnum> = Vec::new();
+
+        let return_slot = match &self.return_value {
+            Some(
+                return_value @ ValueAbi {
+                    mode: PassMode::Indirect { .. },
+                    ..
+                },
+            ) => {
+                let return_slot =
+                    build_entry_alloca(ctx, builder, return_value.ty, return_value.align, name)?;
+
+                lowered_arguments.push(return_slot.into());
+
+                Some(return_slot)
+            },
+            _ => None,
+        };
+
+        for (idx, argument) in arguments.iter().enumerate() {
+            let Some(argument_abi) = self.arguments.get(idx)
+            else {
+                lowered_arguments.push((*argument).into());
+
+                continue;
+            };
+
+            match &argument_abi.mode {
+                PassMode::Direct(_) => lowered_arguments.push((*argument).into()),
+                PassMode::Coerced(pieces) => {
+                    let slot = build_entry_alloca(
+                        ctx,
+                        builder,
+                        argument_abi.slot_ty,
+                        argument_abi.align,
+                        name,
+                    )?;
+
+                    builder.build_store(slot, *argument)?;
+
+                    for piece in load_pieces(ctx, builder, slot, pieces, name)? {
+                        lowered_arguments.push(piece.into());
+                    }
+                },
+                PassMode::Indirect { .. } => {
+                    let copy = build_entry_alloca(
+                        ctx,
+                        builder,
+                        argument_abi.ty,
+                        argument_abi.align,
+                        name,
+                    )?;
+
+                    builder.build_store(copy, *argument)?;
+
+                    lowered_arguments.push(copy.into());
+                },
+                PassMode::Ignore => (),
+            }
+        }
+
+        let call = builder.build_call(function, &lowered_arguments, name)?;
+
+        for (location, attribute) in self.attributes(ctx) {
+            call.add_attribute(location, attribute);
+        }
+
+        let Some(return_value) = &self.return_value
+        else {
+            return Ok(None);
+        };
+
+        let returned = match &return_value.mode {
+            PassMode::Direct(_) => call_value(call)?,
+            PassMode::Coerced(pieces) => {
+                let slot = build_entry_alloca(
+                    ctx,
+                    builder,
+                    return_value.slot_ty,
+                    return_value.align,
+                    name,
+                )?;
+
+                builder.build_store(slot, call_value(call)?)?;
+                builder.build_load(return_value.ty, slot, name)?
+            },
+            // It is safe to unwrap here, since the slot is allocated for indirectly returned values
+            PassMode::Indirect { .. } => {
+                builder.build_load(return_value.ty, return_slot.unwrap(), name)?
+            },
+            PassMode::Ignore => return_value.ty.const_zero(),
+        };
+
+        Ok(Some(returned))
+    }
+
+    /// Creates a function with the lowered signature, which calls the Fog function `definition` and returns its returned value.
+    /// This is used to export `publib` functions to C.
+    pub fn build_export_wrapper(
+        &self,
+        ctx: &'ctx Context,
+        module: &Module<'ctx>,
+        builder: &Builder<'ctx>,
+        name: &str,
+        definition: FunctionValue<'ctx>,
+    ) -> Result<FunctionValue<'ctx>>
+    {
+        let wrapper = module.add_function(name, self.fn_type(ctx), None);
+
+        self.add_attributes(ctx, wrapper);
+
+        builder.position_at_end(ctx.append_basic_block(wrapper, "entry"));
+
+        // It is safe to unwrap the parameters, since they have been created from the same lowered signature
+        let mut parameters = wrapper.get_param_iter();
+
+        let return_slot = self
+            .has_sret()
+            .then(|| parameters.next().unwrap().into_pointer_value());
+
+        let mut arguments: Vec<BasicMetadataValueEnum> = Vec::new();
+
+        for argument_abi in &self.arguments {
+            let argument = match &argument_abi.mode {
+                PassMode::Direct(_) => parameters.next().unwrap(),
+                PassMode::Coerced(pieces) => {
+                    let slot = build_entry_alloca(
+                        ctx,
+                        builder,
+                        argument_abi.slot_ty,
+                        argument_abi.align,
+                        "",
+                    )?;
+
+                    store_pieces(
+                        ctx,
+                        builder,
+                        slot,
+                        pieces,
+                        parameters.by_ref().take(pieces.len()),
+                    )?;
+
+                    builder.build_load(argument_abi.ty, slot, "")?
+                },
+                PassMode::Indirect { .. } => {
+                    builder.build_load(
+                        argument_abi.ty,
+                        parameters.next().unwrap().into_pointer_value(),
+                        "",
+                    )?
+                },
+                PassMode::Ignore => argument_abi.ty.const_zero(),
+            };
+
+            arguments.push(argument.into());
+        }
+
+        let call = builder.build_call(definition, &arguments, "")?;
+
+        match &self.return_value {
+            Some(return_value) => {
+                match &return_value.mode {
+                    PassMode::Direct(_) => {
+                        builder.build_return(Some(&call_value(call)?))?;
+                    },
+                    PassMode::Coerced(pieces) => {
+                        let slot = build_entry_alloca(
+                            ctx,
+                            builder,
+                            return_value.slot_ty,
+                            return_value.align,
+                            "",
+                        )?;
+
+                        builder.build_store(slot, call_value(call)?)?;
+
+                        let returned = builder.build_load(coerced_type(ctx, pieces), slot, "")?;
+
+                        builder.build_return(Some(&returned))?;
+                    },
+                    PassMode::Indirect { .. } => {
+                        // It is safe to unwrap here, since the `sret` parameter is present for indirectly returned values
+                        builder.build_store(return_slot.unwrap(), call_value(call)?)?;
+                        builder.build_return(None)?;
+                    },
+                    PassMode::Ignore => {
+                        builder.build_return(None)?;
+                    },
+                }
+            },
+            None => {
+                builder.build_return(None)?;
+            },
+        }
+
+        Ok(wrapper)
+    }
+}
+
+/// The name of the Fog definition of an exported function which is called by its wrapper.
+pub fn internal_symbol_name(function_name: &str) -> String
+{
+    format!("{function_name}.fog")
+}
+
+/// Returns the type the pieces of a coerced aggregate are stored in together.
+fn coerced_type<'ctx>(ctx: &'ctx Context, pieces: &[BasicTypeEnum<'ctx>]) -> BasicTypeEnum<'ctx>
+{
+    match pieces {
+        [piece] => *piece,
+        pieces => ctx.struct_type(pieces, false).into(),
+    }
+}
+
+/// Returns the integer type an eightbyte of `size` bytes is passed as.
+/// Sizes without an integer type are rounded up, the bytes past the aggregate are undefined in the register anyway.
+fn integer_piece(ctx: &Context, size: u64) -> BasicTypeEnum<'_>
+{
+    match size {
+        1 => ctx.i8_type().into(),
+        2 => ctx.i16_type().into(),
+        3 | 4 => ctx.i32_type().into(),
+        _ => ctx.i64_type().into(),
+    }
+}
+
+fn load_pieces<'ctx>(
+    ctx: &'ctx Context,
+    builder: &Builder<'ctx>,
+    slot: PointerValue<'ctx>,
+    pieces: &[BasicTypeEnum<'ctx>],
+    name: &str,
+) -> Result<Vec<BasicValueEnum<'ctx>>>
+{
+    if let [piece] = pieces {
+        return Ok(vec![builder.build_load(*piece, slot, name)?]);
+    }
+
+    let struct_type = ctx.struct_type(pieces, false);
+
+    let mut values = Vec::new();
+
+    for (idx, piece) in pieces.iter().enumerate() {
+        let piece_ptr = builder.build_struct_gep(struct_type, slot, idx as u32, name)?;
+
+        values.push(builder.build_load(*piece, piece_ptr, name)?);
+    }
+
+    Ok(values)
+}
+
+fn store_pieces<'ctx>(
+    ctx: &'ctx Context,
+    builder: &Builder<'ctx>,
+    slot: PointerValue<'ctx>,
+    pieces: &[BasicTypeEnum<'ctx>],
+    values: impl Iterator<Item = BasicValueEnum<'ctx>>,
+) -> Result<()>
+{
+    let struct_type = ctx.struct_type(pieces, false);
+
+    for (idx, value) in values.enumerate() {
+        let piece_ptr = builder.build_struct_gep(struct_type, slot, idx as u32, "")?;
+
+        builder.build_store(piece_ptr, value)?;
+    }
+
+    Ok(())
+}
+
+/// Allocates stack memory in the entry block of the function being built, so that calls in loops do not grow the stack.
+fn build_entry_alloca<'ctx>(
+    ctx: &'ctx Context,
+    builder: &Builder<'ctx>,
+    ty: BasicTypeEnum<'ctx>,
+    align: u32,
+    name: &str,
+) -> Result<PointerValue<'ctx>>
+{
+    let function = current_function(builder)?;
+
+    // It is safe to unwrap here, since the builder is positioned in a block of the function
+    let entry_block = function.get_first_basic_block().unwrap();
+
+    let entry_builder = ctx.create_builder();
+
+    match entry_block.get_first_instruction() {
+        Some(instruction) => entry_builder.position_before(&instruction),
+        None => entry_builder.position_at_end(entry_block),
+    }
+
+    let slot = entry_builder.build_alloca(ty, name)?;
+
+    if let Some(instruction) = slot.as_instruction() {
+        instruction
+            .set_alignment(align)
+            .map_err(|err| CodeGenError::LibraryLLVMError(err.to_string()))?;
+    }
+
+    Ok(slot)
+}
+
+pub(crate) fn current_function<'ctx>(builder: &Builder<'ctx>) -> Result<FunctionValue<'ctx>>
+{
+    builder
+        .get_insert_block()
+        .and_then(|block| block.get_parent())
+        .ok_or_else(|| {
+            CodeGenError::LibraryLLVMError(
+                "The builder is not positioned in a function.".to_string(),
+            )
+            .into()
+        })
+}
+
+/// Returns the value returned by a call, the called function must not return `void`.
+pub(crate) fn call_value(call: CallSiteValue<'_>) -> Result<BasicValueEnum<'_>>
+{
+    BasicValueEnum::try_from(call.as_any_value_enum()).map_err(|_| {
+        CodeGenError::LibraryLLVMError("The called function does not return a value.".to_string())
+            .into()
+    })
+}
@@ -85,6 +85,8 @@ pub fn create_new_variable<'a, 'b>(
     Ok((v_ptr, var_type.into()))
 }
 
+/// Pre-allocates every [`StatementVariant::NewVariable`] in the body, including the ones in nested scopes (if branches, loops and code blocks).
+/// This ensures that variables declared inside loops do not allocate on every iteration, and that every variable has a stack slot the debug information can describe.
 pub fn create_allocation_table<'ctx>(
     ctx: &'ctx Context,
     builder: &'ctx Builder<'_>,
@@ -96,19 +98,36 @@ pub fn create_allocation_table<'ctx>(
     let mut allocation_table: HashMap<UniqueId, PointerValue> = HashMap::new();
 
     for tkn_inst in parsed_tokens {
-        // Inner token
-        let tkn = &tkn_inst.inner;
+        preallocate_variables(
+            ctx,
+            builder,
+            &tkn_inst.inner,
+            &mut allocation_table,
+            custom_types.clone(),
+        )?;
+    }
 
+    Ok(allocation_table)
+}
+
+fn preallocate_variables<'ctx>(
+    ctx: &'ctx Context,
+    builder: &'ctx Builder<'_>,
+    tkn: &StatementVariant,
+    allocation_table: &mut HashMap<UniqueId, PointerValue<'ctx>>,
+    custom_types: Rc<IndexMap<String, CustomItem>>,
+) -> anyhow::Result<()>
+{
+    match tkn {
         // If a NewVariable was created in the loop pre-allocate it
         // We dont need to preallocate variabled for functions called by this since they get deallocated automaticly.
-        if let StatementVariant::NewVariable {
+        StatementVariant::NewVariable {
             variable_name,
             variable_type,
             variable_value: _,
             variable_id,
             is_mutable: _,
-        } = tkn
-        {
+        } => {
             // Allocate the variable here
             // We can ignore the initial value of the variable since NewVariables will be interpreted as setvalue for the variables preallocated.
             let variable_pointer = builder.build_alloca(
@@ -118,8 +137,46 @@ pub fn create_allocation_table<'ctx>(
 
             // Store the pointer to the allocated variable
             allocation_table.insert(*variable_id, variable_pointer);
-        }
+        },
+        StatementVariant::If(if_definition) => {
+            for tkn_inst in if_definition
+                .true_branch
+                .iter()
+                .chain(if_definition.false_branch.iter())
+            {
+                preallocate_variables(
+                    ctx,
+                    builder,
+                    &tkn_inst.inner,
+                    allocation_table,
+                    custom_types.clone(),
+                )?;
+            }
+        },
+        StatementVariant::Loop(loop_body) => {
+            for tkn_inst in loop_body {
+                preallocate_variables(
+                    ctx,
+                    builder,
+                    &tkn_inst.inner,
+                    allocation_table,
+                    custom_types.clone(),
+                )?;
+            }
+        },
+        StatementVariant::CodeBlock(statements) => {
+            for statement in statements {
+                preallocate_variables(
+                    ctx,
+                    builder,
+                    statement,
+                    allocation_table,
+                    custom_types.clone(),
+                )?;
+            }
+        },
+        _ => (),
     }
 
-    Ok(allocation_table)
+    Ok(())
 }
@@ -0,0 +1,409 @@
+use std::{collections::HashMap, rc::Rc};
+
+use common::{
+    DEFAULT_COMPILER_ADDRESS_SPACE_SIZE,
+    anyhow::Result,
+    codegen::Order,
+    error::{Spanned, codegen::CodeGenError},
+    inkwell::{
+        AddressSpace, FloatPredicate, IntPredicate,
+        builder::Builder,
+        context::Context,
+        module::Module,
+        types::{BasicMetadataTypeEnum, FunctionType},
+        values::{BasicValueEnum, FunctionValue, IntValue, PointerValue},
+    },
+    parser::{
+        builtin::BuiltinFunction, common::StatementVariant, numeric_value::MathematicalSymbol,
+        variable::UniqueId,
+    },
+    ty::{Type, Value},
+};
+
+use crate::abi::current_function;
+
+/// The exit code of a program stopped by a failed assertion.
+pub const ASSERTION_FAILURE_EXIT_CODE: u64 = 101;
+
+/// Returns the function declared in the module with the name, or declares it if it has not been declared yet.
+/// This is used for declaring the C library functions the generated code calls.
+pub fn get_or_declare_function<'ctx>(
+    module: &Module<'ctx>,
+    name: &str,
+    function_type: FunctionType<'ctx>,
+) -> FunctionValue<'ctx>
+{
+    module
+        .get_function(name)
+        .unwrap_or_else(|| module.add_function(name, function_type, None))
+}
+
+/// Generates the `assert` built-in function: if the condition is `false` the message is printed and the program exits with [`ASSERTION_FAILURE_EXIT_CODE`].
+/// `assert_eq` is generated by passing in the result of the comparison as the condition.
+/// The builder is positioned after the assertion, where the generation of the function can continue.
+/// If the builder is positioned before the terminator of the block (ie. the `ret void` of a function returning `void`), the terminator is moved after the assertion.
+pub fn build_assert<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    condition: IntValue<'ctx>,
+    message: &str,
+) -> Result<()>
+{
+    let ptr_type = context.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
+    let i32_type = context.i32_type();
+
+    let printf =
+        get_or_declare_function(module, "printf", i32_type.fn_type(&[ptr_type.into()], true));
+
+    let exit = get_or_declare_function(
+        module,
+        "exit",
+        context.void_type().fn_type(&[i32_type.into()], false),
+    );
+
+    let function = current_function(builder)?;
+
+    // A block can only have one terminator, the branch of the assertion replaces it
+    let terminator = builder
+        .get_insert_block()
+        .and_then(|block| block.get_terminator());
+
+    if let Some(terminator) = terminator {
+        terminator.remove_from_basic_block();
+    }
+
+    let failed_block = context.append_basic_block(function, "assert_failed");
+    let passed_block = context.append_basic_block(function, "assert_passed");
+
+    builder.build_conditional_branch(condition, passed_block, failed_block)?;
+
+    builder.position_at_end(failed_block);
+
+    let format = builder.build_global_string_ptr("assertion failed: %s\n", "assert_format")?;
+    let message = builder.build_global_string_ptr(message, "assert_message")?;
+
+    builder.build_call(
+        printf,
+        &[
+            format.as_pointer_value().into(),
+            message.as_pointer_value().into(),
+        ],
+        "",
+    )?;
+
+    builder.build_call(
+        exit,
+        &[i32_type
+            .const_int(ASSERTION_FAILURE_EXIT_CODE, false)
+            .into()],
+        "",
+    )?;
+
+    builder.build_unreachable()?;
+
+    builder.position_at_end(passed_block);
+
+    if let Some(terminator) = terminator {
+        builder.insert_instruction(&terminator, None);
+        builder.position_before(&terminator);
+    }
+
+    Ok(())
+}
+
+/// The variables available in the function the built-in function is called from, see [`create_ir`](crate::irgen::create_ir).
+pub type VariableMap<'ctx> = HashMap<
+    String,
+    (
+        (PointerValue<'ctx>, BasicMetadataTypeEnum<'ctx>),
+        (Type, UniqueId),
+    ),
+>;
+
+/// Generates the call of a built-in function, `location` is printed if the call fails.
+/// The arguments can be literals, the arguments of the function, and the arithmetic and the comparisons of these.
+pub fn build_builtin_call<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    variables: &VariableMap<'ctx>,
+    builtin: BuiltinFunction,
+    arguments: &[&Spanned<StatementVariant>],
+    location: &str,
+) -> Result<()>
+{
+    let arguments = arguments
+        .iter()
+        .map(|argument| build_operand(context, builder, variables, argument.get_inner()))
+        .collect::<Result<Vec<_>>>()?;
+
+    // The number of the arguments has been checked by the semantic analysis
+    let condition = match (builtin, arguments.as_slice()) {
+        (BuiltinFunction::Assert, [(condition, Type::Boolean)]) => condition.into_int_value(),
+        (BuiltinFunction::Assert, [(_condition, ty)]) => {
+            return Err(CodeGenError::CodegenTypeMismatch(ty.clone(), Type::Boolean).into());
+        },
+        (BuiltinFunction::AssertEq, [lhs, rhs]) => {
+            build_comparison(builder, lhs.clone(), Order::Equal, rhs.clone())?
+        },
+        _ => {
+            unreachable!(
+                "[INTERNAL ERROR] Built-in function `{builtin}` has been called with {} argument(s).",
+                arguments.len()
+            )
+        },
+    };
+
+    build_assert(
+        context,
+        module,
+        builder,
+        condition,
+        &format!("`{builtin}` at {location}"),
+    )
+}
+
+/// Generates the value of an argument of a built-in function, and returns it with its type.
+fn build_operand<'ctx>(
+    context: &'ctx Context,
+    builder: &Builder<'ctx>,
+    variables: &VariableMap<'ctx>,
+    statement: &StatementVariant,
+) -> Result<(BasicValueEnum<'ctx>, Type)>
+{
+    Ok(match statement {
+        StatementVariant::Value(value) => {
+            let ty = value.get_type();
+
+            let llvm_value: BasicValueEnum = match value {
+                Value::I64(value) => context.i64_type().const_int(*value as u64, true).into(),
+                Value::U64(value) => context.i64_type().const_int(*value, false).into(),
+                Value::I32(value) => context.i32_type().const_int(*value as u64, true).into(),
+                Value::U32(value) => context.i32_type().const_int(*value as u64, false).into(),
+                Value::I16(value) => context.i16_type().const_int(*value as u64, true).into(),
+                Value::U16(value) => context.i16_type().const_int(*value as u64, false).into(),
+                Value::U8(value) => context.i8_type().const_int(*value as u64, false).into(),
+                Value::Boolean(value) => context.bool_type().const_int(*value as u64, false).into(),
+                Value::F64(value) => context.f64_type().const_float(**value).into(),
+                Value::F32(value) => context.f32_type().const_float(**value as f64).into(),
+                _ => return Err(CodeGenError::UnsupportedBuiltinArgument(statement.clone()).into()),
+            };
+
+            (llvm_value, ty)
+        },
+        StatementVariant::BasicReference { variable_name } => {
+            let ((ptr, _llvm_ty), (ty, _id)) = variables
+                .get(variable_name)
+                .ok_or_else(|| CodeGenError::InternalVariableNotFound(variable_name.clone()))?;
+
+            (
+                builder.build_load(
+                    ty.to_basic_type_enum(context, Rc::default())?,
+                    *ptr,
+                    variable_name,
+                )?,
+                ty.clone(),
+            )
+        },
+        StatementVariant::Brackets(statements, _ty) if statements.len() == 1 => {
+            build_operand(context, builder, variables, statements[0].get_inner())?
+        },
+        StatementVariant::NegateValue(value) => {
+            let (value, ty) = build_operand(context, builder, variables, value.get_inner())?;
+
+            if ty.is_int() {
+                (
+                    builder.build_int_neg(value.into_int_value(), "neg")?.into(),
+                    ty,
+                )
+            }
+            else if ty.is_float() {
+                (
+                    builder
+                        .build_float_neg(value.into_float_value(), "neg")?
+                        .into(),
+                    ty,
+                )
+            }
+            else {
+                return Err(CodeGenError::InvalidMathematicalValue.into());
+            }
+        },
+        StatementVariant::MathematicalExpression { lhs, symbol, rhs } => {
+            let lhs = build_operand(context, builder, variables, lhs.get_inner())?;
+            let rhs = build_operand(context, builder, variables, rhs.get_inner())?;
+
+            build_arithmetic(builder, lhs, *symbol, rhs)?
+        },
+        StatementVariant::Comparison(lhs, order, rhs, _ty) => {
+            let lhs = build_operand(context, builder, variables, lhs.get_inner())?;
+            let rhs = build_operand(context, builder, variables, rhs.get_inner())?;
+
+            (
+                build_comparison(builder, lhs, order.clone(), rhs)?.into(),
+                Type::Boolean,
+            )
+        },
+        _ => return Err(CodeGenError::UnsupportedBuiltinArgument(statement.clone()).into()),
+    })
+}
+
+fn is_signed(ty: &Type) -> bool
+{
+    matches!(ty, Type::I64 | Type::I32 | Type::I16)
+}
+
+/// Converts the operands to the same type, the narrower operand is extended to the type of the wider one.
+/// Unsuffixed literals get the smallest type which fits them, so `1 + 300` adds an `u8` to an `u16`.
+fn unify_operands<'ctx>(
+    builder: &Builder<'ctx>,
+    (lhs, lhs_ty): (BasicValueEnum<'ctx>, Type),
+    (rhs, rhs_ty): (BasicValueEnum<'ctx>, Type),
+) -> Result<(BasicValueEnum<'ctx>, BasicValueEnum<'ctx>, Type)>
+{
+    if lhs_ty == rhs_ty {
+        return Ok((lhs, rhs, lhs_ty));
+    }
+
+    if lhs_ty.is_int() && rhs_ty.is_int() {
+        let (lhs, rhs) = (lhs.into_int_value(), rhs.into_int_value());
+
+        let (wide_ty, int_type) =
+            if lhs.get_type().get_bit_width() >= rhs.get_type().get_bit_width() {
+                (lhs_ty.clone(), lhs.get_type())
+            }
+            else {
+                (rhs_ty.clone(), rhs.get_type())
+            };
+
+        let lhs = builder.build_int_cast_sign_flag(lhs, int_type, is_signed(&lhs_ty), "widen")?;
+        let rhs = builder.build_int_cast_sign_flag(rhs, int_type, is_signed(&rhs_ty), "widen")?;
+
+        return Ok((lhs.into(), rhs.into(), wide_ty));
+    }
+
+    if lhs_ty.is_float() && rhs_ty.is_float() {
+        let (lhs, rhs) = (lhs.into_float_value(), rhs.into_float_value());
+
+        let (wide_ty, float_type) = if lhs_ty == Type::F64 {
+            (lhs_ty.clone(), lhs.get_type())
+        }
+        else {
+            (rhs_ty.clone(), rhs.get_type())
+        };
+
+        let lhs = builder.build_float_cast(lhs, float_type, "widen")?;
+        let rhs = builder.build_float_cast(rhs, float_type, "widen")?;
+
+        return Ok((lhs.into(), rhs.into(), wide_ty));
+    }
+
+    Err(CodeGenError::CodegenTypeMismatch(lhs_ty, rhs_ty).into())
+}
+
+fn build_arithmetic<'ctx>(
+    builder: &Builder<'ctx>,
+    lhs: (BasicValueEnum<'ctx>, Type),
+    symbol: MathematicalSymbol,
+    rhs: (BasicValueEnum<'ctx>, Type),
+) -> Result<(BasicValueEnum<'ctx>, Type)>
+{
+    let (lhs, rhs, ty) = unify_operands(builder, lhs, rhs)?;
+
+    let value: BasicValueEnum = if ty.is_int() {
+        let (lhs, rhs) = (lhs.into_int_value(), rhs.into_int_value());
+
+        match (symbol, is_signed(&ty)) {
+            (MathematicalSymbol::Addition, _) => builder.build_int_add(lhs, rhs, "add")?,
+            (MathematicalSymbol::Subtraction, _) => builder.build_int_sub(lhs, rhs, "sub")?,
+            (MathematicalSymbol::Multiplication, _) => builder.build_int_mul(lhs, rhs, "mul")?,
+            (MathematicalSymbol::Division, true) => {
+                builder.build_int_signed_div(lhs, rhs, "div")?
+            },
+            (MathematicalSymbol::Division, false) => {
+                builder.build_int_unsigned_div(lhs, rhs, "div")?
+            },
+            (MathematicalSymbol::Modulo, true) => builder.build_int_signed_rem(lhs, rhs, "rem")?,
+            (MathematicalSymbol::Modulo, false) => {
+                builder.build_int_unsigned_rem(lhs, rhs, "rem")?
+            },
+            (MathematicalSymbol::Power, _) => {
+                return Err(CodeGenError::InvalidMathematicalValue.into());
+            },
+        }
+        .into()
+    }
+    else if ty.is_float() {
+        let (lhs, rhs) = (lhs.into_float_value(), rhs.into_float_value());
+
+        match symbol {
+            MathematicalSymbol::Addition => builder.build_float_add(lhs, rhs, "add")?,
+            MathematicalSymbol::Subtraction => builder.build_float_sub(lhs, rhs, "sub")?,
+            MathematicalSymbol::Multiplication => builder.build_float_mul(lhs, rhs, "mul")?,
+            MathematicalSymbol::Division => builder.build_float_div(lhs, rhs, "div")?,
+            MathematicalSymbol::Modulo => builder.build_float_rem(lhs, rhs, "rem")?,
+            MathematicalSymbol::Power => return Err(CodeGenError::InvalidMathematicalValue.into()),
+        }
+        .into()
+    }
+    else {
+        return Err(CodeGenError::InvalidMathematicalValue.into());
+    };
+
+    Ok((value, ty))
+}
+
+fn build_comparison<'ctx>(
+    builder: &Builder<'ctx>,
+    lhs: (BasicValueEnum<'ctx>, Type),
+    order: Order,
+    rhs: (BasicValueEnum<'ctx>, Type),
+) -> Result<IntValue<'ctx>>
+{
+    let (lhs, rhs, ty) = unify_operands(builder, lhs, rhs)?;
+
+    if ty.is_int() || ty == Type::Boolean {
+        let signed = is_signed(&ty);
+
+        let predicate = match order {
+            Order::Equal => IntPredicate::EQ,
+            Order::NotEqual => IntPredicate::NE,
+            Order::Bigger if signed => IntPredicate::SGT,
+            Order::Bigger => IntPredicate::UGT,
+            Order::EqBigger if signed => IntPredicate::SGE,
+            Order::EqBigger => IntPredicate::UGE,
+            Order::Smaller if signed => IntPredicate::SLT,
+            Order::Smaller => IntPredicate::ULT,
+            Order::EqSmaller if signed => IntPredicate::SLE,
+            Order::EqSmaller => IntPredicate::ULE,
+        };
+
+        Ok(builder.build_int_compare(
+            predicate,
+            lhs.into_int_value(),
+            rhs.into_int_value(),
+            "cmp",
+        )?)
+    }
+    else if ty.is_float() {
+        let predicate = match order {
+            Order::Equal => FloatPredicate::OEQ,
+            Order::NotEqual => FloatPredicate::ONE,
+            Order::Bigger => FloatPredicate::OGT,
+            Order::EqBigger => FloatPredicate::OGE,
+            Order::Smaller => FloatPredicate::OLT,
+            Order::EqSmaller => FloatPredicate::OLE,
+        };
+
+        Ok(builder.build_float_compare(
+            predicate,
+            lhs.into_float_value(),
+            rhs.into_float_value(),
+            "cmp",
+        )?)
+    }
+    else {
+        Err(CodeGenError::ComparisonIncompatibility(ty).into())
+    }
+}
@@ -1,34 +1,36 @@
 use common::{
+    DEFAULT_COMPILER_ADDRESS_SPACE_SIZE,
     anyhow::{self, Result},
     codegen::CustomItem,
+    error::{SpanInfo, Spanned},
     get_unique_id,
     indexmap::IndexMap,
     inkwell::{
+        AddressSpace,
+        basic_block::BasicBlock,
+        builder::Builder,
         context::Context,
         debug_info::{
-            DIFile, DIFlagsConstants, DIScope, DIType, DWARFSourceLanguage, DebugInfoBuilder,
+            AsDIScope, DIFile, DIFlagsConstants, DILocalVariable, DILocation, DIScope,
+            DISubprogram, DIType, DWARFSourceLanguage, DebugInfoBuilder,
         },
         llvm_sys::{
-            core::LLVMDisposeMessage,
-            error::LLVMDisposeErrorMessage,
-            target::{LLVMABIAlignmentOfType, LLVMDisposeTargetData, LLVMStoreSizeOfType},
-            target_machine::{
-                LLVMCodeGenOptLevel, LLVMCodeModel, LLVMCreateTargetDataLayout,
-                LLVMCreateTargetMachine, LLVMDisposeTargetMachine, LLVMGetDefaultTargetTriple,
-                LLVMGetTargetFromTriple, LLVMRelocMode,
+            debuginfo::{
+                LLVMCreateDIBuilderDisallowUnresolved, LLVMDIBuilderCreateEnumerationType,
+                LLVMDIBuilderCreateEnumerator, LLVMDIBuilderCreatePointerType,
+                LLVMDIBuilderFinalize, LLVMDisposeDIBuilder,
             },
+            prelude::{LLVMDIBuilderRef, LLVMMetadataRef},
         },
         module::Module,
-        types::AsTypeRef,
+        targets::TargetData,
+        types::BasicTypeEnum,
+        values::PointerValue,
     },
-    parser::function::FunctionDefinition,
-    ty::Type,
-};
-use std::{
-    ffi::{CStr, CString},
-    ptr,
-    rc::Rc,
+    parser::{common::StatementVariant, function::FunctionDefinition, variable::UniqueId},
+    ty::{Type, Value},
 };
+use std::{collections::HashMap, ptr, rc::Rc};
 
 /// Stores the DebugInformation type equivalents of the passed in [`Type`]s.
 pub fn generate_debug_inforamtion_types<'ctx>(
@@ -76,115 +78,197 @@ pub fn generate_debug_type_from_type_disc<'ctx>(
 {
     let debug_type = match type_disc.clone() {
         Type::Array((array_ty, len)) => {
-            let inner_ty_disc = *array_ty;
-
-            let inner_type = get_basic_debug_type_from_ty(
+            let inner_type = generate_debug_type_from_type_disc(
+                ctx,
+                module,
                 debug_info_builder,
                 custom_types.clone(),
-                inner_ty_disc.clone(),
+                *array_ty,
+                scope,
+                file,
+                unique_id_source,
+            )?;
+
+            let layout = get_type_layout(
+                module,
+                type_disc.to_basic_type_enum(ctx, custom_types.clone())?,
             )?;
 
             debug_info_builder
                 .create_array_type(
-                    inner_type.as_type(),
-                    (inner_ty_disc.sizeof(custom_types.clone()) * len) as u64,
-                    inner_type.as_type().get_align_in_bits(),
+                    inner_type,
+                    layout.size_in_bits,
+                    layout.align_in_bits,
                     &[0..len as i64],
                 )
                 .as_type()
         },
         Type::Struct((struct_name, struct_def, _)) => {
-            let mut struct_field_types: Vec<DIType> = Vec::new();
+            let layout = get_type_layout(
+                module,
+                type_disc.to_basic_type_enum(ctx, custom_types.clone())?,
+            )?;
+
+            let mut member_types: Vec<DIType> = Vec::new();
 
-            let type_discs = struct_def
-                .iter()
-                .map(|(_, val)| val.clone())
-                .collect::<Vec<Type>>();
+            for (idx, (field_name, field_ty)) in struct_def.iter().enumerate() {
+                // Call the function recursively
+                let field_debug_type = generate_debug_type_from_type_disc(
+                    ctx,
+                    module,
+                    debug_info_builder,
+                    custom_types.clone(),
+                    field_ty.clone(),
+                    scope,
+                    file,
+                    unique_id_source,
+                )?;
+
+                member_types.push(
+                    debug_info_builder
+                        .create_member_type(
+                            scope,
+                            field_name,
+                            file,
+                            0,
+                            field_debug_type.get_size_in_bits(),
+                            field_debug_type.get_align_in_bits(),
+                            layout.field_offsets_in_bits[idx],
+                            DIFlagsConstants::ZERO,
+                            field_debug_type,
+                        )
+                        .as_type(),
+                );
+            }
 
-            // Call the function recursively
-            generate_debug_inforamtion_types(
+            debug_info_builder
+                .create_struct_type(
+                    scope,
+                    &struct_name,
+                    file,
+                    0,
+                    layout.size_in_bits,
+                    layout.align_in_bits,
+                    DIFlagsConstants::ZERO,
+                    None,
+                    &member_types,
+                    DWARFSourceLanguage::C as u32,
+                    None,
+                    &get_unique_id(unique_id_source).to_string(),
+                )
+                .as_type()
+        },
+        Type::Enum((inner_ty, variants)) => {
+            let underlying_type = generate_debug_type_from_type_disc(
                 ctx,
                 module,
-                &mut struct_field_types,
                 debug_info_builder,
-                type_discs,
                 custom_types.clone(),
+                *inner_ty.clone(),
                 scope,
                 file,
                 unique_id_source,
             )?;
 
-            let struct_type = type_disc
-                .to_basic_type_enum(ctx, custom_types.clone())
-                .unwrap()
-                .into_struct_type();
-
-            let (size_bits, align_bits) = unsafe {
-                let target_triple = LLVMGetDefaultTargetTriple();
-
-                let mut target = ptr::null_mut();
-                let mut error_message = ptr::null_mut();
-
-                // Get target from triple
-                let target_triple_result =
-                    LLVMGetTargetFromTriple(target_triple, &mut target, &mut error_message);
+            let is_unsigned = matches!(*inner_ty, Type::U64 | Type::U32 | Type::U16 | Type::U8);
 
-                if target_triple_result != 0 {
-                    // Failed to get target
-                    let c_str = CStr::from_ptr(error_message);
+            let enum_name = type_disc.to_string();
 
-                    LLVMDisposeMessage(error_message);
-                    LLVMDisposeMessage(target_triple);
+            unsafe {
+                create_raw_debug_type(module, |raw_builder| {
+                    // The variants whose value is not an integer literal are left out, as their value is not known here
+                    let mut enumerators = variants
+                        .iter()
+                        .filter_map(|(variant_name, variant_value)| {
+                            Some(LLVMDIBuilderCreateEnumerator(
+                                raw_builder,
+                                variant_name.as_ptr().cast(),
+                                variant_name.len(),
+                                get_enum_variant_value(variant_value)?,
+                                is_unsigned as i32,
+                            ))
+                        })
+                        .collect::<Vec<LLVMMetadataRef>>();
 
-                    return Err(anyhow::Error::msg(format!(
-                        "An error occured while getting the target: {}",
-                        c_str.to_string_lossy()
-                    )));
-                }
-
-                let features = CString::new("").unwrap();
-                let generic = CString::new("generic").unwrap();
-
-                let target_machine = LLVMCreateTargetMachine(
-                    target,
-                    target_triple,
-                    generic.as_ptr(),
-                    features.as_ptr(),
-                    LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault,
-                    LLVMRelocMode::LLVMRelocDefault,
-                    LLVMCodeModel::LLVMCodeModelDefault,
-                );
-
-                let layout = LLVMCreateTargetDataLayout(target_machine);
+                    LLVMDIBuilderCreateEnumerationType(
+                        raw_builder,
+                        scope.as_mut_ptr(),
+                        enum_name.as_ptr().cast(),
+                        enum_name.len(),
+                        file.as_mut_ptr(),
+                        0,
+                        underlying_type.get_size_in_bits(),
+                        underlying_type.get_align_in_bits(),
+                        enumerators.as_mut_ptr(),
+                        enumerators.len() as u32,
+                        underlying_type.as_mut_ptr(),
+                    )
+                })
+            }
+        },
+        Type::Pointer(pointee) => {
+            let layout = get_type_layout(
+                module,
+                type_disc.to_basic_type_enum(ctx, custom_types.clone())?,
+            )?;
 
-                let struct_type = struct_type.as_type_ref();
+            let pointer_name = type_disc.to_string();
 
-                let ty_size = LLVMStoreSizeOfType(layout, struct_type);
-                let alignment = LLVMABIAlignmentOfType(layout, struct_type);
+            match pointee {
+                Some(pointee) => {
+                    let pointee_type = generate_debug_type_from_type_disc(
+                        ctx,
+                        module,
+                        debug_info_builder,
+                        custom_types.clone(),
+                        *pointee,
+                        scope,
+                        file,
+                        unique_id_source,
+                    )?;
 
-                // Free memory allocated by unsafe calls
-                LLVMDisposeMessage(target_triple);
-                LLVMDisposeErrorMessage(error_message);
-                LLVMDisposeTargetMachine(target_machine);
-                LLVMDisposeTargetData(layout);
+                    debug_info_builder
+                        .create_pointer_type(
+                            &pointer_name,
+                            pointee_type,
+                            layout.size_in_bits,
+                            layout.align_in_bits,
+                            AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE),
+                        )
+                        .as_type()
+                },
+                // Untyped pointers are emitted like C's `void *`, which is a pointer without a base type.
+                None => unsafe {
+                    create_raw_debug_type(module, |raw_builder| {
+                        LLVMDIBuilderCreatePointerType(
+                            raw_builder,
+                            ptr::null_mut(),
+                            layout.size_in_bits,
+                            layout.align_in_bits,
+                            DEFAULT_COMPILER_ADDRESS_SPACE_SIZE as u32,
+                            pointer_name.as_ptr().cast(),
+                            pointer_name.len(),
+                        )
+                    })
+                },
+            }
+        },
+        Type::String => {
+            let layout = get_type_layout(
+                module,
+                type_disc.to_basic_type_enum(ctx, custom_types.clone())?,
+            )?;
 
-                (ty_size, alignment)
-            };
+            let char_type =
+                get_basic_debug_type_from_ty(debug_info_builder, custom_types.clone(), Type::U8)?;
 
             debug_info_builder
-                .create_struct_type(
-                    scope,
-                    &struct_name,
-                    file,
-                    69,
-                    size_bits,
-                    align_bits,
-                    DIFlagsConstants::ZERO,
-                    None,
-                    &struct_field_types,
-                    DWARFSourceLanguage::C as u32,
-                    None,
-                    &get_unique_id(unique_id_source).to_string(),
+                .create_pointer_type(
+                    &type_disc.to_string(),
+                    char_type.as_type(),
+                    layout.size_in_bits,
+                    layout.align_in_bits,
+                    AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE),
                 )
                 .as_type()
         },
@@ -197,6 +281,84 @@ pub fn generate_debug_type_from_type_disc<'ctx>(
     Ok(debug_type)
 }
 
+/// The layout of an llvm type on the compile target. Every field is in bits as that is what DWARF expects.
+struct TypeLayout
+{
+    size_in_bits: u64,
+    align_in_bits: u32,
+    /// Only contains entries if the type is a struct.
+    field_offsets_in_bits: Vec<u64>,
+}
+
+/// Fetches the [`TypeLayout`] of an llvm type from the data layout of the module.
+/// The data layout of the compile target must have been set on the module, otherwise the sizes and offsets would not match the generated code.
+fn get_type_layout(module: &Module<'_>, llvm_type: BasicTypeEnum<'_>) -> Result<TypeLayout>
+{
+    let target_data = TargetData::create(module.get_data_layout().as_str().to_str()?);
+
+    let field_offsets_in_bits = if let BasicTypeEnum::StructType(struct_type) = llvm_type {
+        (0..struct_type.count_fields())
+            .map(|idx| {
+                target_data
+                    .offset_of_element(&struct_type, idx)
+                    .map(|offset| offset * 8)
+                    .ok_or_else(|| {
+                        anyhow::Error::msg(format!(
+                            "The offset of field {idx} could not be fetched from the data layout."
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?
+    }
+    else {
+        Vec::new()
+    };
+
+    Ok(TypeLayout {
+        size_in_bits: target_data.get_store_size(&llvm_type) * 8,
+        align_in_bits: target_data.get_abi_alignment(&llvm_type) * 8,
+        field_offsets_in_bits,
+    })
+}
+
+/// inkwell does not wrap every `DIBuilder` function (ie. enumerations or pointers without a pointee).
+/// This function creates a temporary raw `DIBuilder` for the module, which the closure can use to create the debug type with.
+///
+/// # Safety
+/// The closure must return a valid `DIType` metadata node.
+unsafe fn create_raw_debug_type<'ctx>(
+    module: &Module<'ctx>,
+    create_type: impl FnOnce(LLVMDIBuilderRef) -> LLVMMetadataRef,
+) -> DIType<'ctx>
+{
+    unsafe {
+        let raw_builder = LLVMCreateDIBuilderDisallowUnresolved(module.as_mut_ptr());
+
+        let debug_type = create_type(raw_builder);
+
+        LLVMDIBuilderFinalize(raw_builder);
+        LLVMDisposeDIBuilder(raw_builder);
+
+        // The metadata node is owned by the llvm context, and [`DIType`] is only a wrapper around its reference.
+        std::mem::transmute::<LLVMMetadataRef, DIType<'ctx>>(debug_type)
+    }
+}
+
+/// Returns the value of an enum variant if it was set to an integer literal.
+fn get_enum_variant_value(variant_value: &Spanned<StatementVariant>) -> Option<i64>
+{
+    match variant_value.get_inner() {
+        StatementVariant::Value(Value::I64(value)) => Some(*value),
+        StatementVariant::Value(Value::U64(value)) => Some(*value as i64),
+        StatementVariant::Value(Value::I32(value)) => Some(*value as i64),
+        StatementVariant::Value(Value::U32(value)) => Some(*value as i64),
+        StatementVariant::Value(Value::I16(value)) => Some(*value as i64),
+        StatementVariant::Value(Value::U16(value)) => Some(*value as i64),
+        StatementVariant::Value(Value::U8(value)) => Some(*value as i64),
+        _ => None,
+    }
+}
+
 /// Creates a basic debug type from a simple type.
 /// A simple type is basically any primitive which encoding is int or uint.
 fn get_basic_debug_type_from_ty<'ctx>(
@@ -207,7 +369,7 @@ fn get_basic_debug_type_from_ty<'ctx>(
 {
     let debug_type = debug_info_builder.create_basic_type(
         &type_disc.to_string(),
-        type_disc.sizeof(custom_types.clone()) as u64,
+        (type_disc.sizeof(custom_types.clone()) * 8) as u64,
         type_disc.get_dwarf_encoding(),
         DIFlagsConstants::ZERO,
     )?;
@@ -229,7 +391,7 @@ pub fn create_subprogram_debug_information<'ctx>(
     function_name: &String,
     function_definition: &FunctionDefinition,
     return_type: Type,
-) -> Result<common::inkwell::debug_info::DISubprogram<'ctx>>
+) -> Result<DISubprogram<'ctx>>
 {
     let debug_return_type = if return_type == Type::Void {
         None
@@ -275,17 +437,264 @@ pub fn create_subprogram_debug_information<'ctx>(
             DIFlagsConstants::ZERO,
         );
 
+    let function_line = function_definition.span.char_start.line as u32;
+
     Ok(debug_info_builder.create_function(
         debug_scope,
         function_name,
         None,
         debug_info_file,
-        69,
+        function_line,
         debug_subroutine_type,
         true,
         true,
-        69,
+        function_line,
         DIFlagsConstants::ZERO,
         is_optimized,
     ))
 }
+
+/// Stores everything needed to attach debug information to the body of a function.
+/// This should only be created when compiling a debug build, as the function will only have a subprogram in that case.
+pub struct FunctionDebugInformation<'a, 'ctx>
+{
+    pub context: &'ctx Context,
+    pub module: &'a Module<'ctx>,
+    pub debug_info_builder: &'a DebugInfoBuilder<'ctx>,
+    pub file: DIFile<'ctx>,
+    pub subprogram: DISubprogram<'ctx>,
+    /// The span of the function's name, this is used as the location of the function's arguments.
+    pub function_span: SpanInfo,
+    pub custom_types: Rc<IndexMap<String, CustomItem>>,
+    pub unique_id_source: &'a mut usize,
+}
+
+impl<'ctx> FunctionDebugInformation<'_, 'ctx>
+{
+    /// Returns the outermost scope of the function.
+    pub fn function_scope(&self) -> DIScope<'ctx>
+    {
+        self.subprogram.as_debug_info_scope()
+    }
+
+    /// Creates a [`DILocation`] from the start of a span.
+    /// Columns are 0 based in [`SpanInfo`], while they are 1 based in DWARF.
+    pub fn create_location(&self, span: &SpanInfo, scope: DIScope<'ctx>) -> DILocation<'ctx>
+    {
+        self.debug_info_builder.create_debug_location(
+            self.context,
+            span.char_start.line as u32,
+            span.char_start.column as u32 + 1,
+            scope,
+            None,
+        )
+    }
+
+    /// Sets the location of every instruction generated after this call to the start of the statement's span.
+    pub fn set_statement_location(
+        &self,
+        builder: &Builder<'ctx>,
+        span: &SpanInfo,
+        scope: DIScope<'ctx>,
+    )
+    {
+        builder.set_current_debug_location(self.create_location(span, scope));
+    }
+
+    /// Creates a new lexical block inside `parent_scope`, which starts at the span passed in.
+    pub fn create_lexical_block(
+        &self,
+        span: &SpanInfo,
+        parent_scope: DIScope<'ctx>,
+    ) -> DIScope<'ctx>
+    {
+        self.debug_info_builder
+            .create_lexical_block(
+                parent_scope,
+                self.file,
+                span.char_start.line as u32,
+                span.char_start.column as u32 + 1,
+            )
+            .as_debug_info_scope()
+    }
+
+    /// Generates the debug type of a [`Type`].
+    pub fn create_debug_type(&mut self, ty: &Type) -> Result<DIType<'ctx>>
+    {
+        generate_debug_type_from_type_disc(
+            self.context,
+            self.module,
+            self.debug_info_builder,
+            self.custom_types.clone(),
+            ty.clone(),
+            self.file.as_debug_info_scope(),
+            self.file,
+            self.unique_id_source,
+        )
+    }
+
+    /// Creates a `DILocalVariable` for a function argument and declares it on the argument's stack copy.
+    /// `arg_no` is 1 based.
+    pub fn declare_argument(
+        &mut self,
+        arg_name: &str,
+        arg_no: u32,
+        arg_ty: &Type,
+        storage: PointerValue<'ctx>,
+        block: BasicBlock<'ctx>,
+    ) -> Result<()>
+    {
+        let debug_type = self.create_debug_type(arg_ty)?;
+
+        let variable = self.debug_info_builder.create_parameter_variable(
+            self.function_scope(),
+            arg_name,
+            arg_no,
+            self.file,
+            self.function_span.char_start.line as u32,
+            debug_type,
+            true,
+            DIFlagsConstants::ZERO,
+        );
+
+        self.insert_declare(
+            storage,
+            variable,
+            self.create_location(&self.function_span, self.function_scope()),
+            block,
+        );
+
+        Ok(())
+    }
+
+    /// Creates a `DILocalVariable` for every [`StatementVariant::NewVariable`] in the body, including the ones in nested scopes.
+    /// Every nested scope (ie. the branches of an if statement or the body of a loop) gets its own lexical block.
+    /// The variables must have been pre-allocated with [`crate::allocate::create_allocation_table`].
+    pub fn declare_local_variables(
+        &mut self,
+        body: &[Spanned<StatementVariant>],
+        allocation_table: &HashMap<UniqueId, PointerValue<'ctx>>,
+        block: BasicBlock<'ctx>,
+        scope: DIScope<'ctx>,
+    ) -> Result<()>
+    {
+        for statement in body {
+            self.declare_statement_variables(
+                statement.get_inner(),
+                statement.get_span(),
+                allocation_table,
+                block,
+                scope,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn declare_statement_variables(
+        &mut self,
+        statement: &StatementVariant,
+        span: &SpanInfo,
+        allocation_table: &HashMap<UniqueId, PointerValue<'ctx>>,
+        block: BasicBlock<'ctx>,
+        scope: DIScope<'ctx>,
+    ) -> Result<()>
+    {
+        match statement {
+            StatementVariant::NewVariable {
+                variable_name,
+                variable_type,
+                variable_id,
+                ..
+            } => {
+                // If the variable has not been pre-allocated there is no storage to describe
+                let Some(storage) = allocation_table.get(variable_id)
+                else {
+                    return Ok(());
+                };
+
+                let debug_type = self.create_debug_type(variable_type)?;
+
+                let variable = self.debug_info_builder.create_auto_variable(
+                    scope,
+                    variable_name,
+                    self.file,
+                    span.char_start.line as u32,
+                    debug_type,
+                    true,
+                    DIFlagsConstants::ZERO,
+                    debug_type.get_align_in_bits(),
+                );
+
+                self.insert_declare(*storage, variable, self.create_location(span, scope), block);
+            },
+            StatementVariant::If(if_definition) => {
+                for branch in [&if_definition.true_branch, &if_definition.false_branch] {
+                    // Empty branches do not need a scope
+                    let Some(first_statement) = branch.first()
+                    else {
+                        continue;
+                    };
+
+                    let branch_scope = self.create_lexical_block(first_statement.get_span(), scope);
+
+                    self.declare_local_variables(branch, allocation_table, block, branch_scope)?;
+                }
+            },
+            StatementVariant::Loop(loop_body) => {
+                let loop_scope = self.create_lexical_block(span, scope);
+
+                self.declare_local_variables(loop_body, allocation_table, block, loop_scope)?;
+            },
+            StatementVariant::CodeBlock(statements) => {
+                let block_scope = self.create_lexical_block(span, scope);
+
+                // The statements of a code block do not have their own spans, so the span of the whole block is used.
+                for statement in statements {
+                    self.declare_statement_variables(
+                        statement,
+                        span,
+                        allocation_table,
+                        block,
+                        block_scope,
+                    )?;
+                }
+            },
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Inserts a `llvm.dbg.declare` call for the variable.
+    /// If the block already has a terminator (ie. the `ret void` of void functions) the call is inserted before it.
+    fn insert_declare(
+        &self,
+        storage: PointerValue<'ctx>,
+        variable: DILocalVariable<'ctx>,
+        location: DILocation<'ctx>,
+        block: BasicBlock<'ctx>,
+    )
+    {
+        match block.get_terminator() {
+            Some(terminator) => {
+                self.debug_info_builder.insert_declare_before_instruction(
+                    storage,
+                    Some(variable),
+                    None,
+                    location,
+                    terminator,
+                );
+            },
+            None => {
+                self.debug_info_builder.insert_declare_at_end(
+                    storage,
+                    Some(variable),
+                    None,
+                    location,
+                    block,
+                );
+            },
+        }
+    }
+}
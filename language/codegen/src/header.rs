@@ -0,0 +1,187 @@
+use std::{collections::HashSet, fmt::Write};
+
+use common::{
+    error::codegen::CodeGenError, indexmap::IndexMap, parser::function::FunctionSignature, ty::Type,
+};
+
+/// Generates a C header declaring the exported functions of a library, so that C and C++ projects can link against it.
+/// The structs used by the functions are defined in the header, in the order they depend on each other.
+pub fn generate_c_header(
+    library_name: &str,
+    exports: &[FunctionSignature],
+) -> Result<String, CodeGenError>
+{
+    let mut structs = StructDefinitions::default();
+    let mut declarations = String::new();
+
+    for signature in exports {
+        let unsupported =
+            |ty: &Type| CodeGenError::UnsupportedCHeaderType(signature.name.clone(), ty.clone());
+
+        // Arrays cannot be passed by value in C
+        if matches!(signature.return_type, Type::Array(_)) {
+            return Err(unsupported(&signature.return_type));
+        }
+
+        let return_type = c_type(&signature.return_type, &mut structs)
+            .ok_or_else(|| unsupported(&signature.return_type))?;
+
+        let mut arguments = Vec::new();
+
+        for (name, (ty, _id)) in signature.args.arguments.iter() {
+            if matches!(ty, Type::Array(_)) {
+                return Err(unsupported(ty));
+            }
+
+            let ty_name = c_type(ty, &mut structs).ok_or_else(|| unsupported(ty))?;
+
+            arguments.push(format!("{ty_name} {name}"));
+        }
+
+        if signature.args.ellipsis_present {
+            arguments.push("...".to_string());
+        }
+
+        if arguments.is_empty() {
+            arguments.push("void".to_string());
+        }
+
+        let _ = writeln!(
+            declarations,
+            "{return_type} {}({});",
+            signature.name,
+            arguments.join(", ")
+        );
+    }
+
+    let include_guard = format!(
+        "FOG_{}_H",
+        library_name
+            .chars()
+            .map(|char| {
+                if char.is_ascii_alphanumeric() {
+                    char.to_ascii_uppercase()
+                }
+                else {
+                    '_'
+                }
+            })
+            .collect::<String>()
+    );
+
+    let mut header = String::new();
+
+    let _ = writeln!(
+        header,
+        "/* Generated by the Fog compiler from library `{library_name}`. Do not edit. */"
+    );
+    let _ = writeln!(header, "#ifndef {include_guard}");
+    let _ = writeln!(header, "#define {include_guard}\n");
+    header.push_str("#include <stdbool.h>\n#include <stdint.h>\n\n");
+    header.push_str("#ifdef __cplusplus\nextern \"C\" {\n#endif\n\n");
+
+    // Structs are declared up front, so pointers to structs can be used before the struct is defined
+    for name in structs.definitions.keys() {
+        let _ = writeln!(header, "struct {name};");
+    }
+
+    if !structs.definitions.is_empty() {
+        header.push('\n');
+    }
+
+    for definition in structs.definitions.values() {
+        header.push_str(definition);
+        header.push('\n');
+    }
+
+    header.push_str(&declarations);
+
+    header.push_str("\n#ifdef __cplusplus\n}\n#endif\n\n");
+    let _ = writeln!(header, "#endif /* {include_guard} */");
+
+    Ok(header)
+}
+
+/// The definitions of the structs used by the exported functions.
+#[derive(Default)]
+struct StructDefinitions
+{
+    /// The definitions are ordered so that every struct is defined after the structs it contains.
+    definitions: IndexMap<String, String>,
+    /// The structs whose fields are being converted, these can only be referenced through pointers.
+    in_progress: HashSet<String>,
+}
+
+/// Returns the C type of a Fog type. The definitions of the structs used by the type are added to `structs`.
+/// Returns `None` if the type has no C equivalent.
+fn c_type(ty: &Type, structs: &mut StructDefinitions) -> Option<String>
+{
+    let c_type = match ty {
+        Type::I64 => "int64_t".to_string(),
+        Type::F64 => "double".to_string(),
+        Type::U64 => "uint64_t".to_string(),
+        Type::I32 => "int32_t".to_string(),
+        Type::F32 => "float".to_string(),
+        Type::U32 => "uint32_t".to_string(),
+        Type::I16 => "int16_t".to_string(),
+        Type::F16 => "_Float16".to_string(),
+        Type::U16 => "uint16_t".to_string(),
+        Type::U8 => "uint8_t".to_string(),
+        Type::String => "const char*".to_string(),
+        Type::Boolean => "bool".to_string(),
+        Type::Void => "void".to_string(),
+        // Enums are represented by the values of their variants
+        Type::Enum((inner_type, _variants)) => c_type(inner_type, structs)?,
+        // A struct can reference itself through a pointer
+        Type::Pointer(Some(inner_type)) => {
+            match inner_type.as_ref() {
+                Type::Struct((name, _, _)) if structs.in_progress.contains(name) => {
+                    format!("struct {name}*")
+                },
+                inner_type => format!("{}*", c_type(inner_type, structs)?),
+            }
+        },
+        Type::Pointer(None) => "void*".to_string(),
+        Type::Struct((name, fields, _attributes)) => {
+            // Structs cannot contain themselves by value
+            if structs.in_progress.contains(name) {
+                return None;
+            }
+
+            if !structs.definitions.contains_key(name) {
+                structs.in_progress.insert(name.clone());
+
+                let mut definition = format!("struct {name}\n{{\n");
+
+                for (field_name, field_type) in fields.iter() {
+                    // Array fields are declared with their length after the name of the field
+                    let (field_type, length) = match field_type {
+                        Type::Array((inner_type, length)) => {
+                            (inner_type.as_ref(), format!("[{length}]"))
+                        },
+                        field_type => (field_type, String::new()),
+                    };
+
+                    let _ = writeln!(
+                        definition,
+                        "    {} {field_name}{length};",
+                        c_type(field_type, structs)?
+                    );
+                }
+
+                definition.push_str("};\n");
+
+                // The structs used by the fields have been inserted already, so they are defined first
+                structs.in_progress.remove(name);
+                structs.definitions.insert(name.clone(), definition);
+            }
+
+            format!("struct {name}")
+        },
+        Type::Array(_) | Type::Trait { .. } | Type::Unresolved(_) | Type::TraitObject(_) => {
+            return None;
+        },
+    };
+
+    Some(c_type)
+}
@@ -1,18 +1,20 @@
 use common::{
-    DEFAULT_COMPILER_ADDRESS_SPACE_SIZE,
     anyhow::Result,
-    codegen::{CustomItem, struct_field_to_ty_list, ty_enum_to_metadata_ty_enum, ty_to_llvm_ty},
-    error::codegen::CodeGenError,
+    codegen::CustomItem,
     indexmap::IndexMap,
-    inkwell::{AddressSpace, context::Context, module::Module, types::BasicType},
+    inkwell::{context::Context, module::Module},
     parser::function::{FunctionDefinition, FunctionSignature},
-    ty::Type,
 };
 use std::{collections::HashMap, rc::Rc};
 
+use crate::abi::{FunctionAbi, TargetAbi};
+
+/// Declares the `external` functions in the module.
+/// The functions are declared with the C calling convention of the target, so structs are passed the way C compilers pass them.
 pub fn import_user_lib_functions<'a>(
     ctx: &'a Context,
     module: &Module<'a>,
+    target_abi: &TargetAbi,
     imported_functions: Rc<HashMap<String, FunctionSignature>>,
     parsed_functions: Rc<IndexMap<String, FunctionDefinition>>,
     custom_types: Rc<IndexMap<String, CustomItem>>,
@@ -25,120 +27,11 @@ pub fn import_user_lib_functions<'a>(
             continue;
         }
 
-        let mut args = Vec::new();
-
-        for (_, arg_ty) in import_sig.args.arguments.iter() {
-            let argument_sig = ty_enum_to_metadata_ty_enum(
-                arg_ty
-                    .0
-                    .clone()
-                    .to_basic_type_enum(ctx, custom_types.clone())?,
-            );
-
-            args.push(argument_sig);
-        }
-
-        let function_type = match &import_sig.return_type {
-            Type::I32 => {
-                let return_type = ctx.i32_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::F32 => {
-                let return_type = ctx.f32_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::U32 => {
-                let return_type = ctx.i32_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::U8 => {
-                let return_type = ctx.i32_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::String => {
-                let return_type =
-                    ctx.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::Boolean => {
-                let return_type = ctx.bool_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::Void => {
-                let return_type = ctx.void_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::Struct((_struct_name, struct_inner, _)) => {
-                let return_type = ctx.struct_type(
-                    &struct_field_to_ty_list(ctx, struct_inner, custom_types.clone())?,
-                    import_sig.args.ellipsis_present,
-                );
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::I64 => {
-                let return_type = ctx.i64_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::F64 => {
-                let return_type = ctx.f32_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::U64 => {
-                let return_type = ctx.i64_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::I16 => {
-                let return_type = ctx.i16_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::F16 => {
-                let return_type = ctx.f16_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::U16 => {
-                let return_type = ctx.i16_type();
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::Array((inner_ty, len)) => {
-                let array_ty = ty_to_llvm_ty(ctx, &inner_ty, custom_types.clone())?;
-
-                let return_type = array_ty.array_type(*len as u32);
-
-                return_type.fn_type(&args, false)
-            },
-            Type::Pointer(_) => {
-                let return_type =
-                    ctx.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
-
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::Enum((ty, _)) => {
-                let return_type = ty.to_basic_type_enum(ctx, custom_types.clone())?;
+        let function_abi = FunctionAbi::new(ctx, target_abi, import_sig, custom_types.clone())?;
 
-                return_type.fn_type(&args, import_sig.args.ellipsis_present)
-            },
-            Type::Trait { .. } => return Err(CodeGenError::TraitIsNotType.into()),
-            Type::TraitObject(_) => {
-                unimplemented!()
-            },
-            Type::Unresolved(_) => todo!(),
-        };
+        let function = module.add_function(import_name, function_abi.fn_type(ctx), None);
 
-        module.add_function(import_name, function_type, None);
+        function_abi.add_attributes(ctx, function);
     }
 
     Ok(())
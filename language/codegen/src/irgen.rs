@@ -13,12 +13,13 @@ use common::{
         builder::Builder,
         context::Context,
         debug_info::{AsDIScope, DWARFEmissionKind, DWARFSourceLanguage},
-        module::Module,
+        module::{Linkage, Module},
         types::BasicMetadataTypeEnum,
         values::{BasicMetadataValueEnum, BasicValue, BasicValueEnum, FunctionValue, PointerValue},
     },
     parser::{
-        common::StatementVariant,
+        builtin::BuiltinFunction,
+        common::{ItemVisibility, StatementVariant},
         function::{CompilerInstruction, FunctionDefinition},
         numeric_value::MathematicalSymbol,
         variable::{ControlFlowType, UniqueId},
@@ -29,8 +30,10 @@ use common::{
 use std::{collections::HashMap, rc::Rc};
 
 use crate::{
+    abi::{FunctionAbi, TargetAbi, internal_symbol_name},
     allocate::{allocate_string, create_allocation_table, create_new_variable},
-    debug::create_subprogram_debug_information,
+    builtin::{VariableMap, build_builtin_call},
+    debug::{FunctionDebugInformation, create_subprogram_debug_information},
     // pointer::set_value_of_ptr,
 };
 
@@ -42,24 +45,32 @@ pub fn create_ir<'main, 'ctx>(
     ctx: &'main Context,
     // The list of ParsedToken-s
     parsed_tokens: Vec<Spanned<StatementVariant>>,
-    // This argument is initialized with the HashMap of the arguments
-    available_arguments: HashMap<String, (BasicValueEnum<'ctx>, (Type, UniqueId))>,
+    // This argument is initialized with the arguments in the order of the function's signature
+    available_arguments: IndexMap<String, (BasicValueEnum<'ctx>, (Type, UniqueId))>,
     // Type returned type of the Function
     fn_ret_ty: Type,
     this_fn_block: BasicBlock<'ctx>,
     this_fn: FunctionValue<'ctx>,
     parsed_functions: Rc<IndexMap<String, FunctionDefinition>>,
     custom_items: Rc<IndexMap<String, CustomItem>>,
+    // This is only present when compiling a debug build
+    mut debug_information: Option<FunctionDebugInformation<'_, 'ctx>>,
 ) -> Result<()>
 where
     'main: 'ctx,
 {
-    let mut variable_map: HashMap<
-        String,
-        ((PointerValue, BasicMetadataTypeEnum), (Type, UniqueId)),
-    > = HashMap::new();
+    let mut variable_map: VariableMap = HashMap::new();
+
+    // The stores of the arguments are located at the function's name
+    if let Some(debug_information) = &debug_information {
+        debug_information.set_statement_location(
+            builder,
+            &debug_information.function_span,
+            debug_information.function_scope(),
+        );
+    }
 
-    for (arg_name, (arg_val, arg_ty)) in available_arguments {
+    for (arg_idx, (arg_name, (arg_val, arg_ty))) in available_arguments.into_iter().enumerate() {
         let (v_ptr, ty) = match arg_val {
             BasicValueEnum::ArrayValue(value) => {
                 let v_ptr = builder.build_alloca(value.get_type(), &arg_name)?;
@@ -95,9 +106,34 @@ where
             BasicValueEnum::ScalableVectorValue(_scalable_vector_value) => todo!(),
         };
 
+        if let Some(debug_information) = &mut debug_information {
+            debug_information.declare_argument(
+                &arg_name,
+                arg_idx as u32 + 1,
+                &arg_ty.0,
+                v_ptr,
+                this_fn_block,
+            )?;
+        }
+
         variable_map.insert(arg_name, ((v_ptr, ty), arg_ty));
     }
 
+    // Pre-allocate every variable of the function
+    let allocation_table =
+        create_allocation_table(ctx, builder, &parsed_tokens, custom_items.clone())?;
+
+    if let Some(debug_information) = &mut debug_information {
+        let function_scope = debug_information.function_scope();
+
+        debug_information.declare_local_variables(
+            &parsed_tokens,
+            &allocation_table,
+            this_fn_block,
+            function_scope,
+        )?;
+    }
+
     // create_ir_from_parsed_token_list(
     //     module,
     //     builder,
@@ -107,12 +143,46 @@ where
     //     this_fn_block,
     //     &mut variable_map,
     //     this_fn,
-    //     &HashMap::new(),
+    //     &allocation_table,
     //     None,
     //     parsed_functions.clone(),
     //     custom_items.clone(),
     // )?;
 
+    // The built-in functions are not defined in any module, their calls are generated in place
+    for statement in &parsed_tokens {
+        if let StatementVariant::FunctionCall {
+            identifier,
+            arguments,
+        } = &statement.inner
+            && let StatementVariant::BasicReference { variable_name } = &identifier.inner
+            && let Some(builtin) = BuiltinFunction::from_name(variable_name)
+        {
+            if let Some(debug_information) = &debug_information {
+                debug_information.set_statement_location(
+                    builder,
+                    &statement.span,
+                    debug_information.function_scope(),
+                );
+            }
+
+            build_builtin_call(
+                ctx,
+                module,
+                builder,
+                &variable_map,
+                builtin,
+                &arguments.values().collect::<Vec<_>>(),
+                &format!(
+                    "{}:{}:{}",
+                    this_fn.get_name().to_string_lossy(),
+                    statement.span.char_start.line,
+                    statement.span.char_start.column
+                ),
+            )?;
+        }
+    }
+
     Ok(())
 }
 
@@ -225,13 +295,16 @@ pub fn create_function_call_args<'ctx>(
 }
 
 /// This function is solely for generating the LLVM-IR from the main sourec file.
+/// `is_optimized` marks the debug information as describing optimized code, `debug_info` emits the subprograms, variables and types instead of only the line tables.
 pub fn generate_ir<'ctx>(
     parsed_functions: Rc<IndexMap<String, FunctionDefinition>>,
     context: &'ctx Context,
     module: &Module<'ctx>,
     builder: &'ctx Builder<'ctx>,
     custom_types: Rc<IndexMap<String, CustomItem>>,
+    target_abi: &TargetAbi,
     is_optimized: bool,
+    debug_info: bool,
     flags_passed_in: &str,
     path_to_src_file: &str,
 ) -> Result<()>
@@ -251,11 +324,11 @@ pub fn generate_ir<'ctx>(
         1,
         "",
         {
-            if is_optimized {
-                DWARFEmissionKind::LineTablesOnly
+            if debug_info {
+                DWARFEmissionKind::Full
             }
             else {
-                DWARFEmissionKind::Full
+                DWARFEmissionKind::LineTablesOnly
             }
         },
         0,
@@ -319,7 +392,9 @@ pub fn generate_ir<'ctx>(
             module,
             builder,
             &custom_types,
+            target_abi,
             is_optimized,
+            debug_info,
             &debug_info_builder,
             debug_info_file,
             debug_scope,
@@ -329,6 +404,9 @@ pub fn generate_ir<'ctx>(
         )?;
     }
 
+    // Resolve every temporary node of the debug information
+    debug_info_builder.finalize();
+
     Ok(())
 }
 
@@ -338,7 +416,9 @@ fn create_function_with_ir<'ctx>(
     module: &Module<'ctx>,
     builder: &'ctx Builder<'ctx>,
     custom_types: &Rc<IndexMap<String, CustomItem>>,
+    target_abi: &TargetAbi,
     is_optimized: bool,
+    debug_info: bool,
     debug_info_builder: &common::inkwell::debug_info::DebugInfoBuilder<'ctx>,
     debug_info_file: common::inkwell::debug_info::DIFile<'ctx>,
     debug_scope: common::inkwell::debug_info::DIScope<'ctx>,
@@ -353,7 +433,34 @@ fn create_function_with_ir<'ctx>(
         custom_types.clone(),
     )?;
 
-    let function = module.add_function(function_name, function_type, None);
+    // Exported functions are called from C, so they must follow the C calling convention of the target
+    let export_abi = if function_definition.visibility == ItemVisibility::PublicLibrary {
+        Some(FunctionAbi::new(
+            context,
+            target_abi,
+            &function_definition.signature,
+            custom_types.clone(),
+        )?)
+    }
+    else {
+        None
+    };
+
+    // If the lowered signature differs from the Fog signature, the definition is exported through a wrapper which converts the arguments and the return value
+    let is_wrapped = export_abi
+        .as_ref()
+        .is_some_and(|export_abi| !export_abi.is_direct());
+
+    let function = if is_wrapped {
+        module.add_function(
+            &internal_symbol_name(function_name),
+            function_type,
+            Some(Linkage::Internal),
+        )
+    }
+    else {
+        module.add_function(function_name, function_type, None)
+    };
 
     add_compiler_hints_to_fn(
         context,
@@ -363,7 +470,21 @@ fn create_function_with_ir<'ctx>(
 
     let return_type = function_definition.signature.return_type.clone();
 
-    if !is_optimized {
+    // Reset the debug location left over from the previous function
+    builder.unset_current_debug_location();
+
+    if let Some(export_abi) = &export_abi {
+        if is_wrapped {
+            export_abi.build_export_wrapper(context, module, builder, function_name, function)?;
+        }
+        else {
+            export_abi.add_attributes(context, function);
+        }
+    }
+
+    let mut debug_information = None;
+
+    if debug_info {
         let debug_subprogram = create_subprogram_debug_information(
             context,
             module,
@@ -380,6 +501,17 @@ fn create_function_with_ir<'ctx>(
         .map_err(|err| CodeGenError::LibraryLLVMError(err.to_string()))?;
 
         function.set_subprogram(debug_subprogram);
+
+        debug_information = Some(FunctionDebugInformation {
+            context,
+            module,
+            debug_info_builder,
+            file: debug_info_file,
+            subprogram: debug_subprogram,
+            function_span: function_definition.span,
+            custom_types: custom_types.clone(),
+            unique_id_source,
+        });
     }
 
     let basic_block = context.append_basic_block(function, "main");
@@ -394,7 +526,7 @@ fn create_function_with_ir<'ctx>(
         builder.position_before(&instruction);
     }
 
-    let mut arguments: HashMap<String, (BasicValueEnum, (Type, UniqueId))> = HashMap::new();
+    let mut arguments: IndexMap<String, (BasicValueEnum, (Type, UniqueId))> = IndexMap::new();
 
     for (idx, argument) in function.get_param_iter().enumerate() {
         // Get the name of the argument from the function signature's argument list
@@ -419,14 +551,14 @@ fn create_function_with_ir<'ctx>(
         module,
         builder,
         context,
-        // function_definition.body.clone(),
-        todo!(),
+        function_definition.body.clone(),
         arguments,
         function_definition.signature.return_type.clone(),
         basic_block,
         function,
         parsed_functions.clone(),
         custom_types.clone(),
+        debug_information,
     )?;
 
     Ok(())
@@ -464,7 +596,27 @@ pub fn add_compiler_hints_to_fn(
 
                 function.add_attribute(common::inkwell::attributes::AttributeLoc::Function, attr);
             },
-            CompilerInstruction::Feature(_) => {
+            CompilerInstruction::NoInline => {
+                let attr =
+                    context.create_enum_attribute(Attribute::get_named_enum_kind_id("noinline"), 0);
+
+                function.add_attribute(common::inkwell::attributes::AttributeLoc::Function, attr);
+            },
+            CompilerInstruction::OptNone => {
+                // LLVM requires `optnone` functions to be `noinline` too
+                for attribute_name in ["optnone", "noinline"] {
+                    let attr = context.create_enum_attribute(
+                        Attribute::get_named_enum_kind_id(attribute_name),
+                        0,
+                    );
+
+                    function
+                        .add_attribute(common::inkwell::attributes::AttributeLoc::Function, attr);
+                }
+            },
+            // Tests are called by the test harness, they do not need any attributes
+            CompilerInstruction::Test => {},
+            CompilerInstruction::Feature(_) | CompilerInstruction::Cfg(_) => {
                 return Err(
                     CodeGenError::InternalFunctionCompilerHintParsingError(hint.clone()).into(),
                 );
@@ -1,17 +1,26 @@
+/// Lowers the arguments and return values of C functions to the calling convention of the target.
+pub mod abi;
 /// Handles everything allocation related. (Strings, Variables, etc.)
 pub mod allocate;
+/// Generates the built-in functions of the language.
+pub mod builtin;
 /// Handles the llvm-ir generation od debug symbols and information.
 pub mod debug;
+/// Generates C headers for the exported functions of libraries.
+pub mod header;
 /// Handles the llvm-ir generation of external libaries / functions
 pub mod import;
 /// Generates the llvm-ir from language code.
 pub mod irgen;
 /// Handles pointers in the programming language
 pub mod pointer;
+/// Generates the test harness, which runs the tests of a project.
+pub mod test_harness;
 
 use common::{
     anyhow::Result,
     codegen::CustomItem,
+    compiler::{EmitKind, OptLevel, OptimizationSettings},
     error::{application::ApplicationError, codegen::CodeGenError},
     indexmap::IndexMap,
     inkwell::{
@@ -19,15 +28,19 @@ use common::{
         context::Context,
         module::Module,
         passes::PassBuilderOptions,
-        targets::{InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple},
+        targets::{FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple},
     },
     parser::function::{FunctionDefinition, FunctionSignature},
     tracing::info,
 };
 use parser::parser::Settings;
-use std::{collections::HashMap, io::ErrorKind, path::PathBuf, rc::Rc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
-use crate::{import::import_user_lib_functions, irgen::generate_ir};
+use crate::{abi::TargetAbi, import::import_user_lib_functions, irgen::generate_ir};
 
 /// Main function to the codegen module.
 /// This function handles everything IR generation related.
@@ -36,13 +49,8 @@ pub fn llvm_codegen_main<'ctx>(
     builder: &'ctx Builder<'ctx>,
     module: &Module<'ctx>,
     parsed_functions: Rc<IndexMap<String, FunctionDefinition>>,
-    path_to_ir_output: PathBuf,
-
-    #[allow(unused_variables)]
-    // For some reason inkwell crashes when having a break statement in the IR when generating object files.
-    path_to_o_output: PathBuf,
-
-    is_optimized: bool,
+    optimization: &OptimizationSettings,
+    position_independent: bool,
     imported_functions: Rc<HashMap<String, FunctionSignature>>,
     custom_types: Rc<IndexMap<String, CustomItem>>,
     flags_passed_in: &str,
@@ -68,27 +76,8 @@ pub fn llvm_codegen_main<'ctx>(
         }
     }
 
-    // Import functions defined by the user via llvm
-    import_user_lib_functions(
-        context,
-        module,
-        imported_functions,
-        parsed_functions.clone(),
-        custom_types.clone(),
-    )?;
-
-    generate_ir(
-        parsed_functions,
-        context,
-        module,
-        builder,
-        custom_types,
-        is_optimized,
-        flags_passed_in,
-        path_to_src,
-    )?;
-
     // Init target
+    // This needs to happen before generating the IR, as the layout of the debug types and the C calling convention are fetched from the target.
     Target::initialize_x86(&InitializationConfig::default());
 
     // Create target
@@ -101,61 +90,115 @@ pub fn llvm_codegen_main<'ctx>(
             &target_triple,
             &cpu_name.unwrap_or_else(|| TargetMachine::get_host_cpu_name().to_string()),
             &cpu_features.unwrap_or_else(|| TargetMachine::get_host_cpu_features().to_string()),
-            common::inkwell::OptimizationLevel::Aggressive,
-            RelocMode::Default,
+            optimization.opt_level.codegen_level(),
+            // Code linked into shared libraries must be position independent
+            if position_independent {
+                RelocMode::PIC
+            }
+            else {
+                RelocMode::Default
+            },
             common::inkwell::targets::CodeModel::Default,
         )
         .unwrap();
 
-    // Create opt passes list
-    let passes = ["globaldce", "sink", "mem2reg"].join(",");
-
-    // Run optimization passes if the user prompted to
-    if is_optimized {
-        let passes = passes.as_str();
-
-        info!("Running optimization passes: {passes}...");
-        module
-            .run_passes(passes, &target_machine, PassBuilderOptions::create())
-            .map_err(|_| CodeGenError::InternalOptimisationPassFailed)?;
-    }
-
     // Set target triple
     module.set_triple(&target_machine.get_triple());
 
-    // Set target data layout
+    // Set target data layout, the layout of the debug types is fetched from it
     module.set_data_layout(&target_machine.get_target_data().get_data_layout());
 
-    // Write LLVM IR to a file.
-    module.print_to_file(&path_to_ir_output).map_err(|err| {
-        ApplicationError::FileError(std::io::Error::new(
-            ErrorKind::ExecutableFileBusy,
-            err.to_string(),
-        ))
-    })?;
-
-    // This returns a panic when we want to display a `break` statement
-    // target_machine
-    //     .write_to_file(
-    //         module,
-    //         common::inkwell::targets::FileType::Object,
-    //         &path_to_o_output,
-    //     )
-    //     .map_err(|err| {
-    //         ApplicationError::FileError(std::io::Error::new(
-    //             ErrorKind::ExecutableFileBusy,
-    //             err.to_string(),
-    //         ))
-    //     })?;
+    let target_abi = TargetAbi::from_target_machine(&target_machine);
+
+    // Import functions defined by the user via llvm
+    import_user_lib_functions(
+        context,
+        module,
+        &target_abi,
+        imported_functions,
+        parsed_functions.clone(),
+        custom_types.clone(),
+    )?;
+
+    generate_ir(
+        parsed_functions,
+        context,
+        module,
+        builder,
+        custom_types,
+        &target_abi,
+        optimization.opt_level != OptLevel::O0,
+        optimization.debug_info,
+        flags_passed_in,
+        path_to_src,
+    )?;
+
+    let passes = optimization.pass_pipeline();
+
+    info!("Running optimization passes: {passes}...");
+    module
+        .run_passes(&passes, &target_machine, PassBuilderOptions::create())
+        .map_err(|err| {
+            if optimization.passes.is_some() {
+                CodeGenError::InvalidPassPipeline(passes.clone(), err.to_string())
+            }
+            else {
+                CodeGenError::InternalOptimisationPassFailed
+            }
+        })?;
 
     Ok(target_machine)
 }
 
+/// Writes an artifact produced by LLVM from the module.
+/// The module must have been generated by [`llvm_codegen_main`] with the same [`TargetMachine`].
+pub fn write_llvm_artifact(
+    module: &Module<'_>,
+    target_machine: &TargetMachine,
+    emit_kind: EmitKind,
+    path: &Path,
+) -> Result<()>
+{
+    let file_error = |err: String| ApplicationError::FileError(std::io::Error::other(err));
+
+    match emit_kind {
+        EmitKind::Ir => {
+            module
+                .print_to_file(path)
+                .map_err(|err| file_error(err.to_string()))?;
+        },
+        EmitKind::Bc => {
+            if !module.write_bitcode_to_path(path) {
+                return Err(file_error(format!(
+                    "Failed to write bitcode to `{}`.",
+                    path.display()
+                ))
+                .into());
+            }
+        },
+        EmitKind::Asm => {
+            target_machine
+                .write_to_file(module, FileType::Assembly, path)
+                .map_err(|err| file_error(err.to_string()))?;
+        },
+        EmitKind::Obj => {
+            target_machine
+                .write_to_file(module, FileType::Object, path)
+                .map_err(|err| file_error(err.to_string()))?;
+        },
+        EmitKind::Tokens | EmitKind::Ast | EmitKind::Fir => {
+            unreachable!("[INTERNAL ERROR] `{emit_kind}` is not an artifact produced by LLVM.")
+        },
+    }
+
+    Ok(())
+}
+
 /// Wrapper function for the LLVM codegen init function.
 pub fn llvm_codegen<'ctx>(
     target_ir_path: PathBuf,
     target_o_path: PathBuf,
-    optimization: bool,
+    optimization: &OptimizationSettings,
     parser_state: Settings,
     function_table: &common::indexmap::IndexMap<String, FunctionDefinition>,
     imported_functions: Rc<std::collections::HashMap<String, FunctionSignature>>,
@@ -174,8 +217,6 @@ pub fn llvm_codegen<'ctx>(
     //     builder,
     //     &module,
     //     Rc::new(function_table.clone()),
-    //     target_ir_path,
-    //     target_o_path.clone(),
     //     optimization,
     //     imported_functions,
     //     parser_state.custom_types,
@@ -0,0 +1,512 @@
+use common::{
+    DEFAULT_COMPILER_ADDRESS_SPACE_SIZE,
+    anyhow::Result,
+    inkwell::{
+        AddressSpace, IntPredicate,
+        builder::Builder,
+        context::Context,
+        module::{Linkage, Module},
+        targets::TargetMachine,
+        values::{FunctionValue, PointerValue},
+    },
+};
+
+use crate::{abi::call_value, builtin::get_or_declare_function};
+
+/// The name of the function running a single test in the test harness.
+const RUN_TEST_FUNCTION_NAME: &str = "__fog_run_test";
+
+/// Size of the buffer the captured output of a failed test is copied through.
+const OUTPUT_BUFFER_SIZE: u32 = 4096;
+
+/// Generates the `main` function of the test harness, which runs the tests in the order they are passed in.
+/// The tests are declared in the harness module and are linked from the modules of the project, every test must take no arguments and return `void`.
+/// If the harness is started with an argument, only the tests whose name contains the argument are run.
+/// The harness prints the result of every test and a summary, and exits with `1` if any of the tests has failed.
+/// If `fork_per_test` is set every test is run in a forked process, and its output is only printed if the test has failed. This is only supported on Linux.
+pub fn generate_test_harness<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    target_machine: &TargetMachine,
+    tests: &[String],
+    fork_per_test: bool,
+) -> Result<()>
+{
+    let ptr_type = context.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
+    let i32_type = context.i32_type();
+
+    let run_test = if fork_per_test {
+        generate_forking_run_test(context, module, builder, target_machine)?
+    }
+    else {
+        generate_run_test(context, module, builder)?
+    };
+
+    let printf =
+        get_or_declare_function(module, "printf", i32_type.fn_type(&[ptr_type.into()], true));
+
+    let strstr = get_or_declare_function(
+        module,
+        "strstr",
+        ptr_type.fn_type(&[ptr_type.into(), ptr_type.into()], false),
+    );
+
+    let main = module.add_function(
+        "main",
+        i32_type.fn_type(&[i32_type.into(), ptr_type.into()], false),
+        None,
+    );
+
+    // It is safe to unwrap here, as the arguments are defined by the function type
+    let argc = main.get_nth_param(0).unwrap().into_int_value();
+    let argv = main.get_nth_param(1).unwrap().into_pointer_value();
+
+    let entry_block = context.append_basic_block(main, "entry");
+    let read_filter_block = context.append_basic_block(main, "read_filter");
+
+    builder.position_at_end(entry_block);
+
+    let passed = builder.build_alloca(i32_type, "passed")?;
+    let failed = builder.build_alloca(i32_type, "failed")?;
+    let filtered_out = builder.build_alloca(i32_type, "filtered_out")?;
+    let filter = builder.build_alloca(ptr_type, "filter")?;
+
+    for counter in [passed, failed, filtered_out] {
+        builder.build_store(counter, i32_type.const_zero())?;
+    }
+
+    builder.build_store(filter, ptr_type.const_null())?;
+
+    // The first argument of the harness is the filter
+    let has_filter = builder.build_int_compare(
+        IntPredicate::SGT,
+        argc,
+        i32_type.const_int(1, false),
+        "has_filter",
+    )?;
+
+    let mut next_block = context.append_basic_block(main, "test_0");
+
+    builder.build_conditional_branch(has_filter, read_filter_block, next_block)?;
+
+    builder.position_at_end(read_filter_block);
+
+    // It is safe to index into `argv`, as it contains at least two arguments here
+    let filter_argument = unsafe {
+        builder.build_in_bounds_gep(
+            ptr_type,
+            argv,
+            &[i32_type.const_int(1, false)],
+            "filter_argument",
+        )?
+    };
+
+    let filter_value = builder.build_load(ptr_type, filter_argument, "filter_value")?;
+
+    builder.build_store(filter, filter_value)?;
+    builder.build_unconditional_branch(next_block)?;
+
+    for (idx, test_name) in tests.iter().enumerate() {
+        let test_block = next_block;
+        let match_block = context.append_basic_block(main, &format!("match_{idx}"));
+        let skip_block = context.append_basic_block(main, &format!("skip_{idx}"));
+        let run_block = context.append_basic_block(main, &format!("run_{idx}"));
+
+        next_block = context.append_basic_block(main, &format!("test_{}", idx + 1));
+
+        let test = module.add_function(
+            test_name,
+            context.void_type().fn_type(&[], false),
+            Some(Linkage::External),
+        );
+
+        builder.position_at_end(test_block);
+
+        let name = builder.build_global_string_ptr(test_name, &format!("test_name_{idx}"))?;
+
+        let filter_value = builder
+            .build_load(ptr_type, filter, "filter_value")?
+            .into_pointer_value();
+
+        let is_unfiltered = builder.build_is_null(filter_value, "is_unfiltered")?;
+
+        builder.build_conditional_branch(is_unfiltered, run_block, match_block)?;
+
+        // Only the tests containing the filter in their name are run
+        builder.position_at_end(match_block);
+
+        let found = call_value(builder.build_call(
+            strstr,
+            &[name.as_pointer_value().into(), filter_value.into()],
+            "found",
+        )?)?
+        .into_pointer_value();
+
+        let is_matching = builder.build_is_not_null(found, "is_matching")?;
+
+        builder.build_conditional_branch(is_matching, run_block, skip_block)?;
+
+        builder.position_at_end(skip_block);
+
+        build_increment(context, builder, filtered_out)?;
+
+        builder.build_unconditional_branch(next_block)?;
+
+        builder.position_at_end(run_block);
+
+        let is_passed = call_value(builder.build_call(
+            run_test,
+            &[
+                name.as_pointer_value().into(),
+                test.as_global_value().as_pointer_value().into(),
+            ],
+            "is_passed",
+        )?)?
+        .into_int_value();
+
+        let is_failed = builder.build_not(is_passed, "is_failed")?;
+
+        for (counter, is_counted) in [(passed, is_passed), (failed, is_failed)] {
+            let count = builder
+                .build_load(i32_type, counter, "count")?
+                .into_int_value();
+
+            let increment = builder.build_int_z_extend(is_counted, i32_type, "increment")?;
+
+            let count = builder.build_int_add(count, increment, "count")?;
+
+            builder.build_store(counter, count)?;
+        }
+
+        builder.build_unconditional_branch(next_block)?;
+    }
+
+    // Print the summary of the tests
+    builder.position_at_end(next_block);
+
+    let [passed, failed, filtered_out] = [passed, failed, filtered_out].map(|counter| {
+        builder
+            .build_load(i32_type, counter, "count")
+            .map(|count| count.into_int_value())
+    });
+
+    let failed = failed?;
+
+    let has_failed = builder.build_int_compare(
+        IntPredicate::NE,
+        failed,
+        i32_type.const_zero(),
+        "has_failed",
+    )?;
+
+    let result = builder.build_select(
+        has_failed,
+        builder
+            .build_global_string_ptr("FAILED", "result_failed")?
+            .as_pointer_value(),
+        builder
+            .build_global_string_ptr("ok", "result_ok")?
+            .as_pointer_value(),
+        "result",
+    )?;
+
+    let summary = builder.build_global_string_ptr(
+        "\ntest result: %s. %d passed; %d failed; %d filtered out\n",
+        "summary",
+    )?;
+
+    builder.build_call(
+        printf,
+        &[
+            summary.as_pointer_value().into(),
+            result.into(),
+            passed?.into(),
+            failed.into(),
+            filtered_out?.into(),
+        ],
+        "",
+    )?;
+
+    let exit_code = builder.build_int_z_extend(has_failed, i32_type, "exit_code")?;
+
+    builder.build_return(Some(&exit_code))?;
+
+    Ok(())
+}
+
+/// Generates the function running a single test in the harness process, the test has passed if it returns.
+/// The function takes the name of the test and a pointer to it, and returns whether the test has passed.
+fn generate_run_test<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+) -> Result<FunctionValue<'ctx>>
+{
+    let ptr_type = context.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
+
+    let run_test = module.add_function(
+        RUN_TEST_FUNCTION_NAME,
+        context
+            .bool_type()
+            .fn_type(&[ptr_type.into(), ptr_type.into()], false),
+        Some(Linkage::Internal),
+    );
+
+    // It is safe to unwrap here, as the arguments are defined by the function type
+    let name = run_test.get_nth_param(0).unwrap().into_pointer_value();
+    let test = run_test.get_nth_param(1).unwrap().into_pointer_value();
+
+    builder.position_at_end(context.append_basic_block(run_test, "entry"));
+
+    builder.build_indirect_call(context.void_type().fn_type(&[], false), test, &[], "")?;
+
+    build_print_result(context, module, builder, name, "ok")?;
+
+    builder.build_return(Some(&context.bool_type().const_int(1, false)))?;
+
+    Ok(run_test)
+}
+
+/// Generates the function running a single test in a forked process, the test has passed if the process has exited with `0`.
+/// The output of the process is written into a temporary file, which is printed if the test has failed.
+/// The function takes the name of the test and a pointer to it, and returns whether the test has passed.
+fn generate_forking_run_test<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    target_machine: &TargetMachine,
+) -> Result<FunctionValue<'ctx>>
+{
+    let ptr_type = context.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
+    let i32_type = context.i32_type();
+    let bool_type = context.bool_type();
+
+    // `size_t`, `ssize_t` and `off_t` are pointer sized on Linux
+    let size_type = context.ptr_sized_int_type(&target_machine.get_target_data(), None);
+
+    let declare = |name: &str, function_type| get_or_declare_function(module, name, function_type);
+
+    let fflush = declare("fflush", i32_type.fn_type(&[ptr_type.into()], false));
+    let tmpfile = declare("tmpfile", ptr_type.fn_type(&[], false));
+    let fileno = declare("fileno", i32_type.fn_type(&[ptr_type.into()], false));
+    let fclose = declare("fclose", i32_type.fn_type(&[ptr_type.into()], false));
+    let fork = declare("fork", i32_type.fn_type(&[], false));
+    let dup2 = declare(
+        "dup2",
+        i32_type.fn_type(&[i32_type.into(), i32_type.into()], false),
+    );
+    let exit = declare(
+        "exit",
+        context.void_type().fn_type(&[i32_type.into()], false),
+    );
+    let waitpid = declare(
+        "waitpid",
+        i32_type.fn_type(&[i32_type.into(), ptr_type.into(), i32_type.into()], false),
+    );
+    let lseek = declare(
+        "lseek",
+        size_type.fn_type(&[i32_type.into(), size_type.into(), i32_type.into()], false),
+    );
+    let read = declare(
+        "read",
+        size_type.fn_type(&[i32_type.into(), ptr_type.into(), size_type.into()], false),
+    );
+    let write = declare(
+        "write",
+        size_type.fn_type(&[i32_type.into(), ptr_type.into(), size_type.into()], false),
+    );
+
+    let run_test = module.add_function(
+        RUN_TEST_FUNCTION_NAME,
+        bool_type.fn_type(&[ptr_type.into(), ptr_type.into()], false),
+        Some(Linkage::Internal),
+    );
+
+    // It is safe to unwrap here, as the arguments are defined by the function type
+    let name = run_test.get_nth_param(0).unwrap().into_pointer_value();
+    let test = run_test.get_nth_param(1).unwrap().into_pointer_value();
+
+    let entry_block = context.append_basic_block(run_test, "entry");
+    let child_block = context.append_basic_block(run_test, "child");
+    let parent_block = context.append_basic_block(run_test, "parent");
+    let passed_block = context.append_basic_block(run_test, "passed");
+    let failed_block = context.append_basic_block(run_test, "failed");
+    let copy_block = context.append_basic_block(run_test, "copy_output");
+    let write_block = context.append_basic_block(run_test, "write_output");
+    let done_block = context.append_basic_block(run_test, "done");
+
+    builder.position_at_end(entry_block);
+
+    // If the process could not be forked or waited for, the test has failed
+    let status = builder.build_alloca(i32_type, "status")?;
+    builder.build_store(status, i32_type.const_int(1, false))?;
+
+    let buffer = builder.build_alloca(
+        context.i8_type().array_type(OUTPUT_BUFFER_SIZE),
+        "output_buffer",
+    )?;
+
+    // The buffered output of the harness must be written before forking, so that it is not written by the child process too
+    builder.build_call(fflush, &[ptr_type.const_null().into()], "")?;
+
+    let output = call_value(builder.build_call(tmpfile, &[], "output")?)?.into_pointer_value();
+    let output_fd =
+        call_value(builder.build_call(fileno, &[output.into()], "output_fd")?)?.into_int_value();
+
+    let pid = call_value(builder.build_call(fork, &[], "pid")?)?.into_int_value();
+
+    let is_child =
+        builder.build_int_compare(IntPredicate::EQ, pid, i32_type.const_zero(), "is_child")?;
+
+    builder.build_conditional_branch(is_child, child_block, parent_block)?;
+
+    // The child process writes its output into the temporary file, and exits with `0` if the test has returned
+    builder.position_at_end(child_block);
+
+    for standard_fd in [1, 2] {
+        builder.build_call(
+            dup2,
+            &[
+                output_fd.into(),
+                i32_type.const_int(standard_fd, false).into(),
+            ],
+            "",
+        )?;
+    }
+
+    builder.build_indirect_call(context.void_type().fn_type(&[], false), test, &[], "")?;
+    builder.build_call(exit, &[i32_type.const_zero().into()], "")?;
+    builder.build_unreachable()?;
+
+    builder.position_at_end(parent_block);
+
+    builder.build_call(
+        waitpid,
+        &[pid.into(), status.into(), i32_type.const_zero().into()],
+        "",
+    )?;
+
+    let status = builder
+        .build_load(i32_type, status, "status")?
+        .into_int_value();
+
+    let is_passed =
+        builder.build_int_compare(IntPredicate::EQ, status, i32_type.const_zero(), "is_passed")?;
+
+    builder.build_conditional_branch(is_passed, passed_block, failed_block)?;
+
+    builder.position_at_end(passed_block);
+
+    build_print_result(context, module, builder, name, "ok")?;
+
+    builder.build_unconditional_branch(done_block)?;
+
+    // Print the output of the failed test after its result
+    builder.position_at_end(failed_block);
+
+    build_print_result(context, module, builder, name, "FAILED")?;
+
+    builder.build_call(fflush, &[ptr_type.const_null().into()], "")?;
+
+    builder.build_call(
+        lseek,
+        &[
+            output_fd.into(),
+            size_type.const_zero().into(),
+            i32_type.const_zero().into(),
+        ],
+        "",
+    )?;
+
+    builder.build_unconditional_branch(copy_block)?;
+
+    builder.position_at_end(copy_block);
+
+    let read_size = call_value(builder.build_call(
+        read,
+        &[
+            output_fd.into(),
+            buffer.into(),
+            size_type.const_int(OUTPUT_BUFFER_SIZE as u64, false).into(),
+        ],
+        "read_size",
+    )?)?
+    .into_int_value();
+
+    let has_read = builder.build_int_compare(
+        IntPredicate::SGT,
+        read_size,
+        size_type.const_zero(),
+        "has_read",
+    )?;
+
+    builder.build_conditional_branch(has_read, write_block, done_block)?;
+
+    builder.position_at_end(write_block);
+
+    builder.build_call(
+        write,
+        &[
+            i32_type.const_int(1, false).into(),
+            buffer.into(),
+            read_size.into(),
+        ],
+        "",
+    )?;
+
+    builder.build_unconditional_branch(copy_block)?;
+
+    builder.position_at_end(done_block);
+
+    builder.build_call(fclose, &[output.into()], "")?;
+
+    builder.build_return(Some(&is_passed))?;
+
+    Ok(run_test)
+}
+
+/// Prints the result of the test, ie. `test adding_works ... ok`.
+fn build_print_result<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    name: PointerValue<'ctx>,
+    result: &str,
+) -> Result<()>
+{
+    let ptr_type = context.ptr_type(AddressSpace::from(DEFAULT_COMPILER_ADDRESS_SPACE_SIZE));
+
+    let printf = get_or_declare_function(
+        module,
+        "printf",
+        context.i32_type().fn_type(&[ptr_type.into()], true),
+    );
+
+    let format =
+        builder.build_global_string_ptr(&format!("test %s ... {result}\n"), "result_format")?;
+
+    builder.build_call(printf, &[format.as_pointer_value().into(), name.into()], "")?;
+
+    Ok(())
+}
+
+/// Increments the `i32` counter by one.
+fn build_increment<'ctx>(
+    context: &'ctx Context,
+    builder: &Builder<'ctx>,
+    counter: PointerValue<'ctx>,
+) -> Result<()>
+{
+    let i32_type = context.i32_type();
+
+    let count = builder
+        .build_load(i32_type, counter, "count")?
+        .into_int_value();
+
+    let count = builder.build_int_add(count, i32_type.const_int(1, false), "count")?;
+
+    builder.build_store(counter, count)?;
+
+    Ok(())
+}
@@ -0,0 +1,304 @@
+use codegen::{
+    abi::{FunctionAbi, TargetAbi, internal_symbol_name},
+    import::import_user_lib_functions,
+};
+use common::{
+    codegen::{CustomItem, StructAttributes, create_fn_type_from_ty_disc},
+    indexmap::IndexMap,
+    inkwell::{
+        OptimizationLevel,
+        builder::Builder,
+        context::Context,
+        module::{Linkage, Module},
+        targets::{CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine},
+        values::BasicValue,
+    },
+    parser::function::{FunctionArguments, FunctionDefinition, FunctionSignature},
+    ty::{OrdMap, Type},
+};
+use std::{collections::HashMap, env, path::Path, process::Command, rc::Rc};
+
+fn struct_type(name: &str, fields: &[(&str, Type)]) -> Type
+{
+    let mut struct_fields = OrdMap::new();
+
+    for (field_name, field_type) in fields {
+        struct_fields.insert(field_name.to_string(), field_type.clone());
+    }
+
+    Type::Struct((name.to_string(), struct_fields, StructAttributes::default()))
+}
+
+fn signature(name: &str, arguments: &[Type], return_type: Type) -> FunctionSignature
+{
+    let mut args = FunctionArguments::default();
+
+    for (idx, argument) in arguments.iter().enumerate() {
+        args.arguments
+            .insert(format!("arg{idx}"), (argument.clone(), idx));
+    }
+
+    FunctionSignature {
+        name: name.to_string(),
+        args,
+        return_type,
+    }
+}
+
+/// The functions implemented by `c_abi/library.c`, these are named `c_<name>` in C.
+fn c_functions() -> Vec<(&'static str, Vec<Type>, Type)>
+{
+    let tiny = struct_type("tiny", &[("a", Type::U8), ("b", Type::U8)]);
+    let trio = struct_type("trio", &[("a", Type::U8), ("b", Type::U8), ("c", Type::U8)]);
+    let pair = struct_type("pair", &[("x", Type::F32), ("y", Type::F32)]);
+    let mixed = struct_type("mixed", &[("id", Type::I32), ("weight", Type::F32)]);
+    let vec3 = struct_type(
+        "vec3",
+        &[("x", Type::F32), ("y", Type::F32), ("z", Type::F32)],
+    );
+    let span = struct_type("span", &[("data", Type::Pointer(None)), ("len", Type::U64)]);
+    let sample = struct_type("sample", &[("value", Type::F64), ("tag", Type::I32)]);
+    let matrix = struct_type("matrix", &[("m", Type::Array((Box::new(Type::F32), 4)))]);
+    let large = struct_type(
+        "large",
+        &[("a", Type::I64), ("b", Type::I64), ("c", Type::I64)],
+    );
+
+    let mut functions = Vec::new();
+
+    for (name, ty) in [
+        ("echo_tiny", tiny),
+        ("echo_trio", trio),
+        ("echo_pair", pair.clone()),
+        ("echo_mixed", mixed),
+        ("echo_vec3", vec3),
+        ("echo_span", span.clone()),
+        ("echo_sample", sample),
+        ("echo_matrix", matrix),
+        ("echo_large", large),
+    ] {
+        functions.push((name, vec![ty.clone()], ty));
+    }
+
+    functions.push(("sum_spans", vec![span; 4], Type::U64));
+    functions.push(("scale_pair", vec![Type::F32, pair.clone(), Type::U8], pair));
+    functions.push((
+        "combine",
+        vec![Type::U8, Type::I16, Type::Boolean],
+        Type::I16,
+    ));
+
+    functions
+}
+
+/// Creates a Fog function which calls `c_<name>`, and exports it as `fog_<name>` with the C calling convention.
+fn create_bridge<'ctx>(
+    context: &'ctx Context,
+    module: &Module<'ctx>,
+    builder: &Builder<'ctx>,
+    target_abi: &TargetAbi,
+    name: &str,
+    signature: &FunctionSignature,
+)
+{
+    let custom_types = Rc::new(IndexMap::<String, CustomItem>::new());
+
+    let function_abi =
+        FunctionAbi::new(context, target_abi, signature, custom_types.clone()).unwrap();
+
+    let external = module.get_function(&format!("c_{name}")).unwrap();
+
+    let exported_name = format!("fog_{name}");
+
+    let definition = module.add_function(
+        &internal_symbol_name(&exported_name),
+        create_fn_type_from_ty_disc(context, signature.clone(), custom_types).unwrap(),
+        Some(Linkage::Internal),
+    );
+
+    builder.position_at_end(context.append_basic_block(definition, "main"));
+
+    let returned = function_abi
+        .build_call(
+            context,
+            builder,
+            external,
+            &definition.get_params(),
+            "returned",
+        )
+        .unwrap();
+
+    builder
+        .build_return(returned.as_ref().map(|value| value as &dyn BasicValue))
+        .unwrap();
+
+    function_abi
+        .build_export_wrapper(context, module, builder, &exported_name, definition)
+        .unwrap();
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "x86_64"),
+    ignore = "only the x86-64 calling conventions are supported"
+)]
+fn structs_round_trip_through_c()
+{
+    Target::initialize_native(&InitializationConfig::default()).unwrap();
+
+    let target_triple = TargetMachine::get_default_triple();
+    let target_machine = Target::from_triple(&target_triple)
+        .unwrap()
+        .create_target_machine(
+            &target_triple,
+            "generic",
+            "",
+            OptimizationLevel::None,
+            // The test executable is linked as a position independent executable by default
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .unwrap();
+
+    let target_abi = TargetAbi::from_target_machine(&target_machine);
+
+    let context = Context::create();
+    let builder = context.create_builder();
+    let module = context.create_module("c_abi");
+
+    let functions = c_functions()
+        .into_iter()
+        .map(|(name, arguments, return_type)| {
+            (
+                name,
+                signature(&format!("c_{name}"), &arguments, return_type),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let imported_functions = functions
+        .iter()
+        .map(|(_name, signature)| (signature.name.clone(), signature.clone()))
+        .collect::<HashMap<_, _>>();
+
+    import_user_lib_functions(
+        &context,
+        &module,
+        &target_abi,
+        Rc::new(imported_functions),
+        Rc::new(IndexMap::<String, FunctionDefinition>::new()),
+        Rc::new(IndexMap::new()),
+    )
+    .unwrap();
+
+    for (name, signature) in &functions {
+        create_bridge(&context, &module, &builder, &target_abi, name, signature);
+    }
+
+    module.verify().unwrap();
+
+    module.set_triple(&target_triple);
+    module.set_data_layout(&target_machine.get_target_data().get_data_layout());
+
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_abi");
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let object_path = out_dir.join("fog.o");
+    let executable_path = out_dir.join("c_abi_test");
+
+    target_machine
+        .write_to_file(&module, FileType::Object, &object_path)
+        .unwrap();
+
+    let sources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/c_abi");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let compile_output = Command::new(&compiler)
+        .arg(sources.join("library.c"))
+        .arg(sources.join("main.c"))
+        .arg(&object_path)
+        .arg("-o")
+        .arg(&executable_path)
+        .output()
+        .unwrap_or_else(|err| panic!("failed to run the C compiler `{compiler}`: {err}"));
+
+    assert!(
+        compile_output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compile_output.stderr)
+    );
+
+    let test_output = Command::new(&executable_path).output().unwrap();
+
+    assert!(
+        test_output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&test_output.stderr)
+    );
+}
+
+#[test]
+fn small_structs_are_coerced_on_system_v()
+{
+    Target::initialize_x86(&InitializationConfig::default());
+
+    let target_triple = common::inkwell::targets::TargetTriple::create("x86_64-unknown-linux-gnu");
+    let target_machine = Target::from_triple(&target_triple)
+        .unwrap()
+        .create_target_machine(
+            &target_triple,
+            "generic",
+            "",
+            OptimizationLevel::None,
+            RelocMode::PIC,
+            CodeModel::Default,
+        )
+        .unwrap();
+
+    let target_abi = TargetAbi::from_target_machine(&target_machine);
+
+    let context = Context::create();
+    let module = context.create_module("c_abi_declarations");
+
+    let imported_functions = c_functions()
+        .into_iter()
+        .map(|(name, arguments, return_type)| {
+            let name = format!("c_{name}");
+            let signature = signature(&name, &arguments, return_type);
+
+            (name, signature)
+        })
+        .collect::<HashMap<_, _>>();
+
+    import_user_lib_functions(
+        &context,
+        &module,
+        &target_abi,
+        Rc::new(imported_functions),
+        Rc::new(IndexMap::new()),
+        Rc::new(IndexMap::new()),
+    )
+    .unwrap();
+
+    let ir = module.print_to_string().to_string();
+
+    for declaration in [
+        "declare i16 @c_echo_tiny(i16)",
+        "declare i32 @c_echo_trio(i32)",
+        "declare <2 x float> @c_echo_pair(<2 x float>)",
+        "declare i64 @c_echo_mixed(i64)",
+        "declare { <2 x float>, float } @c_echo_vec3(<2 x float>, float)",
+        "declare { i64, i64 } @c_echo_span(i64, i64)",
+        "declare { double, i64 } @c_echo_sample(double, i64)",
+        "declare { <2 x float>, <2 x float> } @c_echo_matrix(<2 x float>, <2 x float>)",
+        "declare void @c_echo_large(ptr noalias sret(%large) align 8, ptr byval(%large) align 8)",
+        "declare i64 @c_sum_spans(i64, i64, i64, i64, i64, i64, ptr byval(%span) align 8)",
+        "declare signext i16 @c_combine(i8 zeroext, i16 signext, i1 zeroext)",
+    ] {
+        assert!(
+            ir.contains(declaration),
+            "missing `{declaration}` in:\n{ir}"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+#include "structs.h"
+
+struct tiny c_echo_tiny(struct tiny value)
+{
+    return (struct tiny){value.a + 1, value.b + 1};
+}
+
+struct trio c_echo_trio(struct trio value)
+{
+    return (struct trio){value.a + 1, value.b + 1, value.c + 1};
+}
+
+struct pair c_echo_pair(struct pair value)
+{
+    return (struct pair){value.x * 2, value.y * 2};
+}
+
+struct mixed c_echo_mixed(struct mixed value)
+{
+    return (struct mixed){value.id + 1, value.weight * 2};
+}
+
+struct vec3 c_echo_vec3(struct vec3 value)
+{
+    return (struct vec3){value.x * 2, value.y * 2, value.z * 2};
+}
+
+struct span c_echo_span(struct span value)
+{
+    return (struct span){value.data, value.len + 1};
+}
+
+struct sample c_echo_sample(struct sample value)
+{
+    return (struct sample){value.value * 2, value.tag + 1};
+}
+
+struct matrix c_echo_matrix(struct matrix value)
+{
+    return (struct matrix){{value.m[0] * 2, value.m[1] * 2, value.m[2] * 2, value.m[3] * 2}};
+}
+
+struct large c_echo_large(struct large value)
+{
+    return (struct large){value.a + 1, value.b + 1, value.c + 1};
+}
+
+uint64_t c_sum_spans(struct span a, struct span b, struct span c, struct span d)
+{
+    return a.len + b.len * 10 + c.len * 100 + d.len * 1000;
+}
+
+struct pair c_scale_pair(float factor, struct pair value, uint8_t offset)
+{
+    return (struct pair){value.x * factor + offset, value.y * factor + offset};
+}
+
+int16_t c_combine(uint8_t a, int16_t b, bool add)
+{
+    return add ? a + b : a - b;
+}
@@ -0,0 +1,59 @@
+#include <stdio.h>
+
+#include "structs.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                                    \
+    do                                                                                      \
+    {                                                                                       \
+        if (!(condition))                                                                   \
+        {                                                                                   \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+            failures++;                                                                     \
+        }                                                                                   \
+    } while (0)
+
+int main(void)
+{
+    struct tiny tiny = fog_echo_tiny((struct tiny){1, 2});
+    CHECK(tiny.a == 2 && tiny.b == 3);
+
+    struct trio trio = fog_echo_trio((struct trio){1, 2, 3});
+    CHECK(trio.a == 2 && trio.b == 3 && trio.c == 4);
+
+    struct pair pair = fog_echo_pair((struct pair){1.5f, -2.0f});
+    CHECK(pair.x == 3.0f && pair.y == -4.0f);
+
+    struct mixed mixed = fog_echo_mixed((struct mixed){41, 0.25f});
+    CHECK(mixed.id == 42 && mixed.weight == 0.5f);
+
+    struct vec3 vec3 = fog_echo_vec3((struct vec3){1.0f, 2.0f, 3.0f});
+    CHECK(vec3.x == 2.0f && vec3.y == 4.0f && vec3.z == 6.0f);
+
+    int marker = 0;
+    struct span span = fog_echo_span((struct span){&marker, 7});
+    CHECK(span.data == &marker && span.len == 8);
+
+    struct sample sample = fog_echo_sample((struct sample){0.125, -5});
+    CHECK(sample.value == 0.25 && sample.tag == -4);
+
+    struct matrix matrix = fog_echo_matrix((struct matrix){{1.0f, 2.0f, 3.0f, 4.0f}});
+    CHECK(matrix.m[0] == 2.0f && matrix.m[1] == 4.0f && matrix.m[2] == 6.0f && matrix.m[3] == 8.0f);
+
+    struct large large = fog_echo_large((struct large){-1, 1000000000000, 3});
+    CHECK(large.a == 0 && large.b == 1000000000001 && large.c == 4);
+
+    /* The fourth span does not fit into the integer registers left on System V, so it is passed in memory. */
+    uint64_t sum = fog_sum_spans(
+        (struct span){0, 1}, (struct span){0, 2}, (struct span){0, 3}, (struct span){0, 4});
+    CHECK(sum == 4321);
+
+    struct pair scaled = fog_scale_pair(3.0f, (struct pair){1.0f, 2.0f}, 1);
+    CHECK(scaled.x == 4.0f && scaled.y == 7.0f);
+
+    CHECK(fog_combine(200, -300, true) == -100);
+    CHECK(fog_combine(200, -300, false) == 500);
+
+    return failures != 0;
+}
@@ -0,0 +1,101 @@
+/* The structs passed between Fog and C by the C ABI tests. */
+#ifndef FOG_C_ABI_TEST_H
+#define FOG_C_ABI_TEST_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+/* 2 bytes, a single integer register. */
+struct tiny
+{
+    uint8_t a;
+    uint8_t b;
+};
+
+/* 3 bytes, a single integer register on System V and a pointer on Win64. */
+struct trio
+{
+    uint8_t a;
+    uint8_t b;
+    uint8_t c;
+};
+
+/* 8 bytes of floats, a single vector register on System V. */
+struct pair
+{
+    float x;
+    float y;
+};
+
+/* 8 bytes of an integer and a float, a single integer register. */
+struct mixed
+{
+    int32_t id;
+    float weight;
+};
+
+/* 12 bytes of floats, two vector registers on System V. */
+struct vec3
+{
+    float x;
+    float y;
+    float z;
+};
+
+/* 16 bytes, two integer registers on System V. */
+struct span
+{
+    void* data;
+    uint64_t len;
+};
+
+/* 16 bytes, a vector and an integer register on System V. */
+struct sample
+{
+    double value;
+    int32_t tag;
+};
+
+/* 16 bytes of an array, two vector registers on System V. */
+struct matrix
+{
+    float m[4];
+};
+
+/* 24 bytes, passed and returned in memory. */
+struct large
+{
+    int64_t a;
+    int64_t b;
+    int64_t c;
+};
+
+/* Implemented in C, called by Fog. Every field is transformed, so swapped or dropped fields are detected. */
+struct tiny c_echo_tiny(struct tiny value);
+struct trio c_echo_trio(struct trio value);
+struct pair c_echo_pair(struct pair value);
+struct mixed c_echo_mixed(struct mixed value);
+struct vec3 c_echo_vec3(struct vec3 value);
+struct span c_echo_span(struct span value);
+struct sample c_echo_sample(struct sample value);
+struct matrix c_echo_matrix(struct matrix value);
+struct large c_echo_large(struct large value);
+uint64_t c_sum_spans(struct span a, struct span b, struct span c, struct span d);
+struct pair c_scale_pair(float factor, struct pair value, uint8_t offset);
+int16_t c_combine(uint8_t a, int16_t b, bool add);
+
+/* Exported from Fog, these call the C functions above. */
+struct tiny fog_echo_tiny(struct tiny value);
+struct trio fog_echo_trio(struct trio value);
+struct pair fog_echo_pair(struct pair value);
+struct mixed fog_echo_mixed(struct mixed value);
+struct vec3 fog_echo_vec3(struct vec3 value);
+struct span fog_echo_span(struct span value);
+struct sample fog_echo_sample(struct sample value);
+struct matrix fog_echo_matrix(struct matrix value);
+struct large fog_echo_large(struct large value);
+uint64_t fog_sum_spans(struct span a, struct span b, struct span c, struct span d);
+struct pair fog_scale_pair(float factor, struct pair value, uint8_t offset);
+int16_t fog_combine(uint8_t a, int16_t b, bool add);
+
+#endif /* FOG_C_ABI_TEST_H */
@@ -0,0 +1,255 @@
+use codegen::{abi::TargetAbi, irgen::generate_ir};
+use common::{
+    codegen::{CustomItem, If, StructAttributes},
+    error::{CharPosition, SpanInfo, Spanned},
+    indexmap::IndexMap,
+    inkwell::{
+        OptimizationLevel,
+        context::Context,
+        targets::{
+            CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+        },
+    },
+    parser::{
+        common::StatementVariant,
+        function::{FunctionArguments, FunctionDefinition, FunctionSignature},
+        numeric_value::MathematicalSymbol,
+    },
+    ty::{OrdMap, Type, Value},
+};
+use std::rc::Rc;
+
+fn span(line: usize, column: usize) -> SpanInfo
+{
+    SpanInfo::new(
+        CharPosition::new(line, column),
+        CharPosition::new(line, column + 1),
+    )
+}
+
+fn new_variable(
+    name: &str,
+    ty: Type,
+    id: usize,
+    line: usize,
+    column: usize,
+) -> Spanned<StatementVariant>
+{
+    Spanned::new(
+        StatementVariant::NewVariable {
+            variable_name: name.to_string(),
+            variable_type: ty.clone(),
+            variable_value: Box::new(Spanned::new(
+                StatementVariant::Value(ty.into_value_default()),
+                span(line, column),
+            )),
+            variable_id: id,
+            is_mutable: false,
+        },
+        span(line, column),
+    )
+}
+
+/// Generates the debug build IR of a function which declares every kind of variable for the target, and returns the printed module.
+fn generate_debug_module_for(target_triple: &TargetTriple) -> String
+{
+    Target::initialize_native(&InitializationConfig::default()).unwrap();
+
+    let context = Context::create();
+    let builder = context.create_builder();
+    let module = context.create_module("debug_info");
+
+    let mut point_fields = OrdMap::new();
+    point_fields.insert("x".to_string(), Type::I32);
+    point_fields.insert("y".to_string(), Type::F64);
+
+    let point = Type::Struct((
+        "Point".to_string(),
+        point_fields,
+        StructAttributes::default(),
+    ));
+
+    let mut color_variants = OrdMap::new();
+    color_variants.insert(
+        "Red".to_string(),
+        Spanned::new(StatementVariant::Value(Value::U8(0)), span(1, 0)),
+    );
+    color_variants.insert(
+        "Green".to_string(),
+        Spanned::new(StatementVariant::Value(Value::U8(4)), span(1, 0)),
+    );
+    color_variants.insert(
+        "Blue".to_string(),
+        Spanned::new(
+            StatementVariant::MathematicalExpression {
+                lhs: Box::new(Spanned::new(
+                    StatementVariant::Value(Value::U8(1)),
+                    span(1, 0),
+                )),
+                symbol: MathematicalSymbol::Addition,
+                rhs: Box::new(Spanned::new(
+                    StatementVariant::Value(Value::U8(1)),
+                    span(1, 0),
+                )),
+            },
+            span(1, 0),
+        ),
+    );
+
+    let color = Type::Enum((Box::new(Type::U8), color_variants));
+
+    let mut arguments = FunctionArguments::default();
+    arguments
+        .arguments
+        .insert("count".to_string(), (Type::I32, 0));
+
+    let body = vec![
+        new_variable("number", Type::I64, 1, 4, 4),
+        new_variable("point", point, 2, 5, 4),
+        new_variable("numbers", Type::Array((Box::new(Type::U32), 4)), 3, 6, 4),
+        new_variable("color", color, 4, 7, 4),
+        new_variable(
+            "number_ptr",
+            Type::Pointer(Some(Box::new(Type::I64))),
+            5,
+            8,
+            4,
+        ),
+        Spanned::new(
+            StatementVariant::If(If {
+                condition: Box::new(Spanned::new(
+                    StatementVariant::Value(Value::Boolean(true)),
+                    span(9, 7),
+                )),
+                true_branch: vec![new_variable("inner", Type::U8, 6, 10, 8)],
+                false_branch: vec![],
+            }),
+            span(9, 4),
+        ),
+        Spanned::new(
+            StatementVariant::Loop(vec![new_variable("counter", Type::U16, 7, 13, 8)]),
+            span(12, 4),
+        ),
+    ];
+
+    let function = FunctionDefinition {
+        signature: FunctionSignature {
+            name: "debug_info_test".to_string(),
+            args: arguments,
+            return_type: Type::Void,
+        },
+        body,
+        span: span(3, 9),
+        ..Default::default()
+    };
+
+    let mut functions = IndexMap::new();
+    functions.insert("debug_info_test".to_string(), function);
+
+    let target_machine = Target::from_triple(target_triple)
+        .unwrap()
+        .create_target_machine(
+            target_triple,
+            "generic",
+            "",
+            OptimizationLevel::None,
+            RelocMode::Default,
+            CodeModel::Default,
+        )
+        .unwrap();
+
+    module.set_triple(target_triple);
+    module.set_data_layout(&target_machine.get_target_data().get_data_layout());
+
+    generate_ir(
+        Rc::new(functions),
+        &context,
+        &module,
+        &builder,
+        Rc::new(IndexMap::<String, CustomItem>::new()),
+        &TargetAbi::from_target_machine(&target_machine),
+        false,
+        true,
+        "",
+        "main.f",
+    )
+    .unwrap();
+
+    module.verify().unwrap();
+
+    module.print_to_string().to_string()
+}
+
+fn generate_debug_module() -> String
+{
+    generate_debug_module_for(&TargetMachine::get_default_triple())
+}
+
+#[test]
+fn debug_build_emits_variable_metadata()
+{
+    let ir = generate_debug_module();
+
+    assert!(ir.contains(r#"DISubprogram(name: "debug_info_test""#));
+    assert!(ir.contains("isOptimized: false"));
+    assert!(ir.contains(r#"DILocalVariable(name: "count", arg: 1"#));
+
+    for local in [
+        "number",
+        "point",
+        "numbers",
+        "color",
+        "number_ptr",
+        "inner",
+        "counter",
+    ] {
+        assert!(
+            ir.contains(&format!(r#"DILocalVariable(name: "{local}""#)),
+            "missing local variable `{local}`"
+        );
+    }
+}
+
+#[test]
+fn debug_build_emits_composite_types()
+{
+    let ir = generate_debug_module();
+
+    assert!(ir.contains(r#"DICompositeType(tag: DW_TAG_structure_type, name: "Point""#));
+    assert!(ir.contains(r#"DIDerivedType(tag: DW_TAG_member, name: "y""#));
+    assert!(ir.contains("DICompositeType(tag: DW_TAG_array_type"));
+    assert!(ir.contains("DICompositeType(tag: DW_TAG_enumeration_type"));
+    assert!(ir.contains(r#"DIEnumerator(name: "Green", value: 4"#));
+
+    // The value of a variant which is not an integer literal is not known, so it is left out instead of being guessed
+    assert!(!ir.contains(r#"DIEnumerator(name: "Blue""#));
+    assert!(ir.contains("DIDerivedType(tag: DW_TAG_pointer_type"));
+}
+
+#[test]
+fn debug_build_emits_locations_and_scopes()
+{
+    let ir = generate_debug_module();
+
+    // Both the if branch and the loop body get their own scope.
+    assert_eq!(ir.matches("DILexicalBlock(").count(), 2);
+
+    // Columns are 1 based in DWARF.
+    assert!(ir.contains("DILocation(line: 5, column: 5"));
+    assert!(ir.contains("DILocation(line: 13, column: 9"));
+}
+
+#[test]
+fn debug_types_use_the_layout_of_the_compile_target()
+{
+    // Pointers are 32 bits wide on the target, regardless of the host
+    let ir = generate_debug_module_for(&TargetTriple::create("i686-unknown-linux-gnu"));
+
+    assert!(ir.contains("DW_TAG_pointer_type"));
+    assert!(
+        ir.lines()
+            .filter(|line| line.contains("DW_TAG_pointer_type"))
+            .all(|line| line.contains("size: 32")),
+        "{ir}"
+    );
+}
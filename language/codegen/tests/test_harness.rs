@@ -0,0 +1,187 @@
+use codegen::{llvm_codegen_main, test_harness::generate_test_harness, write_llvm_artifact};
+use common::{
+    compiler::{EmitKind, OptimizationSettings, ProjectConfig},
+    indexmap::IndexMap,
+    inkwell::{context::Context, targets::TargetTriple},
+    parser::{cfg::CfgTarget, common::Stream, function::FunctionDefinition},
+    ty::OrdSet,
+};
+use parser::{parser::Settings, tokenizer::tokenize};
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    rc::Rc,
+};
+
+const TARGET_TRIPLE: &str = "x86_64-unknown-linux-gnu";
+
+const TESTS: &str = r#"@test
+pub function arithmetic_works(): void {
+    assert_eq(1 + 1, 2);
+    assert_eq(300 - 1, 299);
+    assert(3 > 2);
+}
+
+@test
+pub function comparisons_work(): void {
+    assert(1 != 2);
+    assert_eq(1 < 2, true);
+    assert_eq(-4, 2 - 6);
+}
+
+@test
+pub function arithmetic_fails(): void {
+    assert_eq(1 + 1, 3);
+}
+"#;
+
+fn parse_tests(source: &str) -> IndexMap<String, FunctionDefinition>
+{
+    let settings = Settings::new(
+        ProjectConfig::default(),
+        vec!["main".to_string()],
+        OrdSet::new(),
+        CfgTarget::from_triple(TARGET_TRIPLE, true),
+        PathBuf::from("main.f"),
+    );
+
+    let context = settings
+        .parse(&mut Stream::new(tokenize(source).unwrap()))
+        .unwrap();
+
+    context
+        .functions
+        .iter()
+        .map(|(_path, name, definition)| (name.to_string(), definition.clone()))
+        .collect()
+}
+
+/// Compiles the tests and a test harness running them into an executable.
+fn build_harness(name: &str, source: &str, fork_per_test: bool) -> PathBuf
+{
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let functions = parse_tests(source);
+    let tests = functions.keys().cloned().collect::<Vec<_>>();
+
+    let context = Context::create();
+    let builder = context.create_builder();
+
+    let module = context.create_module("tests");
+
+    let target_machine = llvm_codegen_main(
+        &context,
+        &builder,
+        &module,
+        Rc::new(functions),
+        &OptimizationSettings::default(),
+        true,
+        Rc::new(HashMap::new()),
+        Rc::new(IndexMap::new()),
+        "",
+        "main.f",
+        Rc::new(TargetTriple::create(TARGET_TRIPLE)),
+        Some("generic".to_string()),
+        Some(String::new()),
+    )
+    .unwrap();
+
+    module.verify().unwrap();
+
+    let harness = context.create_module("harness");
+
+    generate_test_harness(
+        &context,
+        &harness,
+        &builder,
+        &target_machine,
+        &tests,
+        fork_per_test,
+    )
+    .unwrap();
+
+    harness.set_triple(&target_machine.get_triple());
+    harness.set_data_layout(&target_machine.get_target_data().get_data_layout());
+
+    harness.verify().unwrap();
+
+    let tests_object = out_dir.join("tests.o");
+    let harness_object = out_dir.join("harness.o");
+    let executable_path = out_dir.join("harness");
+
+    write_llvm_artifact(&module, &target_machine, EmitKind::Obj, &tests_object).unwrap();
+    write_llvm_artifact(&harness, &target_machine, EmitKind::Obj, &harness_object).unwrap();
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let link_output = Command::new(&compiler)
+        .arg(&tests_object)
+        .arg(&harness_object)
+        .arg("-o")
+        .arg(&executable_path)
+        .output()
+        .unwrap_or_else(|err| panic!("failed to run the C compiler `{compiler}`: {err}"));
+
+    assert!(
+        link_output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&link_output.stderr)
+    );
+
+    executable_path
+}
+
+fn run_harness(executable_path: &Path, filter: Option<&str>) -> (Output, String)
+{
+    let output = Command::new(executable_path).args(filter).output().unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+    (output, stdout)
+}
+
+#[test]
+fn failing_assertions_fail_the_harness()
+{
+    let harness = build_harness("test_harness_forking", TESTS, true);
+
+    let (output, stdout) = run_harness(&harness, None);
+
+    assert!(!output.status.success(), "{stdout}");
+    assert!(stdout.contains("test arithmetic_works ... ok"), "{stdout}");
+    assert!(stdout.contains("test comparisons_work ... ok"), "{stdout}");
+    assert!(
+        stdout.contains("test arithmetic_fails ... FAILED"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("assertion failed: `assert_eq` at arithmetic_fails:"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("2 passed; 1 failed"), "{stdout}");
+
+    let (output, stdout) = run_harness(&harness, Some("works"));
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("2 passed; 0 failed; 1 filtered out"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn failing_assertions_stop_the_harness_without_forking()
+{
+    let harness = build_harness("test_harness_in_process", TESTS, false);
+
+    let (output, stdout) = run_harness(&harness, Some("fails"));
+
+    assert_eq!(output.status.code(), Some(101), "{stdout}");
+
+    let (output, stdout) = run_harness(&harness, Some("work"));
+
+    assert!(output.status.success(), "{stdout}");
+}
@@ -0,0 +1,494 @@
+use std::path::{Path, PathBuf};
+
+use inkwell::targets::TargetMachine;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+use crate::compiler::EmitKind;
+
+/// Name of the project's config file, located in the root of the project.
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Returns the path of the config file of the project.
+pub fn config_path(project_root: &Path) -> PathBuf
+{
+    project_root.join(CONFIG_FILE_NAME)
+}
+
+/// Name of the project's lock file, located next to the config file.
+pub const LOCK_FILE_NAME: &str = "fog.lock";
+
+/// Returns the path of the lock file of the project.
+pub fn lock_file_path(project_root: &Path) -> PathBuf
+{
+    project_root.join(LOCK_FILE_NAME)
+}
+
+/// Name of the workspace manifest, located in the root of the workspace.
+pub const WORKSPACE_FILE_NAME: &str = "workspace.toml";
+
+/// Returns the path of the workspace manifest in the folder.
+pub fn workspace_path(workspace_root: &Path) -> PathBuf
+{
+    workspace_root.join(WORKSPACE_FILE_NAME)
+}
+
+/// Returns the root of the workspace containing `path`, this is the closest folder containing a workspace manifest.
+pub fn find_workspace_root(path: &Path) -> Option<PathBuf>
+{
+    path.ancestors()
+        .find(|ancestor| workspace_path(ancestor).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Returns the root of the project containing `path`, this is the closest folder containing a config file.
+pub fn find_project_root(path: &Path) -> Option<PathBuf>
+{
+    path.ancestors()
+        .find(|ancestor| config_path(ancestor).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Returns the path of the folder containing the source files of the project.
+pub fn source_dir(project_root: &Path) -> PathBuf
+{
+    project_root.join("src")
+}
+
+/// Returns the path of the main source file of the project.
+pub fn main_source_path(project_root: &Path) -> PathBuf
+{
+    source_dir(project_root).join("main.f")
+}
+
+/// Returns the path of every source file in the source folder of the project (including its subfolders), sorted by their path.
+pub fn source_files(project_root: &Path) -> std::io::Result<Vec<PathBuf>>
+{
+    let mut source_files = Vec::new();
+    let mut folders = vec![source_dir(project_root)];
+
+    while let Some(folder) = folders.pop() {
+        for entry in std::fs::read_dir(folder)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                folders.push(path);
+            }
+            else if path.extension().is_some_and(|extension| extension == "f") {
+                source_files.push(path);
+            }
+        }
+    }
+
+    source_files.sort();
+
+    Ok(source_files)
+}
+
+/// Returns the path of the folder containing the dependencies of the project.
+pub fn deps_dir(project_root: &Path) -> PathBuf
+{
+    project_root.join("deps")
+}
+
+/// Returns the target triple of the host machine, this is the target if none is specified.
+pub fn host_triple() -> String
+{
+    TargetMachine::get_default_triple()
+        .as_str()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The operating systems recognized in the `<os>` part of a target triple.
+/// Triples without a vendor (ie. `aarch64-linux-android`) are told apart from triples with one by these names.
+const OPERATING_SYSTEMS: &[&str] = &[
+    "linux",
+    "windows",
+    "win32",
+    "mingw",
+    "cygwin",
+    "darwin",
+    "macos",
+    "ios",
+    "tvos",
+    "watchos",
+    "visionos",
+    "xros",
+    "android",
+    "freebsd",
+    "netbsd",
+    "openbsd",
+    "dragonfly",
+    "solaris",
+    "illumos",
+    "fuchsia",
+    "wasi",
+    "emscripten",
+    "uefi",
+    "cuda",
+    "none",
+];
+
+/// The parts of a target triple: `<arch>-<vendor>-<os>(-<env>)`, or `<arch>-<os>(-<env>)` if it has no vendor.
+/// Every part of the compiler which depends on the target decides it from these components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TripleComponents<'a>
+{
+    pub arch: &'a str,
+    pub vendor: &'a str,
+    pub os: &'a str,
+    pub env: &'a str,
+}
+
+impl<'a> TripleComponents<'a>
+{
+    pub fn parse(target_triple: &'a str) -> Self
+    {
+        let components = target_triple.split('-').collect::<Vec<&str>>();
+        let component = |idx: usize| components.get(idx).copied().unwrap_or_default();
+
+        let is_os = |component: &str| OPERATING_SYSTEMS.iter().any(|os| component.starts_with(os));
+
+        // Triples with four components always have a vendor (ie. `x86_64-unknown-linux-gnu`)
+        if components.len() >= 4 || !is_os(component(1)) {
+            Self {
+                arch: component(0),
+                vendor: component(1),
+                os: component(2),
+                env: component(3),
+            }
+        }
+        else {
+            Self {
+                arch: component(0),
+                vendor: "",
+                os: component(1),
+                env: component(2),
+            }
+        }
+    }
+
+    /// Whether the architecture is 32-bit x86.
+    pub fn is_x86(&self) -> bool
+    {
+        matches!(self.arch, "i386" | "i486" | "i586" | "i686")
+    }
+
+    pub fn is_x86_64(&self) -> bool
+    {
+        matches!(self.arch, "x86_64" | "amd64")
+    }
+
+    /// Whether the architecture is 32-bit ARM (including Thumb).
+    pub fn is_arm(&self) -> bool
+    {
+        (self.arch.starts_with("arm") && self.arch != "arm64") || self.arch.starts_with("thumb")
+    }
+
+    /// Whether the target runs on the Linux kernel, this includes Android.
+    pub fn is_linux(&self) -> bool
+    {
+        self.os == "linux" || self.os == "android"
+    }
+
+    /// Whether the target is Windows with the GNU runtime (ie. `x86_64-w64-mingw32`, `x86_64-pc-windows-gnu`).
+    pub fn is_mingw(&self) -> bool
+    {
+        self.os.starts_with("mingw") || (self.os == "windows" && self.env.starts_with("gnu"))
+    }
+
+    /// The name of the architecture as used by `@cfg(target_arch = "...")`.
+    pub fn normalized_arch(&self) -> &'a str
+    {
+        match self.arch {
+            _ if self.is_x86_64() => "x86_64",
+            _ if self.is_x86() => "x86",
+            "arm64" | "aarch64" => "aarch64",
+            _ if self.is_arm() => "arm",
+            arch if arch.starts_with("riscv64") => "riscv64",
+            arch if arch.starts_with("riscv32") => "riscv32",
+            arch => arch,
+        }
+    }
+
+    /// The name of the operating system as used by `@cfg(target_os = "...")`, `none` for bare metal targets.
+    pub fn normalized_os(&self) -> &'static str
+    {
+        let is = |names: &[&str]| {
+            names
+                .iter()
+                .any(|name| self.os.starts_with(name) || self.env.starts_with(name))
+        };
+
+        // Android triples also contain `linux`, so it has to be checked first
+        if is(&["android"]) {
+            "android"
+        }
+        else if is(&["linux"]) {
+            "linux"
+        }
+        else if is(&["windows", "win32", "mingw", "cygwin"]) {
+            "windows"
+        }
+        else if is(&["darwin", "macos"]) {
+            "macos"
+        }
+        else if is(&["ios"]) {
+            "ios"
+        }
+        else if is(&["tvos"]) {
+            "tvos"
+        }
+        else if is(&["watchos"]) {
+            "watchos"
+        }
+        else if is(&["visionos", "xros"]) {
+            "visionos"
+        }
+        else if is(&["freebsd"]) {
+            "freebsd"
+        }
+        else if is(&["netbsd"]) {
+            "netbsd"
+        }
+        else if is(&["openbsd"]) {
+            "openbsd"
+        }
+        else if is(&["wasi"]) {
+            "wasi"
+        }
+        else {
+            "none"
+        }
+    }
+
+    /// The size of a pointer in bits.
+    pub fn pointer_width(&self) -> u32
+    {
+        if self.arch.contains("64") || matches!(self.arch, "s390x" | "sparcv9") {
+            64
+        }
+        else if matches!(self.arch, "avr" | "msp430") {
+            16
+        }
+        else {
+            32
+        }
+    }
+
+    pub fn object_format(&self) -> ObjectFormat
+    {
+        let is = |names: &[&str]| names.iter().any(|name| self.os.starts_with(name));
+
+        // MinGW (`*-w64-mingw32`) and Cygwin targets produce PE/COFF objects too
+        if is(&["windows", "win32", "mingw", "cygwin", "uefi"]) {
+            ObjectFormat::Coff
+        }
+        else if is(&[
+            "darwin", "macos", "ios", "tvos", "watchos", "visionos", "xros",
+        ]) {
+            ObjectFormat::MachO
+        }
+        else {
+            ObjectFormat::Elf
+        }
+    }
+}
+
+/// The object file format of a target, this decides how the build artifacts are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
+pub enum ObjectFormat
+{
+    /// Linux, BSDs and most other unix-like targets.
+    Elf,
+    /// Apple targets.
+    MachO,
+    /// Windows targets (PE/COFF).
+    Coff,
+}
+
+impl ObjectFormat
+{
+    /// Decides the object format from the operating system part of the target triple, see [`TripleComponents::object_format`].
+    pub fn from_triple(target_triple: &str) -> Self
+    {
+        TripleComponents::parse(target_triple).object_format()
+    }
+
+    pub fn object_extension(&self) -> &'static str
+    {
+        match self {
+            Self::Elf | Self::MachO => "o",
+            Self::Coff => "obj",
+        }
+    }
+
+    /// Executables do not have an extension outside of Windows.
+    pub fn executable_extension(&self) -> Option<&'static str>
+    {
+        match self {
+            Self::Elf | Self::MachO => None,
+            Self::Coff => Some("exe"),
+        }
+    }
+
+    pub fn static_library_extension(&self) -> &'static str
+    {
+        match self {
+            Self::Elf | Self::MachO => "a",
+            Self::Coff => "lib",
+        }
+    }
+
+    pub fn shared_library_extension(&self) -> &'static str
+    {
+        match self {
+            Self::Elf => "so",
+            Self::MachO => "dylib",
+            Self::Coff => "dll",
+        }
+    }
+
+    /// Libraries are prefixed with `lib` on unix-like targets, so that they can be found with `-l<name>`.
+    pub fn library_prefix(&self) -> &'static str
+    {
+        match self {
+            Self::Elf | Self::MachO => "lib",
+            Self::Coff => "",
+        }
+    }
+}
+
+/// The kinds of linked outputs a project can be built into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum OutputKind
+{
+    Executable,
+    StaticLibrary,
+    SharedLibrary,
+}
+
+/// Computes the path of every build artifact of a project for a specific target.
+/// Every artifact is placed into the build folder of the project and is named after the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactPaths
+{
+    build_dir: PathBuf,
+    name: String,
+    target_triple: String,
+    format: ObjectFormat,
+}
+
+impl ArtifactPaths
+{
+    /// `build_path` is the build folder relative to the project's root, as set in the config file.
+    pub fn new(project_root: &Path, build_path: &str, name: &str, target_triple: &str) -> Self
+    {
+        Self {
+            build_dir: project_root.join(build_path),
+            name: name.to_string(),
+            target_triple: target_triple.to_string(),
+            format: ObjectFormat::from_triple(target_triple),
+        }
+    }
+
+    /// Returns the paths of the artifacts emitted for a module of the project, these are named `<project name>.<module name>`.
+    pub fn for_module(&self, module_name: &str) -> Self
+    {
+        Self {
+            name: format!("{}.{module_name}", self.name),
+            ..self.clone()
+        }
+    }
+
+    pub fn build_dir(&self) -> &Path
+    {
+        &self.build_dir
+    }
+
+    pub fn format(&self) -> ObjectFormat
+    {
+        self.format
+    }
+
+    fn with_extension(&self, extension: &str) -> PathBuf
+    {
+        self.build_dir.join(format!("{}.{extension}", self.name))
+    }
+
+    /// Returns the path the artifact requested with `--emit` is written to.
+    pub fn emitted(&self, emit_kind: EmitKind) -> PathBuf
+    {
+        self.with_extension(emit_kind.extension(self.format))
+    }
+
+    pub fn object(&self) -> PathBuf
+    {
+        self.emitted(EmitKind::Obj)
+    }
+
+    pub fn ir(&self) -> PathBuf
+    {
+        self.emitted(EmitKind::Ir)
+    }
+
+    pub fn manifest(&self) -> PathBuf
+    {
+        self.with_extension("manifest")
+    }
+
+    /// The folder of the incremental compilation cache, the object files of the modules are stored here.
+    /// Every target has its own cache.
+    pub fn cache_dir(&self) -> PathBuf
+    {
+        self.build_dir.join("cache").join(&self.target_triple)
+    }
+
+    /// The C header declaring the exported functions of a library.
+    pub fn header(&self) -> PathBuf
+    {
+        self.with_extension("h")
+    }
+
+    /// Executables without an extension are placed in the `bin` folder, so that they cannot collide with the folders of the build folder (ie. a project named `cache`).
+    pub fn executable(&self) -> PathBuf
+    {
+        match self.format.executable_extension() {
+            Some(extension) => self.with_extension(extension),
+            None => self.build_dir.join("bin").join(&self.name),
+        }
+    }
+
+    pub fn static_library(&self) -> PathBuf
+    {
+        self.build_dir.join(format!(
+            "{}{}.{}",
+            self.format.library_prefix(),
+            self.name,
+            self.format.static_library_extension()
+        ))
+    }
+
+    pub fn shared_library(&self) -> PathBuf
+    {
+        self.build_dir.join(format!(
+            "{}{}.{}",
+            self.format.library_prefix(),
+            self.name,
+            self.format.shared_library_extension()
+        ))
+    }
+
+    /// Returns the path of the linked output.
+    pub fn output(&self, output_kind: OutputKind) -> PathBuf
+    {
+        match output_kind {
+            OutputKind::Executable => self.executable(),
+            OutputKind::StaticLibrary => self.static_library(),
+            OutputKind::SharedLibrary => self.shared_library(),
+        }
+    }
+}
@@ -58,6 +58,11 @@ impl StructAttributes
             impl_fn_list,
         }
     }
+
+    pub fn compiler_instructions(&self) -> &OrdSet<CompilerInstruction>
+    {
+        &self.compiler_instructions
+    }
 }
 
 /// Function implementation variant for a struct.
@@ -1,8 +1,20 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+};
 
+use inkwell::OptimizationLevel;
 use serde::{Deserialize, Serialize};
+use strum_macros::Display;
 
-use crate::{dependency::DependencyInfo, distributed_compiler::DistributedCompilerWorker};
+use crate::{
+    artifact::{ObjectFormat, OutputKind},
+    dependency::DependencyInfo,
+    distributed_compiler::DistributedCompilerWorker,
+    error::application::ApplicationError,
+    linker::Linker,
+    ty::OrdSet,
+};
 
 /// This contains the project's `config.toml`.
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -11,9 +23,15 @@ pub struct ProjectConfig
     pub name: String,
     pub is_library: bool,
 
-    /// This is only enabled if its a library
+    /// The features defined by the project, these can be used to gate items and statements with `@feature "<name>"`.
     pub features: Option<Vec<String>>,
 
+    /// The features enabled unless `--no-default-features` is passed in.
+    pub default_features: Option<Vec<String>>,
+
+    /// The features (`<feature>`) and the dependency features (`<dependency>/<feature>`) enabled by a feature, defined under `[feature_dependencies]`.
+    pub feature_dependencies: Option<HashMap<String, Vec<String>>>,
+
     /// This allows the user to use the remote compiler worker feature.
     pub remote_compiler_workers: Option<Vec<DistributedCompilerWorker>>,
 
@@ -21,6 +39,33 @@ pub struct ProjectConfig
     pub build_path: String,
     pub additional_linking_material: Vec<PathBuf>,
     pub dependencies: HashMap<String, DependencyInfo>,
+
+    /// Optimization profiles defined under `[profile.<name>]`.
+    /// The `dev` and `release` profiles are always available, defining them only overrides their settings.
+    pub profile: Option<HashMap<String, Profile>>,
+
+    /// The linker used to link the project, `clang` is used if not set.
+    pub linker: Option<Linker>,
+
+    /// The outputs a library is built into, `fog-lib` is used if not set.
+    /// This is ignored if the project is not a library.
+    pub library_types: Option<Vec<LibraryType>>,
+
+    /// A short description of the project, shown by the registry once the project is published.
+    pub description: Option<String>,
+
+    /// The license of the project (ie. `MIT` or `Apache-2.0`).
+    pub license: Option<String>,
+
+    /// The URL of the project's source repository.
+    pub repository: Option<String>,
+
+    /// Keywords the project can be searched by in the registry.
+    pub keywords: Option<Vec<String>>,
+
+    /// The URL of the registry the project's dependencies are published to.
+    /// The yanked and deprecated versions of the dependencies are looked up at the registry before they are resolved.
+    pub registry: Option<String>,
 }
 
 impl Default for ProjectConfig
@@ -31,11 +76,21 @@ impl Default for ProjectConfig
             name: "project".to_string(),
             is_library: false,
             features: None,
+            default_features: None,
+            feature_dependencies: None,
             remote_compiler_workers: None,
             version: "0.0.1".to_string(),
             build_path: "out".to_string(),
             additional_linking_material: Vec::new(),
             dependencies: HashMap::new(),
+            profile: None,
+            linker: None,
+            library_types: None,
+            description: None,
+            license: None,
+            repository: None,
+            keywords: None,
+            registry: None,
         }
     }
 }
@@ -49,6 +104,361 @@ impl ProjectConfig
             ..Default::default()
         }
     }
+
+    /// Returns the kinds of linked outputs the project is built into.
+    /// A `fog-lib` library does not have a linked output, as it is linked into the projects depending on it.
+    pub fn output_kinds(&self) -> Vec<OutputKind>
+    {
+        if !self.is_library {
+            return vec![OutputKind::Executable];
+        }
+
+        self.library_types
+            .as_deref()
+            .unwrap_or(&[LibraryType::FogLib])
+            .iter()
+            .filter_map(LibraryType::output_kind)
+            .collect()
+    }
+
+    /// Returns the [`Profile`] with the given name. The settings defined in the config file override the built-in settings of the profile.
+    pub fn get_profile(&self, name: &str) -> Result<Profile, ApplicationError>
+    {
+        let user_profile = self
+            .profile
+            .as_ref()
+            .and_then(|profiles| profiles.get(name))
+            .cloned();
+
+        let built_in_profile = match name {
+            "dev" => Profile::dev(),
+            "release" => Profile::release(),
+            _ => {
+                // Custom profiles are based on the `dev` profile
+                return user_profile
+                    .map(|profile| profile.or(Profile::dev()))
+                    .ok_or_else(|| ApplicationError::UnknownProfile(name.to_string()));
+            },
+        };
+
+        Ok(user_profile
+            .map(|profile| profile.or(built_in_profile.clone()))
+            .unwrap_or(built_in_profile))
+    }
+}
+
+/// The features selected with `--features`, `--all-features` and `--no-default-features`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSelection
+{
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+}
+
+impl FeatureSelection
+{
+    /// Selects the features on top of the default features, this is how the features of dependencies are selected.
+    pub fn with_defaults(features: impl IntoIterator<Item = String>) -> Self
+    {
+        Self {
+            features: features.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The features of a project enabled by a [`FeatureSelection`], see [`ProjectConfig::resolve_features`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnabledFeatures
+{
+    pub features: OrdSet<String>,
+    /// The features enabled in the dependencies of the project, by the project's enabled features.
+    pub dependency_features: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ProjectConfig
+{
+    /// Resolves the features enabled by the selection: the selected features, the default features and every feature enabled by them through `feature_dependencies`.
+    /// Every enabled feature must be defined in `features`, and every dependency a feature enables features of must be a dependency of the project.
+    pub fn resolve_features(
+        &self,
+        selection: &FeatureSelection,
+    ) -> Result<EnabledFeatures, ApplicationError>
+    {
+        let defined_features = self.features.clone().unwrap_or_default();
+
+        let mut pending = selection.features.clone();
+
+        if !selection.no_default_features {
+            pending.extend(self.default_features.iter().flatten().cloned());
+        }
+
+        if selection.all_features {
+            pending.extend(defined_features.iter().cloned());
+        }
+
+        let mut enabled_features = EnabledFeatures::default();
+
+        while let Some(feature) = pending.pop() {
+            if !defined_features.contains(&feature) {
+                return Err(ApplicationError::UnknownFeature(
+                    self.name.clone(),
+                    feature,
+                    defined_features,
+                ));
+            }
+
+            if enabled_features.features.contains(&feature) {
+                continue;
+            }
+
+            for enabled in self
+                .feature_dependencies
+                .as_ref()
+                .and_then(|feature_dependencies| feature_dependencies.get(&feature))
+                .into_iter()
+                .flatten()
+            {
+                match enabled.split_once('/') {
+                    Some((dependency, dependency_feature)) => {
+                        if !self.dependencies.contains_key(dependency) {
+                            return Err(ApplicationError::UnknownFeatureDependency(
+                                feature.clone(),
+                                dependency.to_string(),
+                            ));
+                        }
+
+                        enabled_features
+                            .dependency_features
+                            .entry(dependency.to_string())
+                            .or_default()
+                            .insert(dependency_feature.to_string());
+                    },
+                    None => pending.push(enabled.clone()),
+                }
+            }
+
+            enabled_features.features.insert(feature);
+        }
+
+        // The features are sorted, so that they are the same regardless of the order they have been selected in
+        enabled_features.features.sort();
+
+        Ok(enabled_features)
+    }
+}
+
+/// The outputs a library project can be built into, set with `library_types` in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Deserialize, Serialize)]
+pub enum LibraryType
+{
+    /// A static archive (`.a`, `.lib`) of the library's object files.
+    #[serde(rename = "staticlib")]
+    #[strum(serialize = "staticlib")]
+    StaticLib,
+    /// A shared library (`.so`, `.dylib`, `.dll`) exporting the `publib` functions.
+    #[serde(rename = "sharedlib")]
+    #[strum(serialize = "sharedlib")]
+    SharedLib,
+    /// The object files of the library, which are linked into the Fog projects depending on it.
+    #[serde(rename = "fog-lib")]
+    #[strum(serialize = "fog-lib")]
+    FogLib,
+}
+
+impl LibraryType
+{
+    pub fn output_kind(&self) -> Option<OutputKind>
+    {
+        match self {
+            Self::StaticLib => Some(OutputKind::StaticLibrary),
+            Self::SharedLib => Some(OutputKind::SharedLibrary),
+            Self::FogLib => None,
+        }
+    }
+}
+
+/// A `[profile.<name>]` section of the config file. Unset settings fall back to the profile the section overrides.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct Profile
+{
+    pub opt_level: Option<OptLevel>,
+
+    /// A custom LLVM pass pipeline (ie. `"function(mem2reg),globaldce"`), this replaces the pipeline of the `opt_level`.
+    pub passes: Option<String>,
+
+    pub lto: Option<LtoMode>,
+
+    /// Whether debug information should be generated.
+    pub debug: Option<bool>,
+}
+
+impl Profile
+{
+    pub fn dev() -> Self
+    {
+        Self {
+            opt_level: Some(OptLevel::O0),
+            passes: None,
+            lto: None,
+            debug: Some(true),
+        }
+    }
+
+    pub fn release() -> Self
+    {
+        Self {
+            opt_level: Some(OptLevel::O3),
+            passes: None,
+            lto: None,
+            debug: Some(false),
+        }
+    }
+
+    /// Fills the unset settings of the profile from `fallback`.
+    pub fn or(self, fallback: Self) -> Self
+    {
+        Self {
+            opt_level: self.opt_level.or(fallback.opt_level),
+            passes: self.passes.or(fallback.passes),
+            lto: self.lto.or(fallback.lto),
+            debug: self.debug.or(fallback.debug),
+        }
+    }
+
+    /// Creates the [`OptimizationSettings`] the compiler is run with.
+    pub fn optimization_settings(&self) -> OptimizationSettings
+    {
+        OptimizationSettings {
+            opt_level: self.opt_level.unwrap_or_default(),
+            passes: self.passes.clone(),
+            lto: self.lto,
+            debug_info: self.debug.unwrap_or(true),
+        }
+    }
+}
+
+/// Optimization level of the compiler, set with `-O<level>` or with `opt_level` in a profile.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Display,
+    Deserialize,
+    Serialize,
+    clap::ValueEnum,
+)]
+pub enum OptLevel
+{
+    /// No optimizations.
+    #[default]
+    #[value(name = "0")]
+    #[serde(rename = "0")]
+    #[strum(serialize = "0")]
+    O0,
+    #[value(name = "1")]
+    #[serde(rename = "1")]
+    #[strum(serialize = "1")]
+    O1,
+    #[value(name = "2")]
+    #[serde(rename = "2")]
+    #[strum(serialize = "2")]
+    O2,
+    /// Every optimization.
+    #[value(name = "3")]
+    #[serde(rename = "3")]
+    #[strum(serialize = "3")]
+    O3,
+    /// Optimizations for binary size.
+    #[value(name = "s")]
+    #[serde(rename = "s")]
+    #[strum(serialize = "s")]
+    Os,
+    /// Aggressive optimizations for binary size.
+    #[value(name = "z")]
+    #[serde(rename = "z")]
+    #[strum(serialize = "z")]
+    Oz,
+}
+
+impl OptLevel
+{
+    /// Returns the name of the level in LLVM's pass pipelines (ie. `O2` in `default<O2>`).
+    pub fn llvm_name(&self) -> &'static str
+    {
+        match self {
+            Self::O0 => "O0",
+            Self::O1 => "O1",
+            Self::O2 => "O2",
+            Self::O3 => "O3",
+            Self::Os => "Os",
+            Self::Oz => "Oz",
+        }
+    }
+
+    /// Returns the optimization level of the target machine generating the machine code.
+    pub fn codegen_level(&self) -> OptimizationLevel
+    {
+        match self {
+            Self::O0 => OptimizationLevel::None,
+            Self::O1 => OptimizationLevel::Less,
+            Self::O2 | Self::Os | Self::Oz => OptimizationLevel::Default,
+            Self::O3 => OptimizationLevel::Aggressive,
+        }
+    }
+}
+
+/// Link time optimization mode. When enabled, the object files are written as LLVM bitcode and are optimized together with the bitcode of the dependencies while linking.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Deserialize, Serialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum LtoMode
+{
+    Thin,
+    Full,
+}
+
+/// The optimization settings the project is compiled with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OptimizationSettings
+{
+    pub opt_level: OptLevel,
+    pub passes: Option<String>,
+    pub lto: Option<LtoMode>,
+    pub debug_info: bool,
+}
+
+impl OptimizationSettings
+{
+    /// Returns whether the `debug_assertions` cfg option is set, which is the case if the project is compiled without optimizations.
+    pub fn debug_assertions(&self) -> bool
+    {
+        self.opt_level == OptLevel::O0
+    }
+
+    /// Returns the pass pipeline run on the module.
+    /// If LTO is enabled, only the pre-link part of the pipeline is run as the rest runs while linking.
+    pub fn pass_pipeline(&self) -> String
+    {
+        if let Some(passes) = &self.passes {
+            return passes.clone();
+        }
+
+        let level = self.opt_level.llvm_name();
+
+        match self.lto {
+            None => format!("default<{level}>"),
+            Some(LtoMode::Thin) => format!("thinlto-pre-link<{level}>"),
+            Some(LtoMode::Full) => format!("lto-pre-link<{level}>"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -77,3 +487,48 @@ impl HostInformation
         }
     }
 }
+
+/// The artifacts which can be requested with `fog compile --emit`.
+/// The variants are ordered by the stage of the compilation producing them, the compiler stops after the stage of the last requested artifact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Display, clap::ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum EmitKind
+{
+    /// The token stream produced by the tokenizer.
+    Tokens,
+    /// The pretty-printed [`crate::parser::common::StatementVariant`] tree of the parsed functions.
+    Ast,
+    /// Textual Fog IR of the analyzed modules, see [`crate::fir`].
+    Fir,
+    /// Textual LLVM IR.
+    Ir,
+    /// LLVM bitcode.
+    Bc,
+    /// Assembly of the target.
+    Asm,
+    /// Object file of the target.
+    Obj,
+}
+
+impl EmitKind
+{
+    /// Returns the extension of the file the artifact is written to, object files are named after the convention of the target's object format.
+    pub fn extension(&self, format: ObjectFormat) -> &'static str
+    {
+        match self {
+            Self::Tokens => "tokens",
+            Self::Ast => "ast",
+            Self::Fir => "fir",
+            Self::Ir => "ll",
+            Self::Bc => "bc",
+            Self::Asm => "s",
+            Self::Obj => format.object_extension(),
+        }
+    }
+
+    /// Returns whether the artifact is produced by LLVM.
+    pub fn is_llvm_artifact(&self) -> bool
+    {
+        *self >= Self::Ir
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::application::ApplicationError;
+
+/// The name of the user-level file storing the API tokens.
+pub const CREDENTIALS_FILE_NAME: &str = "credentials.toml";
+
+/// Returns the path of the user-level credentials file.
+/// The file is located in the folder set by the `FOG_HOME` environment variable, or in the `.fog` folder of the user's home folder.
+pub fn credentials_path() -> Result<PathBuf, ApplicationError>
+{
+    let fog_home = match env::var_os("FOG_HOME") {
+        Some(fog_home) => PathBuf::from(fog_home),
+        None => {
+            env::home_dir()
+                .ok_or(ApplicationError::CredentialsPathNotFound)?
+                .join(".fog")
+        },
+    };
+
+    Ok(fog_home.join(CREDENTIALS_FILE_NAME))
+}
+
+/// The API tokens of the registries the user has logged in to.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Credentials
+{
+    /// The credentials of the registries, by the URL of the registry.
+    #[serde(default, rename = "registry")]
+    pub registries: BTreeMap<String, RegistryCredentials>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RegistryCredentials
+{
+    /// The API token created by the registry, see [`TokenScope`](crate::dependency_manager::TokenScope).
+    pub token: String,
+}
+
+impl Credentials
+{
+    /// Reads the credentials file at the path, the credentials are empty if the file does not exist yet.
+    pub fn read(credentials_path: &Path) -> Result<Self, ApplicationError>
+    {
+        if !credentials_path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let credentials =
+            fs::read_to_string(credentials_path).map_err(ApplicationError::FileError)?;
+
+        toml::from_str::<Self>(&credentials)
+            .map_err(|_| ApplicationError::InvalidCredentials(credentials_path.to_path_buf()))
+    }
+
+    /// Writes the credentials file to the path, creating its folder if it does not exist.
+    /// On Unix the file is only readable by the user.
+    pub fn write(&self, credentials_path: &Path) -> Result<(), ApplicationError>
+    {
+        if let Some(parent) = credentials_path.parent() {
+            fs::create_dir_all(parent).map_err(ApplicationError::FileError)?;
+        }
+
+        let credentials = toml::to_string(self)
+            .map_err(|_| ApplicationError::InvalidCredentials(credentials_path.to_path_buf()))?;
+
+        let mut open_options = fs::OpenOptions::new();
+
+        open_options.write(true).create(true).truncate(true);
+
+        // The file is only readable by the user from the moment it is created
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+
+            open_options.mode(0o600);
+        }
+
+        let mut file = open_options
+            .open(credentials_path)
+            .map_err(ApplicationError::FileError)?;
+
+        // Files created before the mode has been set may still be readable by other users
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            file.set_permissions(fs::Permissions::from_mode(0o600))
+                .map_err(ApplicationError::FileError)?;
+        }
+
+        file.write_all(credentials.as_bytes())
+            .map_err(ApplicationError::FileError)?;
+
+        Ok(())
+    }
+
+    /// Returns the credentials stored for the registry, the trailing `/` of the URL is ignored.
+    pub fn get(&self, registry: &str) -> Option<&RegistryCredentials>
+    {
+        self.registries.get(registry.trim_end_matches('/'))
+    }
+
+    /// Stores the credentials of the registry, replacing its previous credentials.
+    pub fn insert(&mut self, registry: &str, credentials: RegistryCredentials)
+    {
+        self.registries
+            .insert(registry.trim_end_matches('/').to_string(), credentials);
+    }
+
+    /// Removes the credentials of the registry, returns `None` if the user has not logged in to it.
+    pub fn remove(&mut self, registry: &str) -> Option<RegistryCredentials>
+    {
+        self.registries.remove(registry.trim_end_matches('/'))
+    }
+}
@@ -1,14 +1,99 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Value};
+
+use crate::error::dependency::DependencyError;
 
 /// Contains all the important information about a dependency in a config file.
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct DependencyInfo
 {
+    /// The version requirement of the dependency (ie. `^1.2`, `~0.3`, `>=1, <2`), any version matches if it is not set.
+    #[serde(default = "any_version")]
     pub version: String,
+    #[serde(default)]
     pub features: Vec<String>,
     pub remote: Option<String>,
+    /// The folder of the dependency relative to the project root, if it is developed next to the project instead of being stored in the dependencies folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// The version requirement matching every version.
+pub const ANY_VERSION: &str = "*";
+
+fn any_version() -> String
+{
+    ANY_VERSION.to_string()
+}
+
+/// Adds the dependency to the `[dependencies]` table of the config file's contents, replacing it if the project already depends on it.
+/// The formatting and the comments of the config file are preserved, the fields which are not set are left out.
+pub fn add_dependency_to_config(
+    config_file: &str,
+    name: &str,
+    dependency: &DependencyInfo,
+) -> anyhow::Result<String>
+{
+    let mut document = config_file.parse::<DocumentMut>()?;
+
+    let dependencies = document
+        .entry("dependencies")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or(DependencyError::InvalidDependencyTable)?;
+
+    let mut dependency_table = InlineTable::new();
+
+    if dependency.version != ANY_VERSION || dependency.path.is_none() {
+        dependency_table.insert("version", dependency.version.as_str().into());
+    }
+
+    if !dependency.features.is_empty() {
+        dependency_table.insert(
+            "features",
+            Array::from_iter(dependency.features.iter().map(String::as_str)).into(),
+        );
+    }
+
+    if let Some(remote) = &dependency.remote {
+        dependency_table.insert("remote", remote.as_str().into());
+    }
+
+    if let Some(path) = &dependency.path {
+        dependency_table.insert("path", path.to_string_lossy().to_string().into());
+    }
+
+    // The comments around a replaced dependency are kept
+    match dependencies.get_mut(name).and_then(Item::as_value_mut) {
+        Some(dependency_value) => {
+            let decor = dependency_value.decor().clone();
+
+            *dependency_value = Value::InlineTable(dependency_table);
+            *dependency_value.decor_mut() = decor;
+        },
+        None => {
+            dependencies.insert(name, Item::Value(Value::InlineTable(dependency_table)));
+        },
+    }
+
+    Ok(document.to_string())
+}
+
+/// Removes the dependency from the `[dependencies]` table of the config file's contents.
+/// The formatting and the comments of the config file are preserved.
+pub fn remove_dependency_from_config(config_file: &str, name: &str) -> anyhow::Result<String>
+{
+    let mut document = config_file.parse::<DocumentMut>()?;
+
+    document
+        .get_mut("dependencies")
+        .and_then(|dependencies| dependencies.as_table_like_mut())
+        .and_then(|dependencies| dependencies.remove(name))
+        .ok_or_else(|| DependencyError::DependencyNotRequired(name.to_string()))?;
+
+    Ok(document.to_string())
 }
 
 /// Can be used to fetch a dependency from a remote dependency manager.
@@ -21,6 +106,45 @@ pub struct DependencyRequest
     pub version: String,
 }
 
+/// The status of a version published to the registry.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Eq, PartialEq, Hash)]
+pub struct VersionStatus
+{
+    /// Yanked versions are not selected when the dependencies are resolved, unless they are locked in the lock file.
+    pub yanked: bool,
+    /// The reason the version has been deprecated, deprecated versions can still be selected.
+    pub deprecation: Option<String>,
+}
+
+/// The statuses of the dependencies' versions looked up at the registry, the versions without a status are neither yanked nor deprecated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryStatus
+{
+    versions: HashMap<String, HashMap<String, VersionStatus>>,
+}
+
+impl RegistryStatus
+{
+    pub fn insert(&mut self, dependency: &str, version: String, status: VersionStatus)
+    {
+        self.versions
+            .entry(dependency.to_string())
+            .or_default()
+            .insert(version, status);
+    }
+
+    pub fn get(&self, dependency: &str, version: &str) -> Option<&VersionStatus>
+    {
+        self.versions.get(dependency)?.get(version)
+    }
+
+    pub fn is_yanked(&self, dependency: &str, version: &str) -> bool
+    {
+        self.get(dependency, version)
+            .is_some_and(|status| status.yanked)
+    }
+}
+
 pub fn construct_dependency_path(
     deps_path: PathBuf,
     dependency_name: String,
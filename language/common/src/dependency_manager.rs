@@ -12,6 +12,10 @@ use chrono::NaiveDate;
 use diesel::{PgConnection, r2d2::ConnectionManager};
 #[cfg(feature = "dependency_manager")]
 use rand::TryRngCore;
+use sha2::{Digest, Sha256};
+use strum_macros::{Display, EnumString};
+
+use crate::compiler::ProjectConfig;
 
 /// ****
 /// PLEASE NOTE THAT THESE TYPES ARE COPIES OF THE TYPES FOUND IN THE DEPENDENCY MANAGER WORKSPACE. (It was easier to just copies of the type definitions due to dependencies and diesel.)
@@ -28,7 +32,10 @@ pub struct DependencyInformation
     pub dependency_version: String,
     pub author: String,
     pub date_added: NaiveDate,
-    pub secret: String,
+    /// Yanked versions are only sent if they are requested with their exact version.
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
 }
 
 /// Contains both the raw compressed bytes and the information
@@ -39,48 +46,265 @@ pub struct Dependency
     pub source: Vec<u8>,
 }
 
+/// The body of [`urls::api::PUBLISH_DEP`], sent with an API token with the [`TokenScope::Publish`] scope.
+/// The first version of a package is published by the account of the token, later versions can only be published by the owners of the package.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct DependencyUpdateRequest
+pub struct DependencyUpload
 {
     pub dependency_name: String,
-    // pub author: String,
-    pub secret: String,
-    pub updated_source: Vec<u8>,
+    pub dependency_version: String,
+    /// Compressed ZIP source files
+    pub source_files: Vec<u8>,
+    /// A short description of the package, shown in the search results of the registry.
+    pub description: Option<String>,
+    pub license: Option<String>,
+    /// The URL of the package's source repository.
+    pub repository: Option<String>,
+    /// Keywords the package can be searched by.
+    /// The metadata of the package is replaced with the metadata of every newly published version.
+    pub keywords: Vec<String>,
 }
 
+impl DependencyUpload
+{
+    /// Creates the upload of a project, the name, version and metadata of the package are taken from the project's config.
+    pub fn new(config: &ProjectConfig, source_files: Vec<u8>) -> Self
+    {
+        Self {
+            dependency_name: config.name.clone(),
+            dependency_version: config.version.clone(),
+            source_files,
+            description: config.description.clone(),
+            license: config.license.clone(),
+            repository: config.repository.clone(),
+            keywords: config.keywords.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// The body of [`urls::api::YANK_VERSION`], sent by an owner of the package with the [`TokenScope::Yank`] scope.
+/// Yanked versions are not selected for new dependency requirements, but the projects which have locked them can still use them.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct DependencyUploadReply
+pub struct YankRequest
 {
-    /// Updates can ONLY be uploaded with the use of this secret
-    pub secret_to_dep: String,
+    pub dependency_name: String,
+    pub dependency_version: String,
+    /// Whether the version is yanked or restored.
+    pub yanked: bool,
 }
 
+/// The body of [`urls::api::DEPRECATE_VERSION`], sent by an owner of the package with the [`TokenScope::Yank`] scope.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct DependencyUpload
+pub struct DeprecationRequest
 {
     pub dependency_name: String,
     pub dependency_version: String,
+    /// The reason the version is deprecated, the deprecation is removed if this is not set.
+    pub deprecation: Option<String>,
+}
+
+/// The actions an API token is allowed to perform.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Display,
+    EnumString,
+    serde::Deserialize,
+    serde::Serialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum TokenScope
+{
+    /// Publishing packages and new versions of them.
+    Publish,
+    /// Yanking and deprecating versions.
+    Yank,
+    /// Managing the tokens of the account and the owners of its packages.
+    Manage,
+}
+
+impl TokenScope
+{
+    pub const ALL: [TokenScope; 3] = [Self::Publish, Self::Yank, Self::Manage];
+}
+
+/// The body of [`urls::api::REGISTER`], the account is created with a token which has every scope.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RegisterRequest
+{
+    /// Usernames consist of ASCII letters, digits, `-` and `_`.
+    pub username: String,
+}
+
+/// The body of [`urls::api::CREATE_TOKEN`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenCreateRequest
+{
+    /// The name the token can be revoked by, it must be unique among the tokens of the account.
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+}
+
+/// The reply of [`urls::api::REGISTER`] and [`urls::api::CREATE_TOKEN`].
+/// The token is only sent once, the registry only stores its hash.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenReply
+{
+    pub username: String,
+    pub token: String,
+}
+
+/// The body of [`urls::api::REVOKE_TOKEN`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenRevokeRequest
+{
+    pub name: String,
+}
+
+/// A token of the account, listed by [`urls::api::LIST_TOKENS`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TokenInformation
+{
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub date_created: NaiveDate,
+}
+
+/// The body of [`urls::api::INVITE_OWNER`] and [`urls::api::REMOVE_OWNER`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OwnerRequest
+{
+    pub package_name: String,
+    pub username: String,
+}
+
+/// The body of [`urls::api::ACCEPT_INVITATION`] and query parameters of [`urls::api::LIST_OWNERS`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PackageRequest
+{
+    pub package_name: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PackageOwners
+{
+    pub package_name: String,
+    /// The usernames of the owners, sorted alphabetically.
+    pub owners: Vec<String>,
+}
+
+/// The body of [`urls::api::CLAIM_PACKAGE`].
+/// Packages published before accounts existed are owned by nobody, they can be claimed with the secret received when they were first published.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ClaimRequest
+{
+    pub package_name: String,
+    pub secret: String,
+}
+
+/// The number of items returned on a page by the paginated endpoints.
+pub const PAGE_SIZE: i64 = 20;
+
+/// Query parameters of [`urls::api::SEARCH`], the packages are searched by their name, description and keywords.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SearchRequest
+{
+    pub query: String,
+    /// Pages are numbered from `0`.
+    #[serde(default)]
+    pub page: i64,
+}
+
+/// Query parameters of [`urls::api::RECENTLY_PUBLISHED`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PageRequest
+{
+    /// Pages are numbered from `0`.
+    #[serde(default)]
+    pub page: i64,
+}
+
+/// Query parameters of [`urls::api::PACKAGE_VERSIONS`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct VersionListRequest
+{
+    pub package_name: String,
+}
+
+/// A package found by a search, along with its latest version.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PackageSummary
+{
+    pub package_name: String,
     pub author: String,
-    /// Compressed ZIP source files
-    pub source_files: Vec<u8>,
+    /// The newest version which has not been yanked.
+    pub latest_version: String,
+    /// The date the latest version was published.
+    pub date_updated: NaiveDate,
+    /// The reason the latest version has been deprecated.
+    pub deprecation: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    pub keywords: Vec<String>,
 }
 
-impl DependencyUpload
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SearchResults
 {
-    pub fn new(
-        dependency_name: String,
-        dependency_version: String,
-        author: String,
-        source_files: Vec<u8>,
-    ) -> Self
-    {
-        Self {
-            dependency_name,
-            dependency_version,
-            author,
-            source_files,
-        }
-    }
+    /// The packages on the requested page, the best matches come first.
+    pub packages: Vec<PackageSummary>,
+    pub page: i64,
+    pub page_count: i64,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PublishedVersion
+{
+    pub version: String,
+    pub date_added: NaiveDate,
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct VersionListing
+{
+    pub package_name: String,
+    /// The published versions of the package, the newest version comes first.
+    pub versions: Vec<PublishedVersion>,
+}
+
+/// A version published to the registry, listed by [`urls::api::RECENTLY_PUBLISHED`].
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RecentlyPublished
+{
+    pub package_name: String,
+    pub version: String,
+    pub author: String,
+    pub description: Option<String>,
+    pub date_added: NaiveDate,
+    pub yanked: bool,
+    /// The reason the version has been deprecated.
+    pub deprecation: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RecentlyPublishedPage
+{
+    /// The published versions on the requested page, the most recent version comes first.
+    pub versions: Vec<RecentlyPublished>,
+    pub page: i64,
+    pub page_count: i64,
 }
 
 pub fn write_folder_items<T: Write + Seek>(
@@ -150,6 +374,30 @@ pub fn generate_secret<const LEN: usize>(state: &ServerState) -> String
     state.base64_engine.encode(bytes)
 }
 
+/// Hashes an API token or a dependency secret so that it can be stored and compared without storing the secret itself.
+/// Secrets are random bytes generated by [`generate_secret`], so a single SHA-256 round is sufficient.
+pub fn hash_secret(secret: &str) -> String
+{
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Checks whether `secret` matches the stored `secret_hash`.
+/// The comparison takes the same amount of time regardless of where the hashes differ.
+pub fn verify_secret(secret: &str, secret_hash: &str) -> bool
+{
+    let hash = hash_secret(secret);
+
+    hash.len() == secret_hash.len()
+        && hash
+            .bytes()
+            .zip(secret_hash.bytes())
+            .fold(0, |acc, (lhs, rhs)| acc | (lhs ^ rhs))
+            == 0
+}
+
 pub mod urls
 {
     pub const STATUS: &str = "/api/status";
@@ -159,5 +407,19 @@ pub mod urls
         pub const FETCH_DEP: &str = "/api/fetch_depdendency";
         pub const FETCH_DEP_INFO: &str = "/api/fetch_dependency_information";
         pub const PUBLISH_DEP: &str = "/api/publish_dependency";
+        pub const SEARCH: &str = "/api/search";
+        pub const PACKAGE_VERSIONS: &str = "/api/package_versions";
+        pub const RECENTLY_PUBLISHED: &str = "/api/recently_published";
+        pub const YANK_VERSION: &str = "/api/yank_version";
+        pub const DEPRECATE_VERSION: &str = "/api/deprecate_version";
+        pub const REGISTER: &str = "/api/register";
+        pub const CREATE_TOKEN: &str = "/api/create_token";
+        pub const REVOKE_TOKEN: &str = "/api/revoke_token";
+        pub const LIST_TOKENS: &str = "/api/list_tokens";
+        pub const INVITE_OWNER: &str = "/api/invite_owner";
+        pub const ACCEPT_INVITATION: &str = "/api/accept_invitation";
+        pub const REMOVE_OWNER: &str = "/api/remove_owner";
+        pub const LIST_OWNERS: &str = "/api/list_owners";
+        pub const CLAIM_PACKAGE: &str = "/api/claim_package";
     }
 }
@@ -27,6 +27,10 @@ pub struct DependencyRequest
     pub cpu_features: Option<String>,
     pub cpu_name: Option<String>,
     pub flags_passed_in: String,
+    /// The checksum of the dependency's sources in the lock file of the requesting project, see [`LockedDependency::source_checksum`](crate::lockfile::LockedDependency::source_checksum).
+    /// The sources downloaded from the registry are only compiled if they match it.
+    #[serde(default)]
+    pub source_checksum: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,4 +53,6 @@ pub struct FinishedJob
     pub artifacts_zip_bytes: Vec<u8>,
     pub dependency_config: ProjectConfig,
     pub build_manifest: BuildManifest,
+    /// The checksum of the sources the dependency has been compiled from, see [`checksum_directory`](crate::lockfile::checksum_directory).
+    pub source_checksum: String,
 }
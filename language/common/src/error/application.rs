@@ -26,4 +26,48 @@ pub enum ApplicationError
 
     #[error("An error occured while parsing the project's dependencies: {0}")]
     DependencyError(anyhow::Error),
+
+    #[error(
+        "Profile `{0}` is not defined. Profiles other than `dev` and `release` must be defined in the config file under `[profile.{0}]`."
+    )]
+    UnknownProfile(String),
+
+    /// The first argument is the project, the second is the feature, the third lists the features defined by the project.
+    #[error(
+        "Project `{0}` does not define feature `{1}`. Features must be defined in the config file under `features`, defined features: {2:?}."
+    )]
+    UnknownFeature(String, String, Vec<String>),
+
+    #[error("Feature `{0}` enables features of `{1}`, which is not a dependency of the project.")]
+    UnknownFeatureDependency(String, String),
+
+    #[error("The project is a library, it does not have an executable which could be run.")]
+    LibraryNotRunnable,
+
+    #[error("Checking the project has found {0} error(s).")]
+    CheckFailed(usize),
+
+    #[error("One or more tests have failed.")]
+    TestsFailed,
+
+    #[error(
+        "The latest version of dependency `{0}` could not be looked up. Set the registry with `--registry <URL>` or in the config file, or set the version requirement with `--version <REQUIREMENT>`."
+    )]
+    RegistryRequired(String),
+
+    #[error("The registry responded with `{0}`: {1}")]
+    RegistryRequestFailed(String, String),
+
+    #[error(
+        "You are not logged in to `{0}`. Log in with `fog login --url {0}` or pass in a token with `--token <TOKEN>`."
+    )]
+    NotLoggedIn(String),
+
+    #[error("The credentials file at `{0}` could not be parsed.")]
+    InvalidCredentials(PathBuf),
+
+    #[error(
+        "The credentials file could not be located, set its folder with the `FOG_HOME` environment variable."
+    )]
+    CredentialsPathNotFound,
 }
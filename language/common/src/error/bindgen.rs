@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BindgenError
+{
+    #[error("Unexpected `{1}` in the C header at line {0}.")]
+    UnexpectedToken(usize, String),
+    #[error("The C header ended in the middle of a declaration.")]
+    UnexpectedEof,
+    #[error("Comment opened at line {0} is never closed.")]
+    UnterminatedComment(usize),
+    #[error("Literal opened at line {0} is never closed.")]
+    UnterminatedLiteral(usize),
+    #[error("`additional_linking_material` must be an array of paths in the config file.")]
+    InvalidLinkingMaterial,
+}
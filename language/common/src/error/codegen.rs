@@ -27,6 +27,8 @@ pub enum CodeGenError
     VariableTypeMismatch(Type, Type),
     #[error("[INTERNAL ERROR] The automatic optimizer has failed after the code generation.")]
     InternalOptimisationPassFailed,
+    #[error("The custom pass pipeline `{0}` could not be run: {1}")]
+    InvalidPassPipeline(String, String),
     #[error("[INTERNAL ERROR] Failed to get TargetTriple for host.")]
     FaliedToAcquireTargetTriple,
     #[error(
@@ -80,6 +82,10 @@ pub enum CodeGenError
     InvalidIndexValue(StatementVariant),
     #[error("ParsedToken `{0}` is not a valid variable reference.")]
     InvalidVariableReference(StatementVariant),
+    #[error(
+        "`{0}` cannot be passed to a built-in function. Only literals, arguments and their arithmetic and comparisons are supported."
+    )]
+    UnsupportedBuiltinArgument(StatementVariant),
 
     /// This error can only be returned when an error occured thorugh LLVM-SYS itself.
     #[error("An error has occured while generating LLVM-IR: `{0}`.")]
@@ -99,4 +105,12 @@ pub enum CodeGenError
     EnumInnerTypeMismatch(Type, Type),
     #[error("Traits are not valid types.")]
     TraitIsNotType,
+    #[error("Type `{1}` used by exported function `{0}` cannot be represented in a C header.")]
+    UnsupportedCHeaderType(String, Type),
+    #[error(
+        "Structs and arrays cannot be passed to or returned from C functions on target `{0}`. Only the x86-64 calling conventions are supported."
+    )]
+    UnsupportedAbiTarget(String),
+    #[error("Type `{0}` cannot be passed to or returned from C functions.")]
+    UnsupportedAbiType(Type),
 }
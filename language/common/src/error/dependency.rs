@@ -33,4 +33,43 @@ pub enum DependencyError
     PublishFailedToCompress,
     #[error("Handler thread could not establish a connection to remote `{0}`.")]
     FailedConnectingToRemote(String),
+    #[error("Dependency `{0}` has an invalid version requirement `{1}`.")]
+    InvalidVersionRequirement(String, String),
+    #[error(
+        "No version of dependency `{0}` matches the requirement `{1}`. Available versions: {2:?}."
+    )]
+    NoMatchingVersion(String, String, Vec<String>),
+    #[error(
+        "Every version of dependency `{0}` matching the requirements has been yanked. A yanked version can only be used if it is locked in the lock file."
+    )]
+    MatchingVersionsYanked(String),
+    /// The first argument is the dependency, the second lists the projects requiring it with their requirements.
+    #[error("Dependency `{0}` is required with incompatible versions: {1}.")]
+    VersionConflict(String, String),
+    #[error(
+        "The versions of the dependencies could not be resolved, the requirements keep changing between the selected versions."
+    )]
+    UnresolvableDependencies,
+    #[error("The lock file at `{0}` could not be parsed.")]
+    InvalidLockFile(PathBuf),
+    #[error(
+        "The lock file at `{0}` is out of date. Run the command without `--locked` to update it."
+    )]
+    LockFileOutdated(PathBuf),
+    /// The first argument is the dependency, the second is the locked checksum, the third is the checksum of the fetched dependency.
+    #[error(
+        "The checksum of dependency `{0}` does not match the lock file, expected `{1}` but found `{2}`."
+    )]
+    ChecksumMismatch(String, String, String),
+    /// The first argument is the dependency, the second and third are the paths it is required with.
+    #[error("Dependency `{0}` is required with different paths: `{1}` and `{2}`.")]
+    ConflictingDependencyPaths(String, PathBuf, PathBuf),
+    #[error(
+        "Dependency `{0}` has an invalid version `{1}`. The version must follow the semver specification."
+    )]
+    InvalidDependencyVersion(String, String),
+    #[error("The `dependencies` field of the config file must be a table.")]
+    InvalidDependencyTable,
+    #[error("The project does not depend on `{0}`.")]
+    DependencyNotRequired(String),
 }
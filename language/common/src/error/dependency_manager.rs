@@ -5,6 +5,8 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::dependency_manager::TokenScope;
+
 #[derive(Debug, Error, Serialize, Deserialize)]
 pub enum DependencyManagerError
 {
@@ -30,14 +32,72 @@ pub enum DependencyManagerError
     DependencyNotFound,
     #[error("Invalid path linked to dependency in database")]
     InvalidFileError,
+    #[error("The secret provided does not match the dependency's secret")]
+    InvalidSecret,
+    #[error("The request must be authenticated with an API token")]
+    MissingToken,
+    #[error("The API token is invalid or has been revoked")]
+    InvalidToken,
+    #[error("The API token does not have the `{0}` scope")]
+    MissingScope(TokenScope),
+    #[error("Only the owners of `{0}` can perform this action")]
+    NotAnOwner(String),
+    #[error("Username `{0}` is already taken")]
+    UsernameTaken(String),
+    #[error(
+        "`{0}` is not a valid username, usernames consist of ASCII letters, digits, `-` and `_`"
+    )]
+    InvalidUsername(String),
+    #[error(
+        "`{0}` is not a valid package name, package names consist of ASCII letters, digits, `-` and `_`"
+    )]
+    InvalidPackageName(String),
+    #[error("User `{0}` does not exist")]
+    UserNotFound(String),
+    #[error("The account already has a token named `{0}`")]
+    TokenNameTaken(String),
+    #[error("The account does not have a token named `{0}`")]
+    TokenNotFound(String),
+    #[error("There is no pending invitation to become an owner of `{0}`")]
+    InvitationNotFound(String),
+    #[error("`{1}` is already an owner of `{0}`")]
+    AlreadyOwner(String, String),
+    #[error("`{1}` is not an owner of `{0}`")]
+    OwnerNotFound(String, String),
+    #[error("`{1}` is the last owner of `{0}` and cannot be removed")]
+    LastOwner(String, String),
+    #[error("`{0}` has already been claimed by an account")]
+    PackageAlreadyClaimed(String),
+    #[error("Version `{0}` of this dependency has already been published")]
+    VersionAlreadyExists(String),
+    #[error("`{0}` is not a valid semantic version")]
+    InvalidVersion(String),
 }
 
 impl IntoResponse for DependencyManagerError
 {
     fn into_response(self) -> axum::response::Response
     {
-        let body = self.to_string();
+        let status_code = match self {
+            Self::MissingToken | Self::InvalidToken => StatusCode::UNAUTHORIZED,
+            Self::InvalidSecret | Self::MissingScope(_) | Self::NotAnOwner(_) => {
+                StatusCode::FORBIDDEN
+            },
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        (status_code, self.to_string()).into_response()
+    }
+}
+
+/// Database errors are only logged, the clients are only told that the request has failed.
+#[cfg(feature = "dependency_manager")]
+impl From<diesel::result::Error> for DependencyManagerError
+{
+    fn from(err: diesel::result::Error) -> Self
+    {
+        eprintln!("An error occured while accessing the db: {}", err);
 
-        (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+        Self::GenericDatabaseError
     }
 }
@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+pub enum FirError
+{
+    #[error("Unexpected end of Fog IR, a list opened on line {0} has not been closed.")]
+    UnexpectedEndOfInput(usize),
+    #[error("Unmatched closing parenthesis on line {0}.")]
+    UnmatchedParenthesis(usize),
+    #[error("Unterminated string literal starting on line {0}.")]
+    UnterminatedString(usize),
+    #[error("Invalid escape sequence `\\{1}` on line {0}.")]
+    InvalidEscapeSequence(usize, String),
+    #[error("Expected {1} on line {0}.")]
+    Expected(usize, &'static str),
+    #[error("Unknown {1} `{2}` on line {0}.")]
+    Unknown(usize, &'static str, String),
+    #[error("Invalid number `{1}` on line {0}.")]
+    InvalidNumber(usize, String),
+    #[error(
+        "Invalid span `{1}` on line {0}. Spans are written like so: `@<line>:<column>-<line>:<column>`."
+    )]
+    InvalidSpan(usize, String),
+    #[error("A Fog IR source must contain exactly one `module`.")]
+    InvalidModuleCount,
+}
@@ -17,4 +17,50 @@ pub enum LinkerError
         "Additional linking material included in the project's config (path: `{0}`) has not been found or is invalid."
     )]
     AdditionalLinkingMaterialNotFound(PathBuf),
+    #[error(
+        "Linker `{0}` not found. It must be added to PATH or set as a path in the project's config."
+    )]
+    LinkerNotFound(String),
+    #[error(
+        "Linking with LLD is not supported for target `{0}`. Set `linker = \"clang\"` in the project's config to link for this target."
+    )]
+    UnsupportedLldTarget(String),
+    #[error(
+        "C runtime startup object `{0}` has not been found for target `{1}`. The C library of the target must be installed to link with LLD."
+    )]
+    CrtObjectNotFound(String, String),
+    #[error(
+        "The macOS SDK has not been found. Set `SDKROOT` or install the Xcode command line tools to link with LLD."
+    )]
+    MacOsSdkNotFound,
+    #[error("Linking `{0}` failed: {1}")]
+    LinkingFailed(PathBuf, String),
+    #[error(
+        "`llvm-ar` not found at PATH. It must be added to PATH inorder to create static libraries."
+    )]
+    ArchiverNotFound,
+    #[error(
+        "Build manifest version `{0}` is not supported, the compiler links version `{1}`. Rebuild the project to create a new manifest."
+    )]
+    UnsupportedManifestVersion(u32, u32),
+    #[error(
+        "The build manifest has been created by compiler version `{0}`, but the current version is `{1}`. Rebuild the project to link it."
+    )]
+    CompilerVersionMismatch(String, String),
+    #[error(
+        "The build manifest has been created for target `{0}`, but target `{1}` has been requested."
+    )]
+    TargetMismatch(String, String),
+    #[error("Build artifact `{0}` has not been found or could not be read.")]
+    ArtifactNotFound(PathBuf),
+    #[error("Build artifact `{0}` is not recorded in the build manifest.")]
+    MissingArtifactHash(PathBuf),
+    #[error(
+        "Build artifact `{0}` has been modified since the build manifest was created. Rebuild the project to link it."
+    )]
+    StaleArtifact(PathBuf),
+    #[error("Build output `{0}` is not located inside of the project (root: `{1}`).")]
+    PathOutsideProject(PathBuf, PathBuf),
+    #[error("The project root of `{0}` has not been found.")]
+    ProjectRootNotFound(PathBuf),
 }
@@ -1,13 +1,17 @@
 use std::{fmt::Display, fs, ops::Deref, path::PathBuf};
 
 pub mod application;
+pub mod bindgen;
 pub mod cliparser;
 pub mod codegen;
 pub mod dependency;
 pub mod dependency_manager;
+pub mod fir;
 pub mod linker;
 pub mod parser;
+pub mod semantic;
 pub mod syntax;
+pub mod workspace;
 
 #[derive(Clone, Debug)]
 pub struct ErrorWrapper<T>
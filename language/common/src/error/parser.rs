@@ -3,7 +3,9 @@ use thiserror::Error;
 
 use crate::{
     error::syntax::SyntaxError,
-    parser::{common::StatementVariant, variable::VariableReference},
+    parser::{
+        common::StatementVariant, function::CompilerInstruction, variable::VariableReference,
+    },
     tokenizer::Token,
     ty::Type,
 };
@@ -155,14 +157,24 @@ pub enum ParserError
     ItemTypeExpected,
     #[error("Token `{0}` is not a valid compiler hint.")]
     InvalidCompilerHint(Token),
-    #[error(
-        r#"A function's feature requirement must be defined like so ```@feature "<name>"```."#
-    )]
+    #[error(r#"A function's feature requirement must be defined like so ```@feature "<name>"```."#)]
     InvalidFunctionFeature,
     #[error(
         "Function requires feature `{0}` to be enabled but project only has features `{1:?}` enabled."
     )]
     InvalidFeatureRequirement(String, Vec<String>),
+    #[error(
+        r#"Statements can only be gated with ```@feature "<name>"``` or ```@cfg(<predicate>)```, `{0}` cannot be applied to a statement."#
+    )]
+    InvalidStatementInstruction(CompilerInstruction),
+    #[error(
+        r#"A cfg predicate must be defined like so ```@cfg(target_os = "linux")```, predicates can be combined with `all(...)`, `any(...)` and `not(...)`."#
+    )]
+    InvalidCfgPredicate,
+    #[error(
+        "Unknown cfg option `{0}`, available options: `target_os`, `target_arch`, `pointer_width`, `debug_assertions`, `feature`."
+    )]
+    UnknownCfgOption(String),
     #[error("Module path contains an invalid token: `{0}`.")]
     InvalidModulePathDefinition(Token),
     #[error("Imported function was not found in the dependencies: `{0:?}`.")]
@@ -175,6 +187,8 @@ pub enum ParserError
     InvalidValue(Option<Type>, StatementVariant),
     #[error("Enum variant `{0}` was not found in specified enum.")]
     EnumVariantNotFound(String),
+    #[error("Variant `{0}` is present in the enum's variants more than once.")]
+    DuplicateEnumVariant(String),
     #[error(
         "Type `{0}` does not contain any fields and may not be accessed via any field. (Only structs have fields)"
     )]
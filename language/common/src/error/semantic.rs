@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SemanticError
+{
+    #[error(
+        "The project is not a library, but its main source file does not define a `main` function."
+    )]
+    MissingMain,
+    #[error(
+        "The `main` function has an invalid signature. No arguments should be taken and `int` is returned."
+    )]
+    InvalidMain,
+
+    /// The first argument is the name of the type, the second and third are the modules defining it.
+    #[error("Type `{0}` is defined in both module `{1}` and module `{2}`.")]
+    DuplicateType(String, String, String),
+
+    /// The first argument is the name of the function, the second and third are the modules defining it.
+    #[error(
+        "Function `{0}` is defined in both module `{1}` and module `{2}`. Public functions and tests must have unique names."
+    )]
+    DuplicateFunction(String, String, String),
+
+    #[error("Test `{0}` has an invalid signature. Tests take no arguments and return `void`.")]
+    InvalidTestSignature(String),
+    #[error("Function `{0}` cannot be defined, as `{0}` is a built-in function.")]
+    BuiltinRedefined(String),
+
+    /// The first argument is the name of the built-in function, the second is the number of arguments it takes, the third is the number of arguments it was called with.
+    #[error("Built-in function `{0}` takes {1} argument(s), but it was called with {2}.")]
+    InvalidBuiltinCall(String, usize, usize),
+}
@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceError
+{
+    #[error("The workspace manifest at `{0}` could not be read: {1}")]
+    InvalidManifest(PathBuf, String),
+    #[error("Workspace member at `{0}` is missing a config file.")]
+    MemberMissingConfig(PathBuf),
+    #[error("The workspace has multiple members named `{0}`.")]
+    DuplicateMember(String),
+    #[error("The workspace does not have a member named `{0}`. Members: {1:?}.")]
+    UnknownPackage(String, Vec<String>),
+    /// The argument lists the members depending on each other.
+    #[error("The members of the workspace depend on each other in a cycle: {0}.")]
+    DependencyCycle(String),
+    /// The first argument is the dependency, the second and third are the versions the members have resolved it to.
+    #[error(
+        "Dependency `{0}` is resolved to different versions by the members of the workspace: `{1}` and `{2}`. Every member must use the same version of a dependency."
+    )]
+    ConflictingVersions(String, String, String),
+    #[error(
+        "Multiple members of the workspace have been selected, select the member to run with `--package <NAME>`."
+    )]
+    PackageRequired,
+}
@@ -0,0 +1,79 @@
+use std::rc::Rc;
+
+use crate::{
+    codegen::CustomItem,
+    parser::{
+        common::Context,
+        function::{FunctionDefinition, FunctionSignature},
+    },
+};
+
+/// Converts Fog IR text back into a [`FirModule`].
+pub mod parser;
+/// Writes a [`FirModule`] as Fog IR text.
+pub mod printer;
+mod sexpr;
+
+/// The typed and name-resolved contents of a single [`Context`] (a module).
+/// This is what gets written as Fog IR. Printing a [`FirModule`] with spans included and parsing it back results in the same [`FirModule`].
+/// Every item is stored with its full access path, which includes the name of the item.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FirModule
+{
+    /// Path of the module.
+    pub path: Vec<String>,
+    pub external_decls: Vec<(Vec<String>, FunctionSignature)>,
+    pub items: Vec<(Vec<String>, CustomItem)>,
+    pub functions: Vec<(Vec<String>, FunctionDefinition)>,
+}
+
+impl FirModule
+{
+    pub fn from_context(ctx: &Context) -> Self
+    {
+        Self {
+            path: ctx.path.clone(),
+            external_decls: ctx
+                .external_decls
+                .iter()
+                .map(|(path, _name, signature)| (path.clone(), signature.clone()))
+                .collect(),
+            items: ctx
+                .items
+                .iter()
+                .map(|(path, _name, item)| (path.clone(), item.clone()))
+                .collect(),
+            functions: ctx
+                .functions
+                .iter()
+                .map(|(path, _name, function)| (path.clone(), function.clone()))
+                .collect(),
+        }
+    }
+
+    /// Creates a [`Context`] from the module. The name of every item is the last element of its path.
+    pub fn into_context(self) -> Context
+    {
+        let mut ctx = Context::new(self.path);
+
+        for (path, signature) in self.external_decls {
+            let name = Rc::new(path.last().cloned().unwrap_or_default());
+
+            ctx.external_decls.insert(path, name, signature);
+        }
+
+        for (path, item) in self.items {
+            let name = Rc::new(path.last().cloned().unwrap_or_default());
+
+            ctx.items.insert(path, name, item);
+        }
+
+        for (path, function) in self.functions {
+            let name = Rc::new(path.last().cloned().unwrap_or_default());
+
+            ctx.functions.insert(path, name, function);
+        }
+
+        ctx
+    }
+}
@@ -0,0 +1,885 @@
+use std::str::FromStr;
+
+use crate::{
+    codegen::{
+        CustomItem, FunctionArgumentIdentifier, If, Order, ParsedState, StructAttributes,
+        StructDefinition,
+    },
+    error::{CharPosition, SpanInfo, Spanned, fir::FirError},
+    fir::{
+        FirModule,
+        sexpr::{SExpr, read},
+    },
+    parser::{
+        cfg::CfgPredicate,
+        common::{ItemVisibility, StatementVariant},
+        function::{CompilerInstruction, FunctionArguments, FunctionDefinition, FunctionSignature},
+        numeric_value::MathematicalSymbol,
+        variable::ControlFlowType,
+    },
+    ty::{NotNan, OrdMap, OrdSet, Type, Value},
+};
+
+type FirResult<T> = Result<T, FirError>;
+
+/// Parses Fog IR text produced by [`super::printer::FirPrinter`].
+/// Missing optional clauses take their default values, this includes spans which are only written if the printer was told to include them.
+pub fn parse_module(source: &str) -> FirResult<FirModule>
+{
+    let nodes = read(source)?;
+
+    let [module] = nodes.as_slice()
+    else {
+        return Err(FirError::InvalidModuleCount);
+    };
+
+    let items = tagged(module, "module", "a `module`")?;
+
+    let mut fir_module = FirModule {
+        path: clause(items, "path")
+            .map(names)
+            .transpose()?
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    for item in items {
+        match item.head() {
+            Some("path") => (),
+            Some("external") => {
+                let items = &item.as_list().unwrap()[1..];
+
+                fir_module.external_decls.push((
+                    required_clause(items, "path", item.line, "the `path` of an external")
+                        .and_then(names)?,
+                    signature(items, item.line)?,
+                ));
+            },
+            Some("item") => {
+                let items = &item.as_list().unwrap()[1..];
+
+                fir_module.items.push((
+                    required_clause(items, "path", item.line, "the `path` of an item")
+                        .and_then(names)?,
+                    custom_item(nth(items, 1, item.line, "an item definition")?)?,
+                ));
+            },
+            Some("function") => {
+                let items = &item.as_list().unwrap()[1..];
+
+                fir_module.functions.push((
+                    required_clause(items, "path", item.line, "the `path` of a function")
+                        .and_then(names)?,
+                    function(item)?,
+                ));
+            },
+            other => {
+                return Err(FirError::Unknown(
+                    item.line,
+                    "module item",
+                    other.unwrap_or_default().to_string(),
+                ));
+            },
+        }
+    }
+
+    Ok(fir_module)
+}
+
+fn function(expr: &SExpr) -> FirResult<FunctionDefinition>
+{
+    let (items, span) = strip_span(tagged(expr, "function", "a `function`")?)?;
+
+    Ok(FunctionDefinition {
+        signature: signature(items, expr.line)?,
+        body: required_clause(items, "body", expr.line, "the `body` of a function")?
+            .iter()
+            .map(statement)
+            .collect::<FirResult<_>>()?,
+        module_path: clause(items, "module")
+            .map(names)
+            .transpose()?
+            .unwrap_or_default(),
+        visibility: clause(items, "visibility")
+            .map(|items| visibility(nth(items, 0, expr.line, "a visibility")?))
+            .transpose()?
+            .unwrap_or_default(),
+        compiler_instructions: clause(items, "instructions")
+            .map(compiler_instructions)
+            .transpose()?
+            .unwrap_or_default(),
+        enabling_features: features(clause(items, "features").unwrap_or_default())?,
+        span,
+    })
+}
+
+fn features(items: &[SExpr]) -> FirResult<OrdSet<String>>
+{
+    let mut features = OrdSet::default();
+
+    for feature in items {
+        features.insert(name(feature, "a feature name")?);
+    }
+
+    Ok(features)
+}
+
+/// Parses the signature of a function, external or trait function.
+/// The first item must be the name of the function.
+fn signature(items: &[SExpr], line: usize) -> FirResult<FunctionSignature>
+{
+    let mut args = FunctionArguments::new();
+
+    if let Some(arguments) = clause(items, "args") {
+        for argument in arguments {
+            let argument_items = list(argument, "an argument")?;
+
+            args.arguments.insert(
+                name(
+                    nth(argument_items, 0, argument.line, "an argument name")?,
+                    "an argument name",
+                )?,
+                (
+                    ty(nth(argument_items, 2, argument.line, "an argument type")?)?,
+                    number(nth(argument_items, 1, argument.line, "an argument id")?)?,
+                ),
+            );
+        }
+    }
+
+    args.ellipsis_present = clause(items, "ellipsis").is_some();
+    args.receiver_referenced = clause(items, "receiver").is_some();
+
+    if let Some(items) = clause(items, "generics") {
+        args.generics = generics(items)?;
+    }
+
+    Ok(FunctionSignature {
+        name: name(nth(items, 0, line, "a function name")?, "a function name")?,
+        args,
+        return_type: clause(items, "returns")
+            .map(|items| ty(nth(items, 0, line, "a return type")?))
+            .transpose()?
+            .unwrap_or_default(),
+    })
+}
+
+fn generics(items: &[SExpr]) -> FirResult<OrdMap<String, OrdSet<String>>>
+{
+    let mut generics = OrdMap::new();
+
+    for generic in items {
+        let generic_items = list(generic, "a generic")?;
+
+        let mut traits = OrdSet::default();
+
+        for trait_name in generic_items.iter().skip(1) {
+            traits.insert(name(trait_name, "a trait name")?);
+        }
+
+        generics.insert(
+            name(
+                nth(generic_items, 0, generic.line, "a generic name")?,
+                "a generic name",
+            )?,
+            traits,
+        );
+    }
+
+    Ok(generics)
+}
+
+fn compiler_instructions(items: &[SExpr]) -> FirResult<OrdSet<CompilerInstruction>>
+{
+    let mut instructions = OrdSet::default();
+
+    for instruction in items {
+        instructions.insert(match instruction.as_atom() {
+            Some("cold") => CompilerInstruction::Cold,
+            Some("nofree") => CompilerInstruction::NoFree,
+            Some("inline") => CompilerInstruction::Inline,
+            Some("nounwind") => CompilerInstruction::NoUnWind,
+            Some("noinline") => CompilerInstruction::NoInline,
+            Some("optnone") => CompilerInstruction::OptNone,
+            Some("test") => CompilerInstruction::Test,
+            _ if instruction.head() == Some("cfg") => {
+                let cfg = tagged(instruction, "cfg", "a compiler instruction")?;
+
+                CompilerInstruction::Cfg(cfg_predicate(nth(
+                    cfg,
+                    0,
+                    instruction.line,
+                    "a cfg predicate",
+                )?)?)
+            },
+            _ => {
+                let feature = tagged(instruction, "feature", "a compiler instruction")?;
+
+                CompilerInstruction::Feature(name(
+                    nth(feature, 0, instruction.line, "a feature name")?,
+                    "a feature name",
+                )?)
+            },
+        });
+    }
+
+    Ok(instructions)
+}
+
+fn cfg_predicate(expr: &SExpr) -> FirResult<CfgPredicate>
+{
+    if expr.as_atom() == Some("debug_assertions") {
+        return Ok(CfgPredicate::DebugAssertions);
+    }
+
+    let items = list(expr, "a cfg predicate")?;
+    let rest = items.get(1..).unwrap_or_default();
+
+    let value = || {
+        name(
+            nth(rest, 0, expr.line, "the value of a cfg predicate")?,
+            "the value of a cfg predicate",
+        )
+    };
+
+    let predicates = || {
+        rest.iter()
+            .map(cfg_predicate)
+            .collect::<FirResult<Vec<_>>>()
+    };
+
+    match expr.head() {
+        Some("target_os") => Ok(CfgPredicate::TargetOs(value()?)),
+        Some("target_arch") => Ok(CfgPredicate::TargetArch(value()?)),
+        Some("pointer_width") => {
+            Ok(CfgPredicate::PointerWidth(number(nth(
+                rest,
+                0,
+                expr.line,
+                "a pointer width",
+            )?)?))
+        },
+        Some("feature") => Ok(CfgPredicate::Feature(value()?)),
+        Some("all") => Ok(CfgPredicate::All(predicates()?)),
+        Some("any") => Ok(CfgPredicate::Any(predicates()?)),
+        Some("not") => {
+            Ok(CfgPredicate::Not(Box::new(cfg_predicate(nth(
+                rest,
+                0,
+                expr.line,
+                "a cfg predicate",
+            )?)?)))
+        },
+        _ => Err(unknown(expr, "cfg predicate")),
+    }
+}
+
+fn visibility(expr: &SExpr) -> FirResult<ItemVisibility>
+{
+    match expr.as_atom() {
+        Some("private") => Ok(ItemVisibility::Private),
+        Some("public") => Ok(ItemVisibility::Public),
+        Some("publib") => Ok(ItemVisibility::PublicLibrary),
+        Some("branch") => Ok(ItemVisibility::Branch),
+        _ => Err(unknown(expr, "visibility")),
+    }
+}
+
+fn custom_item(expr: &SExpr) -> FirResult<CustomItem>
+{
+    let items = list(expr, "an item definition")?;
+    let rest = items.get(1..).unwrap_or_default();
+
+    match expr.head() {
+        Some("struct-def") => {
+            Ok(CustomItem::Struct(StructDefinition {
+                visibility: clause(rest, "visibility")
+                    .map(|items| visibility(nth(items, 0, expr.line, "a visibility")?))
+                    .transpose()?
+                    .unwrap_or_default(),
+                name: name(nth(rest, 0, expr.line, "a struct name")?, "a struct name")?,
+                fields: fields(rest)?,
+                generics: clause(rest, "generics")
+                    .map(generics)
+                    .transpose()?
+                    .unwrap_or_default(),
+                attributes: struct_attributes(rest)?,
+            }))
+        },
+        Some("enum-def") => {
+            Ok(CustomItem::Enum((
+                ty(nth(rest, 0, expr.line, "an enum type")?)?,
+                variants(rest.get(1..).unwrap_or_default())?,
+            )))
+        },
+        Some("trait-def") => {
+            Ok(CustomItem::Trait {
+                name: name(nth(rest, 0, expr.line, "a trait name")?, "a trait name")?,
+                functions: trait_functions(rest)?,
+                access_path: clause(rest, "path")
+                    .map(names)
+                    .transpose()?
+                    .unwrap_or_default(),
+            })
+        },
+        _ => Err(unknown(expr, "item definition")),
+    }
+}
+
+fn fields(items: &[SExpr]) -> FirResult<OrdMap<String, Type>>
+{
+    let mut fields = OrdMap::new();
+
+    for field in clause(items, "fields").unwrap_or_default() {
+        let field_items = list(field, "a field")?;
+
+        fields.insert(
+            name(
+                nth(field_items, 0, field.line, "a field name")?,
+                "a field name",
+            )?,
+            ty(nth(field_items, 1, field.line, "a field type")?)?,
+        );
+    }
+
+    Ok(fields)
+}
+
+fn variants(items: &[SExpr]) -> FirResult<OrdMap<String, Spanned<StatementVariant>>>
+{
+    let mut variants = OrdMap::new();
+
+    for variant in items {
+        let variant_items = list(variant, "an enum variant")?;
+
+        variants.insert(
+            name(
+                nth(variant_items, 0, variant.line, "a variant name")?,
+                "a variant name",
+            )?,
+            statement(nth(variant_items, 1, variant.line, "a variant value")?)?,
+        );
+    }
+
+    Ok(variants)
+}
+
+fn trait_functions(items: &[SExpr]) -> FirResult<OrdMap<String, FunctionSignature>>
+{
+    let mut functions = OrdMap::new();
+
+    for function in clause(items, "functions").unwrap_or_default() {
+        let function_signature =
+            signature(tagged(function, "fn", "a trait function")?, function.line)?;
+
+        functions.insert(function_signature.name.clone(), function_signature);
+    }
+
+    Ok(functions)
+}
+
+fn struct_attributes(items: &[SExpr]) -> FirResult<StructAttributes>
+{
+    let mut traits_implemented = OrdSet::default();
+
+    for trait_path in clause(items, "traits").unwrap_or_default() {
+        traits_implemented.insert(names(list(trait_path, "a trait path")?)?);
+    }
+
+    let mut impl_fn_list = OrdMap::new();
+
+    for implementation in clauses(items, "impl") {
+        let line = implementation
+            .first()
+            .map(|item| item.line)
+            .unwrap_or_default();
+
+        impl_fn_list.insert(
+            name(
+                nth(implementation, 0, line, "a function name")?,
+                "a function name",
+            )?,
+            ParsedState::Parsed(function(nth(implementation, 1, line, "a function")?)?),
+        );
+    }
+
+    Ok(StructAttributes::new(
+        clause(items, "instructions")
+            .map(compiler_instructions)
+            .transpose()?
+            .unwrap_or_default(),
+        traits_implemented,
+        impl_fn_list,
+    ))
+}
+
+fn ty(expr: &SExpr) -> FirResult<Type>
+{
+    if let Some(atom) = expr.as_atom() {
+        return match atom {
+            "i64" => Ok(Type::I64),
+            "f64" => Ok(Type::F64),
+            "u64" => Ok(Type::U64),
+            "i32" => Ok(Type::I32),
+            "f32" => Ok(Type::F32),
+            "u32" => Ok(Type::U32),
+            "i16" => Ok(Type::I16),
+            "f16" => Ok(Type::F16),
+            "u16" => Ok(Type::U16),
+            "u8" => Ok(Type::U8),
+            "string" => Ok(Type::String),
+            "bool" => Ok(Type::Boolean),
+            "void" => Ok(Type::Void),
+            "ptr" => Ok(Type::Pointer(None)),
+            _ => Err(unknown(expr, "type")),
+        };
+    }
+
+    let items = list(expr, "a type")?;
+    let rest = items.get(1..).unwrap_or_default();
+
+    match expr.head() {
+        Some("enum") => {
+            Ok(Type::Enum((
+                Box::new(ty(nth(rest, 0, expr.line, "an enum type")?)?),
+                variants(rest.get(1..).unwrap_or_default())?,
+            )))
+        },
+        Some("struct") => {
+            Ok(Type::Struct((
+                name(nth(rest, 0, expr.line, "a struct name")?, "a struct name")?,
+                fields(rest)?,
+                struct_attributes(rest)?,
+            )))
+        },
+        Some("array") => {
+            Ok(Type::Array((
+                Box::new(ty(nth(rest, 0, expr.line, "an array type")?)?),
+                number(nth(rest, 1, expr.line, "an array length")?)?,
+            )))
+        },
+        Some("ptr") => {
+            Ok(Type::Pointer(Some(Box::new(ty(nth(
+                rest,
+                0,
+                expr.line,
+                "a pointer type",
+            )?)?))))
+        },
+        Some("trait") => {
+            Ok(Type::Trait {
+                name: name(nth(rest, 0, expr.line, "a trait name")?, "a trait name")?,
+                access_path: clause(rest, "path")
+                    .map(names)
+                    .transpose()?
+                    .unwrap_or_default(),
+                functions: trait_functions(rest)?,
+            })
+        },
+        Some("unresolved") => {
+            Ok(Type::Unresolved(name(
+                nth(rest, 0, expr.line, "a type name")?,
+                "a type name",
+            )?))
+        },
+        Some("trait-object") => {
+            let mut traits = OrdSet::default();
+
+            for trait_path in rest {
+                traits.insert(names(list(trait_path, "a trait path")?)?);
+            }
+
+            Ok(Type::TraitObject(traits))
+        },
+        _ => Err(unknown(expr, "type")),
+    }
+}
+
+fn value(expr: &SExpr) -> FirResult<Value>
+{
+    if expr.as_atom() == Some("void") {
+        return Ok(Value::Void);
+    }
+
+    let items = list(expr, "a value")?;
+    let rest = items.get(1..).unwrap_or_default();
+    let inner = || nth(rest, 0, expr.line, "a value");
+
+    match expr.head() {
+        Some("i64") => Ok(Value::I64(number(inner()?)?)),
+        Some("f64") => Ok(Value::F64(float(inner()?, NotNan::<f64>::new)?)),
+        Some("u64") => Ok(Value::U64(number(inner()?)?)),
+        Some("i32") => Ok(Value::I32(number(inner()?)?)),
+        Some("f32") => Ok(Value::F32(float(inner()?, NotNan::<f32>::new)?)),
+        Some("u32") => Ok(Value::U32(number(inner()?)?)),
+        Some("i16") => Ok(Value::I16(number(inner()?)?)),
+        // Half precision floats are written as `f32`s.
+        Some("f16") => {
+            Ok(Value::F16(float(inner()?, |value: f32| {
+                NotNan::new_f16(value as f16)
+            })?))
+        },
+        Some("u16") => Ok(Value::U16(number(inner()?)?)),
+        Some("u8") => Ok(Value::U8(number(inner()?)?)),
+        Some("string") => Ok(Value::String(name(inner()?, "a string")?)),
+        Some("bool") => Ok(Value::Boolean(number(inner()?)?)),
+        Some("struct-value") => {
+            let mut values = OrdMap::new();
+
+            for field in clause(rest, "values").unwrap_or_default() {
+                let field_items = list(field, "a field value")?;
+
+                values.insert(
+                    name(
+                        nth(field_items, 0, field.line, "a field name")?,
+                        "a field name",
+                    )?,
+                    Box::new(statement(nth(
+                        field_items,
+                        1,
+                        field.line,
+                        "a field value",
+                    )?)?),
+                );
+            }
+
+            Ok(Value::Struct((
+                name(inner()?, "a struct name")?,
+                fields(rest)?,
+                values,
+                struct_attributes(rest)?,
+            )))
+        },
+        Some("array-value") => {
+            Ok(Value::Array((
+                Box::new(ty(inner()?)?),
+                number(nth(rest, 1, expr.line, "an array length")?)?,
+            )))
+        },
+        Some("enum-value") => {
+            Ok(Value::Enum((
+                ty(inner()?)?,
+                variants(rest.get(2..).unwrap_or_default())?,
+                name(nth(rest, 1, expr.line, "a variant name")?, "a variant name")?,
+            )))
+        },
+        Some("ptr-value") => {
+            Ok(Value::Pointer((
+                number(inner()?)?,
+                rest.get(1).map(ty).transpose()?.map(Box::new),
+            )))
+        },
+        _ => Err(unknown(expr, "value")),
+    }
+}
+
+fn statement(expr: &SExpr) -> FirResult<Spanned<StatementVariant>>
+{
+    let (items, span) = strip_span(list(expr, "a statement")?)?;
+
+    Ok(Spanned::new(statement_variant(items, expr.line)?, span))
+}
+
+fn boxed_statement(expr: &SExpr) -> FirResult<Box<Spanned<StatementVariant>>>
+{
+    statement(expr).map(Box::new)
+}
+
+fn statements(items: &[SExpr]) -> FirResult<Vec<Spanned<StatementVariant>>>
+{
+    items.iter().map(statement).collect()
+}
+
+/// Parses the items of the list representing a [`StatementVariant`], the span must already be stripped.
+fn statement_variant(items: &[SExpr], line: usize) -> FirResult<StatementVariant>
+{
+    let head = items
+        .first()
+        .and_then(SExpr::as_atom)
+        .ok_or(FirError::Expected(line, "a statement"))?;
+
+    let rest = items.get(1..).unwrap_or_default();
+    let arg = |idx: usize, what: &'static str| nth(rest, idx, line, what);
+
+    Ok(match head {
+        "let" => {
+            let is_mutable = arg(2, "a variable type")?.as_atom() == Some("mut");
+            let offset = is_mutable as usize;
+
+            StatementVariant::NewVariable {
+                variable_name: name(arg(0, "a variable name")?, "a variable name")?,
+                variable_id: number(arg(1, "a variable id")?)?,
+                is_mutable,
+                variable_type: ty(arg(2 + offset, "a variable type")?)?,
+                variable_value: boxed_statement(arg(3 + offset, "a variable value")?)?,
+            }
+        },
+        "ref" => {
+            StatementVariant::BasicReference {
+                variable_name: name(arg(0, "a variable name")?, "a variable name")?,
+            }
+        },
+        "index" => {
+            StatementVariant::ArrayReference {
+                variable_reference: boxed_statement(arg(0, "an array reference")?)?,
+                index: boxed_statement(arg(1, "an index")?)?,
+            }
+        },
+        "field" => {
+            StatementVariant::StructFieldReference {
+                variable_reference: boxed_statement(arg(0, "a struct reference")?)?,
+                field_name: name(arg(1, "a field name")?, "a field name")?,
+            }
+        },
+        "value" => StatementVariant::Value(value(arg(0, "a value")?)?),
+        "cast" => {
+            StatementVariant::TypeCast(boxed_statement(arg(0, "a value")?)?, ty(arg(1, "a type")?)?)
+        },
+        "math" => {
+            StatementVariant::MathematicalExpression {
+                symbol: mathematical_symbol(arg(0, "a mathematical symbol")?)?,
+                lhs: boxed_statement(arg(1, "a left hand side")?)?,
+                rhs: boxed_statement(arg(2, "a right hand side")?)?,
+            }
+        },
+        "neg" => StatementVariant::NegateValue(boxed_statement(arg(0, "a value")?)?),
+        "brackets" => {
+            StatementVariant::Brackets(
+                statements(rest.get(1..).unwrap_or_default())?,
+                ty(arg(0, "a type")?)?,
+            )
+        },
+        "call" => {
+            let mut arguments = OrdMap::new();
+
+            for argument in rest.get(1..).unwrap_or_default() {
+                let argument_items = list(argument, "an argument")?.get(1..).unwrap_or_default();
+
+                let identifier = match argument.head() {
+                    Some("arg") => {
+                        FunctionArgumentIdentifier::Identifier(name(
+                            nth(argument_items, 0, argument.line, "an argument name")?,
+                            "an argument name",
+                        )?)
+                    },
+                    Some("arg-index") => {
+                        FunctionArgumentIdentifier::Index(number(nth(
+                            argument_items,
+                            0,
+                            argument.line,
+                            "an argument index",
+                        )?)?)
+                    },
+                    _ => return Err(unknown(argument, "argument")),
+                };
+
+                arguments.insert(
+                    identifier,
+                    statement(nth(argument_items, 1, argument.line, "an argument value")?)?,
+                );
+            }
+
+            StatementVariant::FunctionCall {
+                identifier: boxed_statement(arg(0, "a function identifier")?)?,
+                arguments,
+            }
+        },
+        "set" => {
+            StatementVariant::SetValue {
+                receiver: boxed_statement(arg(0, "a receiver")?)?,
+                value: boxed_statement(arg(1, "a value")?)?,
+            }
+        },
+        "modify" => {
+            StatementVariant::ModifyValueArithmetic {
+                symbol: mathematical_symbol(arg(0, "a mathematical symbol")?)?,
+                receiver: boxed_statement(arg(1, "a receiver")?)?,
+                value: boxed_statement(arg(2, "a value")?)?,
+            }
+        },
+        "return" => {
+            StatementVariant::ReturnValue {
+                value: boxed_statement(arg(0, "a value")?)?,
+            }
+        },
+        "cmp" => {
+            StatementVariant::Comparison(
+                boxed_statement(arg(2, "a left hand side")?)?,
+                order(arg(0, "an order")?)?,
+                boxed_statement(arg(3, "a right hand side")?)?,
+                ty(arg(1, "a type")?)?,
+            )
+        },
+        "if" => {
+            StatementVariant::If(If {
+                condition: boxed_statement(arg(0, "a condition")?)?,
+                true_branch: statements(clause(rest, "then").unwrap_or_default())?,
+                false_branch: statements(clause(rest, "else").unwrap_or_default())?,
+            })
+        },
+        "block" => {
+            StatementVariant::CodeBlock(
+                rest.iter()
+                    .map(|statement| {
+                        statement_variant(list(statement, "a statement")?, statement.line)
+                    })
+                    .collect::<FirResult<_>>()?,
+            )
+        },
+        "loop" => StatementVariant::Loop(statements(rest)?),
+        "break" => StatementVariant::ControlFlow(ControlFlowType::Break),
+        "continue" => StatementVariant::ControlFlow(ControlFlowType::Continue),
+        "array" => {
+            StatementVariant::ArrayInitialization {
+                values: statements(rest)?,
+            }
+        },
+        "addr" => StatementVariant::GetPointerTo(boxed_statement(arg(0, "a value")?)?),
+        "deref" => StatementVariant::DerefPointer(boxed_statement(arg(0, "a pointer")?)?),
+        _ => return Err(FirError::Unknown(line, "statement", head.to_string())),
+    })
+}
+
+fn mathematical_symbol(expr: &SExpr) -> FirResult<MathematicalSymbol>
+{
+    match expr.as_atom() {
+        Some("add") => Ok(MathematicalSymbol::Addition),
+        Some("sub") => Ok(MathematicalSymbol::Subtraction),
+        Some("div") => Ok(MathematicalSymbol::Division),
+        Some("mul") => Ok(MathematicalSymbol::Multiplication),
+        Some("mod") => Ok(MathematicalSymbol::Modulo),
+        Some("pow") => Ok(MathematicalSymbol::Power),
+        _ => Err(unknown(expr, "mathematical symbol")),
+    }
+}
+
+fn order(expr: &SExpr) -> FirResult<Order>
+{
+    match expr.as_atom() {
+        Some("eq") => Ok(Order::Equal),
+        Some("ne") => Ok(Order::NotEqual),
+        Some("gt") => Ok(Order::Bigger),
+        Some("ge") => Ok(Order::EqBigger),
+        Some("lt") => Ok(Order::Smaller),
+        Some("le") => Ok(Order::EqSmaller),
+        _ => Err(unknown(expr, "order")),
+    }
+}
+
+fn unknown(expr: &SExpr, what: &'static str) -> FirError
+{
+    let mut text = String::new();
+    expr.write(&mut text, 0);
+
+    FirError::Unknown(expr.line, what, text)
+}
+
+fn list<'a>(expr: &'a SExpr, what: &'static str) -> FirResult<&'a [SExpr]>
+{
+    expr.as_list().ok_or(FirError::Expected(expr.line, what))
+}
+
+/// Returns the items of the list following its `head` atom.
+fn tagged<'a>(expr: &'a SExpr, head: &str, what: &'static str) -> FirResult<&'a [SExpr]>
+{
+    if expr.head() != Some(head) {
+        return Err(FirError::Expected(expr.line, what));
+    }
+
+    Ok(&expr.as_list().unwrap()[1..])
+}
+
+fn nth<'a>(items: &'a [SExpr], idx: usize, line: usize, what: &'static str)
+-> FirResult<&'a SExpr>
+{
+    items
+        .get(idx)
+        .filter(|item| !item.is_span())
+        .ok_or(FirError::Expected(line, what))
+}
+
+/// Returns every clause (a list starting with the `head` atom) from the items, without the head.
+fn clauses<'a>(items: &'a [SExpr], head: &'a str) -> impl Iterator<Item = &'a [SExpr]>
+{
+    items
+        .iter()
+        .filter(move |item| item.head() == Some(head))
+        .map(|item| &item.as_list().unwrap()[1..])
+}
+
+fn clause<'a>(items: &'a [SExpr], head: &'a str) -> Option<&'a [SExpr]>
+{
+    clauses(items, head).next()
+}
+
+fn required_clause<'a>(
+    items: &'a [SExpr],
+    head: &'a str,
+    line: usize,
+    what: &'static str,
+) -> FirResult<&'a [SExpr]>
+{
+    clause(items, head).ok_or(FirError::Expected(line, what))
+}
+
+fn name(expr: &SExpr, what: &'static str) -> FirResult<String>
+{
+    expr.as_name()
+        .filter(|_| !expr.is_span())
+        .map(str::to_string)
+        .ok_or(FirError::Expected(expr.line, what))
+}
+
+fn names(items: &[SExpr]) -> FirResult<Vec<String>>
+{
+    items.iter().map(|item| name(item, "a name")).collect()
+}
+
+fn number<T: FromStr>(expr: &SExpr) -> FirResult<T>
+{
+    let atom = expr
+        .as_atom()
+        .ok_or(FirError::Expected(expr.line, "a number"))?;
+
+    atom.parse()
+        .map_err(|_| FirError::InvalidNumber(expr.line, atom.to_string()))
+}
+
+fn float<F: FromStr, T, E>(expr: &SExpr, create: impl FnOnce(F) -> Result<T, E>) -> FirResult<T>
+{
+    create(number(expr)?).map_err(|_| {
+        FirError::InvalidNumber(expr.line, expr.as_atom().unwrap_or_default().to_string())
+    })
+}
+
+/// Removes the trailing span atom from the items of a list if there is one.
+/// Nodes without a span get the default span.
+fn strip_span(items: &[SExpr]) -> FirResult<(&[SExpr], SpanInfo)>
+{
+    match items.split_last() {
+        Some((last, rest)) if last.is_span() => Ok((rest, span(last)?)),
+        _ => Ok((items, SpanInfo::default())),
+    }
+}
+
+fn span(expr: &SExpr) -> FirResult<SpanInfo>
+{
+    let text = expr.as_atom().unwrap_or_default();
+    let invalid_span = || FirError::InvalidSpan(expr.line, text.to_string());
+
+    let position = |position: &str| -> FirResult<CharPosition> {
+        let (line, column) = position.split_once(':').ok_or_else(invalid_span)?;
+
+        Ok(CharPosition::new(
+            line.parse().map_err(|_| invalid_span())?,
+            column.parse().map_err(|_| invalid_span())?,
+        ))
+    };
+
+    let (start, end) = text
+        .strip_prefix('@')
+        .and_then(|span| span.split_once('-'))
+        .ok_or_else(invalid_span)?;
+
+    Ok(SpanInfo::new(position(start)?, position(end)?))
+}
@@ -0,0 +1,701 @@
+use crate::{
+    codegen::{
+        CustomItem, FunctionArgumentIdentifier, Order, ParsedState, StructAttributes,
+        StructDefinition,
+    },
+    error::{SpanInfo, Spanned},
+    fir::{FirModule, sexpr::SExpr},
+    parser::{
+        cfg::CfgPredicate,
+        common::{ItemVisibility, StatementVariant},
+        function::{CompilerInstruction, FunctionArguments, FunctionDefinition, FunctionSignature},
+        numeric_value::MathematicalSymbol,
+        variable::ControlFlowType,
+    },
+    ty::{OrdMap, OrdSet, Type, Value},
+};
+
+/// Header written at the top of every Fog IR output.
+pub const FIR_HEADER: &str = "; Fog IR";
+
+/// Writes [`FirModule`]s as Fog IR text.
+/// The format is documented in the `Fog IR` chapter of the documentation.
+#[derive(Debug, Clone, Copy)]
+pub struct FirPrinter
+{
+    /// Whether the span of every item and statement should be written.
+    /// Leaving the spans out keeps the output stable when only the formatting of the source changes, which is useful for golden tests.
+    pub include_spans: bool,
+}
+
+impl FirPrinter
+{
+    pub fn new(include_spans: bool) -> Self
+    {
+        Self { include_spans }
+    }
+
+    pub fn print_module(&self, module: &FirModule) -> String
+    {
+        let mut output = format!("{FIR_HEADER}\n");
+
+        self.module(module).write(&mut output, 0);
+
+        output.push('\n');
+
+        output
+    }
+
+    fn module(&self, module: &FirModule) -> SExpr
+    {
+        let mut items = vec![path("path", &module.path)];
+
+        for (item_path, signature) in &module.external_decls {
+            let mut external = vec![SExpr::name(&signature.name), path("path", item_path)];
+
+            external.extend(self.signature_clauses(signature));
+
+            items.push(SExpr::tagged("external", external));
+        }
+
+        for (item_path, item) in &module.items {
+            items.push(SExpr::tagged(
+                "item",
+                vec![path("path", item_path), self.custom_item(item)],
+            ));
+        }
+
+        for (item_path, function) in &module.functions {
+            items.push(self.function(item_path, function));
+        }
+
+        SExpr::tagged("module", items)
+    }
+
+    fn function(&self, item_path: &[String], function: &FunctionDefinition) -> SExpr
+    {
+        let mut items = vec![
+            SExpr::name(&function.signature.name),
+            path("path", item_path),
+            path("module", &function.module_path),
+        ];
+
+        items.push(SExpr::tagged(
+            "visibility",
+            vec![visibility(&function.visibility)],
+        ));
+
+        if !function.compiler_instructions.is_empty() {
+            items.push(compiler_instructions(&function.compiler_instructions));
+        }
+
+        if !function.enabling_features.is_empty() {
+            items.push(SExpr::tagged(
+                "features",
+                function
+                    .enabling_features
+                    .iter()
+                    .map(SExpr::string)
+                    .collect(),
+            ));
+        }
+
+        items.extend(self.signature_clauses(&function.signature));
+        items.push(SExpr::tagged("body", self.statements(&function.body)));
+
+        self.push_span(&mut items, &function.span);
+
+        SExpr::tagged("function", items)
+    }
+
+    /// Writes the arguments, generics and the return type of the signature.
+    fn signature_clauses(&self, signature: &FunctionSignature) -> Vec<SExpr>
+    {
+        let mut clauses = self.argument_clauses(&signature.args);
+
+        clauses.push(SExpr::tagged(
+            "returns",
+            vec![self.ty(&signature.return_type)],
+        ));
+
+        clauses
+    }
+
+    fn argument_clauses(&self, arguments: &FunctionArguments) -> Vec<SExpr>
+    {
+        let mut clauses = Vec::new();
+
+        if !arguments.arguments.is_empty() {
+            clauses.push(SExpr::tagged(
+                "args",
+                arguments
+                    .arguments
+                    .iter()
+                    .map(|(name, (ty, id))| {
+                        SExpr::list(vec![
+                            SExpr::name(name),
+                            SExpr::atom(id.to_string()),
+                            self.ty(ty),
+                        ])
+                    })
+                    .collect(),
+            ));
+        }
+
+        if arguments.ellipsis_present {
+            clauses.push(SExpr::tagged("ellipsis", vec![]));
+        }
+
+        if arguments.receiver_referenced {
+            clauses.push(SExpr::tagged("receiver", vec![]));
+        }
+
+        if !arguments.generics.is_empty() {
+            clauses.push(generics(&arguments.generics));
+        }
+
+        clauses
+    }
+
+    /// Writes a signature without any path information, this is used for trait functions.
+    fn function_signature(&self, signature: &FunctionSignature) -> SExpr
+    {
+        let mut items = vec![SExpr::name(&signature.name)];
+
+        items.extend(self.signature_clauses(signature));
+
+        SExpr::tagged("fn", items)
+    }
+
+    fn custom_item(&self, item: &CustomItem) -> SExpr
+    {
+        match item {
+            CustomItem::Struct(StructDefinition {
+                visibility: struct_visibility,
+                name,
+                fields,
+                generics: struct_generics,
+                attributes,
+            }) => {
+                let mut items = vec![
+                    SExpr::name(name),
+                    SExpr::tagged("visibility", vec![visibility(struct_visibility)]),
+                    self.fields(fields),
+                ];
+
+                if !struct_generics.is_empty() {
+                    items.push(generics(struct_generics));
+                }
+
+                items.extend(self.struct_attributes(attributes));
+
+                SExpr::tagged("struct-def", items)
+            },
+            CustomItem::Enum((ty, variants)) => {
+                let mut items = vec![self.ty(ty)];
+
+                items.extend(self.variants(variants));
+
+                SExpr::tagged("enum-def", items)
+            },
+            CustomItem::Trait {
+                name,
+                functions,
+                access_path,
+            } => {
+                SExpr::tagged(
+                    "trait-def",
+                    vec![
+                        SExpr::name(name),
+                        path("path", access_path),
+                        self.trait_functions(functions),
+                    ],
+                )
+            },
+        }
+    }
+
+    fn fields(&self, fields: &OrdMap<String, Type>) -> SExpr
+    {
+        SExpr::tagged(
+            "fields",
+            fields
+                .iter()
+                .map(|(name, ty)| SExpr::list(vec![SExpr::name(name), self.ty(ty)]))
+                .collect(),
+        )
+    }
+
+    fn variants(&self, variants: &OrdMap<String, Spanned<StatementVariant>>) -> Vec<SExpr>
+    {
+        variants
+            .iter()
+            .map(|(name, value)| SExpr::list(vec![SExpr::name(name), self.statement(value)]))
+            .collect()
+    }
+
+    fn trait_functions(&self, functions: &OrdMap<String, FunctionSignature>) -> SExpr
+    {
+        SExpr::tagged(
+            "functions",
+            functions
+                .values()
+                .map(|signature| self.function_signature(signature))
+                .collect(),
+        )
+    }
+
+    /// Functions which have not been parsed yet are not written, as they only consist of tokens.
+    fn struct_attributes(&self, attributes: &StructAttributes) -> Vec<SExpr>
+    {
+        let mut clauses = Vec::new();
+
+        if !attributes.compiler_instructions().is_empty() {
+            clauses.push(compiler_instructions(attributes.compiler_instructions()));
+        }
+
+        if !attributes.traits_implemented.is_empty() {
+            clauses.push(SExpr::tagged(
+                "traits",
+                attributes
+                    .traits_implemented
+                    .iter()
+                    .map(|trait_path| {
+                        SExpr::list(trait_path.iter().map(|name| SExpr::name(name)).collect())
+                    })
+                    .collect(),
+            ));
+        }
+
+        for (name, function) in attributes.impl_fn_list.iter() {
+            if let ParsedState::Parsed(function) = function {
+                clauses.push(SExpr::tagged(
+                    "impl",
+                    vec![
+                        SExpr::name(name),
+                        self.function(&function.module_path, function),
+                    ],
+                ));
+            }
+        }
+
+        clauses
+    }
+
+    fn ty(&self, ty: &Type) -> SExpr
+    {
+        match ty {
+            Type::I64 => SExpr::atom("i64"),
+            Type::F64 => SExpr::atom("f64"),
+            Type::U64 => SExpr::atom("u64"),
+            Type::I32 => SExpr::atom("i32"),
+            Type::F32 => SExpr::atom("f32"),
+            Type::U32 => SExpr::atom("u32"),
+            Type::I16 => SExpr::atom("i16"),
+            Type::F16 => SExpr::atom("f16"),
+            Type::U16 => SExpr::atom("u16"),
+            Type::U8 => SExpr::atom("u8"),
+            Type::String => SExpr::atom("string"),
+            Type::Boolean => SExpr::atom("bool"),
+            Type::Void => SExpr::atom("void"),
+            Type::Enum((inner_ty, variants)) => {
+                let mut items = vec![self.ty(inner_ty)];
+
+                items.extend(self.variants(variants));
+
+                SExpr::tagged("enum", items)
+            },
+            Type::Struct((name, fields, attributes)) => {
+                let mut items = vec![SExpr::name(name), self.fields(fields)];
+
+                items.extend(self.struct_attributes(attributes));
+
+                SExpr::tagged("struct", items)
+            },
+            Type::Array((inner_ty, len)) => {
+                SExpr::tagged(
+                    "array",
+                    vec![self.ty(inner_ty), SExpr::atom(len.to_string())],
+                )
+            },
+            Type::Pointer(None) => SExpr::atom("ptr"),
+            Type::Pointer(Some(inner_ty)) => SExpr::tagged("ptr", vec![self.ty(inner_ty)]),
+            Type::Trait {
+                name,
+                access_path,
+                functions,
+            } => {
+                SExpr::tagged(
+                    "trait",
+                    vec![
+                        SExpr::name(name),
+                        path("path", access_path),
+                        self.trait_functions(functions),
+                    ],
+                )
+            },
+            Type::Unresolved(name) => SExpr::tagged("unresolved", vec![SExpr::name(name)]),
+            Type::TraitObject(traits) => {
+                SExpr::tagged(
+                    "trait-object",
+                    traits
+                        .iter()
+                        .map(|trait_path| {
+                            SExpr::list(trait_path.iter().map(|name| SExpr::name(name)).collect())
+                        })
+                        .collect(),
+                )
+            },
+        }
+    }
+
+    fn value(&self, value: &Value) -> SExpr
+    {
+        let number = |ty: &str, number: String| SExpr::tagged(ty, vec![SExpr::atom(number)]);
+
+        match value {
+            Value::I64(inner) => number("i64", inner.to_string()),
+            Value::F64(inner) => number("f64", format!("{:?}", **inner)),
+            Value::U64(inner) => number("u64", inner.to_string()),
+            Value::I32(inner) => number("i32", inner.to_string()),
+            Value::F32(inner) => number("f32", format!("{:?}", **inner)),
+            Value::U32(inner) => number("u32", inner.to_string()),
+            Value::I16(inner) => number("i16", inner.to_string()),
+            // Every half precision float can be represented exactly as an `f32`.
+            Value::F16(inner) => number("f16", format!("{:?}", **inner as f32)),
+            Value::U16(inner) => number("u16", inner.to_string()),
+            Value::U8(inner) => number("u8", inner.to_string()),
+            Value::String(inner) => SExpr::tagged("string", vec![SExpr::string(inner)]),
+            Value::Boolean(inner) => number("bool", inner.to_string()),
+            Value::Void => SExpr::atom("void"),
+            Value::Struct((name, fields, values, attributes)) => {
+                let mut items = vec![
+                    SExpr::name(name),
+                    self.fields(fields),
+                    SExpr::tagged(
+                        "values",
+                        values
+                            .iter()
+                            .map(|(name, value)| {
+                                SExpr::list(vec![SExpr::name(name), self.statement(value)])
+                            })
+                            .collect(),
+                    ),
+                ];
+
+                items.extend(self.struct_attributes(attributes));
+
+                SExpr::tagged("struct-value", items)
+            },
+            Value::Array((inner_ty, len)) => {
+                SExpr::tagged(
+                    "array-value",
+                    vec![self.ty(inner_ty), SExpr::atom(len.to_string())],
+                )
+            },
+            Value::Enum((ty, variants, variant)) => {
+                let mut items = vec![self.ty(ty), SExpr::name(variant)];
+
+                items.extend(self.variants(variants));
+
+                SExpr::tagged("enum-value", items)
+            },
+            Value::Pointer((address, inner_ty)) => {
+                let mut items = vec![SExpr::atom(address.to_string())];
+
+                if let Some(inner_ty) = inner_ty {
+                    items.push(self.ty(inner_ty));
+                }
+
+                SExpr::tagged("ptr-value", items)
+            },
+        }
+    }
+
+    fn push_span(&self, items: &mut Vec<SExpr>, span: &SpanInfo)
+    {
+        if self.include_spans {
+            items.push(SExpr::span(span));
+        }
+    }
+
+    fn statements(&self, statements: &[Spanned<StatementVariant>]) -> Vec<SExpr>
+    {
+        statements
+            .iter()
+            .map(|statement| self.statement(statement))
+            .collect()
+    }
+
+    fn statement(&self, statement: &Spanned<StatementVariant>) -> SExpr
+    {
+        let mut items = self.statement_items(statement.get_inner());
+
+        self.push_span(&mut items, statement.get_span());
+
+        SExpr::list(items)
+    }
+
+    /// Returns the items of the list representing the [`StatementVariant`] without its span.
+    fn statement_items(&self, statement: &StatementVariant) -> Vec<SExpr>
+    {
+        let tagged = |head: &str, mut items: Vec<SExpr>| {
+            items.insert(0, SExpr::atom(head));
+
+            items
+        };
+
+        match statement {
+            StatementVariant::NewVariable {
+                variable_name,
+                variable_type,
+                variable_value,
+                variable_id,
+                is_mutable,
+            } => {
+                let mut items = vec![
+                    SExpr::name(variable_name),
+                    SExpr::atom(variable_id.to_string()),
+                ];
+
+                if *is_mutable {
+                    items.push(SExpr::atom("mut"));
+                }
+
+                items.push(self.ty(variable_type));
+                items.push(self.statement(variable_value));
+
+                tagged("let", items)
+            },
+            StatementVariant::BasicReference { variable_name } => {
+                tagged("ref", vec![SExpr::name(variable_name)])
+            },
+            StatementVariant::ArrayReference {
+                variable_reference,
+                index,
+            } => {
+                tagged(
+                    "index",
+                    vec![self.statement(variable_reference), self.statement(index)],
+                )
+            },
+            StatementVariant::StructFieldReference {
+                variable_reference,
+                field_name,
+            } => {
+                tagged(
+                    "field",
+                    vec![self.statement(variable_reference), SExpr::name(field_name)],
+                )
+            },
+            StatementVariant::Value(value) => tagged("value", vec![self.value(value)]),
+            StatementVariant::TypeCast(value, ty) => {
+                tagged("cast", vec![self.statement(value), self.ty(ty)])
+            },
+            StatementVariant::MathematicalExpression { lhs, symbol, rhs } => {
+                tagged(
+                    "math",
+                    vec![
+                        mathematical_symbol(symbol),
+                        self.statement(lhs),
+                        self.statement(rhs),
+                    ],
+                )
+            },
+            StatementVariant::NegateValue(value) => tagged("neg", vec![self.statement(value)]),
+            StatementVariant::Brackets(statements, ty) => {
+                let mut items = vec![self.ty(ty)];
+
+                items.extend(self.statements(statements));
+
+                tagged("brackets", items)
+            },
+            StatementVariant::FunctionCall {
+                identifier,
+                arguments,
+            } => {
+                let mut items = vec![self.statement(identifier)];
+
+                items.extend(arguments.iter().map(|(argument_identifier, argument)| {
+                    match argument_identifier {
+                        FunctionArgumentIdentifier::Identifier(name) => {
+                            SExpr::tagged("arg", vec![SExpr::name(name), self.statement(argument)])
+                        },
+                        FunctionArgumentIdentifier::Index(idx) => {
+                            SExpr::tagged(
+                                "arg-index",
+                                vec![SExpr::atom(idx.to_string()), self.statement(argument)],
+                            )
+                        },
+                    }
+                }));
+
+                tagged("call", items)
+            },
+            StatementVariant::SetValue { receiver, value } => {
+                tagged("set", vec![self.statement(receiver), self.statement(value)])
+            },
+            StatementVariant::ModifyValueArithmetic {
+                receiver,
+                symbol,
+                value,
+            } => {
+                tagged(
+                    "modify",
+                    vec![
+                        mathematical_symbol(symbol),
+                        self.statement(receiver),
+                        self.statement(value),
+                    ],
+                )
+            },
+            StatementVariant::ReturnValue { value } => {
+                tagged("return", vec![self.statement(value)])
+            },
+            StatementVariant::Comparison(lhs, order, rhs, ty) => {
+                tagged(
+                    "cmp",
+                    vec![
+                        SExpr::atom(order_name(order)),
+                        self.ty(ty),
+                        self.statement(lhs),
+                        self.statement(rhs),
+                    ],
+                )
+            },
+            StatementVariant::If(if_definition) => {
+                tagged(
+                    "if",
+                    vec![
+                        self.statement(&if_definition.condition),
+                        SExpr::tagged("then", self.statements(&if_definition.true_branch)),
+                        SExpr::tagged("else", self.statements(&if_definition.false_branch)),
+                    ],
+                )
+            },
+            // The statements of a code block do not store their own span
+            StatementVariant::CodeBlock(statements) => {
+                tagged(
+                    "block",
+                    statements
+                        .iter()
+                        .map(|statement| SExpr::list(self.statement_items(statement)))
+                        .collect(),
+                )
+            },
+            StatementVariant::Loop(statements) => tagged("loop", self.statements(statements)),
+            StatementVariant::ControlFlow(ControlFlowType::Break) => vec![SExpr::atom("break")],
+            StatementVariant::ControlFlow(ControlFlowType::Continue) => {
+                vec![SExpr::atom("continue")]
+            },
+            StatementVariant::ArrayInitialization { values } => {
+                tagged("array", self.statements(values))
+            },
+            StatementVariant::GetPointerTo(value) => tagged("addr", vec![self.statement(value)]),
+            StatementVariant::DerefPointer(value) => tagged("deref", vec![self.statement(value)]),
+        }
+    }
+}
+
+fn path(head: &str, path: &[String]) -> SExpr
+{
+    SExpr::tagged(head, path.iter().map(|name| SExpr::name(name)).collect())
+}
+
+fn generics(generics: &OrdMap<String, OrdSet<String>>) -> SExpr
+{
+    SExpr::tagged(
+        "generics",
+        generics
+            .iter()
+            .map(|(name, traits)| {
+                let mut items = vec![SExpr::name(name)];
+
+                items.extend(traits.iter().map(SExpr::string));
+
+                SExpr::list(items)
+            })
+            .collect(),
+    )
+}
+
+fn compiler_instructions(instructions: &OrdSet<CompilerInstruction>) -> SExpr
+{
+    SExpr::tagged(
+        "instructions",
+        instructions
+            .iter()
+            .map(|instruction| {
+                match instruction {
+                    CompilerInstruction::Cold => SExpr::atom("cold"),
+                    CompilerInstruction::NoFree => SExpr::atom("nofree"),
+                    CompilerInstruction::Inline => SExpr::atom("inline"),
+                    CompilerInstruction::NoUnWind => SExpr::atom("nounwind"),
+                    CompilerInstruction::NoInline => SExpr::atom("noinline"),
+                    CompilerInstruction::OptNone => SExpr::atom("optnone"),
+                    CompilerInstruction::Test => SExpr::atom("test"),
+                    CompilerInstruction::Feature(feature) => {
+                        SExpr::tagged("feature", vec![SExpr::string(feature)])
+                    },
+                    CompilerInstruction::Cfg(predicate) => {
+                        SExpr::tagged("cfg", vec![cfg_predicate(predicate)])
+                    },
+                }
+            })
+            .collect(),
+    )
+}
+
+fn cfg_predicate(predicate: &CfgPredicate) -> SExpr
+{
+    match predicate {
+        CfgPredicate::TargetOs(os) => SExpr::tagged("target_os", vec![SExpr::string(os)]),
+        CfgPredicate::TargetArch(arch) => SExpr::tagged("target_arch", vec![SExpr::string(arch)]),
+        CfgPredicate::PointerWidth(width) => {
+            SExpr::tagged("pointer_width", vec![SExpr::atom(width.to_string())])
+        },
+        CfgPredicate::DebugAssertions => SExpr::atom("debug_assertions"),
+        CfgPredicate::Feature(feature) => SExpr::tagged("feature", vec![SExpr::string(feature)]),
+        CfgPredicate::All(predicates) => {
+            SExpr::tagged("all", predicates.iter().map(cfg_predicate).collect())
+        },
+        CfgPredicate::Any(predicates) => {
+            SExpr::tagged("any", predicates.iter().map(cfg_predicate).collect())
+        },
+        CfgPredicate::Not(predicate) => SExpr::tagged("not", vec![cfg_predicate(predicate)]),
+    }
+}
+
+fn visibility(visibility: &ItemVisibility) -> SExpr
+{
+    SExpr::atom(match visibility {
+        ItemVisibility::Private => "private",
+        ItemVisibility::Public => "public",
+        ItemVisibility::PublicLibrary => "publib",
+        ItemVisibility::Branch => "branch",
+    })
+}
+
+fn mathematical_symbol(symbol: &MathematicalSymbol) -> SExpr
+{
+    SExpr::atom(match symbol {
+        MathematicalSymbol::Addition => "add",
+        MathematicalSymbol::Subtraction => "sub",
+        MathematicalSymbol::Division => "div",
+        MathematicalSymbol::Multiplication => "mul",
+        MathematicalSymbol::Modulo => "mod",
+        MathematicalSymbol::Power => "pow",
+    })
+}
+
+fn order_name(order: &Order) -> &'static str
+{
+    match order {
+        Order::Equal => "eq",
+        Order::NotEqual => "ne",
+        Order::Bigger => "gt",
+        Order::EqBigger => "ge",
+        Order::Smaller => "lt",
+        Order::EqSmaller => "le",
+    }
+}
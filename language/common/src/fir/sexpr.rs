@@ -0,0 +1,350 @@
+use std::fmt::Write;
+
+use crate::error::{SpanInfo, fir::FirError};
+
+/// Lists are printed on a single line if they fit into this many columns.
+const MAX_LINE_WIDTH: usize = 100;
+
+/// Lists starting with these atoms are always broken up into multiple lines if they contain other lists.
+const BLOCK_HEADS: &[&str] = &[
+    "module", "function", "body", "then", "else", "loop", "block", "item", "impl",
+];
+
+/// A node of the s-expression tree Fog IR is written in.
+/// `line` is the line the node starts on in the source, nodes created by the printer have it set to `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SExpr
+{
+    pub kind: SExprKind,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SExprKind
+{
+    Atom(String),
+    Str(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr
+{
+    pub fn atom(atom: impl Into<String>) -> Self
+    {
+        Self {
+            kind: SExprKind::Atom(atom.into()),
+            line: 0,
+        }
+    }
+
+    pub fn string(string: impl Into<String>) -> Self
+    {
+        Self {
+            kind: SExprKind::Str(string.into()),
+            line: 0,
+        }
+    }
+
+    pub fn list(items: Vec<SExpr>) -> Self
+    {
+        Self {
+            kind: SExprKind::List(items),
+            line: 0,
+        }
+    }
+
+    /// Creates a list which starts with the `head` atom.
+    pub fn tagged(head: &str, mut items: Vec<SExpr>) -> Self
+    {
+        items.insert(0, Self::atom(head));
+
+        Self::list(items)
+    }
+
+    /// Names which are valid identifiers are written as atoms, everything else is written as a string.
+    pub fn name(name: &str) -> Self
+    {
+        let mut chars = name.chars();
+
+        let is_identifier = chars
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && chars.all(|char| char.is_ascii_alphanumeric() || char == '_');
+
+        if is_identifier {
+            Self::atom(name)
+        }
+        else {
+            Self::string(name)
+        }
+    }
+
+    /// Span atoms are written like so: `@<line>:<column>-<line>:<column>`.
+    pub fn span(span: &SpanInfo) -> Self
+    {
+        Self::atom(format!(
+            "@{}:{}-{}:{}",
+            span.char_start.line, span.char_start.column, span.char_end.line, span.char_end.column
+        ))
+    }
+
+    pub fn as_atom(&self) -> Option<&str>
+    {
+        match &self.kind {
+            SExprKind::Atom(atom) => Some(atom),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[SExpr]>
+    {
+        match &self.kind {
+            SExprKind::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns the name stored in the node, names can either be atoms or strings.
+    pub fn as_name(&self) -> Option<&str>
+    {
+        match &self.kind {
+            SExprKind::Atom(name) | SExprKind::Str(name) => Some(name),
+            SExprKind::List(_) => None,
+        }
+    }
+
+    pub fn is_span(&self) -> bool
+    {
+        self.as_atom().is_some_and(|atom| atom.starts_with('@'))
+    }
+
+    /// Returns the first atom of the list if the node is a list.
+    pub fn head(&self) -> Option<&str>
+    {
+        self.as_list()
+            .and_then(|items| items.first())
+            .and_then(SExpr::as_atom)
+    }
+
+    fn is_forced_multiline(&self) -> bool
+    {
+        let Some(items) = self.as_list()
+        else {
+            return false;
+        };
+
+        let has_list_child = items.iter().any(|item| item.as_list().is_some());
+
+        (has_list_child && self.head().is_some_and(|head| BLOCK_HEADS.contains(&head)))
+            || items.iter().any(SExpr::is_forced_multiline)
+    }
+
+    fn write_flat(&self, output: &mut String)
+    {
+        match &self.kind {
+            SExprKind::Atom(atom) => output.push_str(atom),
+            SExprKind::Str(string) => write_escaped(output, string),
+            SExprKind::List(items) => {
+                output.push('(');
+
+                for (idx, item) in items.iter().enumerate() {
+                    if idx != 0 {
+                        output.push(' ');
+                    }
+
+                    item.write_flat(output);
+                }
+
+                output.push(')');
+            },
+        }
+    }
+
+    /// Writes the node, breaking up lists which are too long or contain blocks.
+    /// The leading atoms of a broken up list are kept on the first line, every other item is written on its own line.
+    pub fn write(&self, output: &mut String, indent: usize)
+    {
+        let mut flat = String::new();
+        self.write_flat(&mut flat);
+
+        let Some(items) = self.as_list()
+        else {
+            output.push_str(&flat);
+            return;
+        };
+
+        if !self.is_forced_multiline() && indent + flat.len() <= MAX_LINE_WIDTH {
+            output.push_str(&flat);
+            return;
+        }
+
+        output.push('(');
+
+        let leading_atoms = items
+            .iter()
+            .take_while(|item| item.as_list().is_none())
+            .count()
+            .max(1);
+
+        for (idx, item) in items.iter().enumerate() {
+            if idx < leading_atoms {
+                if idx != 0 {
+                    output.push(' ');
+                }
+            }
+            else {
+                output.push('\n');
+                output.push_str(&" ".repeat(indent + 2));
+            }
+
+            item.write(output, indent + 2);
+        }
+
+        output.push(')');
+    }
+}
+
+fn write_escaped(output: &mut String, string: &str)
+{
+    output.push('"');
+
+    for char in string.chars() {
+        match char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\0' => output.push_str("\\0"),
+            char if char.is_control() => {
+                let _ = write!(output, "\\u{{{:x}}}", char as u32);
+            },
+            char => output.push(char),
+        }
+    }
+
+    output.push('"');
+}
+
+/// Reads every top level node from the source.
+pub(crate) fn read(source: &str) -> Result<Vec<SExpr>, FirError>
+{
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    // The list of nodes on every open level, and the line the level was opened on.
+    let mut stack: Vec<(Vec<SExpr>, usize)> = vec![(Vec::new(), 0)];
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\n' => line += 1,
+            char if char.is_whitespace() => (),
+            ';' => while chars.next_if(|char| *char != '\n').is_some() {},
+            '(' => stack.push((Vec::new(), line)),
+            ')' => {
+                if stack.len() == 1 {
+                    return Err(FirError::UnmatchedParenthesis(line));
+                }
+
+                let (items, list_line) = stack.pop().unwrap();
+
+                stack.last_mut().unwrap().0.push(SExpr {
+                    kind: SExprKind::List(items),
+                    line: list_line,
+                });
+            },
+            '"' => {
+                let string_line = line;
+                let mut string = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.next() {
+                                Some('"') => string.push('"'),
+                                Some('\\') => string.push('\\'),
+                                Some('n') => string.push('\n'),
+                                Some('r') => string.push('\r'),
+                                Some('t') => string.push('\t'),
+                                Some('0') => string.push('\0'),
+                                Some('u') => string.push(read_unicode_escape(&mut chars, line)?),
+                                Some(other) => {
+                                    return Err(FirError::InvalidEscapeSequence(
+                                        line,
+                                        other.to_string(),
+                                    ));
+                                },
+                                None => return Err(FirError::UnterminatedString(string_line)),
+                            }
+                        },
+                        Some(char) => {
+                            if char == '\n' {
+                                line += 1;
+                            }
+
+                            string.push(char);
+                        },
+                        None => return Err(FirError::UnterminatedString(string_line)),
+                    }
+                }
+
+                stack.last_mut().unwrap().0.push(SExpr {
+                    kind: SExprKind::Str(string),
+                    line: string_line,
+                });
+            },
+            char => {
+                let mut atom = String::from(char);
+
+                while let Some(char) = chars
+                    .next_if(|char| !char.is_whitespace() && !matches!(char, '(' | ')' | '"' | ';'))
+                {
+                    atom.push(char);
+                }
+
+                stack.last_mut().unwrap().0.push(SExpr {
+                    kind: SExprKind::Atom(atom),
+                    line,
+                });
+            },
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(FirError::UnexpectedEndOfInput(stack.last().unwrap().1));
+    }
+
+    Ok(stack.pop().unwrap().0)
+}
+
+/// Reads the `{<hex>}` part of a `\u{<hex>}` escape sequence.
+fn read_unicode_escape(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    line: usize,
+) -> Result<char, FirError>
+{
+    let mut sequence = String::from("u");
+
+    if chars.next_if_eq(&'{').is_none() {
+        return Err(FirError::InvalidEscapeSequence(line, sequence));
+    }
+
+    sequence.push('{');
+
+    while let Some(char) = chars.next_if(|char| *char != '}' && *char != '"') {
+        sequence.push(char);
+    }
+
+    if chars.next_if_eq(&'}').is_none() {
+        return Err(FirError::InvalidEscapeSequence(line, sequence));
+    }
+
+    u32::from_str_radix(&sequence[2..], 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or(FirError::InvalidEscapeSequence(
+            line,
+            format!("{sequence}}}"),
+        ))
+}
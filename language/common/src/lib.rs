@@ -3,12 +3,18 @@
 
 pub const DEFAULT_COMPILER_ADDRESS_SPACE_SIZE: u16 = 0;
 
+/// Target-aware paths of the build artifacts
+pub mod artifact;
+
 /// LLVM-IR generation code with inkwell
 pub mod codegen;
 
 /// Main fog compiler frontend
 pub mod compiler;
 
+/// The API tokens of the registries the user has logged in to
+pub mod credentials;
+
 /// Types for handling project dependencies
 pub mod dependency;
 
@@ -18,12 +24,18 @@ pub mod distributed_compiler;
 /// Error type definitions which can occure in the compiler
 pub mod error;
 
+/// Textual Fog IR, a printable and parsable form of the analyzed modules
+pub mod fir;
+
 /// Used for hanlidng imports from dependencies and external files
 pub mod imports;
 
 /// Linking interface with clang (Can be compiled as a standalone tool)
 pub mod linker;
 
+/// The lock file recording the exact versions of the dependencies
+pub mod lockfile;
+
 /// Parsing the tokens produced by the tokenizer
 pub mod parser;
 
@@ -35,6 +47,9 @@ pub mod compression;
 /// Custom language types and type wrappers
 pub mod ty;
 
+/// Workspaces containing multiple projects
+pub mod workspace;
+
 pub mod dependency_manager;
 
 /// Used for handling errors in the fog toolset
@@ -58,6 +73,8 @@ pub use strum_macros;
 
 /// Config file parsing / handling
 pub use toml;
+/// Editing config files without losing their formatting
+pub use toml_edit;
 
 /// Used for communicating with the dependency manager server and to create the FDCN
 pub use tokio;
@@ -89,6 +106,12 @@ pub use flate2;
 pub use rmp_serde;
 pub use serde_json;
 
+/// Hashing build artifacts
+pub use sha2;
+
+/// Versions and version requirements of dependencies
+pub use semver;
+
 /// Tracing
 pub use tracing;
 pub use tracing_subscriber;
@@ -1,28 +1,211 @@
 use std::{
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{Command, ExitStatus, Stdio},
 };
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use sha2::{Digest, Sha256};
+
+use crate::{
+    artifact::{OutputKind, config_path, host_triple, workspace_path},
+    compiler::LtoMode,
+    error::{application::ApplicationError, linker::LinkerError},
+};
+
+/// The linker used to link the build outputs, set with `linker` in the config file.
+/// It is serialized as `"clang"`, `"lld"` or the path of the linker executable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Linker
+{
+    /// Links through the `clang` compiler driver found at PATH.
+    #[default]
+    Clang,
+    /// Invokes the LLD flavour matching the target (`ld.lld`, `lld-link` or `ld64.lld`) found at PATH.
+    Lld,
+    /// Invokes the linker executable at the path with LLD style arguments.
+    /// The flavour is selected with the `-flavor` argument only if the executable is the generic `lld` driver.
+    Path(PathBuf),
+}
+
+impl From<String> for Linker
+{
+    fn from(value: String) -> Self
+    {
+        match value.as_str() {
+            "clang" => Self::Clang,
+            "lld" => Self::Lld,
+            _ => Self::Path(PathBuf::from(value)),
+        }
+    }
+}
+
+impl From<Linker> for String
+{
+    fn from(value: Linker) -> Self
+    {
+        match value {
+            Linker::Clang => "clang".to_string(),
+            Linker::Lld => "lld".to_string(),
+            Linker::Path(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// A linked output of the project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildOutput
+{
+    pub kind: OutputKind,
+    pub path: PathBuf,
+}
+
+/// The version of the build manifest format written by the compiler.
+/// Manifests of other versions cannot be linked, the project must be rebuilt to create a new one.
+pub const BUILD_MANIFEST_VERSION: u32 = 2;
+
+/// The version of the compiler, the build artifacts are only linked by the version which produced them.
+pub const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Manifests written before the format has been versioned do not contain a version.
+fn unversioned_manifest() -> u32
+{
+    1
+}
+
+/// The content hash of an input of the linker, recorded when the build manifest is created.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactHash
+{
+    pub path: PathBuf,
+    /// The hex encoded SHA-256 hash of the file's contents.
+    pub sha256: String,
+}
+
+/// A dependency of the project the build outputs have been compiled with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestDependency
+{
+    pub name: String,
+    pub version: String,
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildManifest
 {
+    /// The version of the manifest format, see [`BUILD_MANIFEST_VERSION`].
+    #[serde(default = "unversioned_manifest")]
+    pub version: u32,
+
+    /// The version of the compiler which created the manifest.
+    #[serde(default)]
+    pub compiler_version: String,
+
     pub build_output_paths: Vec<PathBuf>,
     pub additional_linking_material: Vec<PathBuf>,
-    pub output_path: PathBuf,
+
+    /// The outputs the build outputs are linked into. A `fog-lib` library does not have any.
+    pub outputs: Vec<BuildOutput>,
+
+    /// The symbols exported from shared libraries, these are the `publib` functions of the library.
+    #[serde(default)]
+    pub exported_symbols: Vec<String>,
+
+    /// If set, the build outputs are LLVM bitcode which must be linked with link time optimization.
+    pub lto: Option<LtoMode>,
+
+    /// The target the build outputs have been compiled for.
+    #[serde(default = "host_triple")]
+    pub target_triple: String,
+
+    /// The CPU the build outputs have been compiled for, if it was not the generic CPU of the target.
+    #[serde(default)]
+    pub cpu_name: Option<String>,
+
+    /// The CPU features enabled when compiling the build outputs.
+    #[serde(default)]
+    pub cpu_features: Option<String>,
+
+    #[serde(default)]
+    pub linker: Linker,
+
+    /// The dependencies of the project, sorted by their name.
+    #[serde(default)]
+    pub dependencies: Vec<ManifestDependency>,
+
+    /// The hashes of the build outputs and the additional linking material.
+    #[serde(default)]
+    pub artifacts: Vec<ArtifactHash>,
+}
+
+/// Returns the hex encoded SHA-256 hash of the file's contents.
+pub fn hash_artifact(path: &Path) -> Result<String, LinkerError>
+{
+    let contents = fs::read(path).map_err(|_| LinkerError::ArtifactNotFound(path.to_path_buf()))?;
+
+    Ok(format!("{:x}", Sha256::digest(contents)))
+}
+
+/// Strips the project root from the path, relative paths are relative to the current working directory.
+/// Returns `None` if the path is located outside of the project.
+fn localize_path(path: &Path, roots: &[PathBuf]) -> Option<PathBuf>
+{
+    let path = std::path::absolute(path).ok()?;
+
+    roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .map(Path::to_path_buf)
 }
 
 impl BuildManifest
 {
+    /// Reads the build manifest at the path.
+    /// The paths of the manifest are relative to the project root, which is the closest folder to the manifest containing a config file or a workspace manifest.
+    /// The members of a workspace write their manifests into the build folder of the workspace, so their paths are relative to the workspace root.
+    pub fn read(manifest_path: &Path) -> Result<Self, LinkerError>
+    {
+        let manifest =
+            fs::read_to_string(manifest_path).map_err(|err| LinkerError::Other(Box::new(err)))?;
+
+        let manifest =
+            toml::from_str::<Self>(&manifest).map_err(|_| LinkerError::InvalidManifestFormat)?;
+
+        let manifest_path = fs::canonicalize(manifest_path)
+            .map_err(|_| LinkerError::ProjectRootNotFound(manifest_path.to_path_buf()))?;
+
+        let project_root = manifest_path
+            .ancestors()
+            .find(|ancestor| config_path(ancestor).is_file() || workspace_path(ancestor).is_file())
+            .map(Path::to_path_buf)
+            .ok_or_else(|| LinkerError::ProjectRootNotFound(manifest_path.clone()))?;
+
+        Ok(manifest.resolve_paths(&project_root))
+    }
+
+    /// Returns the path of the executable output, if the project is not a library.
+    pub fn executable_path(&self) -> Option<&Path>
+    {
+        self.outputs
+            .iter()
+            .find(|output| output.kind == OutputKind::Executable)
+            .map(|output| output.path.as_path())
+    }
+
     pub fn run_build_output(
         &self,
         project_root: PathBuf,
         args: Vec<String>,
     ) -> anyhow::Result<ExitStatus>
     {
-        Ok(Command::new(self.output_path.clone())
+        let executable_path = self
+            .executable_path()
+            .ok_or(ApplicationError::LibraryNotRunnable)?;
+
+        Ok(Command::new(executable_path)
             .args(args)
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
@@ -31,28 +214,168 @@ impl BuildManifest
             .status()?)
     }
 
-    pub fn localize_paths(self, root: PathBuf) -> Self
+    /// Returns the inputs of the linker, these are the build outputs and the additional linking material.
+    fn linked_artifacts(&self) -> impl Iterator<Item = &PathBuf>
+    {
+        self.build_output_paths
+            .iter()
+            .chain(&self.additional_linking_material)
+    }
+
+    /// Records the hash of every build output and additional linking material.
+    /// This must be called after the build outputs have been written.
+    pub fn record_artifact_hashes(&mut self) -> Result<(), LinkerError>
+    {
+        self.artifacts = self
+            .linked_artifacts()
+            .map(|path| {
+                Ok(ArtifactHash {
+                    path: path.clone(),
+                    sha256: hash_artifact(path)?,
+                })
+            })
+            .collect::<Result<Vec<_>, LinkerError>>()?;
+
+        Ok(())
+    }
+
+    /// Checks whether the build outputs can be linked by this compiler.
+    /// The manifest must have been created by the same version of the compiler, for the requested target (if there is one).
+    /// Every build output and additional linking material must be unchanged since the manifest has been created.
+    pub fn verify(&self, target_triple: Option<&str>) -> Result<(), LinkerError>
+    {
+        if self.version != BUILD_MANIFEST_VERSION {
+            return Err(LinkerError::UnsupportedManifestVersion(
+                self.version,
+                BUILD_MANIFEST_VERSION,
+            ));
+        }
+
+        if self.compiler_version != COMPILER_VERSION {
+            return Err(LinkerError::CompilerVersionMismatch(
+                self.compiler_version.clone(),
+                COMPILER_VERSION.to_string(),
+            ));
+        }
+
+        if let Some(target_triple) = target_triple
+            && target_triple != self.target_triple
+        {
+            return Err(LinkerError::TargetMismatch(
+                self.target_triple.clone(),
+                target_triple.to_string(),
+            ));
+        }
+
+        for path in self.linked_artifacts() {
+            let recorded_hash = self
+                .artifacts
+                .iter()
+                .find(|artifact| artifact.path == *path)
+                .ok_or_else(|| LinkerError::MissingArtifactHash(path.clone()))?;
+
+            if hash_artifact(path)? != recorded_hash.sha256 {
+                return Err(LinkerError::StaleArtifact(path.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes the paths of the manifest relative to the project root, so that the project can be moved without invalidating the manifest.
+    /// The build outputs must be located inside of the project, the additional linking material outside of the project keeps its absolute path.
+    pub fn localize_paths(self, root: &Path) -> Result<Self, LinkerError>
+    {
+        // The build outputs may have been created through symlinks or relative paths
+        let roots = [
+            std::path::absolute(root),
+            fs::canonicalize(root),
+        ]
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| LinkerError::ProjectRootNotFound(root.to_path_buf()))?;
+
+        let localize_output = |path: PathBuf| {
+            localize_path(&path, &roots)
+                .ok_or_else(|| LinkerError::PathOutsideProject(path.clone(), root.to_path_buf()))
+        };
+
+        let localize_material = |path: PathBuf| {
+            localize_path(&path, &roots)
+                .or_else(|| std::path::absolute(&path).ok())
+                .unwrap_or(path)
+        };
+
+        Ok(Self {
+            build_output_paths: self
+                .build_output_paths
+                .into_iter()
+                .map(localize_output)
+                .collect::<Result<Vec<_>, _>>()?,
+            additional_linking_material: self
+                .additional_linking_material
+                .into_iter()
+                .map(localize_material)
+                .collect(),
+            outputs: self
+                .outputs
+                .into_iter()
+                .map(|output| {
+                    Ok(BuildOutput {
+                        kind: output.kind,
+                        path: localize_output(output.path)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, LinkerError>>()?,
+            artifacts: self
+                .artifacts
+                .into_iter()
+                .map(|artifact| {
+                    ArtifactHash {
+                        path: localize_material(artifact.path),
+                        sha256: artifact.sha256,
+                    }
+                })
+                .collect(),
+            ..self
+        })
+    }
+
+    /// Joins the relative paths of the manifest to the project root, this reverses [`BuildManifest::localize_paths`].
+    pub fn resolve_paths(self, root: &Path) -> Self
     {
         Self {
             build_output_paths: self
                 .build_output_paths
                 .iter()
-                .map(|p| {
-                    p.strip_prefix(fs::canonicalize(&root).unwrap())
-                        .unwrap()
-                        .to_path_buf()
-                })
-                .collect::<Vec<PathBuf>>(),
+                .map(|path| root.join(path))
+                .collect(),
             additional_linking_material: self
                 .additional_linking_material
                 .iter()
-                .map(|p| {
-                    p.strip_prefix(fs::canonicalize(&root).unwrap())
-                        .unwrap()
-                        .to_path_buf()
+                .map(|path| root.join(path))
+                .collect(),
+            outputs: self
+                .outputs
+                .into_iter()
+                .map(|output| {
+                    BuildOutput {
+                        kind: output.kind,
+                        path: root.join(output.path),
+                    }
+                })
+                .collect(),
+            artifacts: self
+                .artifacts
+                .into_iter()
+                .map(|artifact| {
+                    ArtifactHash {
+                        path: root.join(artifact.path),
+                        sha256: artifact.sha256,
+                    }
                 })
-                .collect::<Vec<PathBuf>>(),
-            output_path: self.output_path.strip_prefix(&root).unwrap().to_path_buf(),
+                .collect(),
+            ..self
         }
     }
 }
@@ -0,0 +1,251 @@
+use std::{
+    fs,
+    io::{Read, Seek},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+use crate::error::dependency::DependencyError;
+
+/// The version of the lock file format, lock files of a different version are regenerated.
+pub const LOCK_FILE_VERSION: u32 = 1;
+
+/// The exact versions of the dependencies a project has been built with, stored in the `fog.lock` file next to the config file.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LockFile
+{
+    pub version: u32,
+
+    /// The locked dependencies, sorted by their name.
+    #[serde(default, rename = "dependency")]
+    pub dependencies: Vec<LockedDependency>,
+}
+
+/// A dependency resolved to an exact version.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LockedDependency
+{
+    pub name: String,
+    pub version: String,
+    pub source: DependencySource,
+    /// The enabled features of the dependency, sorted by their name.
+    pub features: Vec<String>,
+    /// The SHA-256 checksum of the dependency's files, see [`checksum_directory`] and [`checksum_archive`].
+    /// Path dependencies do not have a checksum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// The SHA-256 checksum of the sources a remote compiler worker has downloaded from the registry and compiled the dependency from.
+    /// Only the dependencies compiled by a remote have one, the worker verifies the sources it downloads against it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_checksum: Option<String>,
+}
+
+/// The location a dependency has been fetched from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencySource
+{
+    /// The url of the dependency manager the dependency has been downloaded from.
+    Registry(String),
+    /// The folder containing the dependency, relative to the project root.
+    Path(PathBuf),
+    /// The name of the remote compiler worker which has compiled the dependency.
+    Remote(String),
+}
+
+impl Default for LockFile
+{
+    fn default() -> Self
+    {
+        Self {
+            version: LOCK_FILE_VERSION,
+            dependencies: Vec::new(),
+        }
+    }
+}
+
+impl LockFile
+{
+    /// Reads the lock file at the path, returns `None` if the project does not have a lock file yet.
+    pub fn read(lock_file_path: &Path) -> Result<Option<Self>, DependencyError>
+    {
+        if !lock_file_path.is_file() {
+            return Ok(None);
+        }
+
+        let lock_file = fs::read_to_string(lock_file_path)
+            .map_err(|err| DependencyError::FileError(err.into()))?;
+
+        toml::from_str::<Self>(&lock_file)
+            .map(Some)
+            .map_err(|_| DependencyError::InvalidLockFile(lock_file_path.to_path_buf()))
+    }
+
+    /// Writes the lock file to the path.
+    pub fn write(&self, lock_file_path: &Path) -> Result<(), DependencyError>
+    {
+        let lock_file =
+            toml::to_string(self).map_err(|err| DependencyError::FileError(err.into()))?;
+
+        fs::write(lock_file_path, lock_file).map_err(|err| DependencyError::FileError(err.into()))
+    }
+
+    /// Returns the locked version of the dependency.
+    pub fn get(&self, name: &str) -> Option<&LockedDependency>
+    {
+        self.dependencies
+            .iter()
+            .find(|dependency| dependency.name == name)
+    }
+
+    /// Inserts the dependency into the lock file, replacing its previously locked version.
+    pub fn insert(&mut self, dependency: LockedDependency)
+    {
+        self.dependencies
+            .retain(|locked_dependency| locked_dependency.name != dependency.name);
+
+        self.dependencies.push(dependency);
+
+        self.dependencies
+            .sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+    }
+}
+
+impl LockedDependency
+{
+    /// Checks that the `checksum` of the fetched dependency matches the locked checksum, if the dependency has been locked with one.
+    pub fn verify_checksum(&self, checksum: &str) -> Result<(), DependencyError>
+    {
+        if let Some(locked_checksum) = &self.checksum
+            && locked_checksum != checksum
+        {
+            return Err(DependencyError::ChecksumMismatch(
+                self.name.clone(),
+                locked_checksum.clone(),
+                checksum.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the `checksum` of the sources a remote has compiled the dependency from matches the locked checksum, if the dependency has been locked with one.
+    pub fn verify_source_checksum(&self, checksum: &str) -> Result<(), DependencyError>
+    {
+        if let Some(locked_checksum) = &self.source_checksum
+            && locked_checksum != checksum
+        {
+            return Err(DependencyError::ChecksumMismatch(
+                self.name.clone(),
+                locked_checksum.clone(),
+                checksum.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Hashes the files sorted by their paths, the checksum does not depend on the order or the metadata of the files.
+fn checksum_files(mut files: Vec<(String, Vec<u8>)>) -> String
+{
+    files.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+    let mut hasher = Sha256::new();
+
+    for (path, contents) in files {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(contents);
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// Returns the hex encoded SHA-256 checksum of the files in the folder, the folders named in `excluded_folders` are skipped.
+/// The checksum of a folder is the same as the checksum of the archive it has been extracted from.
+pub fn checksum_directory(root: &Path, excluded_folders: &[&str])
+-> Result<String, DependencyError>
+{
+    let mut files = Vec::new();
+    let mut folders = vec![root.to_path_buf()];
+
+    while let Some(folder) = folders.pop() {
+        for entry in fs::read_dir(&folder).map_err(|err| DependencyError::FileError(err.into()))? {
+            let path = entry
+                .map_err(|err| DependencyError::FileError(err.into()))?
+                .path();
+
+            if path.is_dir() {
+                if folder == root
+                    && path.file_name().is_some_and(|name| {
+                        excluded_folders.iter().any(|excluded| name == *excluded)
+                    })
+                {
+                    continue;
+                }
+
+                folders.push(path);
+            }
+            else {
+                let relative_path = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                let contents =
+                    fs::read(&path).map_err(|err| DependencyError::FileError(err.into()))?;
+
+                files.push((relative_path, contents));
+            }
+        }
+    }
+
+    Ok(checksum_files(files))
+}
+
+/// Returns the hex encoded SHA-256 checksum of the files in the archive.
+pub fn checksum_archive<T: Read + Seek>(
+    archive: &mut ZipArchive<T>,
+) -> Result<String, DependencyError>
+{
+    let mut files = Vec::new();
+
+    for archive_idx in 0..archive.len() {
+        let mut archived_file = archive
+            .by_index(archive_idx)
+            .map_err(|err| DependencyError::FileError(err.into()))?;
+
+        if !archived_file.is_file() {
+            continue;
+        }
+
+        let Some(path) = archived_file.enclosed_name()
+        else {
+            continue;
+        };
+
+        let relative_path = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut contents = Vec::new();
+
+        archived_file
+            .read_to_end(&mut contents)
+            .map_err(|err| DependencyError::FileError(err.into()))?;
+
+        files.push((relative_path, contents));
+    }
+
+    Ok(checksum_files(files))
+}
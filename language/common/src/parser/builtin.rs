@@ -0,0 +1,30 @@
+use strum_macros::{Display, EnumString};
+
+/// The functions provided by the compiler, these can be called without being defined or imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum BuiltinFunction
+{
+    /// `assert(condition)` stops the program with a failure if the condition is `false`.
+    Assert,
+    /// `assert_eq(lhs, rhs)` stops the program with a failure if the values are not equal, it is generated as `assert(lhs == rhs)`.
+    AssertEq,
+}
+
+impl BuiltinFunction
+{
+    /// Returns the built-in function with the given name, if there is one.
+    pub fn from_name(name: &str) -> Option<Self>
+    {
+        name.parse().ok()
+    }
+
+    /// The number of arguments the built-in function takes.
+    pub fn argument_count(&self) -> usize
+    {
+        match self {
+            Self::Assert => 1,
+            Self::AssertEq => 2,
+        }
+    }
+}
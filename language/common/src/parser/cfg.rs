@@ -0,0 +1,224 @@
+use std::fmt::Display;
+
+use crate::{
+    anyhow,
+    artifact::TripleComponents,
+    error::{Spanned, parser::ParserError},
+    parser::{common::Streamable, function::CompilerInstructionDiscriminants},
+    tokenizer::{Token, TokenDiscriminants},
+    ty::OrdSet,
+};
+
+/// A predicate of a `@cfg(<predicate>)` compiler instruction.
+/// Items and statements are only compiled if the predicate holds for the target they are compiled for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CfgPredicate
+{
+    /// `target_os = "<os>"`
+    TargetOs(String),
+    /// `target_arch = "<arch>"`
+    TargetArch(String),
+    /// `pointer_width = "<bits>"`
+    PointerWidth(u32),
+    /// `debug_assertions`, holds if the project is compiled without optimizations.
+    DebugAssertions,
+    /// `feature = "<name>"`
+    Feature(String),
+    /// `all(<predicate>, ...)`, holds if every predicate holds.
+    All(Vec<CfgPredicate>),
+    /// `any(<predicate>, ...)`, holds if at least one of the predicates holds.
+    Any(Vec<CfgPredicate>),
+    /// `not(<predicate>)`
+    Not(Box<CfgPredicate>),
+}
+
+impl CfgPredicate
+{
+    /// Evaluates the predicate for the target and the enabled features of the project.
+    pub fn evaluate(&self, target: &CfgTarget, enabled_features: &OrdSet<String>) -> bool
+    {
+        match self {
+            Self::TargetOs(os) => &target.target_os == os,
+            Self::TargetArch(arch) => &target.target_arch == arch,
+            Self::PointerWidth(width) => target.pointer_width == *width,
+            Self::DebugAssertions => target.debug_assertions,
+            Self::Feature(feature) => enabled_features.contains(feature),
+            Self::All(predicates) => {
+                predicates
+                    .iter()
+                    .all(|predicate| predicate.evaluate(target, enabled_features))
+            },
+            Self::Any(predicates) => {
+                predicates
+                    .iter()
+                    .any(|predicate| predicate.evaluate(target, enabled_features))
+            },
+            Self::Not(predicate) => !predicate.evaluate(target, enabled_features),
+        }
+    }
+}
+
+impl Display for CfgPredicate
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let join = |predicates: &[CfgPredicate]| {
+            predicates
+                .iter()
+                .map(|predicate| predicate.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            Self::TargetOs(os) => write!(f, r#"target_os = "{os}""#),
+            Self::TargetArch(arch) => write!(f, r#"target_arch = "{arch}""#),
+            Self::PointerWidth(width) => write!(f, r#"pointer_width = "{width}""#),
+            Self::DebugAssertions => write!(f, "debug_assertions"),
+            Self::Feature(feature) => write!(f, r#"feature = "{feature}""#),
+            Self::All(predicates) => write!(f, "all({})", join(predicates)),
+            Self::Any(predicates) => write!(f, "any({})", join(predicates)),
+            Self::Not(predicate) => write!(f, "not({predicate})"),
+        }
+    }
+}
+
+/// The values `@cfg` predicates are evaluated against, derived from the target triple the project is compiled for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CfgTarget
+{
+    /// The operating system of the target (ie. `linux`, `windows`, `macos`), `none` for bare metal targets.
+    pub target_os: String,
+    /// The architecture of the target (ie. `x86_64`, `x86`, `aarch64`, `arm`).
+    pub target_arch: String,
+    /// The size of a pointer in bits.
+    pub pointer_width: u32,
+    pub debug_assertions: bool,
+}
+
+impl CfgTarget
+{
+    /// Creates the cfg values of a target triple (ie. `x86_64-pc-windows-msvc`).
+    pub fn from_triple(triple: &str, debug_assertions: bool) -> Self
+    {
+        let components = TripleComponents::parse(triple);
+
+        Self {
+            target_os: components.normalized_os().to_string(),
+            target_arch: components.normalized_arch().to_string(),
+            pointer_width: components.pointer_width(),
+            debug_assertions,
+        }
+    }
+}
+
+/// Parses the parenthesized predicate of a `@cfg` compiler instruction, the `cfg` token must already be consumed.
+/// Syntax of a predicate:
+/// ```
+/// <option> ["=" "\"<value>\""] | ("all" | "any" | "not") "(" [{<predicate>} ","] ")"
+/// ```
+pub fn parse_cfg<S: Streamable<Spanned<Token>>>(tokens: &mut S) -> anyhow::Result<CfgPredicate>
+{
+    tokens.try_consume_match(
+        ParserError::InvalidCfgPredicate,
+        &TokenDiscriminants::OpenParentheses,
+    )?;
+
+    let predicate = parse_cfg_predicate(tokens)?;
+
+    tokens.try_consume_match(
+        ParserError::InvalidCfgPredicate,
+        &TokenDiscriminants::CloseParentheses,
+    )?;
+
+    Ok(predicate)
+}
+
+fn parse_cfg_predicate<S: Streamable<Spanned<Token>>>(
+    tokens: &mut S,
+) -> anyhow::Result<CfgPredicate>
+{
+    let option = match tokens.consume().map(|tkn| tkn.get_inner()) {
+        Some(Token::Identifier(option)) => option.clone(),
+        // `feature` is tokenized as a compiler instruction
+        Some(Token::CompilerInstruction(CompilerInstructionDiscriminants::Feature)) => {
+            "feature".to_string()
+        },
+        _ => return Err(ParserError::InvalidCfgPredicate.into()),
+    };
+
+    let predicate = match option.as_str() {
+        "all" | "any" | "not" => {
+            tokens.try_consume_match(
+                ParserError::InvalidCfgPredicate,
+                &TokenDiscriminants::OpenParentheses,
+            )?;
+
+            let mut predicates = Vec::new();
+
+            while tokens
+                .peek_next()
+                .is_some_and(|tkn| tkn.get_inner() != &Token::CloseParentheses)
+            {
+                predicates.push(parse_cfg_predicate(tokens)?);
+
+                // The predicates are separated by commas, a trailing comma is allowed
+                if tokens
+                    .try_consume_match(ParserError::InvalidCfgPredicate, &TokenDiscriminants::Comma)
+                    .is_err()
+                {
+                    break;
+                }
+            }
+
+            tokens.try_consume_match(
+                ParserError::InvalidCfgPredicate,
+                &TokenDiscriminants::CloseParentheses,
+            )?;
+
+            match option.as_str() {
+                "all" => CfgPredicate::All(predicates),
+                "any" => CfgPredicate::Any(predicates),
+                _ => {
+                    let [predicate] = <[CfgPredicate; 1]>::try_from(predicates)
+                        .map_err(|_| ParserError::InvalidCfgPredicate)?;
+
+                    CfgPredicate::Not(Box::new(predicate))
+                },
+            }
+        },
+        "debug_assertions" => CfgPredicate::DebugAssertions,
+        "target_os" | "target_arch" | "pointer_width" | "feature" => {
+            tokens.try_consume_match(
+                ParserError::InvalidCfgPredicate,
+                &TokenDiscriminants::SetValue,
+            )?;
+
+            let value = tokens
+                .try_consume_match(
+                    ParserError::InvalidCfgPredicate,
+                    &TokenDiscriminants::Literal,
+                )?
+                .try_as_literal_ref()
+                .and_then(|val| val.try_as_string_ref())
+                .ok_or(ParserError::InvalidCfgPredicate)?
+                .clone();
+
+            match option.as_str() {
+                "target_os" => CfgPredicate::TargetOs(value),
+                "target_arch" => CfgPredicate::TargetArch(value),
+                "pointer_width" => {
+                    CfgPredicate::PointerWidth(
+                        value
+                            .parse()
+                            .map_err(|_| ParserError::InvalidCfgPredicate)?,
+                    )
+                },
+                _ => CfgPredicate::Feature(value),
+            }
+        },
+        _ => return Err(ParserError::UnknownCfgOption(option).into()),
+    };
+
+    Ok(predicate)
+}
@@ -8,8 +8,9 @@ use crate::{
         CustomItem, DerefMode, FunctionArgumentIdentifier, If, Order, StructAttributes,
         StructDefinition,
     },
-    error::{Spanned, parser::ParserError, syntax::SyntaxError},
+    error::{SpanInfo, Spanned, parser::ParserError, syntax::SyntaxError},
     parser::{
+        cfg::{CfgTarget, parse_cfg},
         function::{
             CompilerInstruction, CompilerInstructionDiscriminants, FunctionArguments,
             FunctionDefinition, FunctionSignature, PathMap,
@@ -627,6 +628,7 @@ impl Context
     pub fn create_function(
         &self,
         vis: ItemVisibility,
+        span: SpanInfo,
         name: String,
         arguments: FunctionArguments,
         return_type: Type,
@@ -646,6 +648,7 @@ impl Context
             compiler_instructions,
             enabling_features,
             body,
+            span,
         }
     }
 
@@ -758,9 +761,9 @@ pub fn find_next_comma(slice: &[Token]) -> Result<usize>
     Err(ParserError::SyntaxError(SyntaxError::CommaNotFound).into())
 }
 
-pub fn parse_compiler_instruction(
+pub fn parse_compiler_instruction<S: Streamable<Spanned<Token>>>(
     instr_buf: &mut OrdSet<CompilerInstruction>,
-    tokens: &mut Stream<Spanned<Token>>,
+    tokens: &mut S,
 ) -> anyhow::Result<()>
 {
     if let Some(tkn) = tokens.consume() {
@@ -781,6 +784,12 @@ pub fn parse_compiler_instruction(
 
                     instr_buf.insert(CompilerInstruction::Feature(feature_name.clone()));
                 }
+                // If this is a cfg the predicate follows it in parentheses.
+                else if instr == &CompilerInstructionDiscriminants::Cfg {
+                    let predicate = parse_cfg(tokens)?;
+
+                    instr_buf.insert(CompilerInstruction::Cfg(predicate));
+                }
                 // If its not a feature we can just store the instruction as is.
                 else {
                     instr_buf.insert((*instr).into());
@@ -802,3 +811,20 @@ pub fn parse_compiler_instruction(
 
     Ok(())
 }
+
+/// Returns whether every feature required by the compiler instructions (`@feature "<name>"`) is enabled, and every `@cfg` predicate holds for the target.
+/// Items and statements which are not enabled are parsed, but left out of the [`Context`].
+pub fn instructions_enabled(
+    compiler_instructions: &OrdSet<CompilerInstruction>,
+    enabled_features: &OrdSet<String>,
+    cfg_target: &CfgTarget,
+) -> bool
+{
+    compiler_instructions.iter().all(|instruction| {
+        match instruction {
+            CompilerInstruction::Feature(feature) => enabled_features.contains(feature),
+            CompilerInstruction::Cfg(predicate) => predicate.evaluate(cfg_target, enabled_features),
+            _ => true,
+        }
+    })
+}
@@ -6,11 +6,13 @@ use strum::{EnumDiscriminants, EnumTryAs};
 
 use crate::{
     anyhow::{self},
-    error::{Spanned, parser::ParserError, syntax::SyntaxError},
+    error::{SpanInfo, Spanned, parser::ParserError, syntax::SyntaxError},
     indexmap::IndexMap,
     parser::{
+        cfg::{CfgPredicate, CfgTarget},
         common::{
             Context, ItemVisibility, StatementVariant, Stream, Streamable, find_closing_braces,
+            instructions_enabled, parse_compiler_instruction,
         },
         numeric_value::MathematicalSymbol,
         statement::parse_statement,
@@ -51,6 +53,19 @@ pub struct FunctionDefinition
 
     /// Features required to be enabled for this function.
     pub enabling_features: OrdSet<String>,
+
+    /// The span of the function's name. This is used as the location of the function in the debug information.
+    pub span: SpanInfo,
+}
+
+impl FunctionDefinition
+{
+    /// Returns whether the function has been marked with `@test`.
+    pub fn is_test(&self) -> bool
+    {
+        self.compiler_instructions
+            .contains(&CompilerInstruction::Test)
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
@@ -128,9 +143,16 @@ pub enum CompilerInstruction
     NoFree,
     Inline,
     NoUnWind,
+    NoInline,
+    /// Disables the optimization passes for the function, this implies [`CompilerInstruction::NoInline`].
+    OptNone,
+    /// Marks the function as a test. Tests are only compiled into the test harness built by `fog test`.
+    Test,
 
     /// Feature flag to only enable compilation of the function if a certain function is enabled
     Feature(String),
+    /// Only enables compilation of the item or statement if the predicate holds for the target, see [`CfgPredicate`].
+    Cfg(CfgPredicate),
 }
 
 impl From<CompilerInstructionDiscriminants> for CompilerInstruction
@@ -142,9 +164,15 @@ impl From<CompilerInstructionDiscriminants> for CompilerInstruction
             CompilerInstructionDiscriminants::NoFree => CompilerInstruction::NoFree,
             CompilerInstructionDiscriminants::Inline => CompilerInstruction::Inline,
             CompilerInstructionDiscriminants::NoUnWind => CompilerInstruction::NoUnWind,
+            CompilerInstructionDiscriminants::NoInline => CompilerInstruction::NoInline,
+            CompilerInstructionDiscriminants::OptNone => CompilerInstruction::OptNone,
+            CompilerInstructionDiscriminants::Test => CompilerInstruction::Test,
             CompilerInstructionDiscriminants::Feature => {
                 CompilerInstruction::Feature(String::new())
             },
+            CompilerInstructionDiscriminants::Cfg => {
+                CompilerInstruction::Cfg(CfgPredicate::All(Vec::new()))
+            },
         }
     }
 }
@@ -454,6 +482,8 @@ pub fn parse_function(
     vis: &ItemVisibility,
     tokens: &mut Stream<Spanned<Token>>,
     mut compiler_instructions: OrdSet<CompilerInstruction>,
+    enabled_features: &OrdSet<String>,
+    cfg_target: &CfgTarget,
 ) -> anyhow::Result<FunctionDefinition>
 {
     // Get the function name token
@@ -462,6 +492,8 @@ pub fn parse_function(
         &TokenDiscriminants::Identifier,
     )?;
 
+    let function_name_span = *function_name_tkn.get_span();
+
     // Parse function name, its safe to unwrap here
     let function_name = function_name_tkn
         .try_as_identifier_ref()
@@ -512,7 +544,7 @@ pub fn parse_function(
     )?;
 
     // Fetch the function body and increment the tokenstream accordingly.
-    let fn_body = parse_fn_body(tokens)?;
+    let fn_body = parse_fn_body(tokens, enabled_features, cfg_target)?;
 
     // This should never return an error since we are already checking the closing brace when fetching the fn body.
     tokens.try_consume_match(
@@ -536,6 +568,7 @@ pub fn parse_function(
 
     Ok(ctx.create_function(
         vis.clone(),
+        function_name_span,
         function_name,
         arguments,
         return_type,
@@ -545,6 +578,96 @@ pub fn parse_function(
     ))
 }
 
+/// Parses the signature of an external function, which is linked from a precompiled library.
+/// The function assumes the first token to be the name of the function.
+/// Syntax of an external function:
+/// ```
+/// "external" <name> "(" [{<arg_name> ":" <type>,}] ["..."] ")" ":" <type> ";"
+/// ```
+pub fn parse_external_function(
+    tokens: &mut Stream<Spanned<Token>>,
+) -> anyhow::Result<FunctionSignature>
+{
+    let function_name = tokens
+        .try_consume_match(
+            ParserError::SyntaxError(SyntaxError::InvalidFunctionName),
+            &TokenDiscriminants::Identifier,
+        )?
+        .try_as_identifier_ref()
+        .unwrap()
+        .to_owned();
+
+    tokens.try_consume_match(
+        ParserError::InvalidSignatureDefinition,
+        &TokenDiscriminants::OpenParentheses,
+    )?;
+
+    let mut arguments = FunctionArguments::new();
+
+    'main_loop: while let Some(tkn) = tokens.consume().cloned() {
+        match tkn.get_inner() {
+            Token::Identifier(arg_name) => {
+                tokens.try_consume_match(
+                    ParserError::InvalidFunctionArgumentDefinition,
+                    &TokenDiscriminants::Colon,
+                )?;
+
+                // External functions may take typed pointers and arrays too
+                let arg_ty = parse_type(tokens)?;
+
+                if arguments
+                    .arguments
+                    .insert(
+                        arg_name.clone(),
+                        (arg_ty, VARIABLE_ID_SOURCE.get_unique_id()),
+                    )
+                    .is_some()
+                {
+                    return Err(ParserError::DuplicateArguments(arg_name.clone()).into());
+                }
+
+                match tokens.consume().map(|tkn| tkn.get_inner()) {
+                    Some(Token::Comma) => continue 'main_loop,
+                    Some(Token::CloseParentheses) => break 'main_loop,
+                    Some(_) => return Err(ParserError::InvalidFunctionArgumentDefinition.into()),
+                    None => return Err(ParserError::EOF.into()),
+                }
+            },
+            // The variable arguments of the function, this must be the last argument
+            Token::Ellipsis => {
+                arguments.ellipsis_present = true;
+
+                tokens.try_consume_match(
+                    ParserError::InvalidEllipsisPosition,
+                    &TokenDiscriminants::CloseParentheses,
+                )?;
+
+                break 'main_loop;
+            },
+            Token::CloseParentheses => break 'main_loop,
+            _ => return Err(ParserError::InvalidFunctionArgumentDefinition.into()),
+        }
+    }
+
+    tokens.try_consume_match(
+        ParserError::SyntaxError(SyntaxError::FunctionRequiresReturn),
+        &TokenDiscriminants::Colon,
+    )?;
+
+    let return_type = parse_type(tokens)?;
+
+    tokens.try_consume_match(
+        ParserError::SyntaxError(SyntaxError::MissingSemiColon),
+        &TokenDiscriminants::SemiColon,
+    )?;
+
+    Ok(FunctionSignature {
+        name: function_name,
+        args: arguments,
+        return_type,
+    })
+}
+
 /// The function assumes the first token to be the first token in the `|`s.
 /// The function does not check or evaluate anything it parses besides syntax checking.
 pub fn parse_generics(
@@ -704,8 +827,11 @@ pub fn parse_fn_sig_arguments(
 /// This function will parse the tokens in the body of the function, but it will not check the validness of the tokens themselves.
 ///
 /// The function parses the tokens but does not evaluate them.
+/// Statements gated behind a disabled feature with `@feature "<name>"` or a `@cfg` predicate not holding for the target are left out of the body.
 pub fn parse_fn_body(
     tokens: &mut Stream<Spanned<Token>>,
+    enabled_features: &OrdSet<String>,
+    cfg_target: &CfgTarget,
 ) -> anyhow::Result<Vec<Spanned<StatementVariant>>>
 {
     // Get the index of the closing brace token
@@ -721,6 +847,27 @@ pub fn parse_fn_body(
     // Iterate over the whole body matching "chunks" of tokens.
     // I dont want to consume the token from the token stream every iteration, since i want to match "patterns" of tokens.
     while fn_body.peek_next().is_some() {
+        let mut statement_instructions = OrdSet::new();
+
+        while fn_body
+            .peek_next()
+            .is_some_and(|tkn| tkn.get_inner() == &Token::CompilerHintSymbol)
+        {
+            fn_body.consume();
+
+            parse_compiler_instruction(&mut statement_instructions, &mut fn_body)?;
+        }
+
+        // Only features and cfg predicates can be applied to statements
+        if let Some(instruction) = statement_instructions.iter().find(|instruction| {
+            !matches!(
+                instruction,
+                CompilerInstruction::Feature(_) | CompilerInstruction::Cfg(_)
+            )
+        }) {
+            return Err(ParserError::InvalidStatementInstruction(instruction.clone()).into());
+        }
+
         // The line expression closing semi colon is not consumed so it must be matched here.
         let stmt = parse_statement(&mut fn_body)?;
 
@@ -733,7 +880,9 @@ pub fn parse_fn_body(
             .is_ok()
         {}
 
-        parsed_tokens.push(stmt);
+        if instructions_enabled(&statement_instructions, enabled_features, cfg_target) {
+            parsed_tokens.push(stmt);
+        }
     }
 
     Ok(parsed_tokens)
@@ -1,8 +1,11 @@
+pub mod builtin;
+pub mod cfg;
 pub mod common;
 pub mod dbg;
 pub mod function;
 pub mod import;
 pub mod numeric_value;
+pub mod pretty;
 pub mod statement;
 pub mod statements;
 pub mod ty;
@@ -0,0 +1,254 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::FunctionArgumentIdentifier,
+    error::{SpanInfo, Spanned},
+    parser::{
+        common::{Context, StatementVariant},
+        function::{FunctionArguments, FunctionSignature},
+    },
+    tokenizer::Token,
+};
+
+/// Dumps the token stream, one token per line prefixed by its position.
+pub fn pretty_print_tokens(tokens: &[Spanned<Token>]) -> String
+{
+    let mut output = String::new();
+
+    for token in tokens {
+        let _ = writeln!(
+            output,
+            "{}:{}\t{:?}",
+            token.span.char_start.line, token.span.char_start.column, token.inner
+        );
+    }
+
+    output
+}
+
+/// Pretty-prints the external declarations and the [`StatementVariant`] tree of every function in the [`Context`].
+/// Every node is printed on its own line, indented by its depth in the tree and followed by the position it starts at.
+pub fn pretty_print_context(ctx: &Context) -> String
+{
+    let mut output = String::new();
+
+    for (path, _name, signature) in ctx.external_decls.iter() {
+        let _ = writeln!(
+            output,
+            "external {}",
+            format_signature(&path.join("::"), signature)
+        );
+    }
+
+    for (path, _name, function) in ctx.functions.iter() {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        let _ = writeln!(
+            output,
+            "{:?} function {}{}",
+            function.visibility,
+            format_signature(&path.join("::"), &function.signature),
+            format_span(Some(&function.span))
+        );
+
+        for statement in &function.body {
+            print_statement(
+                &mut output,
+                statement.get_inner(),
+                Some(statement.get_span()),
+                1,
+            );
+        }
+    }
+
+    output
+}
+
+fn format_signature(name: &str, signature: &FunctionSignature) -> String
+{
+    format!(
+        "{name}({}): {}",
+        format_arguments(&signature.args),
+        signature.return_type
+    )
+}
+
+fn format_arguments(arguments: &FunctionArguments) -> String
+{
+    let mut formatted_arguments = arguments
+        .arguments
+        .iter()
+        .map(|(name, (ty, id))| format!("{name}#{id}: {ty}"))
+        .collect::<Vec<String>>();
+
+    if arguments.ellipsis_present {
+        formatted_arguments.push("...".to_string());
+    }
+
+    formatted_arguments.join(", ")
+}
+
+fn format_span(span: Option<&SpanInfo>) -> String
+{
+    span.map(|span| format!(" @ {}:{}", span.char_start.line, span.char_start.column))
+        .unwrap_or_default()
+}
+
+/// Prints a node which does not represent a statement, but groups its children (ie. the branches of an if statement).
+fn print_label(output: &mut String, label: &str, depth: usize)
+{
+    let _ = writeln!(output, "{}{label}", "  ".repeat(depth));
+}
+
+fn print_statement(
+    output: &mut String,
+    statement: &StatementVariant,
+    span: Option<&SpanInfo>,
+    depth: usize,
+)
+{
+    let label = match statement {
+        StatementVariant::NewVariable {
+            variable_name,
+            variable_type,
+            variable_id,
+            is_mutable,
+            ..
+        } => {
+            format!(
+                "NewVariable {}{variable_name}#{variable_id}: {variable_type}",
+                if *is_mutable { "mut " } else { "" }
+            )
+        },
+        StatementVariant::BasicReference { variable_name } => {
+            format!("BasicReference {variable_name}")
+        },
+        StatementVariant::ArrayReference { .. } => "ArrayReference".to_string(),
+        StatementVariant::StructFieldReference { field_name, .. } => {
+            format!("StructFieldReference .{field_name}")
+        },
+        StatementVariant::Value(value) => format!("Value {value:?}"),
+        StatementVariant::TypeCast(_, ty) => format!("TypeCast -> {ty}"),
+        StatementVariant::MathematicalExpression { symbol, .. } => {
+            format!("MathematicalExpression {symbol:?}")
+        },
+        StatementVariant::NegateValue(_) => "NegateValue".to_string(),
+        StatementVariant::Brackets(_, ty) => format!("Brackets: {ty}"),
+        StatementVariant::FunctionCall { .. } => "FunctionCall".to_string(),
+        StatementVariant::SetValue { .. } => "SetValue".to_string(),
+        StatementVariant::ModifyValueArithmetic { symbol, .. } => {
+            format!("ModifyValueArithmetic {symbol:?}")
+        },
+        StatementVariant::ReturnValue { .. } => "ReturnValue".to_string(),
+        StatementVariant::Comparison(_, order, _, ty) => format!("Comparison {order}: {ty}"),
+        StatementVariant::If(_) => "If".to_string(),
+        StatementVariant::CodeBlock(_) => "CodeBlock".to_string(),
+        StatementVariant::Loop(_) => "Loop".to_string(),
+        StatementVariant::ControlFlow(control_flow) => format!("ControlFlow {control_flow:?}"),
+        StatementVariant::ArrayInitialization { .. } => "ArrayInitialization".to_string(),
+        StatementVariant::GetPointerTo(_) => "GetPointerTo".to_string(),
+        StatementVariant::DerefPointer(_) => "DerefPointer".to_string(),
+    };
+
+    let _ = writeln!(output, "{}{label}{}", "  ".repeat(depth), format_span(span));
+
+    let print_child = |output: &mut String, child: &Spanned<StatementVariant>| {
+        print_statement(output, child.get_inner(), Some(child.get_span()), depth + 1)
+    };
+
+    match statement {
+        StatementVariant::NewVariable { variable_value, .. } => print_child(output, variable_value),
+        StatementVariant::ArrayReference {
+            variable_reference,
+            index,
+        } => {
+            print_child(output, variable_reference);
+            print_child(output, index);
+        },
+        StatementVariant::StructFieldReference {
+            variable_reference, ..
+        } => print_child(output, variable_reference),
+        StatementVariant::TypeCast(value, _)
+        | StatementVariant::NegateValue(value)
+        | StatementVariant::GetPointerTo(value)
+        | StatementVariant::DerefPointer(value)
+        | StatementVariant::ReturnValue { value } => print_child(output, value),
+        StatementVariant::MathematicalExpression { lhs, rhs, .. }
+        | StatementVariant::Comparison(lhs, _, rhs, _) => {
+            print_child(output, lhs);
+            print_child(output, rhs);
+        },
+        StatementVariant::SetValue { receiver, value }
+        | StatementVariant::ModifyValueArithmetic {
+            receiver, value, ..
+        } => {
+            print_child(output, receiver);
+            print_child(output, value);
+        },
+        StatementVariant::Brackets(statements, _)
+        | StatementVariant::Loop(statements)
+        | StatementVariant::ArrayInitialization { values: statements } => {
+            for statement in statements {
+                print_child(output, statement);
+            }
+        },
+        StatementVariant::FunctionCall {
+            identifier,
+            arguments,
+        } => {
+            print_child(output, identifier);
+
+            for (argument_identifier, argument) in arguments.iter() {
+                let argument_label = match argument_identifier {
+                    FunctionArgumentIdentifier::Identifier(name) => format!("Argument {name}"),
+                    FunctionArgumentIdentifier::Index(idx) => format!("Argument #{idx}"),
+                };
+
+                print_label(output, &argument_label, depth + 1);
+
+                print_statement(
+                    output,
+                    argument.get_inner(),
+                    Some(argument.get_span()),
+                    depth + 2,
+                );
+            }
+        },
+        StatementVariant::If(if_definition) => {
+            print_label(output, "Condition", depth + 1);
+            print_statement(
+                output,
+                if_definition.condition.get_inner(),
+                Some(if_definition.condition.get_span()),
+                depth + 2,
+            );
+
+            for (branch_label, branch) in [
+                ("True", &if_definition.true_branch),
+                ("False", &if_definition.false_branch),
+            ] {
+                print_label(output, branch_label, depth + 1);
+
+                for statement in branch {
+                    print_statement(
+                        output,
+                        statement.get_inner(),
+                        Some(statement.get_span()),
+                        depth + 2,
+                    );
+                }
+            }
+        },
+        // The statements of a code block do not store their own span
+        StatementVariant::CodeBlock(statements) => {
+            for statement in statements {
+                print_statement(output, statement, None, depth + 1);
+            }
+        },
+        StatementVariant::BasicReference { .. }
+        | StatementVariant::Value(_)
+        | StatementVariant::ControlFlow(_) => (),
+    }
+}
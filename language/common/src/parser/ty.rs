@@ -1,21 +1,154 @@
 use crate::{
-    codegen::{StructAttributes, StructDefinition},
+    codegen::{CustomItem, StructAttributes, StructDefinition},
     error::{Spanned, parser::ParserError, syntax::SyntaxError},
     parser::{
-        common::{Context, ItemVisibility, Stream, Streamable},
+        common::{
+            Context, ItemVisibility, StatementVariant, Stream, Streamable, find_closing_braces,
+        },
         function::{CompilerInstruction, parse_generics},
+        statement::parse_expr,
     },
     tokenizer::{self, Token, TokenDiscriminants},
-    ty::{OrdMap, OrdSet, Type},
+    ty::{OrdMap, OrdSet, Type, unparsed_const_to_typed_literal_unsafe},
 };
 
+/// The function parses the entire enum, but does not validate the values of the variants.
+/// Variants without a value take the index of the variant as their value.
+/// Syntax of an enum:
+/// ```
+/// <vis> "enum" <name> ["<" <type> ">"] "{" [{<variant> ["=" <value>],}] "}"
+/// ```
 pub fn parse_enum(
     _ctx: &mut Context,
     _vis: &ItemVisibility,
-    _tokens: &mut Stream<Spanned<Token>>,
+    tokens: &mut Stream<Spanned<Token>>,
     _compiler_instructions: OrdSet<CompilerInstruction>,
-)
+) -> anyhow::Result<(String, CustomItem)>
 {
+    let mut variants: OrdMap<String, Spanned<StatementVariant>> = OrdMap::new();
+
+    // The first token after the keyword should be the enum's name
+    let enum_name = tokens
+        .try_consume_match(
+            ParserError::SyntaxError(SyntaxError::CustomTypeRequiresName(Token::TypeDefinition(
+                tokenizer::TypeToken::Enum,
+            ))),
+            &TokenDiscriminants::Identifier,
+        )?
+        .get_inner()
+        .try_as_identifier_ref()
+        .unwrap()
+        .to_owned();
+
+    // The type of the variants can be set between "<" ">", the default type of an enum is `uint`
+    let enum_ty = if let Some(Spanned {
+        inner: Token::OpenAngledBrackets,
+        ..
+    }) = tokens.peek_next()
+    {
+        tokens.consume();
+
+        let ty = parse_type(tokens)?;
+
+        tokens.try_consume_match(
+            ParserError::SyntaxError(SyntaxError::InvalidTypeGenericDefinition),
+            &TokenDiscriminants::CloseAngledBrackets,
+        )?;
+
+        ty
+    }
+    else {
+        Type::U32
+    };
+
+    tokens.try_consume_match(
+        ParserError::SyntaxError(SyntaxError::InvalidEnumBodyDefinition),
+        &TokenDiscriminants::OpenBraces,
+    )?;
+
+    // Get the index of the closing brace token
+    let body_closing_tkn = find_closing_braces(&*tokens)
+        .ok_or(ParserError::SyntaxError(SyntaxError::LeftOpenBraces))?;
+
+    // It is safe to unwrap here, since we have already checked if the closing braces would be in the TokenStream
+    let mut enum_body = tokens.child_iterator_bulk(body_closing_tkn).unwrap();
+
+    while let Some(variant_tkn) = enum_body.consume().cloned() {
+        let Token::Identifier(variant_name) = variant_tkn.get_inner()
+        else {
+            return Err(ParserError::SyntaxError(SyntaxError::InvalidEnumBodyDefinition).into());
+        };
+
+        let value = match enum_body.peek_next().map(|tkn| tkn.get_inner().clone()) {
+            Some(Token::SetValue) => {
+                enum_body.consume();
+
+                // Find the "," separating the variants, skipping the commas nested in the value
+                let mut depth = 0;
+
+                let value_end = enum_body.map_next_pos(|tkn| {
+                    match tkn.get_inner() {
+                        Token::OpenBraces | Token::OpenParentheses | Token::OpenSquareBrackets => {
+                            depth += 1
+                        },
+                        Token::CloseBraces
+                        | Token::CloseParentheses
+                        | Token::CloseSquareBrackets => depth -= 1,
+                        Token::Comma if depth == 0 => return true,
+                        _ => (),
+                    }
+
+                    false
+                });
+
+                // The value of the last variant may not be followed by a ","
+                if let Some(value_end) = value_end {
+                    let mut value_tokens = enum_body.child_iterator_bulk(value_end).unwrap();
+
+                    let value = parse_expr(&mut value_tokens)?;
+
+                    drop(value_tokens);
+
+                    enum_body.consume();
+
+                    value
+                }
+                else {
+                    parse_expr(&mut enum_body)?
+                }
+            },
+            Some(Token::Comma) | None => {
+                enum_body.consume();
+
+                Spanned::new(
+                    StatementVariant::Value(unparsed_const_to_typed_literal_unsafe(
+                        &variants.len().to_string(),
+                        Some(enum_ty.clone()),
+                    )?),
+                    *variant_tkn.get_span(),
+                )
+            },
+            Some(_) => {
+                return Err(
+                    ParserError::SyntaxError(SyntaxError::InvalidEnumBodyDefinition).into(),
+                );
+            },
+        };
+
+        if variants.insert(variant_name.clone(), value).is_some() {
+            return Err(ParserError::DuplicateEnumVariant(variant_name.clone()).into());
+        }
+    }
+
+    // Drop child buffer explicitly
+    drop(enum_body);
+
+    tokens.try_consume_match(
+        ParserError::SyntaxError(SyntaxError::LeftOpenBraces),
+        &TokenDiscriminants::CloseBraces,
+    )?;
+
+    Ok((enum_name, CustomItem::Enum((enum_ty, variants))))
 }
 
 /// The function parses the entire struct, but does not validate the fields of the struct.
@@ -78,28 +211,25 @@ pub fn parse_struct(
                     &TokenDiscriminants::Colon,
                 )?;
 
-                // After the colon the field's type should follow
-                if let Some(ty) = tokens.consume() {
-                    // Fetch the type of the field
-                    let field_ty = create_ty_token(ty)?;
-
-                    // Store the field of the struct
-                    fields.insert(name.clone(), field_ty);
-
-                    // Field closing token
-                    if let Some(closing_tkn) = tokens.consume() {
-                        match closing_tkn.get_inner() {
-                            // If we have reached the end of the struct definition
-                            Token::CloseBraces => break 'main_loop,
-                            // If there is a trailing comma or more fields
-                            Token::Comma => continue 'main_loop,
-                            _ => {
-                                return Err(ParserError::SyntaxError(
-                                    SyntaxError::InvalidStructDefinition,
-                                )
-                                .into());
-                            },
-                        }
+                // After the colon the field's type should follow, this can be an array or a typed pointer too
+                let field_ty = parse_type(tokens)?;
+
+                // Store the field of the struct
+                fields.insert(name.clone(), field_ty);
+
+                // Field closing token
+                if let Some(closing_tkn) = tokens.consume() {
+                    match closing_tkn.get_inner() {
+                        // If we have reached the end of the struct definition
+                        Token::CloseBraces => break 'main_loop,
+                        // If there is a trailing comma or more fields
+                        Token::Comma => continue 'main_loop,
+                        _ => {
+                            return Err(ParserError::SyntaxError(
+                                SyntaxError::InvalidStructDefinition,
+                            )
+                            .into());
+                        },
                     }
                 }
                 // If the struct is cut in half
@@ -825,6 +825,14 @@ impl<T: Hash> Hash for OrdSet<T>
     }
 }
 
+impl<T: Hash + Eq> FromIterator<T> for OrdSet<T>
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self
+    {
+        Self(IndexSet::from_iter(iter))
+    }
+}
+
 impl<T: Hash + Eq + Clone> OrdSet<T>
 {
     pub fn new() -> Self
@@ -0,0 +1,156 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    artifact::{config_path, workspace_path},
+    compiler::ProjectConfig,
+    error::{application::ApplicationError, workspace::WorkspaceError},
+};
+
+/// This contains the workspace's `workspace.toml`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct WorkspaceConfig
+{
+    /// The folders of the member projects, relative to the workspace's root.
+    pub members: Vec<PathBuf>,
+
+    /// The build folder shared by the members, relative to the workspace's root.
+    /// The `build_path` set in the config files of the members is ignored.
+    #[serde(default = "default_build_path")]
+    pub build_path: String,
+}
+
+fn default_build_path() -> String
+{
+    "out".to_string()
+}
+
+/// A workspace groups multiple projects, which share a build folder and a lock file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace
+{
+    pub root_dir: PathBuf,
+    pub config: WorkspaceConfig,
+}
+
+/// A project listed in the workspace manifest.
+#[derive(Debug, Clone)]
+pub struct WorkspaceMember
+{
+    pub root_dir: PathBuf,
+    pub config: ProjectConfig,
+    /// The names of the members the project depends on with a path.
+    pub member_dependencies: BTreeSet<String>,
+}
+
+impl Workspace
+{
+    /// Reads the workspace manifest located in `root_dir`.
+    pub fn read(root_dir: &Path) -> Result<Self, WorkspaceError>
+    {
+        let manifest_path = workspace_path(root_dir);
+
+        let manifest = fs::read_to_string(&manifest_path).map_err(|err| {
+            WorkspaceError::InvalidManifest(manifest_path.clone(), err.to_string())
+        })?;
+
+        let config = toml::from_str::<WorkspaceConfig>(&manifest)
+            .map_err(|err| WorkspaceError::InvalidManifest(manifest_path, err.to_string()))?;
+
+        Ok(Self {
+            root_dir: root_dir.to_path_buf(),
+            config,
+        })
+    }
+
+    /// Reads the config file of every member and returns the members in build order.
+    /// Members depending on other members with a path are built after them, otherwise the order of the manifest is kept.
+    pub fn members(&self) -> anyhow::Result<Vec<WorkspaceMember>>
+    {
+        let mut members = Vec::with_capacity(self.config.members.len());
+
+        for member_path in &self.config.members {
+            let root_dir = self.root_dir.join(member_path);
+
+            let config_file = fs::read_to_string(config_path(&root_dir))
+                .map_err(|_| WorkspaceError::MemberMissingConfig(root_dir.clone()))?;
+
+            let config = toml::from_str::<ProjectConfig>(&config_file)
+                .map_err(ApplicationError::ConfigError)?;
+
+            if members
+                .iter()
+                .any(|member: &WorkspaceMember| member.config.name == config.name)
+            {
+                return Err(WorkspaceError::DuplicateMember(config.name).into());
+            }
+
+            members.push(WorkspaceMember {
+                root_dir,
+                config,
+                member_dependencies: BTreeSet::new(),
+            });
+        }
+
+        // Path dependencies are matched to the members by their canonical folders
+        let member_dirs = members
+            .iter()
+            .map(|member| fs::canonicalize(&member.root_dir).ok())
+            .collect::<Vec<_>>();
+
+        for member_idx in 0..members.len() {
+            let member_dependencies = members[member_idx]
+                .config
+                .dependencies
+                .values()
+                .filter_map(|dependency| dependency.path.as_ref())
+                .filter_map(|path| fs::canonicalize(members[member_idx].root_dir.join(path)).ok())
+                .filter_map(|path| {
+                    member_dirs
+                        .iter()
+                        .position(|member_dir| member_dir.as_ref() == Some(&path))
+                })
+                .map(|dependency_idx| members[dependency_idx].config.name.clone())
+                .collect();
+
+            members[member_idx].member_dependencies = member_dependencies;
+        }
+
+        build_order(members)
+    }
+}
+
+/// Sorts the members so that every member comes after the members it depends on.
+fn build_order(mut remaining: Vec<WorkspaceMember>) -> anyhow::Result<Vec<WorkspaceMember>>
+{
+    let mut ordered: Vec<WorkspaceMember> = Vec::with_capacity(remaining.len());
+
+    while !remaining.is_empty() {
+        let Some(next_idx) = remaining.iter().position(|member| {
+            member.member_dependencies.iter().all(|dependency| {
+                ordered
+                    .iter()
+                    .any(|ordered_member| &ordered_member.config.name == dependency)
+            })
+        })
+        else {
+            return Err(WorkspaceError::DependencyCycle(
+                remaining
+                    .iter()
+                    .map(|member| format!("`{}`", member.config.name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+            .into());
+        };
+
+        ordered.push(remaining.remove(next_idx));
+    }
+
+    Ok(ordered)
+}
@@ -0,0 +1,124 @@
+use common::artifact::{ArtifactPaths, ObjectFormat, TripleComponents};
+use std::path::Path;
+
+#[test]
+fn detects_object_formats_from_target_triples()
+{
+    for (target_triple, format) in [
+        ("x86_64-unknown-linux-gnu", ObjectFormat::Elf),
+        ("aarch64-linux-android", ObjectFormat::Elf),
+        ("x86_64-apple-darwin", ObjectFormat::MachO),
+        ("aarch64-apple-ios", ObjectFormat::MachO),
+        ("arm64-apple-tvos", ObjectFormat::MachO),
+        ("arm64_32-apple-watchos", ObjectFormat::MachO),
+        ("x86_64-pc-windows-msvc", ObjectFormat::Coff),
+        ("x86_64-pc-windows-gnu", ObjectFormat::Coff),
+        ("x86_64-w64-mingw32", ObjectFormat::Coff),
+        ("i686-w64-mingw32", ObjectFormat::Coff),
+        ("x86_64-pc-cygwin", ObjectFormat::Coff),
+    ] {
+        assert_eq!(
+            ObjectFormat::from_triple(target_triple),
+            format,
+            "{target_triple}"
+        );
+    }
+}
+
+#[test]
+fn names_mingw_artifacts_like_windows_artifacts()
+{
+    let artifact_paths =
+        ArtifactPaths::new(Path::new("project"), "out", "app", "x86_64-w64-mingw32");
+
+    assert_eq!(
+        artifact_paths.executable(),
+        Path::new("project/out/app.exe")
+    );
+    assert_eq!(
+        artifact_paths.shared_library(),
+        Path::new("project/out/app.dll")
+    );
+}
+
+#[test]
+fn places_executables_apart_from_the_build_folders()
+{
+    // A project named `cache` must not collide with the cache folder
+    let artifact_paths = ArtifactPaths::new(
+        Path::new("project"),
+        "out",
+        "cache",
+        "x86_64-unknown-linux-gnu",
+    );
+
+    assert_eq!(
+        artifact_paths.executable(),
+        Path::new("project/out/bin/cache")
+    );
+    assert!(
+        !artifact_paths
+            .cache_dir()
+            .starts_with(artifact_paths.executable())
+    );
+    assert_eq!(
+        ArtifactPaths::new(Path::new("project"), "out", "app", "aarch64-apple-darwin").executable(),
+        Path::new("project/out/bin/app")
+    );
+}
+
+#[test]
+fn parses_triples_with_and_without_vendors()
+{
+    let linux = TripleComponents::parse("x86_64-unknown-linux-gnu");
+
+    assert_eq!(
+        (linux.arch, linux.vendor, linux.os, linux.env),
+        ("x86_64", "unknown", "linux", "gnu")
+    );
+
+    let android = TripleComponents::parse("aarch64-linux-android");
+
+    assert_eq!(
+        (android.arch, android.vendor, android.os, android.env),
+        ("aarch64", "", "linux", "android")
+    );
+    assert_eq!(android.normalized_os(), "android");
+    assert!(android.is_linux());
+
+    let bare_metal = TripleComponents::parse("thumbv7em-none-eabihf");
+
+    assert_eq!(bare_metal.os, "none");
+    assert_eq!(bare_metal.normalized_os(), "none");
+    assert_eq!(bare_metal.normalized_arch(), "arm");
+    assert_eq!(bare_metal.pointer_width(), 32);
+
+    let mingw = TripleComponents::parse("x86_64-w64-mingw32");
+
+    assert_eq!((mingw.vendor, mingw.os), ("w64", "mingw32"));
+    assert_eq!(mingw.normalized_os(), "windows");
+    assert!(mingw.is_mingw());
+    assert!(!TripleComponents::parse("x86_64-pc-windows-msvc").is_mingw());
+
+    let apple = TripleComponents::parse("arm64-apple-macosx14.0.0");
+
+    assert_eq!(apple.normalized_arch(), "aarch64");
+    assert_eq!(apple.normalized_os(), "macos");
+    assert_eq!(apple.object_format(), ObjectFormat::MachO);
+
+    // The other Apple platforms are named after themselves and use Mach-O like macOS
+    for (target_triple, os) in [
+        ("arm64-apple-tvos", "tvos"),
+        ("aarch64-apple-watchos", "watchos"),
+        ("arm64-apple-xros1.0", "visionos"),
+    ] {
+        let apple = TripleComponents::parse(target_triple);
+
+        assert_eq!(apple.normalized_os(), os, "{target_triple}");
+        assert_eq!(
+            apple.object_format(),
+            ObjectFormat::MachO,
+            "{target_triple}"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use common::{
+    artifact::{CONFIG_FILE_NAME, OutputKind, WORKSPACE_FILE_NAME},
+    error::linker::LinkerError,
+    linker::{BUILD_MANIFEST_VERSION, BuildManifest, BuildOutput, COMPILER_VERSION, Linker},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Creates an empty project with a single build output in it.
+fn create_project(name: &str) -> PathBuf
+{
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(root.join("out")).unwrap();
+    fs::write(root.join(CONFIG_FILE_NAME), "").unwrap();
+    fs::write(root.join("out/project.o"), "object").unwrap();
+
+    root
+}
+
+fn manifest(root: &Path) -> BuildManifest
+{
+    BuildManifest {
+        version: BUILD_MANIFEST_VERSION,
+        compiler_version: COMPILER_VERSION.to_string(),
+        build_output_paths: vec![root.join("out/project.o")],
+        additional_linking_material: Vec::new(),
+        outputs: vec![BuildOutput {
+            kind: OutputKind::Executable,
+            path: root.join("out/project"),
+        }],
+        exported_symbols: Vec::new(),
+        lto: None,
+        target_triple: "x86_64-unknown-linux-gnu".to_string(),
+        cpu_name: None,
+        cpu_features: None,
+        linker: Linker::Clang,
+        dependencies: Vec::new(),
+        artifacts: Vec::new(),
+    }
+}
+
+/// Records the hashes of the manifest and writes it into the build folder with localized paths.
+fn write_manifest(root: &Path, mut manifest: BuildManifest) -> PathBuf
+{
+    manifest.record_artifact_hashes().unwrap();
+
+    let manifest_path = root.join("out/project.manifest");
+
+    fs::write(
+        &manifest_path,
+        common::toml::to_string(&manifest.localize_paths(root).unwrap()).unwrap(),
+    )
+    .unwrap();
+
+    manifest_path
+}
+
+#[test]
+fn manifest_paths_are_relative_to_the_project()
+{
+    let root = create_project("manifest_paths");
+    let manifest_path = write_manifest(&root, manifest(&root));
+
+    let written = fs::read_to_string(&manifest_path).unwrap();
+
+    assert!(written.contains("build_output_paths = [\"out/project.o\"]"));
+    assert!(!written.contains(&root.display().to_string()));
+
+    let manifest = BuildManifest::read(&manifest_path).unwrap();
+
+    manifest.verify(Some("x86_64-unknown-linux-gnu")).unwrap();
+
+    assert_eq!(
+        manifest.build_output_paths,
+        vec![fs::canonicalize(&root).unwrap().join("out/project.o")]
+    );
+}
+
+#[test]
+fn workspace_manifest_paths_are_relative_to_the_workspace()
+{
+    // The members write their artifacts into the build folder of the workspace, which does not have a config file in its root
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("manifest_workspace");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(root.join("out")).unwrap();
+    fs::create_dir_all(root.join("app")).unwrap();
+    fs::write(root.join(WORKSPACE_FILE_NAME), "members = [\"app\"]\n").unwrap();
+    fs::write(root.join("app").join(CONFIG_FILE_NAME), "").unwrap();
+    fs::write(root.join("out/project.o"), "object").unwrap();
+
+    let manifest = BuildManifest::read(&write_manifest(&root, manifest(&root))).unwrap();
+
+    manifest.verify(None).unwrap();
+
+    assert_eq!(
+        manifest.build_output_paths,
+        vec![fs::canonicalize(&root).unwrap().join("out/project.o")]
+    );
+}
+
+#[test]
+fn stale_artifacts_are_refused()
+{
+    let root = create_project("manifest_stale");
+    let manifest_path = write_manifest(&root, manifest(&root));
+
+    fs::write(root.join("out/project.o"), "modified object").unwrap();
+
+    let manifest = BuildManifest::read(&manifest_path).unwrap();
+
+    assert!(matches!(
+        manifest.verify(None),
+        Err(LinkerError::StaleArtifact(path)) if path.ends_with("out/project.o")
+    ));
+}
+
+#[test]
+fn mismatched_manifests_are_refused()
+{
+    let root = create_project("manifest_mismatch");
+
+    let manifest = BuildManifest::read(&write_manifest(&root, manifest(&root))).unwrap();
+
+    assert!(matches!(
+        manifest.verify(Some("aarch64-apple-darwin")),
+        Err(LinkerError::TargetMismatch(..))
+    ));
+
+    let manifest = BuildManifest::read(&write_manifest(
+        &root,
+        BuildManifest {
+            compiler_version: "0.0.0".to_string(),
+            ..manifest
+        },
+    ))
+    .unwrap();
+
+    assert!(matches!(
+        manifest.verify(None),
+        Err(LinkerError::CompilerVersionMismatch(..))
+    ));
+
+    // Manifests written before the format was versioned
+    let manifest_path = root.join("out/project.manifest");
+
+    fs::write(
+        &manifest_path,
+        "build_output_paths = []\nadditional_linking_material = []\noutputs = []\n",
+    )
+    .unwrap();
+
+    assert!(matches!(
+        BuildManifest::read(&manifest_path).unwrap().verify(None),
+        Err(LinkerError::UnsupportedManifestVersion(
+            1,
+            BUILD_MANIFEST_VERSION
+        ))
+    ));
+}
+
+#[test]
+fn build_outputs_outside_of_the_project_are_refused()
+{
+    let root = create_project("manifest_outside");
+
+    let manifest = BuildManifest {
+        build_output_paths: vec![PathBuf::from("/elsewhere/project.o")],
+        ..manifest(&root)
+    };
+
+    assert!(matches!(
+        manifest.localize_paths(&root),
+        Err(LinkerError::PathOutsideProject(..))
+    ));
+}
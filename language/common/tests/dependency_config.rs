@@ -0,0 +1,71 @@
+use common::dependency::{
+    ANY_VERSION, DependencyInfo, add_dependency_to_config, remove_dependency_from_config,
+};
+
+const CONFIG_FILE: &str = r#"name = "project"
+is_library = false
+version = "0.0.1"
+build_path = "out"
+additional_linking_material = []
+
+# Dependencies of the project
+[dependencies]
+math = { version = "^1.2" } # Used for geometry
+"#;
+
+fn dependency(version: &str) -> DependencyInfo
+{
+    DependencyInfo {
+        version: version.to_string(),
+        features: Vec::new(),
+        remote: None,
+        path: None,
+    }
+}
+
+#[test]
+fn adds_dependency_preserving_formatting()
+{
+    let config_file = add_dependency_to_config(
+        CONFIG_FILE,
+        "shared",
+        &DependencyInfo {
+            features: vec!["simd".to_string()],
+            path: Some("../shared".into()),
+            ..dependency(ANY_VERSION)
+        },
+    )
+    .unwrap();
+
+    assert!(config_file.starts_with(CONFIG_FILE));
+    assert!(config_file.contains(r#"shared = { features = ["simd"], path = "../shared" }"#));
+
+    // Adding a dependency again replaces its requirement
+    let config_file = add_dependency_to_config(&config_file, "math", &dependency("^2")).unwrap();
+
+    assert!(config_file.contains(r#"math = { version = "^2" } # Used for geometry"#));
+    assert!(config_file.contains("# Dependencies of the project"));
+}
+
+#[test]
+fn removes_dependency_preserving_formatting()
+{
+    let config_file = remove_dependency_from_config(CONFIG_FILE, "math").unwrap();
+
+    assert!(!config_file.contains("math"));
+    assert!(config_file.contains("# Dependencies of the project\n[dependencies]\n"));
+
+    assert!(remove_dependency_from_config(&config_file, "math").is_err());
+}
+
+#[test]
+fn creates_dependencies_table()
+{
+    let config_file =
+        add_dependency_to_config("name = \"project\"\n", "math", &dependency("~0.3")).unwrap();
+
+    assert_eq!(
+        config_file,
+        "name = \"project\"\n\n[dependencies]\nmath = { version = \"~0.3\" }\n"
+    );
+}
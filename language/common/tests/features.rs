@@ -0,0 +1,101 @@
+use common::{
+    compiler::{FeatureSelection, ProjectConfig},
+    error::application::ApplicationError,
+    toml,
+};
+
+const CONFIG_FILE: &str = r#"name = "geometry"
+is_library = true
+version = "0.1.0"
+build_path = "out"
+additional_linking_material = []
+features = ["logging", "simd", "serde", "tracing"]
+default_features = ["logging"]
+
+[feature_dependencies]
+serde = ["simd", "json/serde"]
+tracing = ["logging", "json/pretty", "json/serde"]
+
+[dependencies]
+json = { version = "^1" }
+"#;
+
+fn enabled(config: &ProjectConfig, selection: FeatureSelection) -> Vec<String>
+{
+    config
+        .resolve_features(&selection)
+        .unwrap()
+        .features
+        .iter()
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn resolves_selected_and_default_features()
+{
+    let config = toml::from_str::<ProjectConfig>(CONFIG_FILE).unwrap();
+
+    assert_eq!(enabled(&config, FeatureSelection::default()), ["logging"]);
+
+    assert_eq!(
+        enabled(
+            &config,
+            FeatureSelection {
+                features: vec!["serde".to_string()],
+                no_default_features: true,
+                ..Default::default()
+            }
+        ),
+        ["serde", "simd"]
+    );
+
+    assert_eq!(
+        enabled(
+            &config,
+            FeatureSelection {
+                all_features: true,
+                ..Default::default()
+            }
+        ),
+        ["logging", "serde", "simd", "tracing"]
+    );
+}
+
+#[test]
+fn enables_dependency_features()
+{
+    let config = toml::from_str::<ProjectConfig>(CONFIG_FILE).unwrap();
+
+    let enabled_features = config
+        .resolve_features(&FeatureSelection::with_defaults([
+            "serde".to_string(),
+            "tracing".to_string(),
+        ]))
+        .unwrap();
+
+    assert_eq!(
+        enabled_features.dependency_features["json"]
+            .iter()
+            .collect::<Vec<_>>(),
+        ["pretty", "serde"]
+    );
+}
+
+#[test]
+fn reports_unknown_features()
+{
+    let mut config = toml::from_str::<ProjectConfig>(CONFIG_FILE).unwrap();
+
+    assert!(matches!(
+        config.resolve_features(&FeatureSelection::with_defaults(["gpu".to_string()])),
+        Err(ApplicationError::UnknownFeature(..))
+    ));
+
+    config.dependencies.clear();
+
+    assert!(matches!(
+        config.resolve_features(&FeatureSelection::with_defaults(["serde".to_string()])),
+        Err(ApplicationError::UnknownFeatureDependency(..))
+    ));
+}
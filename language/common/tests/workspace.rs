@@ -0,0 +1,97 @@
+use common::{
+    artifact::{CONFIG_FILE_NAME, WORKSPACE_FILE_NAME},
+    compiler::ProjectConfig,
+    dependency::{ANY_VERSION, DependencyInfo},
+    error::workspace::WorkspaceError,
+    toml,
+    workspace::Workspace,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn create_workspace(name: &str, members: &[&str]) -> PathBuf
+{
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    fs::write(
+        root.join(WORKSPACE_FILE_NAME),
+        format!("members = {members:?}\nbuild_path = \"build\"\n"),
+    )
+    .unwrap();
+
+    root
+}
+
+/// Writes a member into the workspace, which depends on the members in `path_dependencies` with a path.
+fn write_member(workspace_root: &Path, name: &str, path_dependencies: &[&str])
+{
+    let member_root = workspace_root.join(name);
+
+    fs::create_dir_all(&member_root).unwrap();
+
+    let mut config = ProjectConfig::new_from_name(name.to_string());
+
+    for dependency in path_dependencies {
+        config.dependencies.insert(
+            dependency.to_string(),
+            DependencyInfo {
+                version: ANY_VERSION.to_string(),
+                features: Vec::new(),
+                remote: None,
+                path: Some(Path::new("..").join(dependency)),
+            },
+        );
+    }
+
+    fs::write(
+        member_root.join(CONFIG_FILE_NAME),
+        toml::to_string(&config).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn orders_members_by_their_dependencies()
+{
+    let root = create_workspace("workspace_order", &["app", "geometry", "math", "tools"]);
+
+    write_member(&root, "app", &["geometry", "math"]);
+    write_member(&root, "geometry", &["math"]);
+    write_member(&root, "math", &[]);
+    write_member(&root, "tools", &[]);
+
+    let workspace = Workspace::read(&root).unwrap();
+
+    assert_eq!(workspace.config.build_path, "build");
+
+    let members = workspace
+        .members()
+        .unwrap()
+        .into_iter()
+        .map(|member| member.config.name)
+        .collect::<Vec<_>>();
+
+    assert_eq!(members, ["math", "geometry", "app", "tools"]);
+}
+
+#[test]
+fn reports_dependency_cycles()
+{
+    let root = create_workspace("workspace_cycle", &["geometry", "math"]);
+
+    write_member(&root, "geometry", &["math"]);
+    write_member(&root, "math", &["geometry"]);
+
+    let error = Workspace::read(&root).unwrap().members().unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<WorkspaceError>(),
+        Some(WorkspaceError::DependencyCycle(..))
+    ));
+}
@@ -0,0 +1,157 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use common::{
+    artifact::ArtifactPaths,
+    parser::common::{Context, ItemVisibility},
+    sha2::{Digest, Sha256},
+    tracing::info,
+};
+
+/// Returns the items of the module which can be used by the other modules: the signatures of its public functions, its types and its external functions.
+/// The interface is hashed into the [`CacheKey`] of the other modules, so they are generated again when it changes.
+pub fn module_interface(context: &Context) -> String
+{
+    let public_functions = context
+        .functions
+        .iter()
+        .filter(|(_path, _name, definition)| definition.visibility != ItemVisibility::Private)
+        .map(|(_path, _name, definition)| &definition.signature)
+        .collect::<Vec<_>>();
+
+    let items = context
+        .items
+        .iter()
+        .map(|(_path, _name, item)| item)
+        .collect::<Vec<_>>();
+
+    let external_functions = context
+        .external_decls
+        .iter()
+        .map(|(_path, _name, signature)| signature)
+        .collect::<Vec<_>>();
+
+    format!("{public_functions:?}\n{items:?}\n{external_functions:?}")
+}
+
+/// Identifies the object file of a module in the [`ModuleCache`].
+/// The key is the hash of everything the object file depends on: the source of the module, the signatures it imports from the other modules, and the settings it is compiled with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey(String);
+
+impl CacheKey
+{
+    /// The interfaces of the other modules must be passed in the same order on every build.
+    pub fn new(source: &str, imported_interfaces: &[&str], compiler_settings: &str) -> Self
+    {
+        let mut hasher = Sha256::new();
+
+        // Every part is prefixed with its length, so that moving text between the parts changes the key
+        for part in [source, compiler_settings]
+            .into_iter()
+            .chain(imported_interfaces.iter().copied())
+        {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+
+        Self(format!("{:x}", hasher.finalize()))
+    }
+}
+
+impl Display for CacheKey
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        f.write_str(&self.0)
+    }
+}
+
+/// The incremental compilation cache of a target, located in the build folder.
+/// The object file of every module is stored under its [`CacheKey`]. If the key of a module is unchanged since the last build, its object file is reused instead of generating the module again.
+#[derive(Debug)]
+pub struct ModuleCache
+{
+    cache_dir: PathBuf,
+    object_extension: &'static str,
+    hits: usize,
+    misses: usize,
+}
+
+impl ModuleCache
+{
+    pub fn new(artifact_paths: &ArtifactPaths) -> io::Result<Self>
+    {
+        let cache_dir = artifact_paths.cache_dir();
+
+        fs::create_dir_all(&cache_dir)?;
+
+        Ok(Self {
+            cache_dir,
+            object_extension: artifact_paths.format().object_extension(),
+            hits: 0,
+            misses: 0,
+        })
+    }
+
+    /// Returns the path the object file of the module is stored at under the key.
+    pub fn object_path(&self, module_name: &str, key: &CacheKey) -> PathBuf
+    {
+        self.cache_dir
+            .join(format!("{module_name}-{key}.{}", self.object_extension))
+    }
+
+    /// Returns whether the object file of the module is cached under the key, in which case it can be reused.
+    pub fn reuse(&mut self, module_name: &str, key: &CacheKey) -> bool
+    {
+        if !self.object_path(module_name, key).is_file() {
+            return false;
+        }
+
+        info!("Module `{module_name}` is unchanged, reusing its object file.");
+
+        self.hits += 1;
+
+        true
+    }
+
+    /// Records that the object file of the module has been stored under the key.
+    /// The object files stored for the module under other keys are removed, as they are outdated.
+    pub fn store(&mut self, module_name: &str, key: &CacheKey) -> io::Result<()>
+    {
+        self.misses += 1;
+
+        let current_object = self.object_path(module_name, key);
+
+        for entry in fs::read_dir(&self.cache_dir)? {
+            let path = entry?.path();
+
+            if path != current_object && cached_module_name(&path) == Some(module_name) {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Logs the number of modules which have been reused and generated.
+    pub fn report(&self)
+    {
+        info!(
+            "Incremental compilation: {} module(s) reused, {} module(s) generated.",
+            self.hits, self.misses
+        );
+    }
+}
+
+/// Returns the name of the module the cached object file belongs to. Object files are named `<module name>-<key>.<extension>`.
+fn cached_module_name(path: &Path) -> Option<&str>
+{
+    path.file_stem()?
+        .to_str()?
+        .rsplit_once('-')
+        .map(|(module_name, _key)| module_name)
+}
@@ -1,63 +1,403 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fs::{self},
-    path::PathBuf,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
     rc::Rc,
+    thread,
 };
 
+use analyzer::semantic::check_modules;
+use codegen::header::generate_c_header;
 use common::{
-    anyhow::{self, Result},
-    compiler::ProjectConfig,
-    error::application::ApplicationError,
+    anyhow::{self, Context as _, Result},
+    artifact::{
+        ArtifactPaths, OutputKind, config_path, lock_file_path, main_source_path, source_dir,
+        source_files,
+    },
+    compiler::{EmitKind, FeatureSelection, OptimizationSettings, ProjectConfig},
+    dependency::RegistryStatus,
+    error::{
+        Spanned, application::ApplicationError, codegen::CodeGenError, dependency::DependencyError,
+        workspace::WorkspaceError,
+    },
+    fir::{FirModule, printer::FirPrinter},
+    indexmap::IndexMap,
     inkwell::targets::{TargetMachine, TargetTriple},
-    linker::BuildManifest,
-    parser::common::{Stream, Streamable},
+    linker::{
+        BUILD_MANIFEST_VERSION, BuildManifest, BuildOutput, COMPILER_VERSION, ManifestDependency,
+    },
+    lockfile::LockFile,
+    parser::{
+        cfg::CfgTarget,
+        common::{Context, ItemVisibility, Stream, Streamable},
+        pretty::{pretty_print_context, pretty_print_tokens},
+    },
+    tokenizer::Token,
     toml,
     tracing::info,
     ty::OrdSet,
+    workspace::{Workspace, WorkspaceMember},
 };
+use imports::resolver::{lock_dependencies, resolve_dependencies};
 use parser::{parser::Settings, tokenizer::tokenize};
 
+use crate::{
+    cache::{CacheKey, ModuleCache, module_interface},
+    parallel::{CodegenJob, CodegenSettings, generate_modules},
+};
+
+/// Incremental compilation, the object files of the unchanged modules are reused between builds.
+pub mod cache;
+/// Generates the modules of the project in parallel.
+pub mod parallel;
+
+/// Name of the module of the main source file.
+const MAIN_MODULE_NAME: &str = "main";
+
+/// Name of the module containing the `main` function of the test harness.
+const TEST_HARNESS_MODULE_NAME: &str = "harness";
+
+/// A source file of the project, every module is compiled into its own object file.
+struct SourceModule
+{
+    /// The path of the source file relative to the source folder, separated by `.` and without the extension (ie. `net.http` for `src/net/http.f`).
+    name: String,
+    /// The module path of the items defined in the module. The items of the main source file are located at the root of the project.
+    path: Vec<String>,
+    file_path: PathBuf,
+    source: String,
+}
+
+impl SourceModule
+{
+    /// The artifacts of the main module are named after the project, the artifacts of the other modules are named `<project name>.<module name>`.
+    fn artifact_paths(&self, artifact_paths: &ArtifactPaths) -> ArtifactPaths
+    {
+        if self.name == MAIN_MODULE_NAME {
+            artifact_paths.clone()
+        }
+        else {
+            artifact_paths.for_module(&self.name)
+        }
+    }
+}
+
 pub struct CompilerState
 {
     pub config: ProjectConfig,
     pub root_dir: PathBuf,
     pub enabled_features: OrdSet<String>,
+
+    /// The features the project's enabled features enable in its dependencies, see [`ProjectConfig::resolve_features`].
+    pub dependency_features: BTreeMap<String, BTreeSet<String>>,
+
+    /// The workspace the project is a member of, its members share the build folder and the lock file of the workspace.
+    pub workspace: Option<Workspace>,
+
+    /// The statuses of the dependencies' versions at the project's registry, yanked versions are only used if they are locked.
+    pub registry_status: RegistryStatus,
 }
 
 impl CompilerState
 {
-    pub fn new(root_dir: PathBuf, enabled_features: OrdSet<String>) -> anyhow::Result<Self>
+    /// Reads the config file of the project and enables the selected features.
+    pub fn new(root_dir: PathBuf, selection: &FeatureSelection) -> anyhow::Result<Self>
     {
         // Read config file
-        let config_file = fs::read_to_string(format!("{}\\config.toml", root_dir.display()))
+        let config_file = fs::read_to_string(config_path(&root_dir))
             .map_err(|_| ApplicationError::ConfigNotFound(root_dir.clone()))?;
 
         let config =
             toml::from_str::<ProjectConfig>(&config_file).map_err(ApplicationError::ConfigError)?;
 
+        let enabled_features = config.resolve_features(selection)?;
+
         Ok(Self {
             config,
             root_dir,
-            enabled_features,
+            enabled_features: enabled_features.features,
+            dependency_features: enabled_features.dependency_features,
+            workspace: None,
+            registry_status: RegistryStatus::default(),
         })
     }
 
+    /// Creates the state of a member of the workspace with its default features, see [`Workspace::members`].
+    pub fn new_workspace_member(
+        workspace: &Workspace,
+        member: WorkspaceMember,
+    ) -> anyhow::Result<Self>
+    {
+        let enabled_features = member
+            .config
+            .resolve_features(&FeatureSelection::default())?;
+
+        Ok(Self {
+            config: member.config,
+            root_dir: member.root_dir,
+            enabled_features: enabled_features.features,
+            dependency_features: enabled_features.dependency_features,
+            workspace: Some(workspace.clone()),
+            registry_status: RegistryStatus::default(),
+        })
+    }
+
+    /// Replaces the enabled features of the project with the selected features.
+    pub fn select_features(&mut self, selection: &FeatureSelection) -> anyhow::Result<()>
+    {
+        let enabled_features = self.config.resolve_features(selection)?;
+
+        self.enabled_features = enabled_features.features;
+        self.dependency_features = enabled_features.dependency_features;
+
+        Ok(())
+    }
+
+    /// Returns the folder containing the project's lock file, the members of a workspace share the lock file in the workspace's root.
+    pub fn lock_root(&self) -> &Path
+    {
+        match &self.workspace {
+            Some(workspace) => &workspace.root_dir,
+            None => &self.root_dir,
+        }
+    }
+
+    /// Returns the paths of the project's artifacts named `name`. The members of a workspace share the build folder of the workspace.
+    pub fn artifact_paths(&self, name: &str, target_triple: &str) -> ArtifactPaths
+    {
+        match &self.workspace {
+            Some(workspace) => {
+                ArtifactPaths::new(
+                    &workspace.root_dir,
+                    &workspace.config.build_path,
+                    name,
+                    target_triple,
+                )
+            },
+            None => {
+                ArtifactPaths::new(&self.root_dir, &self.config.build_path, name, target_triple)
+            },
+        }
+    }
+
+    /// Returns every module of the project, the main source file is always the first module.
+    /// The source of the main source file is passed in as it has already been read.
+    fn source_modules(&self, main_source: &str) -> Result<Vec<SourceModule>>
+    {
+        let source_dir = source_dir(&self.root_dir);
+        let main_source_path = main_source_path(&self.root_dir);
+
+        let mut modules = vec![SourceModule {
+            name: MAIN_MODULE_NAME.to_string(),
+            path: vec![self.config.name.clone()],
+            file_path: main_source_path.clone(),
+            source: main_source.to_string(),
+        }];
+
+        for file_path in source_files(&self.root_dir)? {
+            if file_path == main_source_path {
+                continue;
+            }
+
+            let components = file_path
+                .strip_prefix(&source_dir)?
+                .with_extension("")
+                .iter()
+                .map(|component| component.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+
+            modules.push(SourceModule {
+                name: components.join("."),
+                path: [vec![self.config.name.clone()], components].concat(),
+                source: fs::read_to_string(&file_path)?,
+                file_path,
+            });
+        }
+
+        Ok(modules)
+    }
+
+    /// The name of the LLVM module generated from the module, this matches the name of its artifacts.
+    fn qualified_module_name(&self, module: &SourceModule) -> String
+    {
+        if module.name == MAIN_MODULE_NAME {
+            self.config.name.clone()
+        }
+        else {
+            format!("{}.{}", self.config.name, module.name)
+        }
+    }
+
+    /// Parses the tokens of the module, the error is pointed at the last token consumed by the parser.
+    /// The `@cfg` predicates of the module are evaluated against `cfg_target`.
+    fn parse_module(
+        &self,
+        module: &SourceModule,
+        tokens: &mut Stream<Spanned<Token>>,
+        cfg_target: &CfgTarget,
+    ) -> Result<Context>
+    {
+        let parser_settings = Settings::new(
+            self.config.clone(),
+            module.path.clone(),
+            self.enabled_features.clone(),
+            cfg_target.clone(),
+            module.file_path.clone(),
+        );
+
+        match parser_settings.parse(tokens) {
+            Ok(parsed_context) => Ok(parsed_context),
+            Err(error) => {
+                let spanned_err = tokens
+                    .get_last_consumed()
+                    .map(|tkn| tkn.raise_error(parser_settings.root_path, error))
+                    .unwrap();
+
+                Err(spanned_err.into())
+            },
+        }
+    }
+
+    /// Tokenizes, parses and analyzes every module of the project without generating them.
+    /// Unlike [`CompilerState::compilation_process`], the modules are checked even if an earlier module contains an error, and every error found is returned.
+    /// The project's items are only analyzed if every module could be parsed.
+    /// The `@cfg` predicates are evaluated for `target_triple_name`, or for the host if it is not set, with `debug_assertions` enabled.
+    pub fn check(&self, target_triple_name: Option<&str>) -> Result<Vec<anyhow::Error>>
+    {
+        let Ok(main_source) = fs::read_to_string(main_source_path(&self.root_dir))
+        else {
+            return Ok(vec![CodeGenError::NoMain.into()]);
+        };
+
+        let cfg_target = match target_triple_name {
+            Some(target_triple_name) => CfgTarget::from_triple(target_triple_name, true),
+            None => {
+                CfgTarget::from_triple(
+                    &TargetMachine::get_default_triple()
+                        .as_str()
+                        .to_string_lossy(),
+                    true,
+                )
+            },
+        };
+
+        let mut errors = Vec::new();
+        let mut parsed_modules = Vec::new();
+
+        for module in self.source_modules(&main_source)? {
+            let parsed_context = tokenize_module(&module).and_then(|tokens| {
+                self.parse_module(&module, &mut Stream::new(tokens), &cfg_target)
+            });
+
+            match parsed_context {
+                Ok(parsed_context) => parsed_modules.push(parsed_context),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if errors.is_empty() {
+            errors.extend(
+                check_modules(&parsed_modules, !self.config.is_library)
+                    .into_iter()
+                    .map(anyhow::Error::from),
+            );
+        }
+
+        Ok(errors)
+    }
+
+    /// Returns the dependencies of the project which are located in its dependencies folder, the dependencies compiled by a remote are not included.
+    /// The dependencies are sorted by their name. Their versions are resolved with [`resolve_dependencies`], so that every dependency of the project and of its dependencies match the requirements on them.
+    pub fn local_dependencies(&self) -> Vec<Result<CompilerState>>
+    {
+        let resolved_dependencies = match LockFile::read(&lock_file_path(self.lock_root()))
+            .map_err(anyhow::Error::from)
+            .and_then(|lock_file| {
+                resolve_dependencies(
+                    &self.root_dir,
+                    &self.config,
+                    &self.dependency_features,
+                    lock_file.as_ref(),
+                    &self.registry_status,
+                )
+            }) {
+            Ok(resolved_dependencies) => resolved_dependencies,
+            Err(error) => return vec![Err(error)],
+        };
+
+        let mut dependencies = self
+            .config
+            .dependencies
+            .iter()
+            .filter(|(_name, dependency)| dependency.remote.is_none())
+            .collect::<Vec<_>>();
+
+        dependencies.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        dependencies
+            .into_iter()
+            .map(|(name, _dependency)| {
+                let resolved = resolved_dependencies
+                    .get(name)
+                    .ok_or_else(|| DependencyError::DependencyNotFound(name.clone()))?;
+
+                // The resolved features include the features requested in the dependency tables and the features enabled by the project's features
+                let mut dependency_state = CompilerState::new(
+                    resolved.path.clone(),
+                    &FeatureSelection::with_defaults(resolved.features.iter().cloned()),
+                )?;
+
+                // The dependencies of the dependency are resolved with the statuses looked up for the project
+                dependency_state.registry_status = self.registry_status.clone();
+
+                Ok(dependency_state)
+            })
+            .collect()
+    }
+
+    /// Resolves the dependencies of the project and updates its lock file, see [`update_shared_lock_file`].
+    pub fn update_lock_file(&self, locked: bool) -> Result<LockFile>
+    {
+        update_shared_lock_file(std::slice::from_ref(self), locked)
+    }
+
+    /// Compiles the project until the stage of the last artifact requested in `emit`.
+    /// If `emit` is empty the project is compiled into object files.
+    /// Every artifact is written to the path computed by `artifact_paths` for the target.
+    /// The modules are generated on `codegen_threads` threads, by default every available core is used.
+    /// If `is_test_harness` is set, the project is compiled into a test harness executable instead of its outputs. The harness contains the tests of the project in place of its `main` function, see [`generate_test_harness`](codegen::test_harness::generate_test_harness).
+    /// The function only returns a [`BuildManifest`] if object files have been produced.
     pub fn compilation_process(
         &self,
         file_contents: &str,
-        _target_ir_path: PathBuf,
-        _target_o_path: PathBuf,
-        _build_path: PathBuf,
-        _optimization: bool,
+        artifact_paths: &ArtifactPaths,
+        optimization: &OptimizationSettings,
         _is_lib: bool,
         path_to_src: &str,
-        _flags_passed_in: &str,
+        flags_passed_in: &str,
         target_triple_name: Option<String>,
-        _cpu_name: Option<String>,
-        _cpu_features: Option<String>,
-    ) -> Result<BuildManifest>
+        cpu_name: Option<String>,
+        cpu_features: Option<String>,
+        codegen_threads: Option<NonZeroUsize>,
+        emit: &[EmitKind],
+        is_test_harness: bool,
+    ) -> Result<Option<BuildManifest>>
     {
-        let _target_triple = Rc::new(
+        // Object files are copied out of the cache into the build folder only if they have been requested explicitly
+        let is_object_requested = emit.contains(&EmitKind::Obj);
+
+        let emit: &[EmitKind] = if emit.is_empty() {
+            &[EmitKind::Obj]
+        }
+        else {
+            emit
+        };
+
+        // It is safe to unwrap here as `emit` cannot be empty
+        let last_stage = *emit.iter().max().unwrap();
+
+        let target_triple = Rc::new(
             if let Some(target_triple_name) = target_triple_name {
                 TargetTriple::create(&target_triple_name)
             }
@@ -66,9 +406,35 @@ impl CompilerState
             },
         );
 
+        let cfg_target = CfgTarget::from_triple(
+            &target_triple.as_str().to_string_lossy(),
+            optimization.debug_assertions(),
+        );
+
+        let modules = self.source_modules(file_contents)?;
+
         info!("Tokenizing...");
 
-        let mut tokens = Stream::new(tokenize(file_contents)?);
+        let mut module_tokens = Vec::with_capacity(modules.len());
+
+        for module in &modules {
+            let tokens = tokenize_module(module)?;
+
+            if emit.contains(&EmitKind::Tokens) {
+                fs::write(
+                    module
+                        .artifact_paths(artifact_paths)
+                        .emitted(EmitKind::Tokens),
+                    pretty_print_tokens(&tokens),
+                )?;
+            }
+
+            module_tokens.push(Stream::new(tokens));
+        }
+
+        if last_stage == EmitKind::Tokens {
+            return Ok(None);
+        }
 
         // info!("Creating LLVM context...");
         // let context = Context::create();
@@ -85,12 +451,12 @@ impl CompilerState
         // }
 
         // let mut dependency_output_paths = Vec::new();
-        // let deps_path = PathBuf::from(format!("{}\\deps", self.root_dir.display()));
+        // let deps_path = deps_dir(&self.root_dir);
 
         // info!("Analyzing dependencies...");
 
         // // Create an extern libs folder which we will store all the external (pre compiled) deps in
-        // let extern_libs_path = PathBuf::from(format!("{}\\extern_libs", self.config.build_path));
+        // let extern_libs_path = artifact_paths.build_dir().join("extern_libs");
 
         // let _ = create_dir_all(&extern_libs_path);
 
@@ -132,26 +498,249 @@ impl CompilerState
         //     cpu_features.clone(),
         // )?;
 
-        let parser_settings = Settings::new(
-            self.config.clone(),
-            vec![self.config.name.clone()],
-            self.enabled_features.clone(),
-            PathBuf::from(format!("{path_to_src}\\main.f")),
+        let mut parsed_modules = Vec::with_capacity(modules.len());
+
+        for (module, tokens) in modules.iter().zip(&mut module_tokens) {
+            parsed_modules.push(self.parse_module(module, tokens, &cfg_target)?);
+        }
+
+        // The modules are checked before the AST or FIR is emitted, so that invalid programs are reported instead of being emitted
+        // The test harness provides its own `main` function
+        if let Some(error) =
+            check_modules(&parsed_modules, !self.config.is_library && !is_test_harness)
+                .into_iter()
+                .next()
+        {
+            return Err(error.into());
+        }
+
+        for (module, parsed_context) in modules.iter().zip(&parsed_modules) {
+            let module_artifact_paths = module.artifact_paths(artifact_paths);
+
+            if emit.contains(&EmitKind::Ast) {
+                fs::write(
+                    module_artifact_paths.emitted(EmitKind::Ast),
+                    pretty_print_context(parsed_context),
+                )?;
+            }
+
+            if emit.contains(&EmitKind::Fir) {
+                fs::write(
+                    module_artifact_paths.emitted(EmitKind::Fir),
+                    FirPrinter::new(true).print_module(&FirModule::from_context(parsed_context)),
+                )?;
+            }
+        }
+
+        if matches!(last_stage, EmitKind::Ast | EmitKind::Fir) {
+            return Ok(None);
+        }
+
+        // The `publib` functions are exported from the library outputs
+        let exported_functions = parsed_modules
+            .iter()
+            .filter(|_| !is_test_harness)
+            .flat_map(|parsed_context| parsed_context.functions.iter())
+            .filter(|(_path, _name, definition)| {
+                definition.visibility == ItemVisibility::PublicLibrary
+                    && definition.signature.args.generics.is_empty()
+            })
+            .map(|(_path, _name, definition)| definition.signature.clone())
+            .collect::<Vec<_>>();
+
+        // The types defined in a module can be used by every other module
+        let custom_types = parsed_modules
+            .iter()
+            .flat_map(|parsed_context| parsed_context.items.iter())
+            .map(|(_path, name, item)| (name.to_string(), item.clone()))
+            .collect::<IndexMap<_, _>>();
+
+        // The test harness is always an executable, regardless of the outputs of the project
+        let output_kinds = if is_test_harness {
+            vec![OutputKind::Executable]
+        }
+        else {
+            self.config.output_kinds()
+        };
+        let position_independent = output_kinds.contains(&OutputKind::SharedLibrary);
+
+        // The CPU settings are recorded in the build manifest
+        let manifest_cpu_name = cpu_name.clone();
+        let manifest_cpu_features = cpu_features.clone();
+
+        // Everything besides the sources of the modules which affects the generated object files
+        let compiler_settings = format!(
+            "{COMPILER_VERSION}\n{}\n{}\n{}\n{optimization:?}\n{flags_passed_in}\n{position_independent}\n{path_to_src}\n{:?}\n{is_test_harness}",
+            target_triple.as_str().to_string_lossy(),
+            cpu_name
+                .clone()
+                .unwrap_or_else(|| TargetMachine::get_host_cpu_name().to_string()),
+            cpu_features
+                .clone()
+                .unwrap_or_else(|| TargetMachine::get_host_cpu_features().to_string()),
+            self.enabled_features,
         );
 
-        match parser_settings.parse(&mut tokens) {
-            Ok(_ret) => {
-                dbg!(&_ret.functions.get_names());
-                dbg!(&_ret.functions);
-            },
-            Err(error) => {
-                let spanned_err = tokens
-                    .get_last_consumed()
-                    .map(|tkn| tkn.raise_error(parser_settings.root_path, error))
-                    .unwrap();
+        let module_interfaces = parsed_modules
+            .iter()
+            .map(module_interface)
+            .collect::<Vec<_>>();
+
+        // The other LLVM artifacts can only be emitted by generating the modules
+        let is_cache_enabled = !emit
+            .iter()
+            .any(|emit_kind| emit_kind.is_llvm_artifact() && *emit_kind != EmitKind::Obj);
+
+        // The members of a workspace share the cache folder, so the object files are named after the qualified name of the module
+        // The object files of the test harness are cached separately, so that switching between testing and building does not generate every module again
+        let cache_name = |module: &SourceModule| {
+            let qualified_name = self.qualified_module_name(module);
+
+            if is_test_harness {
+                format!("{qualified_name}@test")
+            }
+            else {
+                qualified_name
+            }
+        };
+
+        let mut cache = ModuleCache::new(artifact_paths)?;
+        let mut cache_keys = Vec::with_capacity(modules.len());
+        let mut codegen_jobs = Vec::new();
+
+        // The cache is looked up before generating the modules, so only the changed modules are sent to the codegen threads
+        for (idx, (module, parsed_context)) in modules.iter().zip(&parsed_modules).enumerate() {
+            let imported_interfaces = module_interfaces
+                .iter()
+                .enumerate()
+                .filter(|(interface_idx, _)| *interface_idx != idx)
+                .map(|(_, interface)| interface.as_str())
+                .collect::<Vec<_>>();
+
+            let cache_key = CacheKey::new(&module.source, &imported_interfaces, &compiler_settings);
+
+            let is_cached = is_cache_enabled
+                && emit.contains(&EmitKind::Obj)
+                && cache.reuse(&cache_name(module), &cache_key);
+
+            if !is_cached {
+                codegen_jobs.push(CodegenJob {
+                    idx,
+                    module_name: module.name.clone(),
+                    qualified_name: self.qualified_module_name(module),
+                    // Tests are only generated into the test harness, which replaces the `main` function of the project
+                    function_table: parsed_context
+                        .functions
+                        .iter()
+                        .filter(|(_path, name, definition)| {
+                            if is_test_harness {
+                                !(idx == 0 && name.as_str() == "main")
+                            }
+                            else {
+                                !definition.is_test()
+                            }
+                        })
+                        .map(|(_path, name, definition)| (name.to_string(), definition.clone()))
+                        .collect(),
+                    imported_functions: parsed_context
+                        .external_decls
+                        .iter()
+                        .map(|(_path, name, signature)| (name.to_string(), signature.clone()))
+                        .collect(),
+                    artifact_paths: module.artifact_paths(artifact_paths),
+                    object_path: emit
+                        .contains(&EmitKind::Obj)
+                        .then(|| cache.object_path(&cache_name(module), &cache_key)),
+                    test_harness: None,
+                });
+            }
+
+            cache_keys.push(cache_key);
+        }
+
+        // The harness is not cached, as it only contains the `main` function running the tests
+        let harness_artifact_paths = artifact_paths.for_module(TEST_HARNESS_MODULE_NAME);
+
+        if is_test_harness {
+            let tests = parsed_modules
+                .iter()
+                .flat_map(|parsed_context| parsed_context.functions.iter())
+                .filter(|(_path, _name, definition)| definition.is_test())
+                .map(|(_path, name, _definition)| name.to_string())
+                .collect::<Vec<_>>();
+
+            info!("Generating a test harness for {} test(s)...", tests.len());
+
+            codegen_jobs.push(CodegenJob {
+                idx: modules.len(),
+                module_name: TEST_HARNESS_MODULE_NAME.to_string(),
+                qualified_name: format!("{}.{TEST_HARNESS_MODULE_NAME}", self.config.name),
+                function_table: IndexMap::new(),
+                imported_functions: Default::default(),
+                object_path: emit
+                    .contains(&EmitKind::Obj)
+                    .then(|| harness_artifact_paths.object()),
+                artifact_paths: harness_artifact_paths.clone(),
+                test_harness: Some(tests),
+            });
+        }
+
+        let generated_modules = codegen_jobs.iter().map(|job| job.idx).collect::<Vec<_>>();
 
-                return Err(spanned_err.into());
+        generate_modules(
+            codegen_jobs,
+            &CodegenSettings {
+                optimization,
+                position_independent,
+                custom_types: &custom_types,
+                flags_passed_in,
+                path_to_src,
+                target_triple: &target_triple.as_str().to_string_lossy(),
+                cpu_name: cpu_name.clone(),
+                cpu_features: cpu_features.clone(),
+                emit,
             },
+            codegen_threads
+                .or_else(|| thread::available_parallelism().ok())
+                .unwrap_or(NonZeroUsize::MIN),
+        )?;
+
+        if !emit.contains(&EmitKind::Obj) {
+            return Ok(None);
+        }
+
+        // The object files are listed in the order of the modules, regardless of the order they have been generated in
+        let mut build_output_paths = Vec::with_capacity(modules.len());
+
+        for (idx, (module, cache_key)) in modules.iter().zip(&cache_keys).enumerate() {
+            if generated_modules.contains(&idx) {
+                cache.store(&cache_name(module), cache_key)?;
+            }
+
+            let object_path = cache.object_path(&cache_name(module), cache_key);
+
+            if is_object_requested {
+                fs::copy(&object_path, module.artifact_paths(artifact_paths).object())?;
+            }
+
+            build_output_paths.push(object_path);
+        }
+
+        if is_test_harness {
+            build_output_paths.push(harness_artifact_paths.object());
+        }
+
+        cache.report();
+
+        // C and C++ projects use the header to call the functions of static and shared libraries
+        if output_kinds
+            .iter()
+            .any(|kind| *kind != OutputKind::Executable)
+        {
+            fs::write(
+                artifact_paths.header(),
+                generate_c_header(&self.config.name, &exported_functions)?,
+            )?;
         }
 
         // let function_table = parser.function_table();
@@ -211,14 +800,184 @@ impl CompilerState
         // link_llvm_to_target(&module, target, target_o_path)?;
         // dependency_output_paths.push(target_ir_path.clone());
 
-        // Ok(BuildManifest {
-        //     // Localize path for later use, if we cannot strip it, it means that the path is already a stripped version, therefor we can skip that
-        //     build_output_paths: dependency_output_paths,
-        //     additional_linking_material: additional_linking_material_list,
-        //     // Localize path for later use
-        //     output_path: build_path,
-        // })
+        let lock_file = LockFile::read(&lock_file_path(self.lock_root()))?;
+
+        let resolved_dependencies = resolve_dependencies(
+            &self.root_dir,
+            &self.config,
+            &self.dependency_features,
+            lock_file.as_ref(),
+            &self.registry_status,
+        )?;
+
+        let mut dependencies = self
+            .config
+            .dependencies
+            .iter()
+            .map(|(name, dependency)| {
+                // The version and features the dependency is built with are recorded
+                let (version, features) = match resolved_dependencies.get(name) {
+                    Some(resolved) => (resolved.version.to_string(), resolved.features.clone()),
+                    // Dependencies compiled by a remote are not resolved, so their locked version is used
+                    None => {
+                        let version = lock_file
+                            .as_ref()
+                            .and_then(|lock_file| lock_file.get(name))
+                            .map(|locked| locked.version.clone())
+                            .unwrap_or_else(|| dependency.version.clone());
+
+                        let features = dependency
+                            .features
+                            .iter()
+                            .chain(self.dependency_features.get(name).into_iter().flatten())
+                            .cloned()
+                            .collect::<BTreeSet<_>>();
+
+                        (version, features.into_iter().collect())
+                    },
+                };
+
+                ManifestDependency {
+                    name: name.clone(),
+                    version,
+                    features,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        dependencies.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+
+        let mut build_manifest = BuildManifest {
+            version: BUILD_MANIFEST_VERSION,
+            compiler_version: COMPILER_VERSION.to_string(),
+            build_output_paths,
+            // The additional linking material is relative to the project root
+            additional_linking_material: self
+                .config
+                .additional_linking_material
+                .iter()
+                .map(|path| self.root_dir.join(path))
+                .collect(),
+            outputs: output_kinds
+                .into_iter()
+                .map(|kind| {
+                    BuildOutput {
+                        kind,
+                        path: artifact_paths.output(kind),
+                    }
+                })
+                .collect(),
+            exported_symbols: exported_functions
+                .into_iter()
+                .map(|signature| signature.name)
+                .collect(),
+            lto: optimization.lto,
+            target_triple: target_triple.as_str().to_string_lossy().into_owned(),
+            cpu_name: manifest_cpu_name,
+            cpu_features: manifest_cpu_features,
+            linker: self.config.linker.clone().unwrap_or_default(),
+            dependencies,
+            artifacts: Vec::new(),
+        };
+
+        build_manifest.record_artifact_hashes()?;
+
+        Ok(Some(build_manifest))
+    }
+}
+
+/// Resolves the dependencies of the projects sharing a lock file and updates the lock file, the versions locked previously are kept if they still match the requirements.
+/// The projects are either a single project or every member of a workspace, in which case the members must agree on the version of every dependency.
+/// The checksums of the dependencies whose version has not changed are verified against the lock file.
+/// If `locked` is set the lock file is not updated, instead an error is returned if it is out of date.
+pub fn update_shared_lock_file(projects: &[CompilerState], locked: bool) -> Result<LockFile>
+{
+    let Some(lock_root) = projects.first().map(CompilerState::lock_root)
+    else {
+        return Ok(LockFile::default());
+    };
+
+    let lock_file_path = lock_file_path(lock_root);
+
+    let previous_lock_file = LockFile::read(&lock_file_path)?;
+
+    let mut lock_file = LockFile::default();
+
+    // The versions selected by the members resolved earlier are preferred over the previously locked versions, so that the members agree on them
+    let mut preferred_versions = previous_lock_file.clone().unwrap_or_default();
+
+    for project in projects {
+        let resolved_dependencies = resolve_dependencies(
+            &project.root_dir,
+            &project.config,
+            &project.dependency_features,
+            Some(&preferred_versions),
+            &project.registry_status,
+        )?;
+
+        for dependency in lock_dependencies(
+            lock_root,
+            &resolved_dependencies,
+            project.config.registry.as_deref(),
+        )?
+        .dependencies
+        {
+            if let Some(locked_dependency) = lock_file.get(&dependency.name) {
+                if locked_dependency.version != dependency.version {
+                    return Err(WorkspaceError::ConflictingVersions(
+                        dependency.name,
+                        locked_dependency.version.clone(),
+                        dependency.version,
+                    )
+                    .into());
+                }
+
+                continue;
+            }
+
+            preferred_versions.insert(dependency.clone());
+            lock_file.insert(dependency);
+        }
+    }
+
+    if let Some(previous_lock_file) = &previous_lock_file {
+        for dependency in &lock_file.dependencies {
+            if let Some(locked_dependency) = previous_lock_file.get(&dependency.name)
+                && let Some(checksum) = &dependency.checksum
+                && locked_dependency.version == dependency.version
+                && locked_dependency.source == dependency.source
+            {
+                locked_dependency.verify_checksum(checksum)?;
+            }
+        }
 
-        Ok(todo!())
+        // The dependencies compiled by a remote are locked when they are downloaded
+        for (name, _dependency) in projects
+            .iter()
+            .flat_map(|project| &project.config.dependencies)
+            .filter(|(_name, dependency)| dependency.remote.is_some())
+        {
+            if let Some(locked_dependency) = previous_lock_file.get(name) {
+                lock_file.insert(locked_dependency.clone());
+            }
+        }
     }
+
+    if previous_lock_file.as_ref() != Some(&lock_file) {
+        if locked {
+            return Err(DependencyError::LockFileOutdated(lock_file_path).into());
+        }
+
+        lock_file.write(&lock_file_path)?;
+
+        info!("Updated lock file `{}`.", lock_file_path.display());
+    }
+
+    Ok(lock_file)
+}
+
+/// Tokenizes the source of the module, the error is prefixed with the path of the source file.
+fn tokenize_module(module: &SourceModule) -> Result<Vec<Spanned<Token>>>
+{
+    tokenize(&module.source).with_context(|| module.file_path.display().to_string())
 }
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, num::NonZeroUsize, path::PathBuf, rc::Rc, thread};
+
+use codegen::{llvm_codegen_main, test_harness::generate_test_harness, write_llvm_artifact};
+use common::{
+    anyhow::{Error, Result},
+    artifact::{ArtifactPaths, TripleComponents},
+    codegen::CustomItem,
+    compiler::{EmitKind, OptimizationSettings},
+    crossbeam::deque::{Injector, Steal},
+    indexmap::IndexMap,
+    inkwell::{context::Context, targets::TargetTriple},
+    parking_lot::Mutex,
+    parser::function::{FunctionDefinition, FunctionSignature},
+    tracing::info,
+};
+
+/// A module which has to be generated, the jobs are distributed between the codegen threads.
+/// Every job contains everything needed to generate the module, as the parsed modules cannot be shared between threads.
+pub struct CodegenJob
+{
+    /// The index of the module, the errors are reported in the order of the modules.
+    pub idx: usize,
+    pub module_name: String,
+    /// The name of the LLVM module.
+    pub qualified_name: String,
+    pub function_table: IndexMap<String, FunctionDefinition>,
+    pub imported_functions: HashMap<String, FunctionSignature>,
+    /// The paths the requested LLVM artifacts of the module are written to.
+    pub artifact_paths: ArtifactPaths,
+    /// The path the object file is written to, `None` if object files have not been requested.
+    pub object_path: Option<PathBuf>,
+    /// The tests of the project, this is only set for the module of the test harness.
+    /// The `main` function of the harness is generated into the module besides the functions of the job.
+    pub test_harness: Option<Vec<String>>,
+}
+
+/// The settings shared by every module, these are the same for every codegen thread.
+pub struct CodegenSettings<'a>
+{
+    pub optimization: &'a OptimizationSettings,
+    pub position_independent: bool,
+    /// The types defined in every module of the project.
+    pub custom_types: &'a IndexMap<String, CustomItem>,
+    pub flags_passed_in: &'a str,
+    pub path_to_src: &'a str,
+    pub target_triple: &'a str,
+    pub cpu_name: Option<String>,
+    pub cpu_features: Option<String>,
+    pub emit: &'a [EmitKind],
+}
+
+/// Generates the modules on `thread_count` threads, every module is generated in its own LLVM context.
+/// The threads take the jobs from a shared queue until it is empty.
+/// As the modules are generated independently of each other, the generated artifacts do not depend on the number of threads.
+/// If generating multiple modules fails, the error of the first module (in the order of the modules) is returned.
+pub fn generate_modules(
+    jobs: Vec<CodegenJob>,
+    settings: &CodegenSettings,
+    thread_count: NonZeroUsize,
+) -> Result<()>
+{
+    let thread_count = thread_count.get().min(jobs.len());
+
+    let queue = Injector::new();
+
+    for job in jobs {
+        queue.push(job);
+    }
+
+    let errors: Mutex<Vec<(usize, Error)>> = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..thread_count {
+            scope.spawn(|| {
+                loop {
+                    match queue.steal() {
+                        Steal::Success(job) => {
+                            if let Err(error) = generate_module(&job, settings) {
+                                errors.lock().push((job.idx, error));
+                            }
+                        },
+                        Steal::Retry => continue,
+                        Steal::Empty => break,
+                    }
+                }
+            });
+        }
+    });
+
+    match errors.into_inner().into_iter().min_by_key(|(idx, _)| *idx) {
+        Some((_idx, error)) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Generates the module in a new LLVM context, and writes its requested artifacts.
+fn generate_module(job: &CodegenJob, settings: &CodegenSettings) -> Result<()>
+{
+    info!("Generating module `{}`...", job.module_name);
+
+    let context = Context::create();
+    let builder = context.create_builder();
+    let module = context.create_module(&job.qualified_name);
+
+    let target_machine = llvm_codegen_main(
+        &context,
+        &builder,
+        &module,
+        Rc::new(job.function_table.clone()),
+        settings.optimization,
+        settings.position_independent,
+        Rc::new(job.imported_functions.clone()),
+        Rc::new(settings.custom_types.clone()),
+        settings.flags_passed_in,
+        settings.path_to_src,
+        Rc::new(TargetTriple::create(settings.target_triple)),
+        settings.cpu_name.clone(),
+        settings.cpu_features.clone(),
+    )?;
+
+    if let Some(tests) = &job.test_harness {
+        // Tests can only be run in forked processes on Linux
+        let fork_per_test = TripleComponents::parse(settings.target_triple).is_linux();
+
+        generate_test_harness(
+            &context,
+            &module,
+            &builder,
+            &target_machine,
+            tests,
+            fork_per_test,
+        )?;
+    }
+
+    for emit_kind in settings
+        .emit
+        .iter()
+        .filter(|emit_kind| emit_kind.is_llvm_artifact() && **emit_kind != EmitKind::Obj)
+    {
+        write_llvm_artifact(
+            &module,
+            &target_machine,
+            *emit_kind,
+            &job.artifact_paths.emitted(*emit_kind),
+        )?;
+    }
+
+    if let Some(object_path) = &job.object_path {
+        // With LTO enabled the object files contain bitcode, which gets optimized while linking
+        let object_kind = if settings.optimization.lto.is_some() {
+            EmitKind::Bc
+        }
+        else {
+            EmitKind::Obj
+        };
+
+        write_llvm_artifact(&module, &target_machine, object_kind, object_path)?;
+    }
+
+    Ok(())
+}
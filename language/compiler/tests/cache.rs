@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use common::{
+    artifact::ArtifactPaths,
+    compiler::ProjectConfig,
+    parser::{cfg::CfgTarget, common::Stream},
+    ty::OrdSet,
+};
+use compiler::cache::{CacheKey, ModuleCache, module_interface};
+use parser::{parser::Settings, tokenizer::tokenize};
+
+const TARGET_TRIPLE: &str = "x86_64-unknown-linux-gnu";
+
+const SOURCE: &str = "pub function add(lhs: int, rhs: int): int { return lhs + rhs; }";
+const SETTINGS: &str = "x86_64-unknown-linux-gnu\nO0";
+
+/// Creates an empty build folder and returns the artifact paths of a project built into it.
+fn create_build_dir(name: &str) -> ArtifactPaths
+{
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+
+    let _ = fs::remove_dir_all(&root);
+
+    ArtifactPaths::new(&root, "out", "project", TARGET_TRIPLE)
+}
+
+/// Writes the object file the code generator would emit for the module and records it in the cache.
+fn store_object(cache: &mut ModuleCache, module_name: &str, key: &CacheKey) -> PathBuf
+{
+    let object_path = cache.object_path(module_name, key);
+
+    fs::write(&object_path, "object").unwrap();
+
+    cache.store(module_name, key).unwrap();
+
+    object_path
+}
+
+fn interface(source: &str) -> String
+{
+    let settings = Settings::new(
+        ProjectConfig::default(),
+        vec!["main".to_string()],
+        OrdSet::new(),
+        CfgTarget::from_triple(TARGET_TRIPLE, true),
+        PathBuf::from("main.f"),
+    );
+
+    module_interface(
+        &settings
+            .parse(&mut Stream::new(tokenize(source).unwrap()))
+            .unwrap(),
+    )
+}
+
+#[test]
+fn reuses_objects_stored_under_the_same_key()
+{
+    let artifact_paths = create_build_dir("cache_hits");
+    let mut cache = ModuleCache::new(&artifact_paths).unwrap();
+    let key = CacheKey::new(SOURCE, &[], SETTINGS);
+
+    assert!(!cache.reuse("main", &key));
+
+    let object_path = store_object(&mut cache, "main", &key);
+
+    assert!(object_path.is_file());
+    assert!(cache.reuse("main", &key));
+
+    // A fresh cache finds the objects of the previous build
+    let mut cache = ModuleCache::new(&artifact_paths).unwrap();
+
+    assert!(cache.reuse("main", &CacheKey::new(SOURCE, &[], SETTINGS)));
+}
+
+#[test]
+fn misses_when_the_source_changes()
+{
+    let mut cache = ModuleCache::new(&create_build_dir("cache_source_misses")).unwrap();
+    let key = CacheKey::new(SOURCE, &[], SETTINGS);
+
+    store_object(&mut cache, "main", &key);
+
+    let changed_key = CacheKey::new(
+        "pub function add(lhs: int, rhs: int): int { return lhs - rhs; }",
+        &[],
+        SETTINGS,
+    );
+
+    assert_ne!(key, changed_key);
+    assert!(!cache.reuse("main", &changed_key));
+
+    // The key is only cached under the module it has been stored for
+    assert!(!cache.reuse("utils", &key));
+}
+
+#[test]
+fn invalidates_modules_when_an_imported_interface_changes()
+{
+    let imported = interface("pub function helper(value: int): int { return value; }");
+    let key = CacheKey::new(SOURCE, &[&imported], SETTINGS);
+
+    // The body of an imported function is not part of its interface
+    let changed_body = interface("pub function helper(value: int): int { return value * 2; }");
+
+    assert_eq!(imported, changed_body);
+    assert_eq!(key, CacheKey::new(SOURCE, &[&changed_body], SETTINGS));
+
+    // Neither are the private functions of the imported module
+    let private_function = interface(
+        "pub function helper(value: int): int { return value; }\npriv function unused(): int { return 0; }",
+    );
+
+    assert_eq!(imported, private_function);
+
+    let changed_signature = interface("pub function helper(value: uint): int { return 0; }");
+
+    assert_ne!(imported, changed_signature);
+    assert_ne!(key, CacheKey::new(SOURCE, &[&changed_signature], SETTINGS));
+
+    let new_item = interface(
+        "pub function helper(value: int): int { return value; }\npub struct Pair { lhs: int, rhs: int, }",
+    );
+
+    assert_ne!(imported, new_item);
+    assert_ne!(key, CacheKey::new(SOURCE, &[&new_item], SETTINGS));
+
+    let mut cache = ModuleCache::new(&create_build_dir("cache_interface_misses")).unwrap();
+
+    store_object(&mut cache, "main", &key);
+
+    assert!(!cache.reuse(
+        "main",
+        &CacheKey::new(SOURCE, &[&changed_signature], SETTINGS)
+    ));
+}
+
+#[test]
+fn invalidates_modules_when_the_settings_change()
+{
+    let key = CacheKey::new(SOURCE, &[], SETTINGS);
+
+    assert_ne!(
+        key,
+        CacheKey::new(SOURCE, &[], "x86_64-unknown-linux-gnu\nO3")
+    );
+    assert_ne!(
+        key,
+        CacheKey::new(SOURCE, &[], "aarch64-unknown-linux-gnu\nO0")
+    );
+
+    // Moving text between the parts of the key changes it too
+    assert_ne!(
+        CacheKey::new("ab", &["c"], SETTINGS),
+        CacheKey::new("a", &["bc"], SETTINGS)
+    );
+}
+
+#[test]
+fn removes_outdated_objects_of_the_stored_module()
+{
+    let mut cache = ModuleCache::new(&create_build_dir("cache_invalidation")).unwrap();
+
+    let old_key = CacheKey::new(SOURCE, &[], SETTINGS);
+    let new_key = CacheKey::new(SOURCE, &[], "x86_64-unknown-linux-gnu\nO3");
+
+    let old_object = store_object(&mut cache, "app", &old_key);
+    let test_object = store_object(&mut cache, "app@test", &old_key);
+    let other_object = store_object(&mut cache, "app.utils", &old_key);
+    let member_object = store_object(&mut cache, "math", &old_key);
+
+    let new_object = store_object(&mut cache, "app", &new_key);
+
+    assert!(!old_object.exists());
+    assert!(new_object.is_file());
+
+    // The objects of the other modules, the test harness and the other members of the workspace are kept
+    assert!(test_object.is_file());
+    assert!(other_object.is_file());
+    assert!(member_object.is_file());
+
+    assert!(!cache.reuse("app", &old_key));
+    assert!(cache.reuse("app", &new_key));
+    assert!(cache.reuse("app@test", &old_key));
+    assert!(cache.reuse("math", &old_key));
+}
@@ -1,6 +1,10 @@
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
-use common::strum;
+use common::{
+    compiler::{EmitKind, LtoMode, OptLevel},
+    dependency_manager::TokenScope,
+    strum,
+};
 use strum::{Display, VariantNames};
 
 #[derive(Clone, Debug, Display, clap::Subcommand, PartialEq, Eq, VariantNames)]
@@ -13,6 +17,13 @@ pub enum CliCommand
     {
         #[arg(short, long, value_name = "MANIFEST_PATH")]
         path: PathBuf,
+
+        #[arg(
+            short,
+            long,
+            help = "The target the manifest must have been built for. The manifest is refused if it was built for a different target."
+        )]
+        target_triple: Option<String>,
     },
     /// Publishes a dependency to the target url
     Publish
@@ -28,18 +39,10 @@ pub enum CliCommand
         #[arg(
             short,
             long,
-            value_name = "AUTHOR_NAME",
-            help = "The name the dependency should be published to (The name must match on following updates)."
+            value_name = "API_TOKEN",
+            help = "The API token to authenticate with. The token stored by `fog login` is used if it is not set."
         )]
-        author: String,
-
-        #[arg(
-            short,
-            long,
-            value_name = "DEPENDENCY_SECRET",
-            help = "Secret key to be able to update and modify a dependency."
-        )]
-        secret: Option<String>,
+        token: Option<String>,
 
         #[arg(
             short,
@@ -52,17 +55,77 @@ pub enum CliCommand
     /// Compiles a project without running it.
     Compile
     {
-        #[arg(short, long, default_value = None, help = "The path to the project's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        #[arg(long, default_value = None, help = "The path to the project's or workspace's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
         path: Option<PathBuf>,
 
         #[arg(
             short,
+            long,
+            value_name = "PACKAGE_NAME",
+            help = "Selects a member of the workspace. Every member is selected by default, unless the path points to a member."
+        )]
+        package: Option<String>,
+
+        #[arg(
+            short = 'F',
+            long,
+            value_delimiter = ',',
+            value_name = "FEATURES",
+            help = "Comma separated list of the project's features to enable."
+        )]
+        features: Vec<String>,
+
+        #[arg(
             long,
             default_value_t = false,
-            help = "Whether the compiler output should be optimized or not."
+            help = "Enables every feature defined by the project."
+        )]
+        all_features: bool,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Does not enable the project's default features."
+        )]
+        no_default_features: bool,
+
+        #[arg(
+            short,
+            long,
+            default_value_t = false,
+            help = "Compiles the project with the `release` profile."
         )]
         release: bool,
 
+        #[arg(
+            long,
+            value_name = "PROFILE",
+            conflicts_with = "release",
+            help = "Compiles the project with a profile defined in the config file. The default profile is `dev`."
+        )]
+        profile: Option<String>,
+
+        #[arg(
+            short = 'O',
+            value_name = "LEVEL",
+            help = "Sets the optimization level (0, 1, 2, 3, s, z), overriding the level of the profile."
+        )]
+        opt_level: Option<OptLevel>,
+
+        #[arg(
+            long,
+            value_name = "PIPELINE",
+            help = "A custom LLVM pass pipeline (ie. `function(mem2reg),globaldce`) which replaces the pipeline of the optimization level."
+        )]
+        passes: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "MODE",
+            help = "Enables link time optimization across the project and its dependencies."
+        )]
+        lto: Option<LtoMode>,
+
         #[arg(
             short,
             long,
@@ -93,21 +156,149 @@ pub enum CliCommand
             help = "Sets the default CPU features of the LLVM target. If the argument is ignored, host values apply."
         )]
         cpu_features: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "THREADS",
+            help = "The number of threads generating the modules of the project in parallel. Every available core is used by default."
+        )]
+        jobs: Option<NonZeroUsize>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Fails instead of updating the lock file, if it does not match the resolved dependencies."
+        )]
+        locked: bool,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "KINDS",
+            help = "Comma separated list of artifacts to write to the build path. The compiler stops after the last requested stage and does not link."
+        )]
+        emit: Vec<EmitKind>,
+    },
+    /// Checks a project and its local dependencies for errors without compiling them.
+    Check
+    {
+        #[arg(long, default_value = None, help = "The path to the project's or workspace's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        path: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "PACKAGE_NAME",
+            help = "Selects a member of the workspace. Every member is selected by default, unless the path points to a member."
+        )]
+        package: Option<String>,
+
+        #[arg(
+            short = 'F',
+            long,
+            value_delimiter = ',',
+            value_name = "FEATURES",
+            help = "Comma separated list of the project's features to enable."
+        )]
+        features: Vec<String>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Enables every feature defined by the project."
+        )]
+        all_features: bool,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Does not enable the project's default features."
+        )]
+        no_default_features: bool,
+
+        #[arg(
+            short,
+            long,
+            hide_default_value = true,
+            help = "The target `@cfg` predicates are evaluated for. Default target is the host."
+        )]
+        target_triple: Option<String>,
     },
     /// Compiles a project and automatically runs it.
     Run
     {
-        #[arg(short, long, default_value = None, help = "The path to the project's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        #[arg(long, default_value = None, help = "The path to the project's or workspace's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
         path: Option<PathBuf>,
 
         #[arg(
             short,
+            long,
+            value_name = "PACKAGE_NAME",
+            help = "Selects a member of the workspace. Every member is selected by default, unless the path points to a member."
+        )]
+        package: Option<String>,
+
+        #[arg(
+            short = 'F',
+            long,
+            value_delimiter = ',',
+            value_name = "FEATURES",
+            help = "Comma separated list of the project's features to enable."
+        )]
+        features: Vec<String>,
+
+        #[arg(
             long,
             default_value_t = false,
-            help = "Whether the compiler output should be optimized or not."
+            help = "Enables every feature defined by the project."
+        )]
+        all_features: bool,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Does not enable the project's default features."
+        )]
+        no_default_features: bool,
+
+        #[arg(
+            short,
+            long,
+            default_value_t = false,
+            help = "Compiles the project with the `release` profile."
         )]
         release: bool,
 
+        #[arg(
+            long,
+            value_name = "PROFILE",
+            conflicts_with = "release",
+            help = "Compiles the project with a profile defined in the config file. The default profile is `dev`."
+        )]
+        profile: Option<String>,
+
+        #[arg(
+            short = 'O',
+            value_name = "LEVEL",
+            help = "Sets the optimization level (0, 1, 2, 3, s, z), overriding the level of the profile."
+        )]
+        opt_level: Option<OptLevel>,
+
+        #[arg(
+            long,
+            value_name = "PIPELINE",
+            help = "A custom LLVM pass pipeline (ie. `function(mem2reg),globaldce`) which replaces the pipeline of the optimization level."
+        )]
+        passes: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "MODE",
+            help = "Enables link time optimization across the project and its dependencies."
+        )]
+        lto: Option<LtoMode>,
+
         #[arg(
             short,
             long,
@@ -138,6 +329,336 @@ pub enum CliCommand
             help = "Sets the default CPU features of the LLVM target. If the argument is ignored, host values apply."
         )]
         cpu_features: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "THREADS",
+            help = "The number of threads generating the modules of the project in parallel. Every available core is used by default."
+        )]
+        jobs: Option<NonZeroUsize>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Fails instead of updating the lock file, if it does not match the resolved dependencies."
+        )]
+        locked: bool,
+    },
+    /// Compiles the tests of a project into a test harness and runs them.
+    Test
+    {
+        #[arg(
+            value_name = "FILTER",
+            help = "Only the tests whose name contains the filter are run."
+        )]
+        filter: Option<String>,
+
+        #[arg(long, default_value = None, help = "The path to the project's or workspace's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        path: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "PACKAGE_NAME",
+            help = "Selects a member of the workspace. Every member is selected by default, unless the path points to a member."
+        )]
+        package: Option<String>,
+
+        #[arg(
+            short = 'F',
+            long,
+            value_delimiter = ',',
+            value_name = "FEATURES",
+            help = "Comma separated list of the project's features to enable."
+        )]
+        features: Vec<String>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Enables every feature defined by the project."
+        )]
+        all_features: bool,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Does not enable the project's default features."
+        )]
+        no_default_features: bool,
+
+        #[arg(
+            short,
+            long,
+            default_value_t = false,
+            help = "Compiles the tests with the `release` profile."
+        )]
+        release: bool,
+
+        #[arg(
+            long,
+            value_name = "PROFILE",
+            conflicts_with = "release",
+            help = "Compiles the tests with a profile defined in the config file. The default profile is `dev`."
+        )]
+        profile: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "THREADS",
+            help = "The number of threads generating the modules of the project in parallel. Every available core is used by default."
+        )]
+        jobs: Option<NonZeroUsize>,
+    },
+    /// Generates Fog declarations from a C header.
+    Bindgen
+    {
+        #[arg(value_name = "HEADER_PATH", help = "The path to the C header.")]
+        header: PathBuf,
+
+        #[arg(
+            short,
+            long,
+            value_name = "OUTPUT_PATH",
+            help = "The path of the generated source file. The default path is `<source directory>/<header name>.f`."
+        )]
+        output: Option<PathBuf>,
+
+        #[arg(short, long, default_value = None, help = "The path to the project's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        path: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "LIBRARY_PATH",
+            help = "Libraries implementing the header, these are added to the additional linking material of the project."
+        )]
+        link: Vec<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            hide_default_value = true,
+            help = "The target the header is read for, this decides the size of `long` and of pointer sized integers. The default target is the host."
+        )]
+        target_triple: Option<String>,
+    },
+    /// Adds a dependency to the project's config file.
+    Add
+    {
+        #[arg(value_name = "DEPENDENCY_NAME", help = "The name of the dependency.")]
+        name: String,
+
+        #[arg(
+            long,
+            value_name = "REQUIREMENT",
+            help = "The version requirement of the dependency (ie. `^1.2`). The latest version published to the registry is required by default."
+        )]
+        version: Option<String>,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "FEATURES",
+            help = "Comma separated list of the features enabled for the dependency."
+        )]
+        features: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "DEPENDENCY_PATH",
+            help = "Adds a path dependency, the path is relative to the project's root."
+        )]
+        dependency_path: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "REMOTE_NAME",
+            help = "The name of the remote compiler worker compiling the dependency."
+        )]
+        remote: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "REGISTRY_URL",
+            help = "The address of the dependency manager the latest version of the dependency is looked up at. The registry set in the project's config file is used by default."
+        )]
+        registry: Option<String>,
+
+        #[arg(short, long, default_value = None, help = "The path to the project's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        path: Option<PathBuf>,
+    },
+    /// Yanks a published version of a dependency, yanked versions are only used by the projects which have locked them.
+    Yank
+    {
+        #[arg(value_name = "DEPENDENCY_NAME", help = "The name of the dependency.")]
+        name: String,
+
+        #[arg(
+            value_name = "VERSION",
+            help = "The published version of the dependency."
+        )]
+        version: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "REMOTE_URL",
+            help = "The address of the dependency manager the dependency is published to."
+        )]
+        url: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "API_TOKEN",
+            help = "The API token to authenticate with. The token stored by `fog login` is used if it is not set."
+        )]
+        token: Option<String>,
+
+        #[arg(long, help = "Restores the yanked version.")]
+        undo: bool,
+    },
+    /// Deprecates a published version of a dependency, deprecated versions can still be depended on.
+    Deprecate
+    {
+        #[arg(value_name = "DEPENDENCY_NAME", help = "The name of the dependency.")]
+        name: String,
+
+        #[arg(
+            value_name = "VERSION",
+            help = "The published version of the dependency."
+        )]
+        version: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "REMOTE_URL",
+            help = "The address of the dependency manager the dependency is published to."
+        )]
+        url: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "API_TOKEN",
+            help = "The API token to authenticate with. The token stored by `fog login` is used if it is not set."
+        )]
+        token: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "MESSAGE",
+            help = "The reason the version is deprecated. The deprecation is removed if it is not set."
+        )]
+        message: Option<String>,
+    },
+    /// Creates an account at a dependency manager and stores its API token in the credentials file.
+    Register
+    {
+        #[arg(value_name = "USERNAME", help = "The name of the account.")]
+        username: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "REMOTE_URL",
+            help = "The address of the dependency manager."
+        )]
+        url: String,
+    },
+    /// Stores the API token of an account in the user-level credentials file.
+    Login
+    {
+        #[arg(
+            short,
+            long,
+            value_name = "REMOTE_URL",
+            help = "The address of the dependency manager."
+        )]
+        url: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "API_TOKEN",
+            help = "The API token of the account. The token is read from the standard input if it is not set."
+        )]
+        token: Option<String>,
+    },
+    /// Removes the API token of a dependency manager from the credentials file.
+    Logout
+    {
+        #[arg(
+            short,
+            long,
+            value_name = "REMOTE_URL",
+            help = "The address of the dependency manager."
+        )]
+        url: String,
+    },
+    /// Manages the API tokens of the account.
+    Token
+    {
+        #[arg(
+            short,
+            long,
+            value_name = "REMOTE_URL",
+            help = "The address of the dependency manager."
+        )]
+        url: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "API_TOKEN",
+            help = "The API token to authenticate with. The token stored by `fog login` is used if it is not set."
+        )]
+        token: Option<String>,
+
+        #[command(subcommand)]
+        command: TokenCommand,
+    },
+    /// Manages the owners of a dependency, only owners can publish, yank or deprecate its versions.
+    Owner
+    {
+        #[arg(
+            short,
+            long,
+            value_name = "REMOTE_URL",
+            help = "The address of the dependency manager."
+        )]
+        url: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "API_TOKEN",
+            help = "The API token to authenticate with. The token stored by `fog login` is used if it is not set."
+        )]
+        token: Option<String>,
+
+        #[command(subcommand)]
+        command: OwnerCommand,
+    },
+    /// Removes a dependency from the project's config file.
+    Remove
+    {
+        #[arg(value_name = "DEPENDENCY_NAME", help = "The name of the dependency.")]
+        name: String,
+
+        #[arg(short, long, default_value = None, help = "The path to the project's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        path: Option<PathBuf>,
+    },
+    /// Prints the resolved dependency graph of the project, the yanked and deprecated versions are marked.
+    Tree
+    {
+        #[arg(short, long, default_value = None, help = "The path to the project's root. Default path is the current directory path.", value_name = "PROJECT_ROOT")]
+        path: Option<PathBuf>,
     },
     /// Initializes a project.
     Init
@@ -152,3 +673,78 @@ pub enum CliCommand
         path: PathBuf,
     },
 }
+
+#[derive(Clone, Debug, clap::Subcommand, PartialEq, Eq)]
+pub enum TokenCommand
+{
+    /// Creates a new API token, the token is only shown once.
+    Create
+    {
+        #[arg(
+            value_name = "TOKEN_NAME",
+            help = "The name of the token, it must be unique per account."
+        )]
+        name: String,
+
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            default_values_t = TokenScope::ALL,
+            help = "The operations the token is allowed to perform."
+        )]
+        scopes: Vec<TokenScope>,
+    },
+    /// Revokes an API token of the account.
+    Revoke
+    {
+        #[arg(value_name = "TOKEN_NAME")]
+        name: String,
+    },
+    /// Lists the API tokens of the account.
+    List,
+}
+
+#[derive(Clone, Debug, clap::Subcommand, PartialEq, Eq)]
+pub enum OwnerCommand
+{
+    /// Invites a user to become an owner of a dependency.
+    Invite
+    {
+        #[arg(value_name = "DEPENDENCY_NAME")]
+        package: String,
+
+        #[arg(value_name = "USERNAME")]
+        username: String,
+    },
+    /// Accepts the invitation to become an owner of a dependency.
+    Accept
+    {
+        #[arg(value_name = "DEPENDENCY_NAME")]
+        package: String,
+    },
+    /// Removes an owner of a dependency, the last owner cannot be removed.
+    Remove
+    {
+        #[arg(value_name = "DEPENDENCY_NAME")]
+        package: String,
+
+        #[arg(value_name = "USERNAME")]
+        username: String,
+    },
+    /// Lists the owners of a dependency.
+    List
+    {
+        #[arg(value_name = "DEPENDENCY_NAME")]
+        package: String,
+    },
+    /// Claims a dependency published before accounts existed with the secret received when it was first published.
+    Claim
+    {
+        #[arg(value_name = "DEPENDENCY_NAME")]
+        package: String,
+
+        #[arg(value_name = "DEPENDENCY_SECRET")]
+        secret: String,
+    },
+}
@@ -1,23 +1,51 @@
 mod cli;
 
-use crate::cli::CliCommand;
+use crate::cli::{CliCommand, OwnerCommand, TokenCommand};
 use clap::Parser;
 use common::{
-    anyhow, clap,
-    compiler::ProjectConfig,
+    anyhow,
+    artifact::{
+        config_path, deps_dir, find_workspace_root, host_triple, lock_file_path, main_source_path,
+        source_dir,
+    },
+    clap,
+    compiler::{EmitKind, FeatureSelection, Profile, ProjectConfig},
     compression::{compress_bytes, zip_folder},
-    dependency_manager::{DependencyUpload, DependencyUploadReply},
-    error::{application::ApplicationError, codegen::CodeGenError, linker::LinkerError},
+    credentials::{Credentials, RegistryCredentials, credentials_path},
+    dependency::{
+        ANY_VERSION, DependencyInfo, DependencyRequest, RegistryStatus, VersionStatus,
+        add_dependency_to_config, remove_dependency_from_config,
+    },
+    dependency_manager::{
+        ClaimRequest, DependencyInformation, DependencyUpload, DeprecationRequest, OwnerRequest,
+        PackageOwners, PackageRequest, PublishedVersion, RegisterRequest, TokenCreateRequest,
+        TokenInformation, TokenReply, TokenRevokeRequest, VersionListRequest, VersionListing,
+        YankRequest, urls::api,
+    },
+    error::{application::ApplicationError, codegen::CodeGenError, workspace::WorkspaceError},
+    futures,
     linker::BuildManifest,
+    lockfile::LockFile,
     reqwest::{self, StatusCode},
     rmp_serde, serde_json, tokio, toml,
-    tracing::{info, warn},
+    tracing::{error, info, warn},
     tracing_subscriber,
-    ty::OrdSet,
+    workspace::Workspace,
+};
+use compiler::{CompilerState, update_shared_lock_file};
+use imports::{
+    bindgen::{CHeaderBindings, add_linking_material_to_config},
+    resolver::resolve_dependencies,
+    tree::render_dependency_tree,
 };
-use compiler::CompilerState;
 use linker::link;
-use std::{env, fs, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashSet},
+    env, fs,
+    num::NonZeroUsize,
+    path::Path,
+    time::Duration,
+};
 use tracing::Level;
 
 #[derive(Parser, Debug)]
@@ -41,158 +69,390 @@ async fn main() -> common::anyhow::Result<()>
     let compiler_command = compiler_args.command;
 
     match compiler_command.clone() {
-        CliCommand::Link { path } => {
+        CliCommand::Link {
+            path,
+            target_triple,
+        } => {
             info!("Reading file on: `{}`", path.display());
 
-            let manifest_string = fs::read_to_string(&path)?;
+            let manifest = BuildManifest::read(&path)?;
 
-            let manifest = toml::from_str::<BuildManifest>(&manifest_string)?;
+            // Artifacts of a different compiler, target or stale artifacts must be rebuilt instead of linked
+            manifest.verify(target_triple.as_deref())?;
 
-            let link_res = link(&manifest)?;
+            link(&manifest)?;
 
-            if !link_res.status.success() {
-                return Err(LinkerError::Other(String::from_utf8(link_res.stderr)?.into()).into());
+            for output in &manifest.outputs {
+                info!(
+                    "Linking finished successfully! Output is available at: {}",
+                    output.path.display()
+                );
             }
-
-            info!(
-                "Linking finished successfully! Binary output is available at: {}",
-                manifest.output_path.display()
-            );
         },
         CliCommand::Compile {
             path: compile_path,
+            package,
+            features,
+            all_features,
+            no_default_features,
             release: is_release,
+            profile,
+            opt_level,
+            passes,
+            lto,
             target_triple,
             llvm_flags,
             cpu_name,
             cpu_features,
+            jobs,
+            locked,
+            ..
         }
         | CliCommand::Run {
             path: compile_path,
+            package,
+            features,
+            all_features,
+            no_default_features,
             release: is_release,
+            profile,
+            opt_level,
+            passes,
+            lto,
             target_triple,
             llvm_flags,
             cpu_name,
             cpu_features,
+            jobs,
+            locked,
         } => {
-            let root_path = if let Some(path) = compile_path.clone() {
-                path
+            let is_run = matches!(compiler_command, CliCommand::Run { .. });
+
+            // Only `fog compile` can request specific artifacts
+            let emit = if let CliCommand::Compile { emit, .. } = &compiler_command {
+                emit.clone()
             }
             else {
-                current_working_dir
+                Vec::new()
             };
 
-            // Check for the main source file
+            let root_path = compile_path.unwrap_or(current_working_dir);
+
             info!("Reading Files...");
 
-            let source_file = fs::read_to_string(format!("{}/src/main.f", root_path.display()))
-                .map_err(|_| ApplicationError::CodeGenError(CodeGenError::NoMain.into()))?;
+            let projects = workspace_projects(&root_path, false).await?;
 
-            let compiler_state = CompilerState::new(root_path.clone(), OrdSet::new())?;
+            // Every member of the workspace is locked, even if only some of them are compiled
+            update_shared_lock_file(&projects, locked)?;
 
-            let compiler_config = compiler_state.config.clone();
+            let projects = select_projects(
+                projects,
+                &root_path,
+                package.as_deref(),
+                &FeatureSelection {
+                    features,
+                    all_features,
+                    no_default_features,
+                },
+            )?;
 
-            if !compiler_config.is_library && compiler_config.features.is_some() {
-                warn!(
-                    "WARNING: Project `{}({})` is not a library, but has features. Features {:?} will be ignored.",
-                    compiler_config.name,
-                    compiler_config.version,
-                    compiler_config.features.clone().unwrap()
-                );
+            if is_run && projects.len() > 1 {
+                return Err(WorkspaceError::PackageRequired.into());
             }
 
-            fs::create_dir_all(compiler_config.build_path)?;
-
-            let build_artifact_name = format!(
-                "{}\\{}\\{}",
-                root_path.display(),
-                compiler_state.config.build_path,
-                compiler_config.name.clone()
-            );
+            let settings = CompileSettings {
+                profile_name: profile
+                    .unwrap_or_else(|| if is_release { "release" } else { "dev" }.to_string()),
+                profile_overrides: Profile {
+                    opt_level,
+                    passes,
+                    lto,
+                    debug: None,
+                },
+                target_triple: target_triple.unwrap_or_else(host_triple),
+                llvm_flags,
+                cpu_name,
+                cpu_features,
+                jobs,
+                emit,
+            };
 
-            let target_ir_path = PathBuf::from(format!("{build_artifact_name}.ll"));
+            let compiler_startup_instant = std::time::Instant::now();
 
-            let target_o_path = PathBuf::from(format!("{build_artifact_name}.obj"));
+            for compiler_state in projects {
+                let root_path = compiler_state.root_dir.clone();
 
-            let build_path = PathBuf::from(format!("{build_artifact_name}.exe"));
+                let Some(build_manifest) = compile_project(compiler_state, &settings).await?
+                else {
+                    continue;
+                };
 
-            let build_manifest_path = PathBuf::from(format!("{build_artifact_name}.manifest"));
-            let build_path_clone = build_path.clone();
-            let compiler_startup_instant = std::time::Instant::now();
-            let root_path_clone = root_path.clone();
-
-            let build_manifest = tokio::task::spawn_blocking(move || {
-                compiler_state.compilation_process(
-                    &source_file,
-                    target_ir_path.clone(),
-                    target_o_path.clone(),
-                    build_path_clone.clone(),
-                    is_release,
-                    compiler_config.is_library,
-                    &format!("{}\\src", root_path.display()),
-                    &llvm_flags,
-                    target_triple,
-                    cpu_name,
-                    cpu_features,
-                )
-            })
-            .await??;
+                if is_run {
+                    let args: Vec<String> = Vec::new();
 
-            // Write build manifest to disc
-            fs::write(build_manifest_path, toml::to_string(&build_manifest)?)?;
+                    let executable_path = build_manifest
+                        .executable_path()
+                        .ok_or(ApplicationError::LibraryNotRunnable)?;
 
-            info!("All build artifacts have been saved.");
+                    info!(
+                        "Running `{} {}`",
+                        executable_path.display(),
+                        /* Pass in the arguments inherited (TODO) */ args.join(" ")
+                    );
 
-            // Link automaticly
-            let link_res = link(&build_manifest).map_err(anyhow::Error::from)?;
+                    let exit_status = build_manifest.run_build_output(root_path, args)?;
 
-            if !link_res.status.success() {
-                return Err(LinkerError::Other(String::from_utf8(link_res.stderr)?.into()).into());
+                    if !exit_status.success() {
+                        if let Some(exit_code) = exit_status.code() {
+                            info!("Process failed with exit code: {exit_code}")
+                        }
+                        else {
+                            info!("Process was interrupted")
+                        }
+                    }
+                }
             }
 
-            info!(
-                "Linking finished successfully! Binary output is available at: {}",
-                build_path.display()
-            );
-
             info!(
                 "Building finished in {:.2?}.",
                 compiler_startup_instant.elapsed()
             );
+        },
+        CliCommand::Check {
+            path,
+            package,
+            features,
+            all_features,
+            no_default_features,
+            target_triple,
+        } => {
+            let root_path = path.unwrap_or(current_working_dir);
+
+            let mut projects = select_projects(
+                workspace_projects(&root_path, false).await?,
+                &root_path,
+                package.as_deref(),
+                &FeatureSelection {
+                    features,
+                    all_features,
+                    no_default_features,
+                },
+            )?;
+
+            // The projects are checked in build order
+            projects.reverse();
+
+            let mut checked_projects = HashSet::new();
+            let mut error_count = 0;
 
-            if matches!(compiler_command.clone(), CliCommand::Run { .. }) {
-                let args: Vec<String> = Vec::new();
+            // The dependencies of the dependencies are checked too, every project is only checked once
+            while let Some(project) = projects.pop() {
+                if !checked_projects.insert(project.root_dir.clone()) {
+                    continue;
+                }
 
                 info!(
-                    "Running `{} {}`",
-                    build_path.display(),
-                    /* Pass in the arguments inherited (TODO) */ args.join(" ")
+                    "Checking `{}({})`...",
+                    project.config.name, project.config.version
                 );
 
-                let exit_status = build_manifest.run_build_output(root_path_clone, args)?;
+                let errors = project.check(target_triple.as_deref())?;
 
-                if !exit_status.success() {
-                    if let Some(exit_code) = exit_status.code() {
-                        info!("Process failed with exit code: {exit_code}")
-                    }
-                    else {
-                        info!("Process was interrupted")
+                for error in &errors {
+                    error!("{error:?}");
+                }
+
+                error_count += errors.len();
+
+                for dependency in project.local_dependencies().into_iter().rev() {
+                    match dependency {
+                        Ok(dependency) => projects.push(dependency),
+                        Err(error) => {
+                            error!("{error:?}");
+
+                            error_count += 1;
+                        },
                     }
                 }
             }
+
+            if error_count > 0 {
+                return Err(ApplicationError::CheckFailed(error_count).into());
+            }
+
+            info!("No errors have been found.");
+        },
+        CliCommand::Test {
+            filter,
+            path,
+            package,
+            features,
+            all_features,
+            no_default_features,
+            release: is_release,
+            profile,
+            jobs,
+        } => {
+            let root_path = path.unwrap_or(current_working_dir);
+
+            let projects = select_projects(
+                workspace_projects(&root_path, false).await?,
+                &root_path,
+                package.as_deref(),
+                &FeatureSelection {
+                    features,
+                    all_features,
+                    no_default_features,
+                },
+            )?;
+
+            let mut failed_projects = Vec::new();
+
+            for compiler_state in projects {
+                let root_path = compiler_state.root_dir.clone();
+
+                let source_file = fs::read_to_string(main_source_path(&root_path))
+                    .map_err(|_| ApplicationError::CodeGenError(CodeGenError::NoMain.into()))?;
+
+                let compiler_config = compiler_state.config.clone();
+
+                let profile_name = profile
+                    .clone()
+                    .unwrap_or_else(|| if is_release { "release" } else { "dev" }.to_string());
+
+                let optimization = compiler_config
+                    .get_profile(&profile_name)?
+                    .optimization_settings();
+
+                // The tests are run on the host
+                let target_triple = host_triple();
+
+                // The harness is named `<project name>.test`, so that it does not overwrite the outputs of the project
+                let artifact_paths = compiler_state
+                    .artifact_paths(&format!("{}.test", compiler_config.name), &target_triple);
+
+                fs::create_dir_all(artifact_paths.build_dir())?;
+
+                let path_to_src = source_dir(&root_path).display().to_string();
+
+                let build_manifest = tokio::task::spawn_blocking(move || {
+                    compiler_state.compilation_process(
+                        &source_file,
+                        &artifact_paths,
+                        &optimization,
+                        compiler_config.is_library,
+                        &path_to_src,
+                        "",
+                        Some(target_triple),
+                        None,
+                        None,
+                        jobs,
+                        &[],
+                        true,
+                    )
+                })
+                .await??
+                .expect("Compiling into object files must produce a build manifest.");
+
+                link(&build_manifest).map_err(anyhow::Error::from)?;
+
+                info!("Running tests of `{}`...", compiler_config.name);
+
+                let exit_status = build_manifest
+                    .run_build_output(root_path, filter.clone().into_iter().collect())?;
+
+                if !exit_status.success() {
+                    failed_projects.push(compiler_config.name);
+                }
+            }
+
+            if !failed_projects.is_empty() {
+                error!("Tests have failed in: {}", failed_projects.join(", "));
+
+                return Err(ApplicationError::TestsFailed.into());
+            }
+        },
+        CliCommand::Bindgen {
+            header,
+            output,
+            path,
+            link,
+            target_triple,
+        } => {
+            let path = path.unwrap_or(current_working_dir);
+
+            info!("Reading header: `{}`", header.display());
+
+            let header_source = fs::read_to_string(&header).map_err(ApplicationError::FileError)?;
+
+            let target_triple = target_triple.unwrap_or_else(host_triple);
+
+            let bindings = CHeaderBindings::from_header(&header_source, &target_triple)?;
+
+            for (name, reason) in &bindings.skipped {
+                warn!("Skipped `{name}`: {reason}.");
+            }
+
+            let module_name = header
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+
+            let output =
+                output.unwrap_or_else(|| source_dir(&path).join(format!("{module_name}.f")));
+
+            let header_name = header.file_name().unwrap_or_default().to_string_lossy();
+
+            fs::write(&output, bindings.to_fog_source(&header_name, &module_name))
+                .map_err(ApplicationError::FileError)?;
+
+            info!(
+                "Generated {} functions, {} structs and {} enums at: {}",
+                bindings.functions.len(),
+                bindings.structs.len(),
+                bindings.enums.len(),
+                output.display()
+            );
+
+            if !link.is_empty() {
+                let config_file = fs::read_to_string(config_path(&path))
+                    .map_err(|_| ApplicationError::ConfigNotFound(path.clone()))?;
+
+                let compiler_config = toml::from_str::<ProjectConfig>(&config_file)
+                    .map_err(ApplicationError::ConfigError)?;
+
+                let libraries = link
+                    .into_iter()
+                    .filter(|library| {
+                        !compiler_config
+                            .additional_linking_material
+                            .contains(library)
+                    })
+                    .collect::<Vec<_>>();
+
+                for library in &libraries {
+                    info!("Adding `{}` to the linking material...", library.display());
+                }
+
+                fs::write(
+                    config_path(&path),
+                    add_linking_material_to_config(&config_file, &libraries)?,
+                )
+                .map_err(ApplicationError::FileError)?;
+            }
         },
         CliCommand::Version => info!("Build version: {}", env!("CARGO_PKG_VERSION")),
         CliCommand::New { path } => {
             info!("Creating project folders...");
-            let path_s = path.display();
 
-            fs::create_dir_all(path_s.to_string()).map_err(ApplicationError::FileError)?;
-            fs::create_dir(format!("{path_s}/out"))?;
-            fs::create_dir(format!("{path_s}/deps"))?;
-            fs::create_dir(format!("{path_s}/src"))?;
+            fs::create_dir_all(&path).map_err(ApplicationError::FileError)?;
+            fs::create_dir(path.join("out"))?;
+            fs::create_dir(deps_dir(&path))?;
+            fs::create_dir(source_dir(&path))?;
 
             fs::write(
-                format!("{}/src/main.f", path_s),
+                main_source_path(&path),
                 include_str!("../../../defaults/default_code.f"),
             )
             .map_err(ApplicationError::FileError)?;
@@ -201,11 +461,8 @@ async fn main() -> common::anyhow::Result<()>
                 path.file_name().unwrap().to_string_lossy().to_string(),
             );
 
-            fs::write(
-                format!("{}/config.toml", path_s),
-                toml::to_string(&project_cfg)?,
-            )
-            .map_err(ApplicationError::FileError)?;
+            fs::write(config_path(&path), toml::to_string(&project_cfg)?)
+                .map_err(ApplicationError::FileError)?;
 
             info!("Successfully created project `{}`", project_cfg.name)
         },
@@ -218,22 +475,21 @@ async fn main() -> common::anyhow::Result<()>
                 .to_string_lossy();
 
             info!("Creating project folders...");
-            fs::create_dir(format!("{}/output", current_working_dir.display()))
+            fs::create_dir(current_working_dir.join("output"))
                 .map_err(ApplicationError::FileError)?;
-            fs::create_dir(format!("{}/deps", current_working_dir.display()))
-                .map_err(ApplicationError::FileError)?;
-            fs::create_dir(format!("{}/src", current_working_dir.display()))
+            fs::create_dir(deps_dir(&current_working_dir)).map_err(ApplicationError::FileError)?;
+            fs::create_dir(source_dir(&current_working_dir))
                 .map_err(ApplicationError::FileError)?;
 
             info!("Creating main source file...");
             fs::write(
-                format!("{}/src/main.f", current_working_dir.display()),
+                main_source_path(&current_working_dir),
                 include_str!("../../../defaults/default_code.f"),
             )?;
 
             info!("Creating config file...");
             fs::write(
-                format!("{}/config.toml", current_working_dir.display()),
+                config_path(&current_working_dir),
                 toml::to_string(&ProjectConfig::new_from_name(get_folder_name.to_string()))?,
             )
             .map_err(ApplicationError::FileError)?;
@@ -243,12 +499,186 @@ async fn main() -> common::anyhow::Result<()>
                 current_working_dir.display()
             );
         },
-        CliCommand::Publish {
-            url,
-            author,
-            secret,
+        CliCommand::Add {
+            name,
+            version,
+            features,
+            dependency_path,
+            remote,
+            registry,
             path,
         } => {
+            let root_path = path.unwrap_or(current_working_dir);
+
+            let config_file = fs::read_to_string(config_path(&root_path))
+                .map_err(|_| ApplicationError::ConfigNotFound(root_path.clone()))?;
+
+            let registry = registry.or_else(|| {
+                toml::from_str::<ProjectConfig>(&config_file)
+                    .ok()
+                    .and_then(|config| config.registry)
+            });
+
+            let version = match (version, &dependency_path, &remote, registry) {
+                (Some(version), ..) => version,
+                // Path dependencies and dependencies compiled by a remote can be used with any version
+                (None, Some(_), ..) | (None, None, Some(_), _) => ANY_VERSION.to_string(),
+                (None, None, None, Some(registry)) => {
+                    info!("Looking up the latest version of `{name}` at `{registry}`...");
+
+                    let response = reqwest::Client::new()
+                        .get(format!("{registry}{}", api::FETCH_DEP_INFO))
+                        .header("Content-Type", "application/json")
+                        .body(serde_json::to_string(&DependencyRequest {
+                            name: name.clone(),
+                            version: ANY_VERSION.to_string(),
+                        })?)
+                        .send()
+                        .await?;
+
+                    let response_code = response.status();
+                    let reply = response.text().await?;
+
+                    if response_code != StatusCode::OK {
+                        return Err(ApplicationError::RegistryRequestFailed(
+                            response_code.to_string(),
+                            reply,
+                        )
+                        .into());
+                    }
+
+                    let dependency_info = serde_json::from_str::<DependencyInformation>(&reply)?;
+
+                    if let Some(deprecation) = &dependency_info.deprecation {
+                        warn!(
+                            "Version `{}` of `{name}` has been deprecated: {deprecation}",
+                            dependency_info.dependency_version
+                        );
+                    }
+
+                    format!("^{}", dependency_info.dependency_version)
+                },
+                (None, None, None, None) => {
+                    return Err(ApplicationError::RegistryRequired(name).into());
+                },
+            };
+
+            let dependency = DependencyInfo {
+                version,
+                features,
+                remote,
+                path: dependency_path,
+            };
+
+            fs::write(
+                config_path(&root_path),
+                add_dependency_to_config(&config_file, &name, &dependency)?,
+            )
+            .map_err(ApplicationError::FileError)?;
+
+            info!("Added dependency `{name}` ({}).", dependency.version);
+        },
+        CliCommand::Remove { name, path } => {
+            let root_path = path.unwrap_or(current_working_dir);
+
+            let config_file = fs::read_to_string(config_path(&root_path))
+                .map_err(|_| ApplicationError::ConfigNotFound(root_path.clone()))?;
+
+            fs::write(
+                config_path(&root_path),
+                remove_dependency_from_config(&config_file, &name)?,
+            )
+            .map_err(ApplicationError::FileError)?;
+
+            info!("Removed dependency `{name}`.");
+        },
+        CliCommand::Tree { path } => {
+            let root_path = path.unwrap_or(current_working_dir);
+
+            // The statuses are always looked up, so that the locked versions which have been yanked since are marked
+            for compiler_state in select_projects(
+                workspace_projects(&root_path, true).await?,
+                &root_path,
+                None,
+                &FeatureSelection::default(),
+            )? {
+                let lock_file = LockFile::read(&lock_file_path(compiler_state.lock_root()))?;
+
+                let resolved_dependencies = resolve_dependencies(
+                    &compiler_state.root_dir,
+                    &compiler_state.config,
+                    &compiler_state.dependency_features,
+                    lock_file.as_ref(),
+                    &compiler_state.registry_status,
+                )?;
+
+                print!(
+                    "{}",
+                    render_dependency_tree(&compiler_state.config, &resolved_dependencies)
+                );
+            }
+        },
+        CliCommand::Yank {
+            name,
+            version,
+            url,
+            token,
+            undo,
+        } => {
+            let published_version = update_version_status(
+                &url,
+                api::YANK_VERSION,
+                &registry_token(&url, token)?,
+                &YankRequest {
+                    dependency_name: name.clone(),
+                    dependency_version: version,
+                    yanked: !undo,
+                },
+            )
+            .await?;
+
+            if published_version.yanked {
+                info!("Yanked `{name}({})`.", published_version.version);
+            }
+            else {
+                info!("Restored `{name}({})`.", published_version.version);
+            }
+        },
+        CliCommand::Deprecate {
+            name,
+            version,
+            url,
+            token,
+            message,
+        } => {
+            let published_version = update_version_status(
+                &url,
+                api::DEPRECATE_VERSION,
+                &registry_token(&url, token)?,
+                &DeprecationRequest {
+                    dependency_name: name.clone(),
+                    dependency_version: version,
+                    deprecation: message,
+                },
+            )
+            .await?;
+
+            match published_version.deprecation {
+                Some(deprecation) => {
+                    info!(
+                        "Deprecated `{name}({})`: {deprecation}",
+                        published_version.version
+                    );
+                },
+                None => {
+                    info!(
+                        "Removed the deprecation of `{name}({})`.",
+                        published_version.version
+                    );
+                },
+            }
+        },
+        CliCommand::Publish { url, token, path } => {
             let path = if let Some(path) = path.clone() {
                 path
             }
@@ -257,12 +687,14 @@ async fn main() -> common::anyhow::Result<()>
             };
 
             // Read config file
-            let config_file = fs::read_to_string(format!("{}/config.toml", path.display()))
+            let config_file = fs::read_to_string(config_path(&path))
                 .map_err(|_| ApplicationError::ConfigNotFound(path.clone()))?;
 
             let compiler_config = toml::from_str::<ProjectConfig>(&config_file)
                 .map_err(ApplicationError::ConfigError)?;
 
+            let token = registry_token(&url, token)?;
+
             info!("Resolving `{url}`...");
 
             let http_client = reqwest::Client::new();
@@ -272,56 +704,672 @@ async fn main() -> common::anyhow::Result<()>
 
             info!("Remote `{url}` responded with: `{}`", response_code);
 
-            let zip = zip_folder(fs::read_dir(path)?, Some(compiler_config.build_path))?;
+            let zip = zip_folder(
+                fs::read_dir(path)?,
+                Some(compiler_config.build_path.clone()),
+            )?;
 
             let zipped_folder = zip.finish_into_readable()?;
 
             if response_code == StatusCode::OK {
                 info!("Uploading dependency...");
 
-                if let Some(_secret_key) = secret {}
-
                 let dependency_instance = DependencyUpload::new(
-                    compiler_config.name.clone(),
-                    compiler_config.version.clone(),
-                    author,
+                    &compiler_config,
                     zipped_folder.into_inner().into_inner(),
                 );
 
-                let serialized_dep_upload = rmp_serde::to_vec(&dependency_instance)?;
-
-                let compressed_body = compress_bytes(&serialized_dep_upload)?;
+                let compressed_body = compress_bytes(&rmp_serde::to_vec(&dependency_instance)?)?;
 
                 info!("Sending dependency...");
 
-                let publish_response_code = http_client
-                    .post(format!("{url}/publish_dependency"))
+                // The first version of a dependency creates it, later versions can only be published by its owners
+                let publish_response = http_client
+                    .post(format!("{url}{}", api::PUBLISH_DEP))
                     .header("Content-Type", "application/octet-stream")
+                    .bearer_auth(token)
                     .body(compressed_body)
                     .send()
                     .await?;
 
-                let response_code = publish_response_code.status();
+                let response_code = publish_response.status();
+                let reply = publish_response.text().await?;
 
-                if response_code == StatusCode::INTERNAL_SERVER_ERROR {
-                    let request_body = publish_response_code.text().await?;
-                    info!("Received response `{response_code}` from server: {request_body}.");
+                if response_code == StatusCode::OK {
+                    let dep_info = serde_json::from_str::<DependencyInformation>(&reply)?;
+
+                    info!(
+                        "Dependency `{}({})` has been successfully published.",
+                        dep_info.dependency_name, dep_info.dependency_version
+                    );
                 }
                 else {
-                    let reply = request_reply.text().await?;
+                    info!("Received response `{response_code}` from server: {reply}.");
+                }
+            }
+
+            info!("Abandoning connection...");
+        },
+        CliCommand::Register { username, url } => {
+            let token_reply = send_registry_request::<TokenReply>(
+                reqwest::Client::new()
+                    .post(format!("{url}{}", api::REGISTER))
+                    .header("Content-Type", "application/json")
+                    .body(serde_json::to_string(&RegisterRequest { username })?),
+            )
+            .await?;
+
+            store_token(&url, token_reply.token)?;
+
+            info!(
+                "Registered `{}` at `{url}`, its token has been stored in the credentials file.",
+                token_reply.username
+            );
+        },
+        CliCommand::Login { url, token } => {
+            let token = match token {
+                Some(token) => token,
+                None => {
+                    info!("Paste the API token of your account:");
 
-                    let dep_reply = serde_json::from_str::<DependencyUploadReply>(&reply)?;
+                    let mut token = String::new();
+
+                    std::io::stdin().read_line(&mut token)?;
+
+                    token.trim().to_string()
+                },
+            };
+
+            store_token(&url, token)?;
+
+            info!("Logged in to `{url}`.");
+        },
+        CliCommand::Logout { url } => {
+            let credentials_path = credentials_path()?;
+            let mut credentials = Credentials::read(&credentials_path)?;
+
+            if credentials.remove(&url).is_some() {
+                credentials.write(&credentials_path)?;
+
+                info!("Logged out of `{url}`.");
+            }
+            else {
+                info!("You are not logged in to `{url}`.");
+            }
+        },
+        CliCommand::Token {
+            url,
+            token,
+            command,
+        } => {
+            let token = registry_token(&url, token)?;
+            let http_client = reqwest::Client::new();
+
+            match command {
+                TokenCommand::Create { name, scopes } => {
+                    let token_reply = send_registry_request::<TokenReply>(
+                        http_client
+                            .post(format!("{url}{}", api::CREATE_TOKEN))
+                            .header("Content-Type", "application/json")
+                            .bearer_auth(token)
+                            .body(serde_json::to_string(&TokenCreateRequest {
+                                name: name.clone(),
+                                scopes,
+                            })?),
+                    )
+                    .await?;
 
                     info!(
-                        "Dependency `{}({})` has been successfully created. This secret token `{}` can be used to update this dependency later.",
-                        compiler_config.name, compiler_config.version, dep_reply.secret_to_dep
+                        "Created token `{name}`: {}\nThe token is only shown once.",
+                        token_reply.token
                     );
+                },
+                TokenCommand::Revoke { name } => {
+                    send_registry_request::<()>(
+                        http_client
+                            .post(format!("{url}{}", api::REVOKE_TOKEN))
+                            .header("Content-Type", "application/json")
+                            .bearer_auth(token)
+                            .body(serde_json::to_string(&TokenRevokeRequest {
+                                name: name.clone(),
+                            })?),
+                    )
+                    .await?;
+
+                    info!("Revoked token `{name}`.");
+                },
+                TokenCommand::List => {
+                    let tokens = send_registry_request::<Vec<TokenInformation>>(
+                        http_client
+                            .get(format!("{url}{}", api::LIST_TOKENS))
+                            .bearer_auth(token),
+                    )
+                    .await?;
+
+                    for token in tokens {
+                        println!(
+                            "{} [{}] (created {})",
+                            token.name,
+                            token
+                                .scopes
+                                .iter()
+                                .map(|scope| scope.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            token.date_created
+                        );
+                    }
+                },
+            }
+        },
+        CliCommand::Owner {
+            url,
+            token,
+            command,
+        } => {
+            let http_client = reqwest::Client::new();
+
+            // Listing the owners does not require an account
+            if let OwnerCommand::List { package } = &command {
+                let package_owners = send_registry_request::<PackageOwners>(
+                    http_client
+                        .get(format!("{url}{}", api::LIST_OWNERS))
+                        .query(&PackageRequest {
+                            package_name: package.clone(),
+                        }),
+                )
+                .await?;
+
+                for owner in package_owners.owners {
+                    println!("{owner}");
                 }
+
+                return Ok(());
             }
 
-            info!("Abandoning connection...");
+            let token = registry_token(&url, token)?;
+
+            let (endpoint, body, message) = match command {
+                OwnerCommand::Invite { package, username } => {
+                    (
+                        api::INVITE_OWNER,
+                        serde_json::to_string(&OwnerRequest {
+                            package_name: package.clone(),
+                            username: username.clone(),
+                        })?,
+                        format!("Invited `{username}` to become an owner of `{package}`."),
+                    )
+                },
+                OwnerCommand::Accept { package } => {
+                    (
+                        api::ACCEPT_INVITATION,
+                        serde_json::to_string(&PackageRequest {
+                            package_name: package.clone(),
+                        })?,
+                        format!("You are now an owner of `{package}`."),
+                    )
+                },
+                OwnerCommand::Remove { package, username } => {
+                    (
+                        api::REMOVE_OWNER,
+                        serde_json::to_string(&OwnerRequest {
+                            package_name: package.clone(),
+                            username: username.clone(),
+                        })?,
+                        format!("Removed `{username}` from the owners of `{package}`."),
+                    )
+                },
+                OwnerCommand::Claim { package, secret } => {
+                    (
+                        api::CLAIM_PACKAGE,
+                        serde_json::to_string(&ClaimRequest {
+                            package_name: package.clone(),
+                            secret,
+                        })?,
+                        format!("You are now the owner of `{package}`."),
+                    )
+                },
+                OwnerCommand::List { .. } => unreachable!(),
+            };
+
+            send_registry_request::<()>(
+                http_client
+                    .post(format!("{url}{endpoint}"))
+                    .header("Content-Type", "application/json")
+                    .bearer_auth(token)
+                    .body(body),
+            )
+            .await?;
+
+            info!("{message}");
         },
     }
 
     Ok(())
 }
+
+/// The settings the selected projects are compiled with.
+struct CompileSettings
+{
+    profile_name: String,
+    /// The settings passed in as arguments, these override the settings of the profile.
+    profile_overrides: Profile,
+    target_triple: String,
+    llvm_flags: String,
+    cpu_name: Option<String>,
+    cpu_features: Option<String>,
+    jobs: Option<NonZeroUsize>,
+    emit: Vec<EmitKind>,
+}
+
+/// Compiles the project and links its outputs.
+/// The function only returns the project's [`BuildManifest`] if it has been linked, the project is not linked if specific artifacts have been requested with `--emit`.
+async fn compile_project(
+    compiler_state: CompilerState,
+    settings: &CompileSettings,
+) -> anyhow::Result<Option<BuildManifest>>
+{
+    let root_path = compiler_state.root_dir.clone();
+
+    // Check for the main source file
+    let source_file = fs::read_to_string(main_source_path(&root_path))
+        .map_err(|_| ApplicationError::CodeGenError(CodeGenError::NoMain.into()))?;
+
+    let compiler_config = compiler_state.config.clone();
+
+    let optimization = settings
+        .profile_overrides
+        .clone()
+        .or(compiler_config.get_profile(&settings.profile_name)?)
+        .optimization_settings();
+
+    info!(
+        "Compiling `{}({})` with profile `{}` (opt-level: {}).",
+        compiler_config.name,
+        compiler_config.version,
+        settings.profile_name,
+        optimization.opt_level
+    );
+
+    if !compiler_state.enabled_features.is_empty() {
+        info!(
+            "Enabled features: {}",
+            compiler_state
+                .enabled_features
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let artifact_paths =
+        compiler_state.artifact_paths(&compiler_config.name, &settings.target_triple);
+
+    fs::create_dir_all(artifact_paths.build_dir())?;
+
+    let build_manifest_path = artifact_paths.manifest();
+
+    let is_emit_requested = !settings.emit.is_empty();
+
+    let llvm_flags = settings.llvm_flags.clone();
+    let target_triple = settings.target_triple.clone();
+    let cpu_name = settings.cpu_name.clone();
+    let cpu_features = settings.cpu_features.clone();
+    let jobs = settings.jobs;
+    let emit = settings.emit.clone();
+    let path_to_src = source_dir(&root_path).display().to_string();
+
+    // The members of a workspace write their artifacts into the build folder of the workspace
+    let manifest_root = compiler_state.lock_root().to_path_buf();
+
+    let build_manifest = tokio::task::spawn_blocking(move || {
+        compiler_state.compilation_process(
+            &source_file,
+            &artifact_paths,
+            &optimization,
+            compiler_config.is_library,
+            &path_to_src,
+            &llvm_flags,
+            Some(target_triple),
+            cpu_name,
+            cpu_features,
+            jobs,
+            &emit,
+            false,
+        )
+    })
+    .await??;
+
+    // If no object files were requested, there is nothing to link
+    let Some(build_manifest) = build_manifest
+    else {
+        info!("All requested artifacts have been saved.");
+
+        return Ok(None);
+    };
+
+    // Write build manifest to disc, its paths are relative to the project root or the workspace root
+    fs::write(
+        build_manifest_path,
+        toml::to_string(&build_manifest.clone().localize_paths(&manifest_root)?)?,
+    )?;
+
+    info!("All build artifacts have been saved.");
+
+    // The compilation stops after the last requested artifact
+    if is_emit_requested {
+        return Ok(None);
+    }
+
+    // Link automaticly
+    link(&build_manifest).map_err(anyhow::Error::from)?;
+
+    for output in &build_manifest.outputs {
+        info!(
+            "Linking finished successfully! Output is available at: {}",
+            output.path.display()
+        );
+    }
+
+    Ok(Some(build_manifest))
+}
+
+/// Looking up the statuses of the dependencies' versions gives up after this long, so that an unreachable registry does not stall the build.
+const REGISTRY_STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Returns every project sharing the lock file with the project at `root_path`, in build order.
+/// If `root_path` is the root of a workspace or of one of its members, every member of the workspace is returned. Otherwise only the project at `root_path` is returned.
+/// The statuses of the dependencies' versions are looked up at the registries of the projects (see [`fetch_registry_status`]), if the projects resolve versions which have not been locked yet or `always_fetch_status` is set.
+/// Projects whose dependencies are all locked do not contact the registry, so they can be built offline.
+async fn workspace_projects(
+    root_path: &Path,
+    always_fetch_status: bool,
+) -> anyhow::Result<Vec<CompilerState>>
+{
+    let mut projects = read_workspace_projects(root_path)?;
+
+    let lock_file = match projects.first() {
+        Some(project) => LockFile::read(&lock_file_path(project.lock_root()))?,
+        None => None,
+    };
+
+    for project in &mut projects {
+        if always_fetch_status || resolves_new_versions(project, lock_file.as_ref()) {
+            fetch_registry_status(project).await;
+        }
+    }
+
+    Ok(projects)
+}
+
+/// Returns whether the project selects a version of a dependency which is not recorded in the lock file.
+/// Locked versions are kept even if they have been yanked, so the statuses at the registry are only needed to select new versions.
+fn resolves_new_versions(project: &CompilerState, lock_file: Option<&LockFile>) -> bool
+{
+    let Ok(resolved_dependencies) = resolve_dependencies(
+        &project.root_dir,
+        &project.config,
+        &project.dependency_features,
+        lock_file,
+        &RegistryStatus::default(),
+    )
+    else {
+        // The statuses can only exclude versions, they cannot fix the resolution
+        return false;
+    };
+
+    resolved_dependencies
+        .values()
+        .filter(|dependency| !dependency.is_path_dependency)
+        .any(|dependency| {
+            lock_file
+                .and_then(|lock_file| lock_file.get(&dependency.name))
+                .is_none_or(|locked_dependency| {
+                    locked_dependency.version != dependency.version.to_string()
+                })
+        })
+}
+
+fn read_workspace_projects(root_path: &Path) -> anyhow::Result<Vec<CompilerState>>
+{
+    let canonical_root = fs::canonicalize(root_path).unwrap_or_else(|_| root_path.to_path_buf());
+
+    if let Some(workspace_root) = find_workspace_root(&canonical_root) {
+        let workspace = Workspace::read(&workspace_root)?;
+        let members = workspace.members()?;
+
+        // Projects nested in the workspace which are not its members, such as its dependencies, are built on their own
+        if workspace_root == canonical_root
+            || members
+                .iter()
+                .any(|member| is_same_dir(&member.root_dir, root_path))
+        {
+            return members
+                .into_iter()
+                .map(|member| CompilerState::new_workspace_member(&workspace, member))
+                .collect();
+        }
+    }
+
+    Ok(vec![CompilerState::new(
+        root_path.to_path_buf(),
+        &FeatureSelection::default(),
+    )?])
+}
+
+/// Looks up the statuses of the published versions of the project's dependencies at the registry set in its config file.
+/// The dependencies required by the project and the dependencies stored in its dependencies folder are looked up.
+/// If a dependency cannot be looked up, its versions are treated as neither yanked nor deprecated.
+async fn fetch_registry_status(project: &mut CompilerState)
+{
+    let Some(registry) = project.config.registry.clone()
+    else {
+        return;
+    };
+
+    let mut names = project
+        .config
+        .dependencies
+        .iter()
+        .filter(|(_name, dependency)| dependency.path.is_none() && dependency.remote.is_none())
+        .map(|(name, _dependency)| name.clone())
+        .collect::<BTreeSet<_>>();
+
+    // The dependencies of the dependencies are stored as `<name>(<version>)` too
+    if let Ok(dir_entries) = fs::read_dir(deps_dir(&project.root_dir)) {
+        names.extend(dir_entries.filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().to_string_lossy().to_string();
+
+            Some(file_name.rsplit_once('(')?.0.to_string())
+        }));
+    }
+
+    let http_client = match reqwest::Client::builder()
+        .timeout(REGISTRY_STATUS_TIMEOUT)
+        .build()
+    {
+        Ok(http_client) => http_client,
+        Err(error) => {
+            warn!("The versions could not be looked up at `{registry}`: {error}");
+
+            return;
+        },
+    };
+
+    // The versions of every dependency are looked up at the same time, so an unreachable registry only delays the build once
+    let listings = futures::future::join_all(names.into_iter().map(|name| {
+        let listing = fetch_version_listing(&http_client, &registry, name.clone());
+
+        async move { (name, listing.await) }
+    }))
+    .await;
+
+    for (name, listing) in listings {
+        match listing {
+            Ok(listing) => {
+                for published_version in listing.versions {
+                    project.registry_status.insert(
+                        &name,
+                        published_version.version,
+                        VersionStatus {
+                            yanked: published_version.yanked,
+                            deprecation: published_version.deprecation,
+                        },
+                    );
+                }
+            },
+            Err(error) => {
+                warn!("The versions of `{name}` could not be looked up at `{registry}`: {error}");
+            },
+        }
+    }
+}
+
+/// Lists the published versions of the package at the registry.
+async fn fetch_version_listing(
+    http_client: &reqwest::Client,
+    registry: &str,
+    package_name: String,
+) -> anyhow::Result<VersionListing>
+{
+    let response = http_client
+        .get(format!("{registry}{}", api::PACKAGE_VERSIONS))
+        .query(&VersionListRequest { package_name })
+        .send()
+        .await?;
+
+    let response_code = response.status();
+    let reply = response.text().await?;
+
+    if response_code != StatusCode::OK {
+        return Err(
+            ApplicationError::RegistryRequestFailed(response_code.to_string(), reply).into(),
+        );
+    }
+
+    Ok(serde_json::from_str::<VersionListing>(&reply)?)
+}
+
+/// Sends a request changing the status of a published version to the dependency manager at `url`.
+async fn update_version_status<T: common::serde::Serialize>(
+    url: &str,
+    endpoint: &str,
+    token: &str,
+    request: &T,
+) -> anyhow::Result<PublishedVersion>
+{
+    send_registry_request(
+        reqwest::Client::new()
+            .post(format!("{url}{endpoint}"))
+            .header("Content-Type", "application/json")
+            .bearer_auth(token)
+            .body(serde_json::to_string(request)?),
+    )
+    .await
+}
+
+/// Sends a request to the dependency manager and parses its JSON reply, the error contains the reply if the request has failed.
+async fn send_registry_request<T: common::serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+) -> anyhow::Result<T>
+{
+    let response = request.send().await?;
+
+    let response_code = response.status();
+    let reply = response.text().await?;
+
+    if response_code != StatusCode::OK {
+        return Err(
+            ApplicationError::RegistryRequestFailed(response_code.to_string(), reply).into(),
+        );
+    }
+
+    Ok(serde_json::from_str::<T>(&reply)?)
+}
+
+/// Returns the token passed in, or the token stored for the registry by `fog login`.
+fn registry_token(url: &str, token: Option<String>) -> anyhow::Result<String>
+{
+    if let Some(token) = token {
+        return Ok(token);
+    }
+
+    Ok(Credentials::read(&credentials_path()?)?
+        .get(url)
+        .ok_or_else(|| ApplicationError::NotLoggedIn(url.to_string()))?
+        .token
+        .clone())
+}
+
+/// Stores the token of the registry in the user-level credentials file.
+fn store_token(url: &str, token: String) -> anyhow::Result<()>
+{
+    let credentials_path = credentials_path()?;
+    let mut credentials = Credentials::read(&credentials_path)?;
+
+    credentials.insert(url, RegistryCredentials { token });
+    credentials.write(&credentials_path)?;
+
+    Ok(())
+}
+
+/// Selects the projects a command operates on from the projects returned by [`workspace_projects`], and enables the selected features in them.
+/// `package` selects a project by its name. Otherwise the project at `root_path` is selected, or every member if `root_path` is the root of the workspace.
+fn select_projects(
+    projects: Vec<CompilerState>,
+    root_path: &Path,
+    package: Option<&str>,
+    feature_selection: &FeatureSelection,
+) -> anyhow::Result<Vec<CompilerState>>
+{
+    let mut selected_projects = select_packages(projects, root_path, package)?;
+
+    for project in &mut selected_projects {
+        project.select_features(feature_selection)?;
+    }
+
+    Ok(selected_projects)
+}
+
+fn select_packages(
+    projects: Vec<CompilerState>,
+    root_path: &Path,
+    package: Option<&str>,
+) -> anyhow::Result<Vec<CompilerState>>
+{
+    if let Some(package) = package {
+        let names = projects
+            .iter()
+            .map(|project| project.config.name.clone())
+            .collect::<Vec<_>>();
+
+        return match projects
+            .into_iter()
+            .find(|project| project.config.name == package)
+        {
+            Some(project) => Ok(vec![project]),
+            None => Err(WorkspaceError::UnknownPackage(package.to_string(), names).into()),
+        };
+    }
+
+    if projects
+        .iter()
+        .any(|project| is_same_dir(&project.root_dir, root_path))
+    {
+        return Ok(projects
+            .into_iter()
+            .filter(|project| is_same_dir(&project.root_dir, root_path))
+            .collect());
+    }
+
+    Ok(projects)
+}
+
+fn is_same_dir(lhs: &Path, rhs: &Path) -> bool
+{
+    match (fs::canonicalize(lhs), fs::canonicalize(rhs)) {
+        (Ok(lhs), Ok(rhs)) => lhs == rhs,
+        _ => lhs == rhs,
+    }
+}
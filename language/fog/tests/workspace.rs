@@ -0,0 +1,91 @@
+use common::{
+    artifact::{CONFIG_FILE_NAME, WORKSPACE_FILE_NAME, main_source_path},
+    compiler::ProjectConfig,
+    linker::BuildManifest,
+    toml,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Creates a workspace without a config file in its root, every member is an executable with an empty `main` function.
+fn create_workspace(name: &str, members: &[&str]) -> PathBuf
+{
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    fs::write(
+        root.join(WORKSPACE_FILE_NAME),
+        format!("members = {members:?}\nbuild_path = \"build\"\n"),
+    )
+    .unwrap();
+
+    for member in members {
+        let member_root = root.join(member);
+
+        fs::create_dir_all(main_source_path(&member_root).parent().unwrap()).unwrap();
+
+        fs::write(
+            member_root.join(CONFIG_FILE_NAME),
+            toml::to_string(&ProjectConfig::new_from_name(member.to_string())).unwrap(),
+        )
+        .unwrap();
+
+        fs::write(
+            main_source_path(&member_root),
+            "pub function main(): int {\n    return 0;\n}\n",
+        )
+        .unwrap();
+    }
+
+    root
+}
+
+#[test]
+fn workspace_members_write_manifests_relative_to_the_workspace()
+{
+    let root = create_workspace("fog_workspace_manifests", &["app", "math"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fog"))
+        .arg("compile")
+        .arg("--path")
+        .arg(&root)
+        .args(["--emit", "obj"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let build_dir = fs::canonicalize(&root).unwrap().join("build");
+
+    for member in ["app", "math"] {
+        let manifest_path = build_dir.join(format!("{member}.manifest"));
+
+        // The paths are written relative to the workspace root
+        assert!(
+            !fs::read_to_string(&manifest_path)
+                .unwrap()
+                .contains(&build_dir.display().to_string())
+        );
+
+        let manifest = BuildManifest::read(&manifest_path).unwrap();
+
+        manifest.verify(None).unwrap();
+
+        assert!(!manifest.build_output_paths.is_empty());
+
+        for path in &manifest.build_output_paths {
+            assert!(path.starts_with(&build_dir), "{}", path.display());
+            assert!(path.is_file(), "{}", path.display());
+        }
+    }
+}
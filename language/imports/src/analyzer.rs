@@ -1,7 +1,10 @@
 use std::rc::Rc;
 
 use common::{
-    anyhow, compiler::ProjectConfig, dashmap::DashMap, parser::function::FunctionSignature,
+    anyhow,
+    compiler::ProjectConfig,
+    dashmap::DashMap,
+    parser::{cfg::CfgTarget, function::FunctionSignature},
     ty::OrdSet,
 };
 use parser::{parser::Settings, tokenizer::tokenize};
@@ -12,11 +15,12 @@ pub fn analyze_dependency(
     config: ProjectConfig,
     module_path: Vec<String>,
     enabled_features: OrdSet<String>,
+    cfg_target: CfgTarget,
 ) -> anyhow::Result<Settings>
 {
     let tokens = tokenize(source_file_contents)?;
 
-    let mut parser = Settings::new(config, module_path, enabled_features, todo!());
+    let mut parser = Settings::new(config, module_path, enabled_features, cfg_target, todo!());
 
     // parser.parse(deps)?;
 
@@ -0,0 +1,1826 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Write},
+    path::PathBuf,
+};
+
+use common::{
+    anyhow,
+    artifact::{ObjectFormat, TripleComponents},
+    error::bindgen::BindgenError,
+    indexmap::IndexMap,
+    parser::{
+        function::{FunctionArguments, FunctionSignature},
+        variable::VARIABLE_ID_SOURCE,
+    },
+    toml_edit::{Array, DocumentMut, Item, Value},
+    ty::Type,
+};
+
+/// The keywords of Fog, names in the header matching these are renamed with a trailing `_`.
+const FOG_KEYWORDS: &[&str] = &[
+    "array",
+    "as",
+    "bool",
+    "break",
+    "cfg",
+    "cold",
+    "const",
+    "continue",
+    "deref",
+    "else",
+    "elseif",
+    "enum",
+    "exp",
+    "external",
+    "false",
+    "feature",
+    "float",
+    "floathalf",
+    "floatlong",
+    "for",
+    "function",
+    "if",
+    "import",
+    "inline",
+    "int",
+    "inthalf",
+    "intlong",
+    "loop",
+    "namespace",
+    "nofree",
+    "noinline",
+    "nounwind",
+    "optnone",
+    "priv",
+    "ptr",
+    "pub",
+    "publib",
+    "ref",
+    "return",
+    "string",
+    "struct",
+    "true",
+    "uint",
+    "uinthalf",
+    "uintlong",
+    "uintsmall",
+    "var",
+    "void",
+    "while",
+];
+
+/// Qualifiers and storage classes which do not change how a type is mapped.
+const QUALIFIERS: &[&str] = &[
+    "const",
+    "volatile",
+    "restrict",
+    "register",
+    "auto",
+    "inline",
+    "noreturn",
+    "_Noreturn",
+    "_Thread_local",
+    "_Nullable",
+    "_Nonnull",
+    "_Null_unspecified",
+    "__const",
+    "__const__",
+    "__volatile",
+    "__volatile__",
+    "__restrict",
+    "__restrict__",
+    "__inline",
+    "__inline__",
+    "__forceinline",
+    "__extension__",
+    "__thread",
+    "__cdecl",
+    "__stdcall",
+    "__fastcall",
+    "__vectorcall",
+];
+
+/// Attributes which are followed by their arguments in parentheses.
+const ATTRIBUTES: &[&str] = &[
+    "__attribute__",
+    "__attribute",
+    "__declspec",
+    "__asm__",
+    "__asm",
+    "asm",
+    "_Alignas",
+    "alignas",
+    "_Pragma",
+    "__pragma",
+];
+
+/// Type keywords which may appear in the casts of constant expressions.
+const INTEGER_KEYWORDS: &[&str] = &["char", "short", "int", "long", "signed", "unsigned"];
+
+/// The prefix of the names given to anonymous structs, unions and enums.
+const ANONYMOUS_PREFIX: &str = "anonymous_";
+
+/// The declarations of a C header, with their types mapped onto Fog types.
+#[derive(Debug, Clone, Default)]
+pub struct CHeaderBindings
+{
+    /// The functions declared in the header, these are linked from the library implementing the header.
+    pub functions: Vec<FunctionSignature>,
+    /// The structs defined in the header, with their fields in declaration order.
+    pub structs: IndexMap<String, IndexMap<String, Type>>,
+    /// The enums defined in the header, with the values of their variants.
+    pub enums: IndexMap<String, Vec<(String, i128)>>,
+    /// The integer constants defined with `#define` and by anonymous enums.
+    pub constants: IndexMap<String, i128>,
+    /// The declarations without a Fog equivalent, with the reason they have been skipped.
+    pub skipped: Vec<(String, String)>,
+}
+
+impl CHeaderBindings
+{
+    /// Parses a C header and maps the functions, structs, enums and integer constants it declares onto Fog types.
+    /// The header is read as written, `#include`s are not followed and macros are not expanded.
+    /// The size of `long` and of the pointer sized integers is decided by the target triple.
+    pub fn from_header(source: &str, target_triple: &str) -> Result<Self, BindgenError>
+    {
+        let (tokens, defines) = tokenize_header(source)?;
+
+        let mut parser = HeaderParser::new(&tokens, DataModel::from_triple(target_triple));
+
+        parser.add_defines(&defines);
+        parser.parse()?;
+
+        Ok(parser.into_bindings())
+    }
+
+    /// Creates the Fog source declaring the bindings.
+    /// The constants are declared as the variants of an enum named `<module_name>_constants`.
+    pub fn to_fog_source(&self, header_name: &str, module_name: &str) -> String
+    {
+        let mut source =
+            format!("// Generated by `fog bindgen` from `{header_name}`. Do not edit.\n");
+
+        if !self.skipped.is_empty() {
+            source.push('\n');
+        }
+
+        for (name, reason) in &self.skipped {
+            let _ = writeln!(source, "// Skipped `{name}`: {reason}.");
+        }
+
+        for (name, fields) in &self.structs {
+            let _ = writeln!(source, "\npub struct {name} {{");
+
+            for (field, ty) in fields {
+                let _ = writeln!(source, "    {field}: {},", fog_type(ty));
+            }
+
+            source.push_str("}\n");
+        }
+
+        for (name, variants) in &self.enums {
+            write_enum(
+                &mut source,
+                name,
+                variants.iter().map(|(name, value)| (name, *value)),
+            );
+        }
+
+        if !self.constants.is_empty() {
+            write_enum(
+                &mut source,
+                &format!("{module_name}_constants"),
+                self.constants.iter().map(|(name, value)| (name, *value)),
+            );
+        }
+
+        if !self.functions.is_empty() {
+            source.push('\n');
+        }
+
+        for signature in &self.functions {
+            let mut arguments = signature
+                .args
+                .arguments
+                .iter()
+                .map(|(name, (ty, _id))| format!("{name}: {}", fog_type(ty)))
+                .collect::<Vec<_>>();
+
+            if signature.args.ellipsis_present {
+                arguments.push("...".to_string());
+            }
+
+            let _ = writeln!(
+                source,
+                "external {}({}): {};",
+                signature.name,
+                arguments.join(", "),
+                fog_type(&signature.return_type)
+            );
+        }
+
+        source
+    }
+}
+
+/// Appends the libraries to `additional_linking_material` in the config file's contents, the libraries already listed are not added again.
+/// The formatting and the comments of the config file are preserved.
+pub fn add_linking_material_to_config(
+    config_file: &str,
+    libraries: &[PathBuf],
+) -> anyhow::Result<String>
+{
+    let mut document = config_file.parse::<DocumentMut>()?;
+
+    let linking_material = document
+        .entry("additional_linking_material")
+        .or_insert(Item::Value(Value::Array(Array::new())))
+        .as_array_mut()
+        .ok_or(BindgenError::InvalidLinkingMaterial)?;
+
+    for library in libraries {
+        let library = library.to_string_lossy().to_string();
+
+        if !linking_material
+            .iter()
+            .any(|listed| listed.as_str() == Some(library.as_str()))
+        {
+            linking_material.push(library);
+        }
+    }
+
+    Ok(document.to_string())
+}
+
+/// Writes an enum with the smallest integer type which can hold the values of every variant.
+fn write_enum<'a>(
+    source: &mut String,
+    name: &str,
+    variants: impl Iterator<Item = (&'a String, i128)> + Clone,
+)
+{
+    let fits = |min: i128, max: i128| {
+        variants
+            .clone()
+            .all(|(_name, value)| (min..=max).contains(&value))
+    };
+
+    let ty = if fits(i32::MIN.into(), i32::MAX.into()) {
+        Type::I32
+    }
+    else if fits(0, u32::MAX.into()) {
+        Type::U32
+    }
+    else if fits(i64::MIN.into(), i64::MAX.into()) {
+        Type::I64
+    }
+    else {
+        Type::U64
+    };
+
+    let _ = writeln!(source, "\npub enum {name}<{}> {{", fog_type(&ty));
+
+    for (variant, value) in variants {
+        let _ = writeln!(source, "    {variant} = {value},");
+    }
+
+    source.push_str("}\n");
+}
+
+/// Returns the Fog syntax of a type mapped from C.
+fn fog_type(ty: &Type) -> String
+{
+    match ty {
+        Type::I64 => "intlong".to_string(),
+        Type::F64 => "floatlong".to_string(),
+        Type::U64 => "uintlong".to_string(),
+        Type::I32 => "int".to_string(),
+        Type::F32 => "float".to_string(),
+        Type::U32 => "uint".to_string(),
+        Type::I16 => "inthalf".to_string(),
+        Type::F16 => "floathalf".to_string(),
+        Type::U16 => "uinthalf".to_string(),
+        Type::U8 => "uintsmall".to_string(),
+        Type::String => "string".to_string(),
+        Type::Boolean => "bool".to_string(),
+        Type::Void => "void".to_string(),
+        Type::Pointer(None) => "ptr".to_string(),
+        Type::Pointer(Some(inner)) => {
+            let inner = fog_type(inner);
+
+            // `>>` is tokenized as a shift, so the brackets of nested types are separated
+            if inner.ends_with('>') {
+                format!("ptr<{inner} >")
+            }
+            else {
+                format!("ptr<{inner}>")
+            }
+        },
+        Type::Array((inner, length)) => format!("array<{}, {length}>", fog_type(inner)),
+        Type::Struct((name, _, _)) | Type::Unresolved(name) => name.clone(),
+        Type::Enum(_) | Type::Trait { .. } | Type::TraitObject(_) => {
+            unreachable!("C types are never mapped onto `{ty}`.")
+        },
+    }
+}
+
+/// Returns the name used in Fog for a name of the header.
+fn fog_name(name: &str) -> String
+{
+    if FOG_KEYWORDS.contains(&name) {
+        format!("{name}_")
+    }
+    else {
+        name.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CToken
+{
+    Identifier(String),
+    Number(String),
+    /// String and character literals, their contents are never needed.
+    Literal,
+    Ellipsis,
+    Punct(char),
+}
+
+impl Display for CToken
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self {
+            CToken::Identifier(text) | CToken::Number(text) => f.write_str(text),
+            CToken::Literal => f.write_str("literal"),
+            CToken::Ellipsis => f.write_str("..."),
+            CToken::Punct(punct) => f.write_char(*punct),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeaderToken
+{
+    token: CToken,
+    line: usize,
+}
+
+/// A `#define` directive of the header.
+#[derive(Debug, Clone)]
+struct Define
+{
+    name: String,
+    /// The tokens the macro expands to, this is `None` for function-like macros.
+    body: Option<Vec<HeaderToken>>,
+}
+
+struct Lexer
+{
+    chars: Vec<char>,
+    idx: usize,
+    line: usize,
+}
+
+impl Lexer
+{
+    fn peek(&self, offset: usize) -> Option<char>
+    {
+        self.chars.get(self.idx + offset).copied()
+    }
+
+    /// Skips whitespace and comments.
+    /// Inside a directive the end of the line ends the directive, in which case `true` is returned.
+    fn skip_trivia(&mut self, in_directive: bool) -> Result<bool, BindgenError>
+    {
+        loop {
+            match (self.peek(0), self.peek(1)) {
+                (None, _) => return Ok(in_directive),
+                // Lines ending with a backslash are continued on the next line
+                (Some('\\'), Some('\n')) => {
+                    self.idx += 2;
+                    self.line += 1;
+                },
+                (Some('\\'), Some('\r')) if self.peek(2) == Some('\n') => {
+                    self.idx += 3;
+                    self.line += 1;
+                },
+                (Some('\n'), _) => {
+                    self.idx += 1;
+                    self.line += 1;
+
+                    if in_directive {
+                        return Ok(true);
+                    }
+                },
+                (Some('/'), Some('/')) => {
+                    while self.peek(0).is_some_and(|char| char != '\n') {
+                        self.idx += 1;
+                    }
+                },
+                (Some('/'), Some('*')) => {
+                    let start_line = self.line;
+
+                    self.idx += 2;
+
+                    loop {
+                        match (self.peek(0), self.peek(1)) {
+                            (None, _) => return Err(BindgenError::UnterminatedComment(start_line)),
+                            (Some('*'), Some('/')) => {
+                                self.idx += 2;
+
+                                break;
+                            },
+                            (Some(char), _) => {
+                                if char == '\n' {
+                                    self.line += 1;
+                                }
+
+                                self.idx += 1;
+                            },
+                        }
+                    }
+                },
+                (Some(char), _) if char.is_whitespace() => self.idx += 1,
+                _ => return Ok(false),
+            }
+        }
+    }
+
+    /// Reads the next token, the cursor must not be at the end of the source.
+    fn next_token(&mut self) -> Result<CToken, BindgenError>
+    {
+        // It is safe to unwrap here, since the trivia skipped before did not reach the end of the source
+        let first = self.peek(0).unwrap();
+        let start = self.idx;
+
+        if first.is_ascii_alphabetic() || first == '_' {
+            while self
+                .peek(0)
+                .is_some_and(|char| char.is_ascii_alphanumeric() || char == '_')
+            {
+                self.idx += 1;
+            }
+
+            return Ok(CToken::Identifier(
+                self.chars[start..self.idx].iter().collect(),
+            ));
+        }
+
+        if first.is_ascii_digit()
+            || (first == '.' && self.peek(1).is_some_and(|char| char.is_ascii_digit()))
+        {
+            while let Some(char) = self.peek(0) {
+                // The sign of an exponent is a part of the number, ie. `1e-5`
+                let exponent_sign = matches!(char, '+' | '-')
+                    && matches!(self.chars[self.idx - 1], 'e' | 'E' | 'p' | 'P');
+
+                if char.is_ascii_alphanumeric() || char == '.' || char == '_' || exponent_sign {
+                    self.idx += 1;
+                }
+                else {
+                    break;
+                }
+            }
+
+            return Ok(CToken::Number(self.chars[start..self.idx].iter().collect()));
+        }
+
+        if first == '"' || first == '\'' {
+            let start_line = self.line;
+
+            self.idx += 1;
+
+            loop {
+                match self.peek(0) {
+                    None | Some('\n') => return Err(BindgenError::UnterminatedLiteral(start_line)),
+                    Some('\\') => self.idx += 2,
+                    Some(char) if char == first => {
+                        self.idx += 1;
+
+                        break;
+                    },
+                    Some(_) => self.idx += 1,
+                }
+            }
+
+            return Ok(CToken::Literal);
+        }
+
+        if first == '.' && self.peek(1) == Some('.') && self.peek(2) == Some('.') {
+            self.idx += 3;
+
+            return Ok(CToken::Ellipsis);
+        }
+
+        self.idx += 1;
+
+        Ok(CToken::Punct(first))
+    }
+}
+
+/// Splits the header into tokens and collects its `#define` directives.
+/// The lines disabled with `#if 0` are skipped, every other conditional is treated as enabled.
+fn tokenize_header(source: &str) -> Result<(Vec<HeaderToken>, Vec<Define>), BindgenError>
+{
+    let mut lexer = Lexer {
+        chars: source.chars().collect(),
+        idx: 0,
+        line: 1,
+    };
+
+    let mut tokens = Vec::new();
+    let mut defines = Vec::new();
+
+    // The depth of the conditionals inside an `#if 0` block, the lines are enabled if this is zero
+    let mut disabled_depth = 0;
+    let mut last_token_line = 0;
+
+    loop {
+        lexer.skip_trivia(false)?;
+
+        let Some(first) = lexer.peek(0)
+        else {
+            break;
+        };
+
+        // Directives start at the beginning of a line
+        if first == '#' && last_token_line != lexer.line {
+            lexer.idx += 1;
+
+            let mut directive = Vec::new();
+            let mut function_like = false;
+
+            while !lexer.skip_trivia(true)? {
+                let line = lexer.line;
+
+                directive.push(HeaderToken {
+                    token: lexer.next_token()?,
+                    line,
+                });
+
+                // A "(" right after the name of a macro makes the macro function-like
+                if directive.len() == 2 {
+                    function_like = lexer.peek(0) == Some('(');
+                }
+            }
+
+            let Some(HeaderToken {
+                token: CToken::Identifier(keyword),
+                ..
+            }) = directive.first()
+            else {
+                continue;
+            };
+
+            if disabled_depth > 0 {
+                match keyword.as_str() {
+                    "if" | "ifdef" | "ifndef" => disabled_depth += 1,
+                    "endif" => disabled_depth -= 1,
+                    "else" | "elif" if disabled_depth == 1 => disabled_depth = 0,
+                    _ => (),
+                }
+
+                continue;
+            }
+
+            match (keyword.as_str(), directive.get(1).map(|token| &token.token)) {
+                ("if", Some(CToken::Number(number))) if number == "0" && directive.len() == 2 => {
+                    disabled_depth = 1;
+                },
+                ("define", Some(CToken::Identifier(name))) => {
+                    defines.push(Define {
+                        name: name.clone(),
+                        body: (!function_like).then(|| directive[2..].to_vec()),
+                    });
+                },
+                _ => (),
+            }
+
+            continue;
+        }
+
+        let line = lexer.line;
+        let token = lexer.next_token()?;
+
+        last_token_line = line;
+
+        if disabled_depth == 0 {
+            tokens.push(HeaderToken { token, line });
+        }
+    }
+
+    Ok((tokens, defines))
+}
+
+/// The sizes of the C types which depend on the target.
+#[derive(Debug, Clone, Copy)]
+struct DataModel
+{
+    long_bits: u8,
+    pointer_bits: u8,
+    is_windows: bool,
+}
+
+impl DataModel
+{
+    fn from_triple(target_triple: &str) -> Self
+    {
+        let triple = TripleComponents::parse(target_triple);
+
+        let pointer_bits = triple.pointer_width() as u8;
+
+        // Windows keeps `long` 32 bits wide on every architecture
+        let is_windows = triple.object_format() == ObjectFormat::Coff;
+
+        Self {
+            long_bits: if is_windows { 32 } else { pointer_bits },
+            pointer_bits,
+            is_windows,
+        }
+    }
+
+    /// The typedefs of the standard headers, which are not declared in the parsed header.
+    fn standard_typedefs(&self) -> HashMap<String, CType>
+    {
+        let integer = |bits, signed| CType::Integer { bits, signed };
+
+        [
+            ("int8_t", integer(8, true)),
+            ("uint8_t", integer(8, false)),
+            ("int16_t", integer(16, true)),
+            ("uint16_t", integer(16, false)),
+            ("int32_t", integer(32, true)),
+            ("uint32_t", integer(32, false)),
+            ("int64_t", integer(64, true)),
+            ("uint64_t", integer(64, false)),
+            ("intmax_t", integer(64, true)),
+            ("uintmax_t", integer(64, false)),
+            ("intptr_t", integer(self.pointer_bits, true)),
+            ("uintptr_t", integer(self.pointer_bits, false)),
+            ("ptrdiff_t", integer(self.pointer_bits, true)),
+            ("ssize_t", integer(self.pointer_bits, true)),
+            ("size_t", integer(self.pointer_bits, false)),
+            (
+                "wchar_t",
+                if self.is_windows {
+                    integer(16, false)
+                }
+                else {
+                    integer(32, true)
+                },
+            ),
+            ("bool", CType::Bool),
+        ]
+        .into_iter()
+        .map(|(name, ty)| (name.to_string(), ty))
+        .collect()
+    }
+}
+
+/// A C type, before it is mapped onto a Fog type.
+#[derive(Debug, Clone)]
+enum CType
+{
+    Void,
+    Bool,
+    /// A `char` without `signed` or `unsigned`, pointers to it are mapped onto strings.
+    Char,
+    Integer
+    {
+        bits: u8,
+        signed: bool,
+    },
+    Float,
+    Double,
+    Struct(String),
+    Union(String),
+    Enum(String),
+    Pointer(Box<CType>),
+    Array(Box<CType>, usize),
+    Function,
+    /// A type name which is not declared in the header.
+    Unknown(String),
+    /// A type without a Fog equivalent, with the reason.
+    Unsupported(String),
+    /// Stands for the type surrounding a nested declarator, ie. the return type of a function pointer.
+    Placeholder,
+}
+
+impl CType
+{
+    fn replace_placeholder(self, ty: CType) -> CType
+    {
+        match self {
+            CType::Placeholder => ty,
+            CType::Pointer(inner) => CType::Pointer(Box::new(inner.replace_placeholder(ty))),
+            CType::Array(inner, length) => {
+                CType::Array(Box::new(inner.replace_placeholder(ty)), length)
+            },
+            other => other,
+        }
+    }
+}
+
+/// The specifiers preceding the declarators of a declaration.
+struct Specifiers
+{
+    ty: CType,
+    is_typedef: bool,
+    is_static: bool,
+}
+
+struct Declarator
+{
+    name: Option<String>,
+    ty: CType,
+    /// Set if the declarator declares a function, the type of the declarator is [`CType::Function`] then.
+    function: Option<CFunction>,
+}
+
+/// The parameters of a function, unnamed parameters have no name.
+type Parameters = Vec<(Option<String>, CType)>;
+
+struct CFunction
+{
+    parameters: Parameters,
+    variadic: bool,
+    return_type: CType,
+}
+
+struct HeaderParser<'a>
+{
+    tokens: &'a [HeaderToken],
+    idx: usize,
+    data_model: DataModel,
+
+    typedefs: HashMap<String, CType>,
+    /// The values of the integer constants and enum variants, these can be used in constant expressions.
+    constant_values: HashMap<String, i128>,
+    /// Object-like macros which are not constants, these usually expand to attributes and are skipped.
+    ignored_macros: HashSet<String>,
+    /// Function-like macros, these are skipped with their arguments.
+    function_macros: HashSet<String>,
+    anonymous_count: usize,
+
+    functions: IndexMap<String, CFunction>,
+    /// The fields are `None` if the struct is only declared, these structs can only be used through pointers.
+    structs: IndexMap<String, Option<Vec<(String, CType)>>>,
+    enums: IndexMap<String, Vec<(String, i128)>>,
+    constants: IndexMap<String, i128>,
+    skipped: Vec<(String, String)>,
+}
+
+impl<'a> HeaderParser<'a>
+{
+    fn new(tokens: &'a [HeaderToken], data_model: DataModel) -> Self
+    {
+        Self {
+            tokens,
+            idx: 0,
+            data_model,
+            typedefs: data_model.standard_typedefs(),
+            constant_values: HashMap::new(),
+            ignored_macros: HashSet::new(),
+            function_macros: HashSet::new(),
+            anonymous_count: 0,
+            functions: IndexMap::new(),
+            structs: IndexMap::new(),
+            enums: IndexMap::new(),
+            constants: IndexMap::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Stores the integer constants of the `#define`s, every other macro is skipped when used.
+    fn add_defines(&mut self, defines: &[Define])
+    {
+        for define in defines {
+            match &define.body {
+                Some(body) => {
+                    if let Some(value) = evaluate(body, &self.constant_values) {
+                        self.constant_values.insert(define.name.clone(), value);
+                        self.constants.insert(define.name.clone(), value);
+                    }
+                    else {
+                        self.ignored_macros.insert(define.name.clone());
+                    }
+                },
+                None => {
+                    self.function_macros.insert(define.name.clone());
+                },
+            }
+        }
+    }
+
+    fn parse(&mut self) -> Result<(), BindgenError>
+    {
+        while let Some(token) = self.peek() {
+            match token {
+                // Empty declarations and the end of `extern "C" { ... }` blocks
+                CToken::Punct(';' | '}') => self.idx += 1,
+                CToken::Identifier(name)
+                    if name == "extern" && self.peek_nth(1) == Some(&CToken::Literal) =>
+                {
+                    self.idx += 2;
+
+                    self.eat_punct('{');
+                },
+                _ => self.parse_declaration()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_declaration(&mut self) -> Result<(), BindgenError>
+    {
+        let Some(specifiers) = self.parse_specifiers()?
+        else {
+            return Err(self.unexpected());
+        };
+
+        loop {
+            // A declaration without declarators only defines the struct or enum, ie. `struct point { int x; int y; };`
+            if self.eat_punct(';') {
+                return Ok(());
+            }
+
+            let Declarator { name, ty, function } = self.parse_declarator(specifiers.ty.clone())?;
+
+            // Attributes and macros may follow the declarator, ie. `int foo(void) __attribute__((pure));`
+            self.skip_until(&[',', ';', '{', '='])?;
+
+            match (name, function) {
+                (Some(name), _) if specifiers.is_typedef => self.add_typedef(name, ty),
+                (Some(name), Some(function)) => {
+                    // Functions defined in the header are compiled into the callers, they are not exported from the library
+                    if self.is_punct('{') {
+                        self.skip_group()?;
+
+                        self.skipped.push((
+                            name,
+                            "functions defined in the header are not exported from the library"
+                                .to_string(),
+                        ));
+
+                        return Ok(());
+                    }
+
+                    if specifiers.is_static {
+                        self.skipped.push((
+                            name,
+                            "static functions are not exported from the library".to_string(),
+                        ));
+                    }
+                    else {
+                        self.functions.entry(name).or_insert(function);
+                    }
+                },
+                (Some(name), None) => {
+                    self.skipped
+                        .push((name, "global variables are not supported".to_string()));
+                },
+                (None, _) => (),
+            }
+
+            // The initializers of variables are not needed
+            if self.eat_punct('=') {
+                self.skip_until(&[',', ';'])?;
+            }
+
+            if !self.eat_punct(',') {
+                self.expect_punct(';')?;
+
+                return Ok(());
+            }
+        }
+    }
+
+    /// Parses the specifiers of a declaration, returns `None` if the declaration does not name a type.
+    fn parse_specifiers(&mut self) -> Result<Option<Specifiers>, BindgenError>
+    {
+        let mut is_typedef = false;
+        let mut is_static = false;
+
+        let mut keywords: Vec<&str> = Vec::new();
+        let mut ty = None;
+
+        loop {
+            self.skip_ignored()?;
+
+            let Some(name) = self.peek_identifier()
+            else {
+                break;
+            };
+
+            match name {
+                "typedef" => is_typedef = true,
+                "static" => is_static = true,
+                "extern" => (),
+                "void" | "char" | "short" | "int" | "long" | "float" | "double" | "unsigned"
+                | "_Bool" | "_Complex" | "__int128" => keywords.push(name),
+                "signed" | "__signed" | "__signed__" => keywords.push("signed"),
+                "struct" | "union" | "enum" if ty.is_none() && keywords.is_empty() => {
+                    self.idx += 1;
+
+                    ty = Some(self.parse_tagged_type(name)?);
+
+                    continue;
+                },
+                // The first identifier is the name of the type, the next one is the name of the declarator
+                _ if ty.is_none() && keywords.is_empty() => {
+                    ty = Some(
+                        self.typedefs
+                            .get(name)
+                            .cloned()
+                            .unwrap_or_else(|| CType::Unknown(name.to_string())),
+                    );
+                },
+                _ => break,
+            }
+
+            self.idx += 1;
+        }
+
+        let ty = match ty {
+            Some(ty) => ty,
+            None if keywords.is_empty() => return Ok(None),
+            None => self.keyword_type(&keywords),
+        };
+
+        Ok(Some(Specifiers {
+            ty,
+            is_typedef,
+            is_static,
+        }))
+    }
+
+    /// Returns the type named by the type keywords, ie. `unsigned long long`.
+    fn keyword_type(&self, keywords: &[&str]) -> CType
+    {
+        let count = |keyword: &str| keywords.iter().filter(|name| **name == keyword).count();
+
+        let signed = count("unsigned") == 0;
+
+        if count("_Complex") > 0 {
+            CType::Unsupported("complex numbers have no Fog equivalent".to_string())
+        }
+        else if count("__int128") > 0 {
+            CType::Unsupported("128 bit integers have no Fog equivalent".to_string())
+        }
+        else if count("void") > 0 {
+            CType::Void
+        }
+        else if count("_Bool") > 0 {
+            CType::Bool
+        }
+        else if count("float") > 0 {
+            CType::Float
+        }
+        else if count("double") > 0 && count("long") > 0 {
+            CType::Unsupported("`long double` has no Fog equivalent".to_string())
+        }
+        else if count("double") > 0 {
+            CType::Double
+        }
+        else if count("char") > 0 && count("unsigned") + count("signed") == 0 {
+            CType::Char
+        }
+        else if count("char") > 0 {
+            CType::Integer { bits: 8, signed }
+        }
+        else if count("short") > 0 {
+            CType::Integer { bits: 16, signed }
+        }
+        else if count("long") == 1 {
+            CType::Integer {
+                bits: self.data_model.long_bits,
+                signed,
+            }
+        }
+        else if count("long") > 1 {
+            CType::Integer { bits: 64, signed }
+        }
+        else {
+            CType::Integer { bits: 32, signed }
+        }
+    }
+
+    /// Parses a struct, union or enum type, the cursor must be after the keyword.
+    /// The definitions of the structs and enums are stored.
+    fn parse_tagged_type(&mut self, kind: &str) -> Result<CType, BindgenError>
+    {
+        self.skip_ignored()?;
+
+        let tag = self.peek_identifier().map(str::to_string);
+
+        if tag.is_some() {
+            self.idx += 1;
+        }
+
+        self.skip_ignored()?;
+
+        let name = tag.unwrap_or_else(|| {
+            self.anonymous_count += 1;
+
+            format!("{ANONYMOUS_PREFIX}{kind}_{}", self.anonymous_count)
+        });
+
+        match kind {
+            "enum" => {
+                if self.is_punct('{') {
+                    self.parse_enum_body(&name)?;
+                }
+
+                Ok(CType::Enum(name))
+            },
+            "union" => {
+                if self.is_punct('{') {
+                    self.skip_group()?;
+                }
+
+                Ok(CType::Union(name))
+            },
+            _ => {
+                if self.is_punct('{') {
+                    let fields = self.parse_struct_body()?;
+
+                    self.structs.insert(name.clone(), Some(fields));
+                }
+                else if !self.structs.contains_key(&name) {
+                    self.structs.insert(name.clone(), None);
+                }
+
+                Ok(CType::Struct(name))
+            },
+        }
+    }
+
+    /// Parses the fields of a struct, the cursor must be at the opening "{".
+    /// Fields without a Fog equivalent are stored with an unsupported type, so the struct is skipped when mapped.
+    fn parse_struct_body(&mut self) -> Result<Vec<(String, CType)>, BindgenError>
+    {
+        self.expect_punct('{')?;
+
+        let mut fields = Vec::new();
+
+        while !self.eat_punct('}') {
+            let Some(specifiers) = self.parse_specifiers()?
+            else {
+                return Err(self.unexpected());
+            };
+
+            // Anonymous struct and union members
+            if self.eat_punct(';') {
+                fields.push((
+                    String::new(),
+                    CType::Unsupported("anonymous members are not supported".to_string()),
+                ));
+
+                continue;
+            }
+
+            loop {
+                let declarator = self.parse_declarator(specifiers.ty.clone())?;
+                let name = declarator.name.unwrap_or_default();
+
+                if self.eat_punct(':') {
+                    self.skip_until(&[',', ';'])?;
+
+                    fields.push((
+                        name,
+                        CType::Unsupported("bit fields are not supported".to_string()),
+                    ));
+                }
+                else if declarator.function.is_some() {
+                    fields.push((
+                        name,
+                        CType::Unsupported("functions cannot be fields".to_string()),
+                    ));
+                }
+                else {
+                    fields.push((name, declarator.ty));
+                }
+
+                self.skip_ignored()?;
+
+                if !self.eat_punct(',') {
+                    self.expect_punct(';')?;
+
+                    break;
+                }
+            }
+        }
+
+        Ok(fields)
+    }
+
+    /// Parses the variants of an enum, the cursor must be at the opening "{".
+    /// Variants without a value take the value of the previous variant plus one.
+    fn parse_enum_body(&mut self, name: &str) -> Result<(), BindgenError>
+    {
+        self.expect_punct('{')?;
+
+        let mut variants = Vec::new();
+        let mut value = 0;
+
+        while !self.eat_punct('}') {
+            let Some(variant) = self.peek_identifier()
+            else {
+                return Err(self.unexpected());
+            };
+
+            self.idx += 1;
+
+            self.skip_ignored()?;
+
+            if self.eat_punct('=') {
+                let start = self.idx;
+
+                self.skip_until(&[',', '}'])?;
+
+                let Some(evaluated) =
+                    evaluate(&self.tokens[start..self.idx], &self.constant_values)
+                else {
+                    self.skipped.push((
+                        name.to_string(),
+                        format!("the value of `{variant}` is not an integer constant"),
+                    ));
+
+                    self.skip_until(&['}'])?;
+                    self.idx += 1;
+
+                    return Ok(());
+                };
+
+                value = evaluated;
+            }
+
+            self.constant_values.insert(variant.to_string(), value);
+            variants.push((variant.to_string(), value));
+
+            value += 1;
+
+            if !self.eat_punct(',') {
+                self.expect_punct('}')?;
+
+                break;
+            }
+        }
+
+        self.enums.insert(name.to_string(), variants);
+
+        Ok(())
+    }
+
+    /// Parses a declarator, which names the declared item and applies pointers, arrays and parameters to the type of the specifiers.
+    fn parse_declarator(&mut self, base: CType) -> Result<Declarator, BindgenError>
+    {
+        let mut ty = base;
+
+        loop {
+            self.skip_ignored()?;
+
+            if !self.eat_punct('*') {
+                break;
+            }
+
+            ty = CType::Pointer(Box::new(ty));
+        }
+
+        // Nested declarators declare pointers to functions and arrays, ie. `void (*callback)(int)`
+        if self.is_punct('(') && matches!(self.peek_nth(1), Some(CToken::Punct('*' | '^'))) {
+            self.idx += 1;
+
+            let inner = self.parse_declarator(CType::Placeholder)?;
+
+            self.expect_punct(')')?;
+
+            let outer = self.parse_suffixes(ty)?;
+
+            return Ok(Declarator {
+                name: inner.name,
+                ty: inner.ty.replace_placeholder(outer),
+                function: None,
+            });
+        }
+
+        let name = self.peek_identifier().map(str::to_string);
+
+        if name.is_some() {
+            self.idx += 1;
+        }
+
+        self.skip_ignored()?;
+
+        if self.is_punct('(') {
+            let (parameters, variadic) = self.parse_parameters()?;
+
+            return Ok(Declarator {
+                name,
+                ty: CType::Function,
+                function: Some(CFunction {
+                    parameters,
+                    variadic,
+                    return_type: ty,
+                }),
+            });
+        }
+
+        Ok(Declarator {
+            name,
+            ty: self.parse_suffixes(ty)?,
+            function: None,
+        })
+    }
+
+    /// Parses the array and parameter suffixes of a declarator.
+    fn parse_suffixes(&mut self, ty: CType) -> Result<CType, BindgenError>
+    {
+        if self.is_punct('(') {
+            self.parse_parameters()?;
+
+            return Ok(CType::Function);
+        }
+
+        let mut lengths = Vec::new();
+
+        while self.eat_punct('[') {
+            let start = self.idx;
+
+            self.skip_until(&[']'])?;
+
+            // Arrays without a length are flexible array members or parameters, which are passed as pointers
+            let length = if start == self.idx {
+                Some(0)
+            }
+            else {
+                evaluate(&self.tokens[start..self.idx], &self.constant_values)
+            };
+
+            self.idx += 1;
+
+            lengths.push(length);
+        }
+
+        // `int matrix[2][3]` is an array of two arrays of three integers
+        Ok(lengths.into_iter().rev().fold(ty, |ty, length| {
+            match length.and_then(|length| usize::try_from(length).ok()) {
+                Some(length) => CType::Array(Box::new(ty), length),
+                None => {
+                    CType::Unsupported("arrays must have an integer constant length".to_string())
+                },
+            }
+        }))
+    }
+
+    /// Parses the parameters of a function, the cursor must be at the opening "(".
+    /// Returns the parameters and whether the function is variadic.
+    fn parse_parameters(&mut self) -> Result<(Parameters, bool), BindgenError>
+    {
+        self.expect_punct('(')?;
+
+        let mut parameters = Vec::new();
+
+        if self.eat_punct(')') {
+            return Ok((parameters, false));
+        }
+
+        loop {
+            self.skip_ignored()?;
+
+            if self.peek() == Some(&CToken::Ellipsis) {
+                self.idx += 1;
+
+                self.expect_punct(')')?;
+
+                return Ok((parameters, true));
+            }
+
+            let Some(specifiers) = self.parse_specifiers()?
+            else {
+                return Err(self.unexpected());
+            };
+
+            // `(void)` declares a function without parameters
+            if parameters.is_empty() && matches!(specifiers.ty, CType::Void) && self.is_punct(')') {
+                self.idx += 1;
+
+                return Ok((parameters, false));
+            }
+
+            let Declarator { name, ty, function } = self.parse_declarator(specifiers.ty)?;
+
+            // Arrays and functions are passed as pointers
+            let ty = match ty {
+                _ if function.is_some() => CType::Pointer(Box::new(CType::Function)),
+                CType::Array(inner, _) => CType::Pointer(inner),
+                ty => ty,
+            };
+
+            parameters.push((name, ty));
+
+            self.skip_ignored()?;
+
+            if !self.eat_punct(',') {
+                self.expect_punct(')')?;
+
+                return Ok((parameters, false));
+            }
+        }
+    }
+
+    fn add_typedef(&mut self, name: String, ty: CType)
+    {
+        // Anonymous structs and enums are named after their typedef, ie. `typedef struct { int x; } point;`
+        let ty = match ty {
+            CType::Struct(anonymous)
+                if anonymous.starts_with(ANONYMOUS_PREFIX) && !self.structs.contains_key(&name) =>
+            {
+                if let Some(fields) = self.structs.shift_remove(&anonymous) {
+                    self.structs.insert(name.clone(), fields);
+                }
+
+                CType::Struct(name.clone())
+            },
+            CType::Enum(anonymous)
+                if anonymous.starts_with(ANONYMOUS_PREFIX) && !self.enums.contains_key(&name) =>
+            {
+                if let Some(variants) = self.enums.shift_remove(&anonymous) {
+                    self.enums.insert(name.clone(), variants);
+                }
+
+                CType::Enum(name.clone())
+            },
+            ty => ty,
+        };
+
+        self.typedefs.insert(name, ty);
+    }
+
+    /// Maps the parsed declarations onto Fog types.
+    fn into_bindings(self) -> CHeaderBindings
+    {
+        let mut bindings = CHeaderBindings {
+            skipped: self.skipped,
+            ..Default::default()
+        };
+
+        // The structs which have a Fog equivalent, a struct is removed if any of its fields cannot be mapped.
+        // This is repeated until no more structs are removed, since removing a struct affects the structs containing it.
+        let mut mapped_structs = self
+            .structs
+            .iter()
+            .filter(|(_name, fields)| fields.is_some())
+            .map(|(name, _fields)| name.clone())
+            .collect::<HashSet<_>>();
+
+        loop {
+            let unmapped = mapped_structs
+                .iter()
+                .find(|name| {
+                    // The structs in `mapped_structs` have fields
+                    self.structs[*name]
+                        .iter()
+                        .flatten()
+                        .any(|(_field, ty)| map_type(ty, &mapped_structs).is_err())
+                })
+                .cloned();
+
+            match unmapped {
+                Some(name) => mapped_structs.remove(&name),
+                None => break,
+            };
+        }
+
+        for (name, fields) in &self.structs {
+            let Some(fields) = fields
+            else {
+                continue;
+            };
+
+            let mapped_fields = fields
+                .iter()
+                .map(|(field, ty)| {
+                    map_type(ty, &mapped_structs)
+                        .map(|ty| (fog_name(field), ty))
+                        .map_err(|reason| format!("field `{field}`: {reason}"))
+                })
+                .collect::<Result<IndexMap<_, _>, _>>();
+
+            match mapped_fields {
+                Ok(fields) => {
+                    bindings.structs.insert(fog_name(name), fields);
+                },
+                Err(reason) => bindings.skipped.push((name.clone(), reason)),
+            }
+        }
+
+        bindings.constants = self
+            .constants
+            .into_iter()
+            .map(|(name, value)| (fog_name(&name), value))
+            .collect();
+
+        for (name, variants) in self.enums {
+            let variants = variants
+                .into_iter()
+                .map(|(variant, value)| (fog_name(&variant), value));
+
+            // The variants of anonymous enums are used as constants
+            if name.starts_with(ANONYMOUS_PREFIX) {
+                bindings.constants.extend(variants);
+            }
+            else {
+                bindings.enums.insert(fog_name(&name), variants.collect());
+            }
+        }
+
+        for (name, function) in self.functions {
+            // The name of an external function is the symbol it is linked by, it cannot be renamed
+            if FOG_KEYWORDS.contains(&name.as_str()) {
+                bindings.skipped.push((
+                    name,
+                    "the name of the function is a Fog keyword".to_string(),
+                ));
+
+                continue;
+            }
+
+            match map_function(name.clone(), &function, &mapped_structs) {
+                Ok(signature) => bindings.functions.push(signature),
+                Err(reason) => bindings.skipped.push((name, reason)),
+            }
+        }
+
+        bindings
+    }
+
+    fn peek(&self) -> Option<&'a CToken>
+    {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, offset: usize) -> Option<&'a CToken>
+    {
+        self.tokens.get(self.idx + offset).map(|token| &token.token)
+    }
+
+    fn peek_identifier(&self) -> Option<&'a str>
+    {
+        match self.peek() {
+            Some(CToken::Identifier(name)) => Some(name.as_str()),
+            _ => None,
+        }
+    }
+
+    fn is_punct(&self, punct: char) -> bool
+    {
+        self.peek() == Some(&CToken::Punct(punct))
+    }
+
+    fn eat_punct(&mut self, punct: char) -> bool
+    {
+        let is_punct = self.is_punct(punct);
+
+        if is_punct {
+            self.idx += 1;
+        }
+
+        is_punct
+    }
+
+    fn expect_punct(&mut self, punct: char) -> Result<(), BindgenError>
+    {
+        if self.eat_punct(punct) {
+            Ok(())
+        }
+        else {
+            Err(self.unexpected())
+        }
+    }
+
+    /// Creates the error for the token at the cursor.
+    fn unexpected(&self) -> BindgenError
+    {
+        match self.tokens.get(self.idx) {
+            Some(token) => BindgenError::UnexpectedToken(token.line, token.token.to_string()),
+            None => BindgenError::UnexpectedEof,
+        }
+    }
+
+    /// Skips a group of tokens in parentheses, brackets or braces, the cursor must be at the opening token.
+    fn skip_group(&mut self) -> Result<(), BindgenError>
+    {
+        let mut depth = 0_usize;
+
+        while let Some(token) = self.peek() {
+            match token {
+                CToken::Punct('(' | '[' | '{') => depth += 1,
+                CToken::Punct(')' | ']' | '}') => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+
+            self.idx += 1;
+
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+
+        Err(BindgenError::UnexpectedEof)
+    }
+
+    /// Skips the tokens until one of the punctuations, which is not consumed. Groups are skipped as a whole.
+    fn skip_until(&mut self, puncts: &[char]) -> Result<(), BindgenError>
+    {
+        loop {
+            match self.peek() {
+                None => return Err(BindgenError::UnexpectedEof),
+                Some(CToken::Punct(punct)) if puncts.contains(punct) => return Ok(()),
+                Some(CToken::Punct('(' | '[' | '{')) => self.skip_group()?,
+                Some(_) => self.idx += 1,
+            }
+        }
+    }
+
+    /// Skips the qualifiers, attributes and macros at the cursor, these do not change how a type is mapped.
+    fn skip_ignored(&mut self) -> Result<(), BindgenError>
+    {
+        while let Some(name) = self.peek_identifier() {
+            if QUALIFIERS.contains(&name) || self.ignored_macros.contains(name) {
+                self.idx += 1;
+            }
+            else if ATTRIBUTES.contains(&name) || self.function_macros.contains(name) {
+                self.idx += 1;
+
+                if self.is_punct('(') {
+                    self.skip_group()?;
+                }
+            }
+            else {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps the signature of a C function onto an external function signature.
+fn map_function(
+    name: String,
+    function: &CFunction,
+    structs: &HashSet<String>,
+) -> Result<FunctionSignature, String>
+{
+    let return_type = map_type(&function.return_type, structs)
+        .map_err(|reason| format!("return type: {reason}"))?;
+
+    let mut arguments = FunctionArguments::new();
+
+    for (idx, (parameter, ty)) in function.parameters.iter().enumerate() {
+        let parameter = parameter
+            .as_deref()
+            .map_or_else(|| format!("arg{idx}"), fog_name);
+
+        let ty =
+            map_type(ty, structs).map_err(|reason| format!("argument `{parameter}`: {reason}"))?;
+
+        arguments
+            .arguments
+            .insert(parameter, (ty, VARIABLE_ID_SOURCE.get_unique_id()));
+    }
+
+    arguments.ellipsis_present = function.variadic;
+
+    Ok(FunctionSignature {
+        name,
+        args: arguments,
+        return_type,
+    })
+}
+
+/// Maps a C type onto a Fog type, the structs in `structs` are the ones which can be used by value.
+/// Returns the reason if the type has no Fog equivalent.
+fn map_type(ty: &CType, structs: &HashSet<String>) -> Result<Type, String>
+{
+    Ok(match ty {
+        CType::Void => Type::Void,
+        CType::Bool => Type::Boolean,
+        // Fog has no signed 8 bit integer, these are mapped onto the unsigned integer of the same size
+        CType::Char | CType::Integer { bits: 8, .. } => Type::U8,
+        CType::Integer {
+            bits: 16,
+            signed: true,
+        } => Type::I16,
+        CType::Integer {
+            bits: 16,
+            signed: false,
+        } => Type::U16,
+        CType::Integer {
+            bits: 32,
+            signed: true,
+        } => Type::I32,
+        CType::Integer {
+            bits: 32,
+            signed: false,
+        } => Type::U32,
+        CType::Integer { signed: true, .. } => Type::I64,
+        CType::Integer { signed: false, .. } => Type::U64,
+        CType::Float => Type::F32,
+        CType::Double => Type::F64,
+        // C enums are represented by an `int`
+        CType::Enum(_) => Type::I32,
+        CType::Struct(name) if structs.contains(name) => Type::Unresolved(fog_name(name)),
+        CType::Struct(name) => return Err(format!("struct `{name}` has no Fog equivalent")),
+        CType::Union(name) => return Err(format!("union `{name}` has no Fog equivalent")),
+        CType::Pointer(inner) => {
+            match inner.as_ref() {
+                CType::Char => Type::String,
+                // Pointers to types without a Fog equivalent are untyped
+                inner => {
+                    match map_type(inner, structs) {
+                        Ok(Type::Void) | Err(_) => Type::Pointer(None),
+                        Ok(inner) => Type::Pointer(Some(Box::new(inner))),
+                    }
+                },
+            }
+        },
+        CType::Array(inner, length) => Type::Array((Box::new(map_type(inner, structs)?), *length)),
+        CType::Function => return Err("functions can only be used through pointers".to_string()),
+        CType::Unknown(name) => return Err(format!("type `{name}` is not declared in the header")),
+        CType::Unsupported(reason) => return Err(reason.clone()),
+        CType::Placeholder => return Err("the declarator is invalid".to_string()),
+    })
+}
+
+/// Evaluates an integer constant expression, returns `None` if the tokens are not an integer constant.
+fn evaluate(tokens: &[HeaderToken], constants: &HashMap<String, i128>) -> Option<i128>
+{
+    let mut evaluator = ConstantEvaluator {
+        tokens,
+        idx: 0,
+        constants,
+    };
+
+    let value = evaluator.binary(0)?;
+
+    (evaluator.idx == tokens.len()).then_some(value)
+}
+
+/// The binary operators of constant expressions, from the lowest precedence to the highest.
+const BINARY_OPERATORS: &[&[&str]] = &[
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct ConstantEvaluator<'a>
+{
+    tokens: &'a [HeaderToken],
+    idx: usize,
+    constants: &'a HashMap<String, i128>,
+}
+
+impl ConstantEvaluator<'_>
+{
+    fn punct(&self, offset: usize) -> Option<char>
+    {
+        match self.tokens.get(self.idx + offset) {
+            Some(HeaderToken {
+                token: CToken::Punct(punct),
+                ..
+            }) => Some(*punct),
+            _ => None,
+        }
+    }
+
+    /// Returns the binary operator at the cursor, the shift operators are made up of two tokens.
+    fn operator(&self) -> Option<&'static str>
+    {
+        Some(match (self.punct(0)?, self.punct(1)) {
+            ('<', Some('<')) => "<<",
+            ('>', Some('>')) => ">>",
+            ('|', _) => "|",
+            ('^', _) => "^",
+            ('&', _) => "&",
+            ('+', _) => "+",
+            ('-', _) => "-",
+            ('*', _) => "*",
+            ('/', _) => "/",
+            ('%', _) => "%",
+            _ => return None,
+        })
+    }
+
+    fn binary(&mut self, level: usize) -> Option<i128>
+    {
+        let Some(operators) = BINARY_OPERATORS.get(level)
+        else {
+            return self.unary();
+        };
+
+        let mut lhs = self.binary(level + 1)?;
+
+        while let Some(operator) = self
+            .operator()
+            .filter(|operator| operators.contains(operator))
+        {
+            self.idx += operator.len();
+
+            let rhs = self.binary(level + 1)?;
+
+            lhs = match operator {
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "<<" => lhs.checked_shl(u32::try_from(rhs).ok()?)?,
+                ">>" => lhs.checked_shr(u32::try_from(rhs).ok()?)?,
+                "+" => lhs.checked_add(rhs)?,
+                "-" => lhs.checked_sub(rhs)?,
+                "*" => lhs.checked_mul(rhs)?,
+                "/" => lhs.checked_div(rhs)?,
+                _ => lhs.checked_rem(rhs)?,
+            };
+        }
+
+        Some(lhs)
+    }
+
+    fn unary(&mut self) -> Option<i128>
+    {
+        let token = &self.tokens.get(self.idx)?.token;
+
+        self.idx += 1;
+
+        match token {
+            CToken::Punct('-') => self.unary()?.checked_neg(),
+            CToken::Punct('+') => self.unary(),
+            CToken::Punct('~') => Some(!self.unary()?),
+            CToken::Punct('!') => Some((self.unary()? == 0).into()),
+            CToken::Punct('(') => {
+                let closing = self.tokens[self.idx..]
+                    .iter()
+                    .position(|token| token.token == CToken::Punct(')'))?;
+
+                // Casts to integer types do not change the value, ie. `(unsigned int)1`
+                let is_cast = closing > 0
+                    && self.tokens[self.idx..self.idx + closing].iter().all(|token| {
+                        matches!(&token.token, CToken::Identifier(name) if INTEGER_KEYWORDS.contains(&name.as_str()) || name.ends_with("_t"))
+                    });
+
+                if is_cast {
+                    self.idx += closing + 1;
+
+                    return self.unary();
+                }
+
+                let value = self.binary(0)?;
+
+                if self.punct(0) != Some(')') {
+                    return None;
+                }
+
+                self.idx += 1;
+
+                Some(value)
+            },
+            CToken::Number(number) => parse_integer_literal(number),
+            CToken::Identifier(name) => self.constants.get(name).copied(),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a C integer literal, the literal may be hexadecimal, octal or binary and may have a suffix.
+fn parse_integer_literal(literal: &str) -> Option<i128>
+{
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+
+    let (digits, radix) = if let Some(digits) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        (digits, 16)
+    }
+    else if let Some(digits) = digits
+        .strip_prefix("0b")
+        .or_else(|| digits.strip_prefix("0B"))
+    {
+        (digits, 2)
+    }
+    else if digits.len() > 1 && digits.starts_with('0') {
+        (&digits[1..], 8)
+    }
+    else {
+        (digits, 10)
+    };
+
+    i128::from_str_radix(digits, radix).ok()
+}
@@ -1,3 +1,6 @@
 pub mod analyzer;
+pub mod bindgen;
 pub mod list_manager;
 pub mod requester;
+pub mod resolver;
+pub mod tree;
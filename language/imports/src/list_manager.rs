@@ -8,7 +8,8 @@ use std::{
 use codegen::llvm_codegen;
 use common::{
     anyhow::{self, ensure},
-    compiler::{HostInformation, ProjectConfig},
+    artifact::{ArtifactPaths, CONFIG_FILE_NAME, main_source_path, source_dir},
+    compiler::{HostInformation, OptimizationSettings, ProjectConfig},
     dashmap::DashMap,
     dependency::DependencyInfo,
     distributed_compiler::DistributedCompilerWorker,
@@ -16,7 +17,9 @@ use common::{
     futures,
     indexmap::IndexSet,
     inkwell::{builder::Builder, context::Context, module::Module, targets::TargetTriple},
-    parser::function::FunctionSignature,
+    lockfile::LockFile,
+    parser::{cfg::CfgTarget, function::FunctionSignature},
+    semver::Version,
     tokio, toml,
     tracing::info,
     ty::OrdSet,
@@ -25,9 +28,11 @@ use common::{
 use crate::{
     analyzer::analyze_dependency,
     requester::{create_remote_list, dependency_requester},
+    resolver::parse_version_requirement,
 };
 
 /// Creates a dependency list from the path provided, by reading in all the folder names and libraries.
+/// The dependencies downloaded from the remotes are verified against and recorded in `lock_file`.
 pub fn create_dependency_functions_list<'ctx>(
     dependency_output_path_list: &mut Vec<PathBuf>,
     // All of the additional linking stuff is put into this list here.
@@ -36,7 +41,8 @@ pub fn create_dependency_functions_list<'ctx>(
     remote_workers: Option<Vec<DistributedCompilerWorker>>,
     deps_path: PathBuf,
     root_dir: PathBuf,
-    optimization: bool,
+    lock_file: &mut LockFile,
+    optimization: &OptimizationSettings,
     context: &'ctx Context,
     builder: &'ctx Builder<'ctx>,
     root_module: &Module<'ctx>,
@@ -80,22 +86,30 @@ pub fn create_dependency_functions_list<'ctx>(
         );
 
         // Create a map of the remotes' thread handlers
-        let (remote_handlers, thread_handles) =
-            create_remote_list(remotes, host_information, root_dir.clone());
+        let (remote_handlers, thread_handles) = create_remote_list(
+            remotes,
+            host_information,
+            root_dir.clone(),
+            Some(lock_file.clone()),
+        );
 
         // Request the dependencies from those remotes
         dependency_requester(&dependency_list, &remote_handlers)?;
 
         // Wait for the threads to finish
-        tokio::runtime::Handle::current().block_on(async move {
-            futures::future::join_all(thread_handles).await;
-        });
+        let downloaded_dependencies = tokio::runtime::Handle::current()
+            .block_on(async move { futures::future::join_all(thread_handles).await });
+
+        // Lock the verified downloads
+        for downloaded_dependency in downloaded_dependencies {
+            lock_file.insert(downloaded_dependency??);
+        }
     }
     else if !dependency_list.is_empty() {
         return Err(DependencyError::MissingDependencies(dependency_list).into());
     }
 
-    let mut dir_entries_remote = fs::read_dir(format!("{}\\remote_compile", root_dir.display()))?;
+    let mut dir_entries_remote = fs::read_dir(root_dir.join("remote_compile"))?;
 
     // Scan and parse downloaded dependencies
     scan_dependencies(
@@ -122,7 +136,7 @@ fn scan_dependencies<'ctx>(
     dependency_output_path_list: &mut Vec<PathBuf>,
     additional_linking_material_list: &mut Vec<PathBuf>,
     dependency_list: &mut HashMap<String, DependencyInfo>,
-    optimization: bool,
+    optimization: &OptimizationSettings,
     context: &'ctx Context,
     builder: &'ctx Builder<'ctx>,
     root_module: &Module<'ctx>,
@@ -176,7 +190,7 @@ fn scan_dependency<'ctx>(
     dependency_list: &mut HashMap<String, DependencyInfo>,
     deps: Rc<DashMap<Vec<String>, FunctionSignature>>,
     dependency_path: &mut PathBuf,
-    optimization: bool,
+    optimization: &OptimizationSettings,
     context: &'ctx Context,
     builder: &'ctx Builder<'ctx>,
     root_module: &Module<'ctx>,
@@ -190,8 +204,11 @@ fn scan_dependency<'ctx>(
     let mut project_dir = fs::read_dir(dependency_path.clone())
         .map_err(|err| DependencyError::FileError(err.into()))?;
 
-    let project_directory_entry =
-        project_dir.find(|entry| entry.as_ref().is_ok_and(|e| e.file_name() == "config.toml"));
+    let project_directory_entry = project_dir.find(|entry| {
+        entry
+            .as_ref()
+            .is_ok_and(|e| e.file_name() == CONFIG_FILE_NAME)
+    });
 
     match project_directory_entry {
         Some(config_file) => {
@@ -210,10 +227,16 @@ fn scan_dependency<'ctx>(
             }
 
             // Remove the library which was found already, so that ideally the dep list will be empty after this function ran.
-            // Match version number
+            // Match version requirement
             if let Some(project_dependency) = dependency_list.remove(&dependency_config.name) {
+                let version_requirement = parse_version_requirement(
+                    &dependency_config.name,
+                    &project_dependency.version,
+                )?;
+
                 ensure!(
-                    project_dependency.version.clone() == dependency_config.version,
+                    Version::parse(&dependency_config.version)
+                        .is_ok_and(|version| version_requirement.matches(&version)),
                     DependencyError::MismatchedVersion(
                         dependency_config.name,
                         project_dependency.version.clone(),
@@ -243,9 +266,8 @@ fn scan_dependency<'ctx>(
                     }
                 }
 
-                let lib_src_file_content =
-                    fs::read_to_string(format!("{}\\src\\main.f", dependency_path.display()))
-                        .map_err(|err| DependencyError::FileError(err.into()))?;
+                let lib_src_file_content = fs::read_to_string(main_source_path(&dependency_path))
+                    .map_err(|err| DependencyError::FileError(err.into()))?;
 
                 // Create context for the dependency
                 let lib_module = context.create_module(&dependency_config.name);
@@ -294,6 +316,10 @@ fn scan_dependency<'ctx>(
                     dependency_config.clone(),
                     current_module_path.clone(),
                     OrdSet::wrap(IndexSet::from_iter(dep_features_enabled.iter().cloned())),
+                    CfgTarget::from_triple(
+                        &target_triple.as_str().to_string_lossy(),
+                        optimization.debug_assertions(),
+                    ),
                 )?;
 
                 // Store the public functions in the main dep list.
@@ -306,36 +332,27 @@ fn scan_dependency<'ctx>(
                     dependency_config
                         .additional_linking_material
                         .iter()
-                        .map(|path| {
-                            PathBuf::from(format!(
-                                "{}\\{}",
-                                original_dep_path_root.display(),
-                                path.display()
-                            ))
-                        }),
+                        .map(|path| original_dep_path_root.join(path)),
                 );
 
                 // let imported_functions = Rc::new(parser_state.imported_functions().clone());
                 let imported_functions = Rc::new(todo!());
 
+                let artifact_paths = ArtifactPaths::new(
+                    &original_dep_path_root,
+                    &dependency_config.build_path,
+                    &dependency_config.name,
+                    &target_triple.as_str().to_string_lossy(),
+                );
+
                 // Generate LLVM-IR for the dependency
-                let target_ir_path = PathBuf::from(format!(
-                    "{}\\{}\\{}.ll",
-                    original_dep_path_root.display(),
-                    dependency_config.build_path.clone(),
-                    dependency_config.name
-                ));
+                let target_ir_path = artifact_paths.ir();
 
                 // Only generate llvm-ir files if they dont exist for the dependency
                 if !fs::exists(&target_ir_path)? {
                     llvm_codegen(
                         target_ir_path.clone(),
-                        PathBuf::from(format!(
-                            "{}\\{}\\{}.o",
-                            original_dep_path_root.display(),
-                            dependency_config.build_path.clone(),
-                            dependency_config.name
-                        )),
+                        artifact_paths.object(),
                         optimization,
                         parser_state.clone(),
                         todo!(),
@@ -343,7 +360,7 @@ fn scan_dependency<'ctx>(
                         context,
                         builder,
                         lib_module.clone(),
-                        &format!("{}\\src", dependency_path.display()),
+                        &source_dir(&original_dep_path_root).display().to_string(),
                         flags_passed_in,
                         target_triple,
                         cpu_name,
@@ -1,12 +1,13 @@
 use std::{collections::HashMap, io::Cursor, path::PathBuf};
 
 use common::{
-    anyhow::Result,
+    anyhow::{self, Result},
     compiler::HostInformation,
     compression::{decompress_bytes, unzip_from_bytes, write_zip_to_fs},
     dependency::DependencyInfo,
     distributed_compiler::{DependencyRequest, DistributedCompilerWorker, FinishedJob},
     error::dependency::DependencyError,
+    lockfile::{DependencySource, LockFile, LockedDependency, checksum_archive},
     rmp_serde,
     tokio::{
         self,
@@ -19,13 +20,16 @@ use common::{
     tracing::info,
 };
 
+/// Creates a handler thread for every remote, the threads return the downloaded dependency with the checksum of its archive.
+/// If the dependency has been locked in `lock_file`, the checksum of the downloaded archive must match the locked checksum.
 pub fn create_remote_list(
     remotes: Vec<DistributedCompilerWorker>,
     host_info: HostInformation,
     root_path: PathBuf,
+    lock_file: Option<LockFile>,
 ) -> (
     HashMap<String, (String, Sender<(String, DependencyInfo)>)>,
-    Vec<JoinHandle<()>>,
+    Vec<JoinHandle<Result<LockedDependency>>>,
 )
 {
     let mut threads = Vec::new();
@@ -42,20 +46,34 @@ pub fn create_remote_list(
             remote.address.clone()
         );
 
-        remote_list.insert(remote.name, (remote.address.clone(), sender));
+        remote_list.insert(remote.name.clone(), (remote.address.clone(), sender));
 
         let root_path = root_path.clone();
+        let lock_file = lock_file.clone();
 
         // Create a remote handler thread
         let thread_handle = spawn(async move {
             let mut tcp_handle = TcpStream::connect(remote.address).await.unwrap();
             let host_info = host_info.clone();
 
+            // The features the dependencies have been requested with
+            let mut requested_features = HashMap::new();
+
             loop {
                 select! {
                     Some((name, info)) = recv.recv() => {
+                        requested_features.insert(name.clone(), info.features.clone());
+
+                        let source_checksum = lock_file.as_ref().and_then(|lock_file| lock_file.get(&name)).and_then(|locked_dependency| locked_dependency.source_checksum.clone());
+
+                        // A locked dependency is requested with its exact version, which the registry serves even if it has been yanked since
+                        let version = match lock_file.as_ref().and_then(|lock_file| lock_file.get(&name)) {
+                            Some(locked_dependency) => format!("={}", locked_dependency.version),
+                            None => info.version,
+                        };
+
                         // Send the request to the remote
-                        let packet = rmp_serde::to_vec(&DependencyRequest {name, version: info.version, features: info.features, target_triple: host_info.target_triple.clone(), cpu_features: host_info.cpu_features.clone(), cpu_name: host_info.cpu_name.clone(), flags_passed_in: String::new() }).unwrap();
+                        let packet = rmp_serde::to_vec(&DependencyRequest {name, version, features: info.features, target_triple: host_info.target_triple.clone(), cpu_features: host_info.cpu_features.clone(), cpu_name: host_info.cpu_name.clone(), flags_passed_in: String::new(), source_checksum }).unwrap();
 
                         // Send len
                         tcp_handle.write_all(&(packet.len() as u32).to_be_bytes()).await.unwrap();
@@ -73,16 +91,37 @@ pub fn create_remote_list(
 
                         let finished_job = rmp_serde::from_slice::<FinishedJob>(&decomp_bytes).unwrap();
 
-                        let zip = unzip_from_bytes(Cursor::new(finished_job.artifacts_zip_bytes)).unwrap();
+                        let mut zip = unzip_from_bytes(Cursor::new(finished_job.artifacts_zip_bytes)).unwrap();
+
+                        let dependency_name = finished_job.info.dependency_name.clone();
+
+                        // Verify the download before writing it to the fs
+                        let checksum = checksum_archive(&mut zip)?;
+
+                        if let Some(locked_dependency) = lock_file.as_ref().and_then(|lock_file| lock_file.get(&dependency_name)) {
+                            locked_dependency.verify_checksum(&checksum)?;
+                            locked_dependency.verify_source_checksum(&finished_job.source_checksum)?;
+                        }
 
                         // Construct zip fs path
-                        let dependency_path = format!("{}\\remote_compile\\{}\\", root_path.display(), finished_job.info.dependency_name.clone());
+                        let dependency_path = root_path.join("remote_compile").join(&dependency_name);
 
                         // Write zip contents to fs
-                        write_zip_to_fs(&(dependency_path.clone().into()), zip).unwrap();
+                        write_zip_to_fs(&dependency_path, zip).unwrap();
+
+                        let mut features = requested_features.remove(&dependency_name).unwrap_or_default();
+
+                        features.sort();
 
                         // Quit thread
-                        break;
+                        break Ok::<_, anyhow::Error>(LockedDependency {
+                            name: dependency_name,
+                            version: finished_job.info.dependency_version,
+                            source: DependencySource::Remote(remote.name),
+                            features,
+                            checksum: Some(checksum),
+                            source_checksum: Some(finished_job.source_checksum),
+                        });
                     }
                 }
             }
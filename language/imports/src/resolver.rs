@@ -0,0 +1,494 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use common::{
+    anyhow,
+    artifact::{config_path, deps_dir},
+    compiler::ProjectConfig,
+    dependency::{RegistryStatus, VersionStatus},
+    error::dependency::DependencyError,
+    lockfile::{DependencySource, LockFile, LockedDependency, checksum_directory},
+    semver::{Version, VersionReq},
+    toml,
+};
+
+/// A requirement of a project on the versions of a dependency.
+#[derive(Debug, Clone)]
+pub struct VersionRequirement
+{
+    /// The name of the project which requires the dependency.
+    pub required_by: String,
+    pub requirement: VersionReq,
+}
+
+/// A dependency version selected by [`resolve_dependencies`].
+#[derive(Debug, Clone)]
+pub struct ResolvedDependency
+{
+    pub name: String,
+    pub version: Version,
+    /// The folder the selected version is located in.
+    pub path: PathBuf,
+    /// The features enabled by any of the projects depending on the dependency, sorted by their name.
+    pub features: Vec<String>,
+    pub config: ProjectConfig,
+    /// Whether the dependency has been required with a path, instead of being looked up in a dependencies folder.
+    pub is_path_dependency: bool,
+    /// Whether the selected version has been yanked or deprecated at the registry.
+    pub status: VersionStatus,
+}
+
+/// Parses the version requirement of a dependency. The requirements follow Cargo's syntax (ie. `^1.2`, `~0.3`, `>=1, <2`), a bare version is treated as a caret requirement.
+pub fn parse_version_requirement(
+    dependency: &str,
+    requirement: &str,
+) -> Result<VersionReq, DependencyError>
+{
+    VersionReq::parse(requirement).map_err(|_| {
+        DependencyError::InvalidVersionRequirement(dependency.to_string(), requirement.to_string())
+    })
+}
+
+/// Lists the versions of `dependency` which are present in the dependencies folder at `deps_path`.
+/// Folders which are not named `<dependency>(<version>)` or whose version is not a semantic version are ignored.
+pub fn available_versions(deps_path: &Path, dependency: &str) -> BTreeMap<Version, PathBuf>
+{
+    let Ok(dir_entries) = fs::read_dir(deps_path)
+    else {
+        return BTreeMap::new();
+    };
+
+    dir_entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            let version = file_name
+                .strip_prefix(dependency)?
+                .strip_prefix('(')?
+                .strip_suffix(')')?;
+
+            Some((Version::parse(version).ok()?, entry.path()))
+        })
+        .collect()
+}
+
+/// Selects the newest available version of `dependency` which matches every requirement.
+/// If there is no such version, the error reports either the requirement no version matches or the conflicting requirements.
+pub fn select_version(
+    dependency: &str,
+    requirements: &[VersionRequirement],
+    available: &BTreeMap<Version, PathBuf>,
+) -> Result<Version, DependencyError>
+{
+    if let Some(version) = available.keys().rev().find(|version| {
+        requirements
+            .iter()
+            .all(|requirement| requirement.requirement.matches(version))
+    }) {
+        return Ok(version.clone());
+    }
+
+    if let Some(unmatched) = requirements.iter().find(|requirement| {
+        !available
+            .keys()
+            .any(|version| requirement.requirement.matches(version))
+    }) {
+        return Err(DependencyError::NoMatchingVersion(
+            dependency.to_string(),
+            unmatched.requirement.to_string(),
+            available
+                .keys()
+                .map(|version| version.to_string())
+                .collect(),
+        ));
+    }
+
+    Err(DependencyError::VersionConflict(
+        dependency.to_string(),
+        requirements
+            .iter()
+            .map(|requirement| {
+                format!(
+                    "`{}` requires `{}`",
+                    requirement.required_by, requirement.requirement
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    ))
+}
+
+/// Resolves the versions of every local dependency of the project, including the dependencies of dependencies.
+/// Every dependency is resolved to a single version which matches the requirements of every project depending on it.
+/// The versions are searched for in the dependencies folder of the projects requiring them. Dependencies compiled by a remote are not resolved.
+/// A dependency required with a path always resolves to the project at the path, relative to the project requiring it.
+/// The versions locked in `lock_file` are kept as long as they match the requirements, otherwise the newest matching version is selected.
+/// The versions yanked at the registry are only kept if they are locked, they are never selected otherwise.
+/// `dependency_features` are the features of the dependencies enabled by the selected features of the project (ie. `json/simd`), they are enabled along with the features requested in the dependency table.
+pub fn resolve_dependencies(
+    root_dir: &Path,
+    config: &ProjectConfig,
+    dependency_features: &BTreeMap<String, BTreeSet<String>>,
+    lock_file: Option<&LockFile>,
+    registry_status: &RegistryStatus,
+) -> anyhow::Result<BTreeMap<String, ResolvedDependency>>
+{
+    let mut selected = BTreeMap::new();
+    let mut previous_selections = HashSet::new();
+
+    // Selecting a version can change the requirements on other dependencies, so the versions are selected until they stop changing.
+    loop {
+        let mut collected = collect_requirements(root_dir, config, dependency_features, &selected)?;
+
+        let selection = collected
+            .requirements
+            .iter()
+            .map(|(name, requirements)| {
+                let available = &collected.available[name];
+
+                let locked_version = lock_file
+                    .and_then(|lock_file| lock_file.get(name))
+                    .and_then(|locked| Version::parse(&locked.version).ok())
+                    .filter(|version| {
+                        available.contains_key(version)
+                            && requirements
+                                .iter()
+                                .all(|requirement| requirement.requirement.matches(version))
+                    });
+
+                let version = match locked_version {
+                    Some(version) => version,
+                    None => {
+                        let selectable = available
+                            .iter()
+                            .filter(|(version, _)| {
+                                !registry_status.is_yanked(name, &version.to_string())
+                            })
+                            .map(|(version, path)| (version.clone(), path.clone()))
+                            .collect();
+
+                        select_version(name, requirements, &selectable).map_err(|error| {
+                            // Report that the matching versions have been yanked, instead of the requirements not matching
+                            if select_version(name, requirements, available).is_ok() {
+                                DependencyError::MatchingVersionsYanked(name.clone())
+                            }
+                            else {
+                                error
+                            }
+                        })?
+                    },
+                };
+
+                Ok((name.clone(), version))
+            })
+            .collect::<Result<BTreeMap<_, _>, DependencyError>>()?;
+
+        if selection == selected {
+            return Ok(selection
+                .into_iter()
+                .map(|(name, version)| {
+                    let path = collected.available[&name][&version].clone();
+                    let is_path_dependency = collected.paths.contains_key(&name);
+
+                    // Path dependencies are not published to the registry
+                    let status = if is_path_dependency {
+                        VersionStatus::default()
+                    }
+                    else {
+                        registry_status
+                            .get(&name, &version.to_string())
+                            .cloned()
+                            .unwrap_or_default()
+                    };
+
+                    let resolved_dependency = ResolvedDependency {
+                        version,
+                        path,
+                        features: collected
+                            .features
+                            .remove(&name)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .collect(),
+                        config: collected.configs.remove(&name).unwrap_or_default(),
+                        is_path_dependency,
+                        status,
+                        name: name.clone(),
+                    };
+
+                    (name, resolved_dependency)
+                })
+                .collect());
+        }
+
+        previous_selections.insert(selected);
+
+        if previous_selections.contains(&selection) {
+            return Err(DependencyError::UnresolvableDependencies.into());
+        }
+
+        selected = selection;
+    }
+}
+
+/// Creates the lock file of the resolved dependencies. The sources are relative to `root_dir`.
+/// The dependencies in the dependencies folder are locked with the `registry` they are downloaded from, or with their path if the project does not have a registry.
+/// The build folder and the dependencies folder of a dependency are not included in its checksum.
+/// Path dependencies are not checksummed, as they are expected to change between builds.
+/// The dependencies are locked with the features they have been resolved with, including the features enabled by the features of the projects.
+pub fn lock_dependencies(
+    root_dir: &Path,
+    resolved_dependencies: &BTreeMap<String, ResolvedDependency>,
+    registry: Option<&str>,
+) -> Result<LockFile, DependencyError>
+{
+    let mut lock_file = LockFile::default();
+
+    for dependency in resolved_dependencies.values() {
+        lock_file.insert(LockedDependency {
+            name: dependency.name.clone(),
+            version: dependency.version.to_string(),
+            source: match registry {
+                Some(registry) if !dependency.is_path_dependency => {
+                    DependencySource::Registry(registry.trim_end_matches('/').to_string())
+                },
+                _ => DependencySource::Path(relative_path(root_dir, &dependency.path)),
+            },
+            features: dependency.features.clone(),
+            checksum: if dependency.is_path_dependency {
+                None
+            }
+            else {
+                Some(checksum_directory(
+                    &dependency.path,
+                    &[dependency.config.build_path.as_str(), "deps"],
+                )?)
+            },
+            source_checksum: None,
+        });
+    }
+
+    Ok(lock_file)
+}
+
+/// The requirements on the dependencies collected by [`collect_requirements`].
+#[derive(Default)]
+struct CollectedRequirements
+{
+    requirements: BTreeMap<String, Vec<VersionRequirement>>,
+    /// The available versions of the dependencies and their locations.
+    available: BTreeMap<String, BTreeMap<Version, PathBuf>>,
+    /// The features enabled by any of the projects depending on the dependency.
+    features: BTreeMap<String, BTreeSet<String>>,
+    /// The configs of the visited dependencies.
+    configs: BTreeMap<String, ProjectConfig>,
+    /// The folders of the dependencies required with a path.
+    paths: BTreeMap<String, PathBuf>,
+}
+
+/// Collects the requirements on the dependencies and their available versions, starting from the project at `root_dir`.
+/// Only the dependencies of the `selected` versions are visited.
+/// The `dependency_features` enabled by the features of the project are added to the features of its dependencies.
+fn collect_requirements(
+    root_dir: &Path,
+    config: &ProjectConfig,
+    dependency_features: &BTreeMap<String, BTreeSet<String>>,
+    selected: &BTreeMap<String, Version>,
+) -> anyhow::Result<CollectedRequirements>
+{
+    let mut collected = CollectedRequirements::default();
+
+    for (name, features) in dependency_features {
+        if config.dependencies.contains_key(name) {
+            collected
+                .features
+                .entry(name.clone())
+                .or_default()
+                .extend(features.iter().cloned());
+        }
+    }
+
+    let mut pending = vec![(root_dir.to_path_buf(), config.clone())];
+
+    loop {
+        while let Some((project_dir, project_config)) = pending.pop() {
+            for (name, dependency) in &project_config.dependencies {
+                // Dependencies compiled by a remote are not present locally
+                if dependency.remote.is_some() {
+                    continue;
+                }
+
+                collected
+                    .requirements
+                    .entry(name.clone())
+                    .or_default()
+                    .push(VersionRequirement {
+                        required_by: project_config.name.clone(),
+                        requirement: parse_version_requirement(name, &dependency.version)?,
+                    });
+
+                collected
+                    .features
+                    .entry(name.clone())
+                    .or_default()
+                    .extend(dependency.features.iter().cloned());
+
+                if let Some(dependency_path) = &dependency.path {
+                    let dependency_path = project_dir.join(dependency_path);
+
+                    let dependency_path = fs::canonicalize(&dependency_path)
+                        .map_err(|_| DependencyError::DependencyMissingConfig(dependency_path))?;
+
+                    match collected.paths.get(name) {
+                        Some(known_path) if *known_path != dependency_path => {
+                            return Err(DependencyError::ConflictingDependencyPaths(
+                                name.clone(),
+                                known_path.clone(),
+                                dependency_path,
+                            )
+                            .into());
+                        },
+                        Some(_) => {},
+                        None => {
+                            let dependency_config =
+                                read_path_dependency_config(name, &dependency_path)?;
+
+                            let version =
+                                Version::parse(&dependency_config.version).map_err(|_| {
+                                    DependencyError::InvalidDependencyVersion(
+                                        name.clone(),
+                                        dependency_config.version.clone(),
+                                    )
+                                })?;
+
+                            // The project at the path is the only version of the dependency
+                            collected.available.insert(
+                                name.clone(),
+                                BTreeMap::from([(version, dependency_path.clone())]),
+                            );
+
+                            collected
+                                .paths
+                                .insert(name.clone(), dependency_path.clone());
+                            collected
+                                .configs
+                                .insert(name.clone(), dependency_config.clone());
+
+                            pending.push((dependency_path, dependency_config));
+                        },
+                    }
+                }
+                else if !collected.paths.contains_key(name) {
+                    let versions = collected.available.entry(name.clone()).or_default();
+
+                    for (version, path) in available_versions(&deps_dir(&project_dir), name) {
+                        versions.entry(version).or_insert(path);
+                    }
+                }
+            }
+        }
+
+        // Visit the selected versions which have been found
+        let found = selected
+            .iter()
+            .filter(|(name, _)| {
+                collected.requirements.contains_key(*name) && !collected.configs.contains_key(*name)
+            })
+            .filter_map(|(name, version)| {
+                Some((
+                    name.clone(),
+                    version,
+                    collected.available.get(name)?.get(version)?.clone(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        if found.is_empty() {
+            break;
+        }
+
+        for (name, version, path) in found {
+            let dependency_config = read_dependency_config(&name, version, &path)?;
+
+            collected.configs.insert(name, dependency_config.clone());
+
+            pending.push((path, dependency_config));
+        }
+    }
+
+    Ok(collected)
+}
+
+/// Reads the config of a dependency required with a path and checks that it is a library.
+fn read_path_dependency_config(name: &str, path: &Path) -> anyhow::Result<ProjectConfig>
+{
+    let config_file = fs::read_to_string(config_path(path))
+        .map_err(|_| DependencyError::DependencyMissingConfig(path.to_path_buf()))?;
+
+    let dependency_config = toml::from_str::<ProjectConfig>(&config_file)?;
+
+    if !dependency_config.is_library {
+        return Err(DependencyError::InvalidDependencyType(name.to_string()).into());
+    }
+
+    Ok(dependency_config)
+}
+
+/// Returns `path` relative to `root_dir`, the path is returned unchanged if the paths do not share a root.
+fn relative_path(root_dir: &Path, path: &Path) -> PathBuf
+{
+    let (Ok(root_dir), Ok(canonical_path)) = (fs::canonicalize(root_dir), fs::canonicalize(path))
+    else {
+        return path.to_path_buf();
+    };
+
+    let common_components = root_dir
+        .components()
+        .zip(canonical_path.components())
+        .take_while(|(lhs, rhs)| lhs == rhs)
+        .count();
+
+    if common_components == 0 {
+        return path.to_path_buf();
+    }
+
+    root_dir
+        .components()
+        .skip(common_components)
+        .map(|_| Component::ParentDir)
+        .chain(canonical_path.components().skip(common_components))
+        .collect()
+}
+
+/// Reads the config of a dependency and checks that it is a library with the version it is stored as.
+fn read_dependency_config(
+    name: &str,
+    version: &Version,
+    path: &Path,
+) -> anyhow::Result<ProjectConfig>
+{
+    let config_file = fs::read_to_string(config_path(path))
+        .map_err(|_| DependencyError::DependencyMissingConfig(path.to_path_buf()))?;
+
+    let dependency_config = toml::from_str::<ProjectConfig>(&config_file)?;
+
+    if !dependency_config.is_library {
+        return Err(DependencyError::InvalidDependencyType(name.to_string()).into());
+    }
+
+    if Version::parse(&dependency_config.version).ok().as_ref() != Some(version) {
+        return Err(DependencyError::MismatchedVersion(
+            name.to_string(),
+            version.to_string(),
+            dependency_config.version,
+        )
+        .into());
+    }
+
+    Ok(dependency_config)
+}
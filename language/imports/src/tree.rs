@@ -0,0 +1,143 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+};
+
+use common::{compiler::ProjectConfig, dependency::DependencyInfo};
+
+use crate::resolver::ResolvedDependency;
+
+/// Renders the dependency graph of the project with the resolved versions.
+/// Every dependency is only expanded the first time it appears, its repetitions are marked with `(*)`.
+/// The dependencies required by more than one project are listed after the graph, with the requirements on them.
+pub fn render_dependency_tree(
+    config: &ProjectConfig,
+    resolved_dependencies: &BTreeMap<String, ResolvedDependency>,
+) -> String
+{
+    let mut tree = DependencyTree {
+        resolved_dependencies,
+        output: format!("{} v{}\n", config.name, config.version),
+        expanded: HashSet::new(),
+        required_by: BTreeMap::new(),
+    };
+
+    tree.render_dependencies(&config.name, &config.dependencies, "");
+
+    let duplicates = tree
+        .required_by
+        .iter()
+        .filter(|(_name, requirements)| requirements.len() > 1)
+        .collect::<Vec<_>>();
+
+    if !duplicates.is_empty() {
+        tree.output
+            .push_str("\nDependencies required by multiple projects:\n");
+
+        for (name, requirements) in duplicates {
+            let _ = writeln!(
+                tree.output,
+                "{name} is required by {}",
+                requirements
+                    .iter()
+                    .map(|(required_by, requirement)| format!("`{required_by}` ({requirement})"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    tree.output
+}
+
+struct DependencyTree<'a>
+{
+    resolved_dependencies: &'a BTreeMap<String, ResolvedDependency>,
+    output: String,
+    /// The dependencies whose dependencies have already been rendered.
+    expanded: HashSet<String>,
+    /// The projects requiring the dependencies with their version requirements.
+    required_by: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl DependencyTree<'_>
+{
+    fn render_dependencies(
+        &mut self,
+        required_by: &str,
+        dependencies: &HashMap<String, DependencyInfo>,
+        prefix: &str,
+    )
+    {
+        let resolved_dependencies = self.resolved_dependencies;
+
+        let mut names = dependencies.keys().collect::<Vec<_>>();
+
+        names.sort();
+
+        for (idx, name) in names.iter().enumerate() {
+            let dependency = &dependencies[*name];
+
+            let (branch, child_prefix) = if idx + 1 == names.len() {
+                ("└── ", "    ")
+            }
+            else {
+                ("├── ", "│   ")
+            };
+
+            self.required_by
+                .entry(name.to_string())
+                .or_default()
+                .insert(required_by.to_string(), dependency.version.clone());
+
+            let _ = write!(self.output, "{prefix}{branch}{name}");
+
+            let Some(resolved_dependency) = resolved_dependencies.get(*name)
+            else {
+                // Dependencies compiled by a remote are not resolved locally
+                match &dependency.remote {
+                    Some(remote) => {
+                        let _ = writeln!(self.output, " {} (remote: {remote})", dependency.version);
+                    },
+                    None => {
+                        let _ = writeln!(self.output, " {} (unresolved)", dependency.version);
+                    },
+                }
+
+                continue;
+            };
+
+            let _ = write!(self.output, " v{}", resolved_dependency.version);
+
+            if !dependency.features.is_empty() {
+                let _ = write!(self.output, " [{}]", dependency.features.join(", "));
+            }
+
+            if resolved_dependency.is_path_dependency {
+                let _ = write!(self.output, " ({})", resolved_dependency.path.display());
+            }
+
+            if resolved_dependency.status.yanked {
+                self.output.push_str(" (yanked)");
+            }
+
+            if let Some(deprecation) = &resolved_dependency.status.deprecation {
+                let _ = write!(self.output, " (deprecated: {deprecation})");
+            }
+
+            if !self.expanded.insert(name.to_string()) {
+                self.output.push_str(" (*)\n");
+
+                continue;
+            }
+
+            self.output.push('\n');
+
+            self.render_dependencies(
+                name,
+                &resolved_dependency.config.dependencies,
+                &format!("{prefix}{child_prefix}"),
+            );
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::PathBuf;
+
+use common::ty::Type;
+use imports::bindgen::{CHeaderBindings, add_linking_material_to_config};
+
+const EXAMPLE_HEADER: &str = include_str!("headers/example.h");
+
+fn example_bindings(target_triple: &str) -> CHeaderBindings
+{
+    CHeaderBindings::from_header(EXAMPLE_HEADER, target_triple).unwrap()
+}
+
+fn variants(values: &[(&str, i128)]) -> Vec<(String, i128)>
+{
+    values
+        .iter()
+        .map(|(name, value)| (name.to_string(), *value))
+        .collect()
+}
+
+#[test]
+fn counts_implicit_enum_values_from_the_previous_variant()
+{
+    let bindings = example_bindings("x86_64-unknown-linux-gnu");
+
+    assert_eq!(
+        bindings.enums["color"],
+        variants(&[
+            ("RED", 0),
+            ("GREEN", 5),
+            ("BLUE", 6),
+            ("ALPHA", -2),
+            ("BETA", -1)
+        ])
+    );
+    assert_eq!(
+        bindings.enums["mode"],
+        variants(&[("MODE_READ", 1), ("MODE_WRITE", 2), ("MODE_APPEND", 3)])
+    );
+}
+
+#[test]
+fn collects_defines_and_anonymous_enums_as_constants()
+{
+    let bindings = example_bindings("x86_64-unknown-linux-gnu");
+
+    assert_eq!(bindings.constants["EXAMPLE_VERSION"], 3);
+    assert_eq!(bindings.constants["EXAMPLE_FLAGS"], 16);
+    assert_eq!(bindings.constants["EXAMPLE_MAX"], 64);
+    assert_eq!(bindings.constants["EXAMPLE_NEXT"], 65);
+
+    // Function-like macros have no value
+    assert!(!bindings.constants.contains_key("EXAMPLE_MIN"));
+}
+
+#[test]
+fn maps_structs_and_functions()
+{
+    let bindings = example_bindings("x86_64-unknown-linux-gnu");
+
+    assert_eq!(
+        bindings.structs["point"].iter().collect::<Vec<_>>(),
+        [
+            (&"x".to_string(), &Type::I32),
+            (&"y".to_string(), &Type::I32)
+        ]
+    );
+    assert_eq!(
+        bindings.structs["buffer"].iter().collect::<Vec<_>>(),
+        [
+            (
+                &"data".to_string(),
+                &Type::Pointer(Some(Box::new(Type::U8)))
+            ),
+            (&"length".to_string(), &Type::U64),
+            (&"scale".to_string(), &Type::F64),
+        ]
+    );
+
+    let function = |name: &str| {
+        bindings
+            .functions
+            .iter()
+            .find(|signature| signature.name == name)
+            .unwrap_or_else(|| panic!("`{name}` has not been mapped"))
+    };
+
+    let distance = function("point_distance");
+
+    assert_eq!(distance.return_type, Type::I32);
+    assert_eq!(
+        distance
+            .args
+            .arguments
+            .values()
+            .map(|(ty, _id)| ty.clone())
+            .collect::<Vec<_>>(),
+        [
+            Type::Unresolved("point".to_string()),
+            Type::Unresolved("point".to_string())
+        ]
+    );
+
+    assert_eq!(function("example_name").return_type, Type::String);
+    assert!(function("example_log").args.ellipsis_present);
+    assert_eq!(function("example_long").return_type, Type::I64);
+
+    // The functions in `#if 0` blocks are not declared
+    assert!(
+        !bindings
+            .functions
+            .iter()
+            .any(|signature| signature.name == "disabled_function")
+    );
+
+    // Unions have no Fog equivalent
+    assert!(
+        bindings
+            .skipped
+            .iter()
+            .any(|(name, _reason)| name == "example_union")
+    );
+}
+
+#[test]
+fn maps_long_by_the_data_model_of_the_target()
+{
+    let bindings = example_bindings("x86_64-pc-windows-msvc");
+
+    let example_long = bindings
+        .functions
+        .iter()
+        .find(|signature| signature.name == "example_long")
+        .unwrap();
+
+    assert_eq!(example_long.return_type, Type::I32);
+}
+
+#[test]
+fn writes_enums_with_their_values()
+{
+    let source = example_bindings("x86_64-unknown-linux-gnu").to_fog_source("example.h", "example");
+
+    assert!(source.contains("    BLUE = 6,"), "{source}");
+    assert!(source.contains("    BETA = -1,"), "{source}");
+    assert!(source.contains("    EXAMPLE_NEXT = 65,"), "{source}");
+}
+
+#[test]
+fn keeps_the_formatting_of_the_config_file()
+{
+    let config_file = r#"# The name of the project
+name = "example"
+is_library = false # Built as an executable
+additional_linking_material = ["libs/existing.a"]
+
+[dependencies]
+"#;
+
+    let config_file = add_linking_material_to_config(
+        config_file,
+        &[
+            PathBuf::from("libs/existing.a"),
+            PathBuf::from("libs/example.a"),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        config_file,
+        r#"# The name of the project
+name = "example"
+is_library = false # Built as an executable
+additional_linking_material = ["libs/existing.a", "libs/example.a"]
+
+[dependencies]
+"#
+    );
+}
+
+#[test]
+fn adds_missing_linking_material_to_the_config_file()
+{
+    let config_file =
+        add_linking_material_to_config("name = \"example\"\n", &[PathBuf::from("libs/example.a")])
+            .unwrap();
+
+    assert_eq!(
+        config_file,
+        "name = \"example\"\nadditional_linking_material = [\"libs/example.a\"]\n"
+    );
+}
@@ -0,0 +1,57 @@
+#ifndef EXAMPLE_H
+#define EXAMPLE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define EXAMPLE_VERSION 3
+#define EXAMPLE_FLAGS (1 << 4)
+#define EXAMPLE_MIN(a, b) ((a) < (b) ? (a) : (b))
+
+#if 0
+int disabled_function(void);
+#endif
+
+/* A point on a plane */
+typedef struct point {
+    int x;
+    int y;
+} point;
+
+struct buffer {
+    uint8_t *data;
+    size_t length;
+    double scale;
+};
+
+enum color {
+    RED,
+    GREEN = 5,
+    BLUE,
+    ALPHA = -2,
+    BETA,
+};
+
+typedef enum {
+    MODE_READ = 1 << 0,
+    MODE_WRITE = 1 << 1,
+    MODE_APPEND,
+} mode;
+
+enum {
+    EXAMPLE_MAX = 64,
+    EXAMPLE_NEXT,
+};
+
+union value {
+    int i;
+    float f;
+};
+
+int point_distance(point a, point b);
+const char *example_name(void);
+void example_log(const char *format, ...);
+long example_long(unsigned long value);
+void example_union(union value value);
+
+#endif
@@ -0,0 +1,609 @@
+use common::{
+    artifact::{CONFIG_FILE_NAME, deps_dir},
+    compiler::ProjectConfig,
+    dependency::{DependencyInfo, RegistryStatus, VersionStatus, construct_dependency_path},
+    error::dependency::DependencyError,
+    lockfile::DependencySource,
+    semver::Version,
+    toml,
+};
+use imports::{
+    resolver::{
+        VersionRequirement, lock_dependencies, parse_version_requirement, resolve_dependencies,
+        select_version,
+    },
+    tree::render_dependency_tree,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn requirement(required_by: &str, requirement: &str) -> VersionRequirement
+{
+    VersionRequirement {
+        required_by: required_by.to_string(),
+        requirement: parse_version_requirement("dependency", requirement).unwrap(),
+    }
+}
+
+fn available(versions: &[&str]) -> BTreeMap<Version, PathBuf>
+{
+    versions
+        .iter()
+        .map(|version| (Version::parse(version).unwrap(), PathBuf::from(version)))
+        .collect()
+}
+
+fn dependencies(requirements: &[(&str, &str)]) -> HashMap<String, DependencyInfo>
+{
+    requirements
+        .iter()
+        .map(|(name, version)| {
+            (
+                name.to_string(),
+                DependencyInfo {
+                    version: version.to_string(),
+                    features: Vec::new(),
+                    remote: None,
+                    path: None,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Writes a library into the dependencies folder of `project_root`.
+fn write_library(project_root: &Path, name: &str, version: &str, requirements: &[(&str, &str)])
+{
+    let library_root = construct_dependency_path(
+        deps_dir(project_root),
+        name.to_string(),
+        version.to_string(),
+    );
+
+    fs::create_dir_all(&library_root).unwrap();
+
+    let config = ProjectConfig {
+        is_library: true,
+        version: version.to_string(),
+        dependencies: dependencies(requirements),
+        ..ProjectConfig::new_from_name(name.to_string())
+    };
+
+    fs::write(
+        library_root.join(CONFIG_FILE_NAME),
+        toml::to_string(&config).unwrap(),
+    )
+    .unwrap();
+}
+
+fn create_project(name: &str) -> PathBuf
+{
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(deps_dir(&root)).unwrap();
+
+    root
+}
+
+#[test]
+fn selects_newest_matching_version()
+{
+    let versions = available(&[
+        "0.3.0", "0.3.4", "0.4.0", "1.1.0", "1.2.5", "1.9.0", "2.0.0",
+    ]);
+
+    let select = |requirements: &[VersionRequirement]| {
+        select_version("dependency", requirements, &versions)
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(select(&[requirement("project", "^1.2")]), "1.9.0");
+    assert_eq!(select(&[requirement("project", "~0.3")]), "0.3.4");
+    assert_eq!(select(&[requirement("project", ">=1, <2")]), "1.9.0");
+    assert_eq!(
+        select(&[
+            requirement("project", "^1.2"),
+            requirement("library", "<1.5")
+        ]),
+        "1.2.5"
+    );
+}
+
+#[test]
+fn reports_unmatched_and_conflicting_requirements()
+{
+    let versions = available(&["0.3.0", "1.2.0"]);
+
+    assert!(matches!(
+        select_version("dependency", &[requirement("project", "^2")], &versions),
+        Err(DependencyError::NoMatchingVersion(..))
+    ));
+
+    match select_version(
+        "dependency",
+        &[
+            requirement("project", "^1.2"),
+            requirement("library", "~0.3"),
+        ],
+        &versions,
+    ) {
+        Err(DependencyError::VersionConflict(dependency, requirements)) => {
+            assert_eq!(dependency, "dependency");
+            assert!(requirements.contains("`project` requires `^1.2`"));
+            assert!(requirements.contains("`library` requires `~0.3`"));
+        },
+        result => panic!("Expected a version conflict, got: {result:?}"),
+    }
+
+    assert!(matches!(
+        parse_version_requirement("dependency", "one point two"),
+        Err(DependencyError::InvalidVersionRequirement(..))
+    ));
+}
+
+#[test]
+fn resolves_dependencies_of_dependencies()
+{
+    let root = create_project("resolver_transitive");
+
+    write_library(&root, "math", "1.0.0", &[]);
+    write_library(&root, "math", "1.4.0", &[]);
+    write_library(&root, "math", "2.0.0", &[]);
+    write_library(&root, "geometry", "0.3.1", &[("math", ">=1.2, <2")]);
+
+    let library_root =
+        construct_dependency_path(deps_dir(&root), "geometry".to_string(), "0.3.1".to_string());
+
+    // The library's own dependencies folder can provide versions as well
+    write_library(&library_root, "math", "1.5.0", &[]);
+
+    let config = ProjectConfig {
+        dependencies: dependencies(&[("math", "^1"), ("geometry", "~0.3")]),
+        ..Default::default()
+    };
+
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        None,
+        &RegistryStatus::default(),
+    )
+    .unwrap();
+
+    assert_eq!(resolved["geometry"].version.to_string(), "0.3.1");
+    assert_eq!(resolved["math"].version.to_string(), "1.5.0");
+    assert_eq!(
+        resolved["math"].path,
+        construct_dependency_path(
+            deps_dir(&library_root),
+            "math".to_string(),
+            "1.5.0".to_string()
+        )
+    );
+}
+
+#[test]
+fn enables_dependency_features_of_the_project_features()
+{
+    let root = create_project("resolver_dependency_features");
+
+    write_library(&root, "math", "1.0.0", &[]);
+
+    let mut config = ProjectConfig {
+        dependencies: dependencies(&[("math", "^1")]),
+        ..Default::default()
+    };
+
+    config.dependencies.get_mut("math").unwrap().features = vec!["simd".to_string()];
+
+    // Enabled by a feature of the project, ie. `fast = ["math/unchecked"]`
+    let dependency_features = BTreeMap::from([(
+        "math".to_string(),
+        BTreeSet::from(["unchecked".to_string()]),
+    )]);
+
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &dependency_features,
+        None,
+        &RegistryStatus::default(),
+    )
+    .unwrap();
+
+    assert_eq!(resolved["math"].features, ["simd", "unchecked"]);
+
+    let lock_file = lock_dependencies(&root, &resolved, None).unwrap();
+
+    assert_eq!(
+        lock_file.get("math").unwrap().features,
+        ["simd", "unchecked"]
+    );
+}
+
+#[test]
+fn reports_conflicts_between_dependencies()
+{
+    let root = create_project("resolver_conflict");
+
+    write_library(&root, "math", "1.4.0", &[]);
+    write_library(&root, "math", "2.0.0", &[]);
+    write_library(&root, "geometry", "0.3.1", &[("math", "^2")]);
+
+    let config = ProjectConfig {
+        dependencies: dependencies(&[("math", "^1.2"), ("geometry", "~0.3")]),
+        ..Default::default()
+    };
+
+    let error = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        None,
+        &RegistryStatus::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<DependencyError>(),
+        Some(DependencyError::VersionConflict(..))
+    ));
+}
+
+#[test]
+fn keeps_locked_versions()
+{
+    let root = create_project("resolver_locked");
+
+    write_library(&root, "math", "1.0.0", &[]);
+
+    let config = ProjectConfig {
+        dependencies: dependencies(&[("math", "^1")]),
+        ..Default::default()
+    };
+
+    let lock_file = lock_dependencies(
+        &root,
+        &resolve_dependencies(
+            &root,
+            &config,
+            &BTreeMap::new(),
+            None,
+            &RegistryStatus::default(),
+        )
+        .unwrap(),
+        None,
+    )
+    .unwrap();
+
+    let locked_math = lock_file.get("math").unwrap();
+
+    assert_eq!(locked_math.version, "1.0.0");
+    assert_eq!(
+        locked_math.source,
+        DependencySource::Path("deps/math(1.0.0)".into())
+    );
+
+    // A newer version does not replace the locked version, as long as it matches the requirement
+    write_library(&root, "math", "1.4.0", &[]);
+
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        Some(&lock_file),
+        &RegistryStatus::default(),
+    )
+    .unwrap();
+
+    assert_eq!(resolved["math"].version.to_string(), "1.0.0");
+    assert_eq!(
+        lock_dependencies(&root, &resolved, None).unwrap(),
+        lock_file
+    );
+
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        None,
+        &RegistryStatus::default(),
+    )
+    .unwrap();
+
+    assert_eq!(resolved["math"].version.to_string(), "1.4.0");
+
+    // Changing the files of the dependency changes its checksum
+    fs::write(
+        construct_dependency_path(deps_dir(&root), "math".to_string(), "1.0.0".to_string())
+            .join("main.f"),
+        "",
+    )
+    .unwrap();
+
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        Some(&lock_file),
+        &RegistryStatus::default(),
+    )
+    .unwrap();
+
+    assert_ne!(
+        lock_dependencies(&root, &resolved, None)
+            .unwrap()
+            .get("math")
+            .unwrap()
+            .checksum,
+        locked_math.checksum
+    );
+}
+
+#[test]
+fn locks_registry_dependencies_with_their_registry()
+{
+    let root = create_project("resolver_registry");
+    let shared_root = create_project("resolver_registry_shared");
+
+    fs::write(
+        shared_root.join(CONFIG_FILE_NAME),
+        toml::to_string(&ProjectConfig {
+            is_library: true,
+            ..ProjectConfig::new_from_name("shared".to_string())
+        })
+        .unwrap(),
+    )
+    .unwrap();
+
+    write_library(&root, "math", "1.0.0", &[]);
+
+    let mut config = ProjectConfig {
+        dependencies: dependencies(&[("math", "^1"), ("shared", "*")]),
+        ..Default::default()
+    };
+
+    config.dependencies.get_mut("shared").unwrap().path =
+        Some("../resolver_registry_shared".into());
+
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        None,
+        &RegistryStatus::default(),
+    )
+    .unwrap();
+
+    let lock_file = lock_dependencies(&root, &resolved, Some("https://registry.example/")).unwrap();
+    let locked_math = lock_file.get("math").unwrap();
+
+    assert_eq!(
+        locked_math.source,
+        DependencySource::Registry("https://registry.example".to_string())
+    );
+    assert!(locked_math.checksum.is_some());
+    assert_eq!(locked_math.source_checksum, None);
+
+    // Path dependencies are locked with their path even if the project has a registry
+    assert_eq!(
+        lock_file.get("shared").unwrap().source,
+        DependencySource::Path("../resolver_registry_shared".into())
+    );
+}
+
+#[test]
+fn resolves_path_dependencies()
+{
+    let root = create_project("resolver_path");
+    let shared_root = create_project("resolver_path_shared");
+
+    let shared_config = ProjectConfig {
+        is_library: true,
+        version: "0.2.0".to_string(),
+        dependencies: dependencies(&[("math", "^1")]),
+        ..ProjectConfig::new_from_name("shared".to_string())
+    };
+
+    fs::write(
+        shared_root.join(CONFIG_FILE_NAME),
+        toml::to_string(&shared_config).unwrap(),
+    )
+    .unwrap();
+
+    // The dependencies of a path dependency are looked up in its own dependencies folder
+    write_library(&shared_root, "math", "1.1.0", &[]);
+
+    // Versions in the dependencies folder are not used for path dependencies
+    write_library(&root, "shared", "0.3.0", &[]);
+
+    let mut config = ProjectConfig {
+        dependencies: dependencies(&[("shared", "~0.2")]),
+        ..Default::default()
+    };
+
+    config.dependencies.get_mut("shared").unwrap().path = Some("../resolver_path_shared".into());
+
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        None,
+        &RegistryStatus::default(),
+    )
+    .unwrap();
+
+    assert!(resolved["shared"].is_path_dependency);
+    assert_eq!(resolved["shared"].version.to_string(), "0.2.0");
+    assert_eq!(resolved["math"].version.to_string(), "1.1.0");
+
+    let lock_file = lock_dependencies(&root, &resolved, None).unwrap();
+    let locked_shared = lock_file.get("shared").unwrap();
+
+    assert_eq!(
+        locked_shared.source,
+        DependencySource::Path("../resolver_path_shared".into())
+    );
+    assert_eq!(locked_shared.checksum, None);
+
+    // Changes to the path dependency do not change the lock file
+    fs::write(shared_root.join("notes.txt"), "changed").unwrap();
+
+    assert_eq!(
+        lock_dependencies(
+            &root,
+            &resolve_dependencies(
+                &root,
+                &config,
+                &BTreeMap::new(),
+                Some(&lock_file),
+                &RegistryStatus::default()
+            )
+            .unwrap(),
+            None,
+        )
+        .unwrap(),
+        lock_file
+    );
+
+    // The version of the path dependency must still match the requirement
+    config.dependencies.get_mut("shared").unwrap().version = "^1".to_string();
+
+    let error = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        None,
+        &RegistryStatus::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<DependencyError>(),
+        Some(DependencyError::NoMatchingVersion(..))
+    ));
+}
+
+#[test]
+fn renders_dependency_tree()
+{
+    let root = create_project("resolver_tree");
+
+    write_library(&root, "math", "1.4.0", &[]);
+    write_library(&root, "geometry", "0.3.1", &[("math", ">=1.2, <2")]);
+
+    let config = ProjectConfig {
+        dependencies: dependencies(&[("math", "^1"), ("geometry", "~0.3")]),
+        ..Default::default()
+    };
+
+    let tree = render_dependency_tree(
+        &config,
+        &resolve_dependencies(
+            &root,
+            &config,
+            &BTreeMap::new(),
+            None,
+            &RegistryStatus::default(),
+        )
+        .unwrap(),
+    );
+
+    assert_eq!(
+        tree,
+        "project v0.0.1
+├── geometry v0.3.1
+│   └── math v1.4.0
+└── math v1.4.0 (*)
+
+Dependencies required by multiple projects:
+math is required by `geometry` (>=1.2, <2), `project` (^1)
+"
+    );
+}
+
+#[test]
+fn skips_yanked_versions_unless_locked()
+{
+    let root = create_project("resolver_yanked");
+
+    write_library(&root, "math", "1.0.0", &[]);
+    write_library(&root, "math", "1.4.0", &[]);
+
+    let config = ProjectConfig {
+        dependencies: dependencies(&[("math", "^1")]),
+        ..Default::default()
+    };
+
+    let lock_file = lock_dependencies(
+        &root,
+        &resolve_dependencies(
+            &root,
+            &config,
+            &BTreeMap::new(),
+            None,
+            &RegistryStatus::default(),
+        )
+        .unwrap(),
+        None,
+    )
+    .unwrap();
+
+    let mut registry_status = RegistryStatus::default();
+
+    registry_status.insert(
+        "math",
+        "1.4.0".to_string(),
+        VersionStatus {
+            yanked: true,
+            deprecation: Some("Use `geometry` instead".to_string()),
+        },
+    );
+
+    // The locked version is kept even though it has been yanked
+    let resolved = resolve_dependencies(
+        &root,
+        &config,
+        &BTreeMap::new(),
+        Some(&lock_file),
+        &registry_status,
+    )
+    .unwrap();
+
+    assert_eq!(resolved["math"].version.to_string(), "1.4.0");
+    assert!(resolved["math"].status.yanked);
+    assert_eq!(
+        render_dependency_tree(&config, &resolved),
+        "project v0.0.1\n└── math v1.4.0 (yanked) (deprecated: Use `geometry` instead)\n"
+    );
+
+    let resolved =
+        resolve_dependencies(&root, &config, &BTreeMap::new(), None, &registry_status).unwrap();
+
+    assert_eq!(resolved["math"].version.to_string(), "1.0.0");
+    assert_eq!(resolved["math"].status, VersionStatus::default());
+
+    registry_status.insert(
+        "math",
+        "1.0.0".to_string(),
+        VersionStatus {
+            yanked: true,
+            deprecation: None,
+        },
+    );
+
+    let error =
+        resolve_dependencies(&root, &config, &BTreeMap::new(), None, &registry_status).unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<DependencyError>(),
+        Some(DependencyError::MatchingVersionsYanked(..))
+    ));
+}
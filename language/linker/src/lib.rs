@@ -1,61 +1,229 @@
 #![feature(iterator_try_collect)]
 
-use common::{error::linker::LinkerError, linker::BuildManifest, toml, tracing::info};
+use common::{
+    artifact::{ObjectFormat, OutputKind, host_triple},
+    error::linker::LinkerError,
+    linker::{BuildManifest, BuildOutput, Linker},
+    tracing::info,
+};
 use std::{
     env, fs,
+    io::ErrorKind,
     path::PathBuf,
     process::{Command, Output},
 };
 
+use crate::lld::{LldFlavor, lld_args, lld_command};
+
+/// Linking with LLD directly, without a compiler driver.
+pub mod lld;
+
+/// Links the build outputs of the manifest at the path.
+/// The manifest is refused if it has been created by a different compiler, or if its build artifacts have changed since.
 pub fn link_from_manifest(build_manifest_path: PathBuf) -> Result<(), LinkerError>
 {
-    host_information()?;
-
     println!("[Build manifest path]: {}", build_manifest_path.display());
 
-    // Check if the file exists and read into a string
-    let build_manifest =
-        fs::read_to_string(build_manifest_path).map_err(|err| LinkerError::Other(Box::new(err)))?;
+    let build_manifest = BuildManifest::read(&build_manifest_path)?;
+
+    build_manifest.verify(None)?;
 
-    let build_manifest = toml::from_str::<BuildManifest>(&build_manifest)
-        .map_err(|_| LinkerError::InvalidManifestFormat)?;
+    host_information(&build_manifest.linker, &build_manifest.target_triple)?;
 
     link(&build_manifest)?;
 
-    println!(
-        "Linking finished output located at: {}",
-        build_manifest.output_path.display()
-    );
+    for output in &build_manifest.outputs {
+        println!(
+            "Linking finished output located at: {}",
+            output.path.display()
+        );
+    }
 
     Ok(())
 }
 
-pub fn host_information() -> Result<(), LinkerError>
+/// Prints the version of the linker which would be used to link for the target.
+/// If no manifest is available the host's triple can be passed in.
+pub fn host_information(linker: &Linker, target_triple: &str) -> Result<(), LinkerError>
 {
     println!("Fog linker [Build version: {}]", env!("CARGO_PKG_VERSION"));
 
-    match Command::new("clang").arg("--version").output() {
-        Ok(output) => {
-            println!("{}", String::from_utf8_lossy(&output.stdout));
+    let mut command = linker_command(linker, target_triple);
+
+    let output = command
+        .arg("--version")
+        .output()
+        .map_err(|err| linker_spawn_error(linker, &command, err))?;
+
+    println!("{}", String::from_utf8_lossy(&output.stdout));
+
+    Ok(())
+}
+
+/// Prints the version of the default linker for the host.
+pub fn default_host_information() -> Result<(), LinkerError>
+{
+    host_information(&Linker::default(), &host_triple())
+}
+
+/// Links the build outputs of the manifest into every output of the manifest.
+pub fn link(build_manifest: &BuildManifest) -> Result<(), LinkerError>
+{
+    let inputs = linker_inputs(build_manifest)?;
+
+    for output in &build_manifest.outputs {
+        info!("Linking `{}`...", output.path.display());
+
+        // Executables are placed in a subfolder of the build folder
+        if let Some(output_dir) = output.path.parent() {
+            fs::create_dir_all(output_dir).map_err(|err| LinkerError::Other(Box::new(err)))?;
+        }
+
+        let process_output = match output.kind {
+            OutputKind::StaticLibrary => archive(build_manifest, output)?,
+            OutputKind::Executable | OutputKind::SharedLibrary => {
+                link_output(build_manifest, output, inputs.clone())?
+            },
+        };
+
+        if !process_output.status.success() {
+            return Err(LinkerError::LinkingFailed(
+                output.path.clone(),
+                String::from_utf8_lossy(&process_output.stderr).into_owned(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Links an executable or a shared library with the linker set in the manifest.
+fn link_output(
+    build_manifest: &BuildManifest,
+    output: &BuildOutput,
+    inputs: Vec<String>,
+) -> Result<Output, LinkerError>
+{
+    let mut command = linker_command(&build_manifest.linker, &build_manifest.target_triple);
+
+    match &build_manifest.linker {
+        Linker::Clang => {
+            command.args(clang_args(build_manifest, output, inputs)?);
+        },
+        Linker::Lld | Linker::Path(_) => {
+            // LLD reads bitcode inputs on its own, so LTO needs no additional arguments
+            command.args(lld_args(build_manifest, output, inputs)?);
+        },
+    }
+
+    command
+        .output()
+        .map_err(|err| linker_spawn_error(&build_manifest.linker, &command, err))
+}
+
+/// Creates a static library from the build outputs with `llvm-ar`.
+/// The additional linking material is not added to the archive, it must be linked separately.
+fn archive(build_manifest: &BuildManifest, output: &BuildOutput) -> Result<Output, LinkerError>
+{
+    let format = match ObjectFormat::from_triple(&build_manifest.target_triple) {
+        ObjectFormat::Elf => "gnu",
+        ObjectFormat::MachO => "darwin",
+        ObjectFormat::Coff => "coff",
+    };
+
+    // `llvm-ar` adds the files to an existing archive, so the archive of the previous build is removed
+    let _ = fs::remove_file(&output.path);
+
+    Command::new("llvm-ar")
+        .arg(format!("--format={format}"))
+        .arg("rcs")
+        .arg(&output.path)
+        .args(&build_manifest.build_output_paths)
+        .output()
+        .map_err(|err| {
+            if err.kind() == ErrorKind::NotFound {
+                LinkerError::ArchiverNotFound
+            }
+            else {
+                LinkerError::Other(Box::new(err))
+            }
+        })
+}
+
+/// Writes the version script exporting only the symbols listed, next to the shared library.
+/// This is used for ELF targets, where every symbol would be exported otherwise.
+pub(crate) fn write_version_script(
+    output: &BuildOutput,
+    exported_symbols: &[String],
+) -> Result<PathBuf, LinkerError>
+{
+    let script_path = output.path.with_extension("exports");
+
+    let mut script = String::from("{\n");
+
+    if !exported_symbols.is_empty() {
+        script.push_str("  global:\n");
+
+        for symbol in exported_symbols {
+            script.push_str(&format!("    {symbol};\n"));
+        }
+    }
+
+    script.push_str("  local: *;\n};\n");
+
+    fs::write(&script_path, script).map_err(|err| LinkerError::Other(Box::new(err)))?;
+
+    Ok(script_path)
+}
+
+/// Returns the file name of the output, this is used as the name shared libraries are loaded by.
+pub(crate) fn output_file_name(output: &BuildOutput) -> String
+{
+    output
+        .path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
 
-            Ok(())
+/// Creates the command invoking the linker, without any arguments.
+fn linker_command(linker: &Linker, target_triple: &str) -> Command
+{
+    let flavor = LldFlavor::from_format(ObjectFormat::from_triple(target_triple));
+
+    match linker {
+        Linker::Clang => Command::new("clang"),
+        Linker::Lld => lld_command(None, flavor),
+        Linker::Path(path) => lld_command(Some(path.as_path()), flavor),
+    }
+}
+
+fn linker_spawn_error(linker: &Linker, command: &Command, err: std::io::Error) -> LinkerError
+{
+    match linker {
+        Linker::Clang if err.kind() == ErrorKind::NotFound => LinkerError::ClangNotFound,
+        Linker::Clang => LinkerError::ClangError(Box::new(err)),
+        _ if err.kind() == ErrorKind::NotFound => {
+            LinkerError::LinkerNotFound(command.get_program().to_string_lossy().into_owned())
         },
-        Err(_e) => Err(LinkerError::ClangNotFound),
+        _ => LinkerError::Other(Box::new(err)),
     }
 }
 
-pub fn link(build_manifest: &BuildManifest) -> Result<Output, LinkerError>
+/// Returns the paths of the build outputs and the additional linking material.
+fn linker_inputs(build_manifest: &BuildManifest) -> Result<Vec<String>, LinkerError>
 {
-    let mut args: Vec<String> = Vec::new();
+    let mut inputs: Vec<String> = Vec::new();
 
-    args.extend(
+    inputs.extend(
         build_manifest
             .build_output_paths
             .iter()
             .map(|p| p.display().to_string()),
     );
 
-    args.extend(
+    inputs.extend(
         build_manifest
             .additional_linking_material
             .iter()
@@ -70,15 +238,61 @@ pub fn link(build_manifest: &BuildManifest) -> Result<Output, LinkerError>
             .try_collect::<Vec<String>>()?,
     );
 
-    args.push("-o".to_string());
-    args.push(build_manifest.output_path.display().to_string());
+    Ok(inputs)
+}
+
+fn clang_args(
+    build_manifest: &BuildManifest,
+    output: &BuildOutput,
+    inputs: Vec<String>,
+) -> Result<Vec<String>, LinkerError>
+{
+    let mut args = inputs;
 
-    info!("Linking...");
+    args.push(format!("--target={}", build_manifest.target_triple));
 
-    let clang_out = Command::new("clang")
-        .args(args.iter())
-        .output()
-        .map_err(|err| LinkerError::ClangError(Box::new(err)))?;
+    // LTO requires a linker which can read bitcode
+    if let Some(lto) = build_manifest.lto {
+        args.push(format!("-flto={lto}"));
+        args.push("-fuse-ld=lld".to_string());
+    }
+
+    if output.kind == OutputKind::SharedLibrary {
+        let exported_symbols = &build_manifest.exported_symbols;
+
+        match ObjectFormat::from_triple(&build_manifest.target_triple) {
+            ObjectFormat::Elf => {
+                let version_script = write_version_script(output, exported_symbols)?;
+
+                args.push("-shared".to_string());
+                args.push(format!("-Wl,-soname,{}", output_file_name(output)));
+                args.push(format!("-Wl,--version-script={}", version_script.display()));
+            },
+            ObjectFormat::MachO => {
+                args.push("-dynamiclib".to_string());
+                args.push(format!(
+                    "-Wl,-install_name,@rpath/{}",
+                    output_file_name(output)
+                ));
+                args.extend(
+                    exported_symbols
+                        .iter()
+                        .map(|symbol| format!("-Wl,-exported_symbol,_{symbol}")),
+                );
+            },
+            ObjectFormat::Coff => {
+                args.push("-shared".to_string());
+                args.extend(
+                    exported_symbols
+                        .iter()
+                        .map(|symbol| format!("-Wl,/EXPORT:{symbol}")),
+                );
+            },
+        }
+    }
+
+    args.push("-o".to_string());
+    args.push(output.path.display().to_string());
 
-    Ok(clang_out)
+    Ok(args)
 }
@@ -0,0 +1,456 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use common::{
+    artifact::{ObjectFormat, OutputKind, TripleComponents},
+    error::linker::LinkerError,
+    linker::{BuildManifest, BuildOutput},
+    tracing::warn,
+};
+
+use crate::{output_file_name, write_version_script};
+
+/// The flavours of LLD, each of them links a different object file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LldFlavor
+{
+    /// ELF, `ld.lld`.
+    Gnu,
+    /// PE/COFF, `lld-link`.
+    Link,
+    /// Mach-O, `ld64.lld`.
+    Darwin,
+}
+
+impl LldFlavor
+{
+    pub fn from_format(format: ObjectFormat) -> Self
+    {
+        match format {
+            ObjectFormat::Elf => Self::Gnu,
+            ObjectFormat::Coff => Self::Link,
+            ObjectFormat::MachO => Self::Darwin,
+        }
+    }
+
+    /// The name LLD is installed under for this flavour.
+    pub fn executable_name(&self) -> &'static str
+    {
+        match self {
+            Self::Gnu => "ld.lld",
+            Self::Link => "lld-link",
+            Self::Darwin => "ld64.lld",
+        }
+    }
+
+    /// The value of LLD's `-flavor` argument.
+    pub fn flavor_name(&self) -> &'static str
+    {
+        match self {
+            Self::Gnu => "gnu",
+            Self::Link => "link",
+            Self::Darwin => "darwin",
+        }
+    }
+}
+
+/// Creates the command invoking LLD. If `lld_path` is set, the executable at the path is invoked.
+/// The flavour is passed in explicitly only if the executable is the generic `lld` driver, flavour specific executables (ie. `ld.lld`) and other linkers do not accept the argument.
+pub fn lld_command(lld_path: Option<&Path>, flavor: LldFlavor) -> Command
+{
+    match lld_path {
+        Some(lld_path) => {
+            let mut command = Command::new(lld_path);
+
+            if is_generic_lld(lld_path) {
+                command.args(["-flavor", flavor.flavor_name()]);
+            }
+
+            command
+        },
+        None => Command::new(flavor.executable_name()),
+    }
+}
+
+/// Returns whether the executable at the path is the generic `lld` driver, which requires the flavour to be selected with `-flavor`.
+fn is_generic_lld(path: &Path) -> bool
+{
+    path.file_stem().is_some_and(|stem| stem.eq_ignore_ascii_case("lld"))
+}
+
+/// Creates the LLD arguments linking `inputs` into the output of the manifest, for the target of the manifest.
+/// The C runtime's startup objects and libraries are added to the arguments, as LLD does not add them on its own.
+pub fn lld_args(
+    build_manifest: &BuildManifest,
+    output: &BuildOutput,
+    inputs: Vec<String>,
+) -> Result<Vec<String>, LinkerError>
+{
+    match LldFlavor::from_format(ObjectFormat::from_triple(&build_manifest.target_triple)) {
+        LldFlavor::Gnu => elf_args(build_manifest, output, inputs),
+        LldFlavor::Link => coff_args(build_manifest, output, inputs),
+        LldFlavor::Darwin => mach_o_args(build_manifest, output, inputs),
+    }
+}
+
+fn elf_args(
+    build_manifest: &BuildManifest,
+    output: &BuildOutput,
+    inputs: Vec<String>,
+) -> Result<Vec<String>, LinkerError>
+{
+    let is_shared = output.kind == OutputKind::SharedLibrary;
+
+    let target_triple = &build_manifest.target_triple;
+    let triple = TripleComponents::parse(target_triple);
+
+    // Android runs on Linux, but it has its own runtime which is not supported
+    let (dynamic_linker, mut search_paths) = if triple.normalized_os() == "linux" {
+        linux_runtime(&triple)
+            .ok_or_else(|| LinkerError::UnsupportedLldTarget(target_triple.clone()))?
+    }
+    else if triple.normalized_os() == "freebsd" {
+        (
+            "/libexec/ld-elf.so.1".to_string(),
+            vec![PathBuf::from("/usr/lib")],
+        )
+    }
+    else {
+        return Err(LinkerError::UnsupportedLldTarget(target_triple.clone()));
+    };
+
+    // Paths set by the user are searched first
+    if let Some(library_path) = env::var_os("LIBRARY_PATH") {
+        search_paths.splice(0..0, env::split_paths(&library_path));
+    }
+
+    let find_crt_object = |name: &str| {
+        search_paths
+            .iter()
+            .map(|path| path.join(name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| LinkerError::CrtObjectNotFound(name.to_string(), target_triple.clone()))
+    };
+
+    let crti = find_crt_object("crti.o")?;
+    let crtn = find_crt_object("crtn.o")?;
+
+    // `crtbegin.o` and `crtend.o` are shipped with the compiler runtime (libgcc), they are not available everywhere
+    let compiler_runtime_dir = find_gcc_runtime_dir(&triple, target_triple);
+
+    if compiler_runtime_dir.is_none() {
+        warn!(
+            "The compiler runtime (`crtbegin.o`, `crtend.o`, `libgcc`) has not been found for `{target_triple}`, linking without it."
+        );
+    }
+
+    // Shared libraries use the position independent variants of the compiler runtime's objects
+    let (crtbegin, crtend) = if is_shared {
+        ("crtbeginS.o", "crtendS.o")
+    }
+    else {
+        ("crtbegin.o", "crtend.o")
+    };
+
+    let mut args = vec![
+        "--eh-frame-hdr".to_string(),
+        "-o".to_string(),
+        output.path.display().to_string(),
+    ];
+
+    if is_shared {
+        let version_script = write_version_script(output, &build_manifest.exported_symbols)?;
+
+        args.push("-shared".to_string());
+        args.push("-soname".to_string());
+        args.push(output_file_name(output));
+        args.push(format!("--version-script={}", version_script.display()));
+    }
+    else {
+        // Only executables are started by the C runtime
+        args.push("--dynamic-linker".to_string());
+        args.push(dynamic_linker);
+        args.push(find_crt_object("crt1.o")?.display().to_string());
+    }
+
+    args.push(crti.display().to_string());
+
+    if let Some(runtime_dir) = &compiler_runtime_dir {
+        args.push(runtime_dir.join(crtbegin).display().to_string());
+        args.push(format!("-L{}", runtime_dir.display()));
+    }
+
+    args.extend(
+        search_paths
+            .iter()
+            .filter(|path| path.is_dir())
+            .map(|path| format!("-L{}", path.display())),
+    );
+
+    args.extend(inputs);
+
+    args.push("-lc".to_string());
+
+    if let Some(runtime_dir) = &compiler_runtime_dir {
+        args.push("-lgcc".to_string());
+        args.push(runtime_dir.join(crtend).display().to_string());
+    }
+
+    args.push(crtn.display().to_string());
+
+    Ok(args)
+}
+
+/// Returns the dynamic linker and the library search paths of a Linux target.
+/// Both glibc and musl are supported, the multiarch library folders follow Debian's naming.
+fn linux_runtime(triple: &TripleComponents) -> Option<(String, Vec<PathBuf>)>
+{
+    let is_musl = triple.env.starts_with("musl");
+
+    let multiarch_arch = if triple.is_x86() {
+        "i386"
+    }
+    else if triple.is_arm() {
+        "arm"
+    }
+    else {
+        triple.arch
+    };
+
+    let env = if triple.env.is_empty() {
+        "gnu"
+    }
+    else {
+        triple.env
+    };
+
+    let multiarch = format!("{multiarch_arch}-linux-{env}");
+
+    let dynamic_linker = if is_musl {
+        let musl_arch = if triple.is_x86() {
+            "i386"
+        }
+        else if triple.is_arm() {
+            if env.ends_with("hf") { "armhf" } else { "arm" }
+        }
+        else {
+            triple.arch
+        };
+
+        format!("/lib/ld-musl-{musl_arch}.so.1")
+    }
+    else {
+        match triple.arch {
+            "x86_64" => "/lib64/ld-linux-x86-64.so.2",
+            "aarch64" => "/lib/ld-linux-aarch64.so.1",
+            "riscv64" => "/lib/ld-linux-riscv64-lp64d.so.1",
+            "powerpc64le" => "/lib64/ld64.so.2",
+            "s390x" => "/lib/ld64.so.1",
+            _ if triple.is_x86() => "/lib/ld-linux.so.2",
+            _ if triple.is_arm() && env.ends_with("hf") => "/lib/ld-linux-armhf.so.3",
+            _ if triple.is_arm() => "/lib/ld-linux.so.3",
+            _ => return None,
+        }
+        .to_string()
+    };
+
+    let mut search_paths = vec![
+        PathBuf::from(format!("/usr/lib/{multiarch}")),
+        PathBuf::from(format!("/lib/{multiarch}")),
+    ];
+
+    if is_musl {
+        search_paths.push(PathBuf::from("/usr/lib/musl/lib"));
+        search_paths.push(PathBuf::from("/usr/local/musl/lib"));
+    }
+    else if triple.arch.ends_with("64") || triple.arch == "powerpc64le" {
+        search_paths.push(PathBuf::from("/usr/lib64"));
+        search_paths.push(PathBuf::from("/lib64"));
+    }
+
+    search_paths.push(PathBuf::from("/usr/lib"));
+    search_paths.push(PathBuf::from("/lib"));
+
+    Some((dynamic_linker, search_paths))
+}
+
+/// Searches for the folder of the newest GCC installation for the target, which contains `crtbegin.o`, `crtend.o` and `libgcc`.
+fn find_gcc_runtime_dir(triple: &TripleComponents, target_triple: &str) -> Option<PathBuf>
+{
+    let env = if triple.env.is_empty() {
+        "gnu"
+    }
+    else {
+        triple.env
+    };
+
+    let candidates = [
+        target_triple.to_string(),
+        format!("{}-linux-{env}", triple.arch),
+        format!("{}-pc-linux-{env}", triple.arch),
+        format!("{}-redhat-linux", triple.arch),
+    ];
+
+    for lib_dir in ["/usr/lib/gcc", "/usr/lib64/gcc"] {
+        for candidate in &candidates {
+            let Ok(entries) = fs::read_dir(Path::new(lib_dir).join(candidate))
+            else {
+                continue;
+            };
+
+            // Pick the newest version of the installed GCC versions
+            let newest = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.join("crtbegin.o").is_file())
+                .max_by_key(|path| {
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .split('.')
+                        .map(|part| part.parse::<u32>().unwrap_or_default())
+                        .collect::<Vec<u32>>()
+                });
+
+            if newest.is_some() {
+                return newest;
+            }
+        }
+    }
+
+    None
+}
+
+fn coff_args(
+    build_manifest: &BuildManifest,
+    output: &BuildOutput,
+    inputs: Vec<String>,
+) -> Result<Vec<String>, LinkerError>
+{
+    let target_triple = &build_manifest.target_triple;
+    let triple = TripleComponents::parse(target_triple);
+
+    // MinGW targets use the GNU runtime, which LLD's COFF flavour cannot link on its own
+    if triple.is_mingw() || (triple.env != "msvc" && !triple.env.is_empty()) {
+        return Err(LinkerError::UnsupportedLldTarget(target_triple.clone()));
+    }
+
+    let machine = match triple.arch {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ if triple.is_x86() => "x86",
+        _ if triple.is_arm() => "arm",
+        _ => return Err(LinkerError::UnsupportedLldTarget(target_triple.clone())),
+    };
+
+    let mut args = vec![
+        "/nologo".to_string(),
+        "/subsystem:console".to_string(),
+        format!("/machine:{machine}"),
+        format!("/out:{}", output.path.display()),
+    ];
+
+    if output.kind == OutputKind::SharedLibrary {
+        args.push("/dll".to_string());
+        args.extend(
+            build_manifest
+                .exported_symbols
+                .iter()
+                .map(|symbol| format!("/export:{symbol}")),
+        );
+    }
+
+    // The folders of the Windows SDK and the MSVC runtime, as set by the developer command prompt
+    if let Some(lib_paths) = env::var_os("LIB") {
+        args.extend(
+            env::split_paths(&lib_paths).map(|path| format!("/libpath:{}", path.display())),
+        );
+    }
+    else {
+        warn!("`LIB` is not set, the MSVC runtime libraries may not be found by `lld-link`.");
+    }
+
+    args.extend(inputs);
+
+    // The static MSVC runtime provides the `mainCRTStartup` entrypoint calling `main`
+    args.push("/defaultlib:libcmt".to_string());
+    args.push("/defaultlib:oldnames".to_string());
+
+    Ok(args)
+}
+
+fn mach_o_args(
+    build_manifest: &BuildManifest,
+    output: &BuildOutput,
+    inputs: Vec<String>,
+) -> Result<Vec<String>, LinkerError>
+{
+    let target_triple = &build_manifest.target_triple;
+    let triple = TripleComponents::parse(target_triple);
+
+    if triple.normalized_os() != "macos" {
+        return Err(LinkerError::UnsupportedLldTarget(target_triple.clone()));
+    }
+
+    let arch = match triple.arch {
+        "aarch64" | "arm64" => "arm64",
+        "x86_64" => "x86_64",
+        _ => return Err(LinkerError::UnsupportedLldTarget(target_triple.clone())),
+    };
+
+    let minimum_version = env::var("MACOSX_DEPLOYMENT_TARGET")
+        .unwrap_or_else(|_| if arch == "arm64" { "11.0" } else { "10.12" }.to_string());
+
+    let sdk_path = find_mac_os_sdk().ok_or(LinkerError::MacOsSdkNotFound)?;
+
+    let mut args = vec![
+        "-arch".to_string(),
+        arch.to_string(),
+        "-platform_version".to_string(),
+        "macos".to_string(),
+        minimum_version.clone(),
+        minimum_version,
+        "-syslibroot".to_string(),
+        sdk_path.display().to_string(),
+        "-o".to_string(),
+        output.path.display().to_string(),
+    ];
+
+    if output.kind == OutputKind::SharedLibrary {
+        args.push("-dylib".to_string());
+        args.push("-install_name".to_string());
+        args.push(format!("@rpath/{}", output_file_name(output)));
+
+        for symbol in &build_manifest.exported_symbols {
+            args.push("-exported_symbol".to_string());
+            args.push(format!("_{symbol}"));
+        }
+    }
+
+    args.extend(inputs);
+
+    // libSystem contains libc, there are no startup objects on macOS as `dyld` calls `main` directly
+    args.push("-lSystem".to_string());
+
+    Ok(args)
+}
+
+/// The SDK is read from `SDKROOT`, or queried from `xcrun` if it is not set.
+fn find_mac_os_sdk() -> Option<PathBuf>
+{
+    if let Some(sdk_root) = env::var_os("SDKROOT") {
+        return Some(PathBuf::from(sdk_root));
+    }
+
+    let output = Command::new("xcrun")
+        .args(["--sdk", "macosx", "--show-sdk-path"])
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
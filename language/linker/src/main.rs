@@ -1,6 +1,6 @@
 use std::{env, path::PathBuf};
 
-use linker::{host_information, link_from_manifest};
+use linker::{default_host_information, link_from_manifest};
 
 pub fn main() -> Result<(), Box<dyn std::error::Error>>
 {
@@ -10,7 +10,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>>
         Some(argument) => {
             match argument.as_str() {
                 "h" | "help" => {
-                    host_information()?;
+                    default_host_information()?;
                 },
                 _ => {
                     link_from_manifest(PathBuf::from(argument.clone()))?;
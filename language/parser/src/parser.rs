@@ -6,8 +6,9 @@ use common::{
     compiler::ProjectConfig,
     error::{Spanned, parser::ParserError},
     parser::{
-        common::{Context, Stream, Streamable, parse_compiler_instruction},
-        function::{CompilerInstruction, parse_function},
+        cfg::CfgTarget,
+        common::{Context, Stream, Streamable, instructions_enabled, parse_compiler_instruction},
+        function::{CompilerInstruction, parse_external_function, parse_function},
         ty::{parse_enum, parse_struct},
     },
     tokenizer::{Token, TokenDiscriminants},
@@ -20,6 +21,8 @@ pub struct Settings
     // Project settings
     pub config: ProjectConfig,
     pub enabled_features: OrdSet<String>,
+    /// The target `@cfg` predicates are evaluated against.
+    pub cfg_target: CfgTarget,
     /// The path to the root of this project.
     /// This is important when we are parsing libraries.
     pub module_path: Vec<String>,
@@ -80,15 +83,37 @@ impl Settings
                     // The only reason the else statement is not `unreachable_unchecked` because im scared of breaking it in future modifications.
                     // Regardless it does not result in any meaningful speedup.
                     if let Token::TypeDefinition(item_type) = item_tkn.get_inner() {
+                        // Items gated behind a disabled feature or cfg predicate are parsed, but left out of the context
+                        let is_enabled = instructions_enabled(
+                            &item_compiler_instruction,
+                            &self.enabled_features,
+                            &self.cfg_target,
+                        );
+
+                        // The cfg predicates have been evaluated, the items do not need them anymore
+                        item_compiler_instruction.retain(|instruction| {
+                            !matches!(instruction, CompilerInstruction::Cfg(_))
+                        });
+
                         // Match the type of the item
                         match item_type {
                             common::tokenizer::TypeToken::Enum => {
-                                parse_enum(
+                                let (enum_name, enum_def) = parse_enum(
                                     &mut ctx,
                                     vis,
                                     tokens,
                                     std::mem::take(&mut item_compiler_instruction),
-                                )
+                                )?;
+
+                                if !is_enabled {
+                                    continue;
+                                }
+
+                                ctx.items.insert(
+                                    combine_path(ctx.path.clone(), enum_name.clone()),
+                                    enum_name.into(),
+                                    enum_def,
+                                );
                             },
                             common::tokenizer::TypeToken::Struct => {
                                 let struct_def = parse_struct(
@@ -98,6 +123,10 @@ impl Settings
                                     std::mem::take(&mut item_compiler_instruction),
                                 )?;
 
+                                if !is_enabled {
+                                    continue;
+                                }
+
                                 ctx.items.insert(
                                     combine_path(ctx.path.clone(), struct_def.name.clone()),
                                     struct_def.name.clone().into(),
@@ -110,8 +139,14 @@ impl Settings
                                     vis,
                                     tokens,
                                     std::mem::take(&mut item_compiler_instruction),
+                                    &self.enabled_features,
+                                    &self.cfg_target,
                                 )?;
 
+                                if !is_enabled {
+                                    continue;
+                                }
+
                                 ctx.functions.insert(
                                     combine_path(
                                         function.module_path.clone(),
@@ -134,14 +169,29 @@ impl Settings
                     }
                 },
                 Token::Import => {
-                    /* 
+                    // Imports gated behind a disabled feature or cfg predicate are skipped
+                    if !instructions_enabled(
+                        &std::mem::take(&mut item_compiler_instruction),
+                        &self.enabled_features,
+                        &self.cfg_target,
+                    ) {
+                        while let Some(tkn) = tokens.consume() {
+                            if tkn.get_inner() == &Token::SemiColon {
+                                break;
+                            }
+                        }
+
+                        continue;
+                    }
+
+                    /*
                         All item imports must point to concrete items, such as a function or enum. It cannot point to a module.
-                        
+
                         Both raw paths and dependencies can be imported via this keyword.
-                        For declaring external function token `Token::External` must be used.  
-                    
+                        For declaring external function token `Token::External` must be used.
+
                         When a file is imported via its raw path, the modules are accessible via its file name.
-                        
+
                         Example:
                         ```
                         import "foo.f";
@@ -155,24 +205,47 @@ impl Settings
                         Example:
                         Given that we have a dependency named `helper`.
                         ```
-                        import helper::hello;                        
+                        import helper::hello;
                         ```
 
                         Imported items can be aliased via the `as` keyword.
-                        Example: 
+                        Example:
                         ```
                         import foo::bar as "hello";
 
                         hello();
-                        
+
                         # Not found
                         bar();
                         ```
                     */
                 },
                 Token::External => {
+                    /*
+                        External functions are declared with their signature, they are linked from the additional linking material.
 
-                }
+                        Example:
+                        ```
+                        external printf(msg: string, ...): void;
+                        ```
+                    */
+                    let signature = parse_external_function(tokens)?;
+
+                    // External functions do not have implementations, the compiler instructions are only used to gate them behind features and cfg predicates
+                    if !instructions_enabled(
+                        &std::mem::take(&mut item_compiler_instruction),
+                        &self.enabled_features,
+                        &self.cfg_target,
+                    ) {
+                        continue;
+                    }
+
+                    ctx.external_decls.insert(
+                        combine_path(ctx.path.clone(), signature.name.clone()),
+                        signature.name.clone().into(),
+                        signature,
+                    );
+                },
 
                 // If the token was not recognized, return an error.
                 _ => return Err(ParserError::ItemRequiresExplicitVisibility.into()),
@@ -186,11 +259,13 @@ impl Settings
         config: ProjectConfig,
         module_path: Vec<String>,
         enabled_features: OrdSet<String>,
+        cfg_target: CfgTarget,
         root_path: PathBuf,
     ) -> Self
     {
         Self {
             enabled_features,
+            cfg_target,
             config,
             module_path,
             root_path,
@@ -380,7 +380,11 @@ fn try_match_token(string_to_match: &[u8]) -> Option<Token>
         b"nofree" => Token::CompilerInstruction(CompilerInstructionDiscriminants::NoFree),
         b"nounwind" => Token::CompilerInstruction(CompilerInstructionDiscriminants::NoUnWind),
         b"inline" => Token::CompilerInstruction(CompilerInstructionDiscriminants::Inline),
+        b"noinline" => Token::CompilerInstruction(CompilerInstructionDiscriminants::NoInline),
+        b"optnone" => Token::CompilerInstruction(CompilerInstructionDiscriminants::OptNone),
+        b"test" => Token::CompilerInstruction(CompilerInstructionDiscriminants::Test),
         b"feature" => Token::CompilerInstruction(CompilerInstructionDiscriminants::Feature),
+        b"cfg" => Token::CompilerInstruction(CompilerInstructionDiscriminants::Cfg),
 
         b"::" => Token::DoubleColon,
         b"<-" => Token::LeftArrow,
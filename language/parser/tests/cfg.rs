@@ -0,0 +1,71 @@
+use common::{
+    parser::{
+        cfg::{CfgPredicate, CfgTarget, parse_cfg},
+        common::Stream,
+    },
+    ty::OrdSet,
+};
+use parser::tokenizer::tokenize;
+
+fn parse(predicate: &str) -> CfgPredicate
+{
+    parse_cfg(&mut Stream::new(tokenize(predicate).unwrap())).unwrap()
+}
+
+#[test]
+fn derives_cfg_values_from_target_triples()
+{
+    let linux = CfgTarget::from_triple("x86_64-unknown-linux-gnu", true);
+
+    assert_eq!(linux.target_os, "linux");
+    assert_eq!(linux.target_arch, "x86_64");
+    assert_eq!(linux.pointer_width, 64);
+
+    let windows = CfgTarget::from_triple("i686-pc-windows-msvc", false);
+
+    assert_eq!(windows.target_os, "windows");
+    assert_eq!(windows.target_arch, "x86");
+    assert_eq!(windows.pointer_width, 32);
+
+    let android = CfgTarget::from_triple("aarch64-linux-android", false);
+
+    assert_eq!(android.target_os, "android");
+    assert_eq!(android.target_arch, "aarch64");
+
+    let mingw = CfgTarget::from_triple("x86_64-w64-mingw32", false);
+
+    assert_eq!(mingw.target_os, "windows");
+    assert_eq!(mingw.target_arch, "x86_64");
+}
+
+#[test]
+fn evaluates_combined_predicates()
+{
+    let predicate = parse(
+        r#"(all(any(target_os = "linux", target_os = "macos"), not(pointer_width = "32"), feature = "simd"))"#,
+    );
+
+    assert_eq!(
+        predicate.to_string(),
+        r#"all(any(target_os = "linux", target_os = "macos"), not(pointer_width = "32"), feature = "simd")"#
+    );
+
+    let features = OrdSet::from_iter(["simd".to_string()]);
+
+    assert!(predicate.evaluate(
+        &CfgTarget::from_triple("x86_64-apple-darwin", false),
+        &features
+    ));
+    assert!(!predicate.evaluate(
+        &CfgTarget::from_triple("x86_64-pc-windows-msvc", false),
+        &features
+    ));
+    assert!(!predicate.evaluate(
+        &CfgTarget::from_triple("i686-unknown-linux-gnu", false),
+        &features
+    ));
+    assert!(!predicate.evaluate(
+        &CfgTarget::from_triple("x86_64-unknown-linux-gnu", false),
+        &OrdSet::new()
+    ));
+}
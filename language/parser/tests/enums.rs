@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use common::{
+    codegen::CustomItem,
+    compiler::ProjectConfig,
+    fir::FirModule,
+    parser::{
+        cfg::CfgTarget,
+        common::{StatementVariant, Stream},
+    },
+    ty::{OrdSet, Value},
+};
+use parser::{parser::Settings, tokenizer::tokenize};
+
+/// Parses the enum declared by the source and returns the values of its variants.
+fn enum_values(source: &str) -> Vec<(String, i32)>
+{
+    let settings = Settings::new(
+        ProjectConfig::default(),
+        vec!["main".to_string()],
+        OrdSet::new(),
+        CfgTarget::from_triple("x86_64-unknown-linux-gnu", true),
+        PathBuf::from("main.f"),
+    );
+
+    let module = FirModule::from_context(
+        &settings
+            .parse(&mut Stream::new(tokenize(source).unwrap()))
+            .unwrap(),
+    );
+
+    let Some((_path, CustomItem::Enum((_ty, variants)))) = module.items.first()
+    else {
+        panic!("the source does not declare an enum");
+    };
+
+    variants
+        .iter()
+        .map(|(name, value)| {
+            let value = match value.get_inner() {
+                StatementVariant::Value(Value::I32(value)) => *value,
+                StatementVariant::NegateValue(value) => {
+                    match value.get_inner() {
+                        StatementVariant::Value(Value::I32(value)) => -*value,
+                        value => panic!("`{name}` has a non-literal value: {value:?}"),
+                    }
+                },
+                value => panic!("`{name}` has a non-literal value: {value:?}"),
+            };
+
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+#[test]
+fn numbers_variants_from_zero()
+{
+    assert_eq!(
+        enum_values("pub enum Numbers<int> { Zero, One, Two }"),
+        [
+            ("Zero".to_string(), 0),
+            ("One".to_string(), 1),
+            ("Two".to_string(), 2)
+        ]
+    );
+}
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use common::{
+    compiler::ProjectConfig,
+    fir::{FirModule, parser::parse_module, printer::FirPrinter},
+    parser::{
+        cfg::{CfgPredicate, CfgTarget},
+        common::Stream,
+        function::CompilerInstruction,
+    },
+    ty::OrdSet,
+};
+use parser::{parser::Settings, tokenizer::tokenize};
+
+const ARITHMETIC: &str = r#"external printf(msg: string, ...): int;
+
+pub function add(lhs: int, rhs: int): int {
+    int result = lhs + rhs * 2 - (lhs / 3);
+    result += 1;
+
+    return result;
+}
+
+pub function main(): int {
+    printf("Sum: %d\n\t(done)", add(1, 2));
+
+    return 0;
+}
+"#;
+
+const CONTROL_FLOW: &str = r#"pub function clamp(value: int, lo: int, hi: int): int {
+    int res = value;
+
+    if (res < lo) {
+        res = lo;
+    }
+    else {
+        res = hi;
+    }
+
+    loop {
+        break;
+    }
+
+    return res;
+}
+"#;
+
+const ITEMS: &str = r#"pub struct Vec2 {
+    x: int,
+    y: float,
+}
+
+pub enum Numbers<uint> {
+    One,
+    Two,
+    SixtySeven = 67,
+}
+
+@inline
+@nounwind
+pub function first(): int {
+    array<int, 4> values = {1, 2, 3, 4};
+    ptr first_ptr = ref values[0];
+    int first = deref first_ptr;
+
+    return first;
+}
+
+@feature "simd"
+pub function gated(): int {
+    return 1;
+}
+
+@cfg(all(target_os = "linux", not(pointer_width = "32")))
+pub function linux_only(): int {
+    return 2;
+}
+"#;
+
+fn parse_source(source: &str) -> FirModule
+{
+    let settings = Settings::new(
+        ProjectConfig::default(),
+        vec!["main".to_string()],
+        OrdSet::from_iter(["simd".to_string()]),
+        CfgTarget::from_triple("x86_64-unknown-linux-gnu", true),
+        PathBuf::from("main.f"),
+    );
+
+    FirModule::from_context(
+        &settings
+            .parse(&mut Stream::new(tokenize(source).unwrap()))
+            .unwrap(),
+    )
+}
+
+/// Prints the module with spans, parses it back and checks that the same module has been parsed.
+fn assert_round_trip(module: &FirModule)
+{
+    let printed = FirPrinter::new(true).print_module(module);
+
+    assert_eq!(&parse_module(&printed).unwrap(), module, "{printed}");
+}
+
+/// Modules printed without spans get the default spans when they are parsed, so printing them again must produce the same text.
+fn assert_stable_without_spans(module: &FirModule)
+{
+    let printer = FirPrinter::new(false);
+    let printed = printer.print_module(module);
+
+    assert_eq!(
+        printer.print_module(&parse_module(&printed).unwrap()),
+        printed
+    );
+}
+
+#[test]
+fn round_trips_parsed_modules()
+{
+    for source in [ARITHMETIC, CONTROL_FLOW, ITEMS] {
+        let module = parse_source(source);
+
+        assert_round_trip(&module);
+        assert_stable_without_spans(&module);
+    }
+}
+
+#[test]
+fn round_trips_handwritten_fir()
+{
+    let source = r#"; Fog IR
+(module
+  (path main)
+  (external printf (path printf) (args (input 0 string)) (ellipsis) (returns i32))
+  (function main
+    (path main main)
+    (module main)
+    (visibility public)
+    (returns i32)
+    (body
+      (let number 1 mut i32 (value (i32 5) @4:18-4:19) @4:12-4:18)
+      (modify add (ref number @5:4-5:10) (value (i32 1) @5:14-5:15) @5:4-5:15)
+      (return (ref number @6:11-6:17) @6:4-6:17))
+    @3:8-3:12))
+"#;
+
+    let module = parse_module(source).unwrap();
+
+    assert_eq!(module.path, ["main"]);
+    assert_eq!(module.external_decls.len(), 1);
+    assert_eq!(module.functions.len(), 1);
+
+    assert_round_trip(&module);
+}
+
+#[test]
+fn round_trips_cfg_instructions()
+{
+    let mut module = parse_source(ITEMS);
+
+    let (_path, function) = module
+        .functions
+        .iter_mut()
+        .find(|(path, _function)| path.last().unwrap() == "linux_only")
+        .unwrap();
+
+    function
+        .compiler_instructions
+        .insert(CompilerInstruction::Cfg(CfgPredicate::Any(vec![
+            CfgPredicate::All(vec![
+                CfgPredicate::TargetOs("linux".to_string()),
+                CfgPredicate::TargetArch("x86_64".to_string()),
+            ]),
+            CfgPredicate::Not(Box::new(CfgPredicate::PointerWidth(32))),
+            CfgPredicate::DebugAssertions,
+            CfgPredicate::Feature("simd".to_string()),
+        ])));
+
+    assert_round_trip(&module);
+    assert_stable_without_spans(&module);
+}